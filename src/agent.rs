@@ -1,12 +1,15 @@
 //! The agent responsible for running the emulator core on the worker thread and communication functionalities.
 
+use crate::agent::heap::Heap;
 use crate::agent::messages::MipsStateUpdate;
-use crate::agent::messages::{Command, RiscStateUpdate, SystemUpdate};
+use crate::agent::messages::{Command, OutputChannel, RiscStateUpdate, SystemUpdate};
 use crate::agent::system_scanner::Scanner;
 use crate::emulation_core::architectures::{AvailableDatapaths, DatapathRef};
 use crate::emulation_core::datapath::{Datapath, DatapathUpdateSignal, Syscall, UPDATE_EVERYTHING};
 use crate::emulation_core::mips::datapath::MipsDatapath;
+use crate::emulation_core::mips::gp_registers::GpRegisterType;
 use crate::emulation_core::riscv::datapath::RiscDatapath;
+use crate::emulation_core::riscv::registers::RiscGpRegisterType;
 use futures::{FutureExt, SinkExt, StreamExt};
 use instant::Instant;
 use messages::DatapathUpdate;
@@ -15,9 +18,12 @@
 use yew::platform::time::sleep;
 use yew_agent::prelude::*;
 
+pub mod console_buffer;
 pub mod datapath_communicator;
 pub mod datapath_reducer;
+pub mod heap;
 pub mod messages;
+pub mod printf;
 pub mod system_scanner;
 
 macro_rules! send_update {
@@ -126,6 +132,11 @@ pub async fn emulation_core_agent(scope: ReactorScope<Command, DatapathUpdate>)
                         state.updates.changed_stack,
                         MipsStateUpdate::UpdateStack(datapath.stack.clone())
                     );
+                    send_update_mips!(
+                        state.scope,
+                        state.updates.changed_interrupts,
+                        MipsStateUpdate::UpdateInterrupts(datapath.interrupts)
+                    );
                 }
                 DatapathRef::RISCV(datapath) => {
                     // Stage always updates
@@ -161,6 +172,11 @@ pub async fn emulation_core_agent(scope: ReactorScope<Command, DatapathUpdate>)
                         state.updates.changed_coprocessor_registers,
                         RiscStateUpdate::UpdateCoprocessorRegisters(datapath.coprocessor.registers)
                     );
+                    send_update_riscv!(
+                        state.scope,
+                        state.updates.changed_interrupts,
+                        RiscStateUpdate::UpdateInterrupts(datapath.interrupts)
+                    );
                 }
             }
             state.updates = Default::default();
@@ -204,8 +220,8 @@ struct EmulatorCoreAgentState {
     last_update: Option<Instant>,
     executing: bool,
     initialized: bool,
-    messages: Vec<String>,
     scanner: Scanner,
+    heap: Heap,
     blocked_on: BlockedOn,
     breakpoints: HashSet<u64>,
 }
@@ -220,8 +236,8 @@ pub fn new(scope: ReactorScope<Command, DatapathUpdate>) -> EmulatorCoreAgentSta
             last_update: None,
             executing: false,
             initialized: false,
-            messages: Vec::new(),
             scanner: Scanner::new(),
+            heap: Heap::default(),
             blocked_on: BlockedOn::Nothing,
             breakpoints: HashSet::default(),
         }
@@ -241,9 +257,22 @@ pub async fn handle_command(&mut self, command: Command) {
                 self.reset_system().await;
             }
             Command::Initialize(initial_pc, mem) => {
-                self.current_datapath.initialize(initial_pc, mem).unwrap();
+                // The heap begins after the highest address the program is loaded to.
+                let heap = Heap::after_image(&mem);
+                let result = self.current_datapath.initialize(initial_pc, mem);
                 self.reset_system().await;
-                self.initialized = true;
+                match result {
+                    Ok(()) => {
+                        self.heap = heap;
+                        self.send_heap_update().await;
+                        self.initialized = true;
+                    }
+                    // A program that does not fit in memory is reported rather than run.
+                    Err(message) => {
+                        self.write_output(OutputChannel::Stderr, format!("Error: {message}\n"))
+                            .await;
+                    }
+                }
             }
             Command::SetExecuteSpeed(speed) => {
                 self.speed = speed;
@@ -281,7 +310,8 @@ pub async fn handle_command(&mut self, command: Command) {
                 self.reset_system().await;
             }
             Command::Input(line) => {
-                self.add_message(format!("> {}", line)).await;
+                self.write_output(OutputChannel::System, format!("> {}\n", line))
+                    .await;
                 self.scanner.feed(line);
             }
             Command::SetBreakpoint(address) => {
@@ -341,50 +371,42 @@ pub async fn execute_syscall_stage(&mut self) {
                 self.executing = false;
             }
             Syscall::PrintInt(val) => {
-                self.add_message(val.to_string()).await;
+                self.write_output(OutputChannel::Stdout, val.to_string())
+                    .await;
             }
             Syscall::PrintFloat(val) => {
-                self.add_message(val.to_string()).await;
+                self.write_output(OutputChannel::Stdout, val.to_string())
+                    .await;
             }
             Syscall::PrintDouble(val) => {
-                self.add_message(val.to_string()).await;
+                self.write_output(OutputChannel::Stdout, val.to_string())
+                    .await;
             }
             Syscall::PrintString(addr) => {
-                let memory = self.current_datapath.get_memory_mut();
-                let mut buffer = Vec::new();
-                'outer: for i in 0.. {
-                    let word = memory.load_word(addr + (i * 4));
-                    match word {
-                        Ok(word) => {
-                            for byte in word.to_be_bytes() {
-                                if byte == 0 {
-                                    // Break on null terminator
-                                    break 'outer;
-                                } else {
-                                    buffer.push(byte);
-                                }
-                            }
-                        }
-                        Err(_) => break,
-                    }
-                }
-
-                let message = String::from_utf8(buffer);
-                match message {
-                    Ok(message) => self.add_message(message).await,
-                    Err(_) => {
-                        self.add_message("Error: Attempted to print invalid string".to_string())
-                            .await
-                    }
-                }
+                let message =
+                    printf::read_null_terminated_string(self.current_datapath.get_memory(), addr);
+                self.write_print_result(message).await;
+            }
+            Syscall::PrintFormatted(addr) => {
+                let message = printf::printf(self.current_datapath.as_datapath_ref(), addr);
+                self.write_print_result(message).await;
             }
             Syscall::ReadInt => {
-                let scan_result = self.scanner.next_int();
+                let scan_result = self.scanner.read_int();
                 match scan_result {
                     None => {
                         self.blocked_on = BlockedOn::Syscall(syscall);
                     }
-                    Some(scan_result) => {
+                    Some(Err(error)) => {
+                        // Keep waiting on the same syscall so the user can try again.
+                        self.blocked_on = BlockedOn::Syscall(syscall);
+                        self.write_output(
+                            OutputChannel::Stderr,
+                            format!("Invalid input: {error}. Please try again.\n"),
+                        )
+                        .await;
+                    }
+                    Some(Ok(scan_result)) => {
                         self.blocked_on = BlockedOn::Nothing;
                         match self.current_datapath.as_datapath_ref() {
                             DatapathRef::MIPS(_) => {
@@ -400,12 +422,21 @@ pub async fn execute_syscall_stage(&mut self) {
                 }
             }
             Syscall::ReadFloat => {
-                let scan_result = self.scanner.next_float();
+                let scan_result = self.scanner.read_float();
                 match scan_result {
                     None => {
                         self.blocked_on = BlockedOn::Syscall(syscall);
                     }
-                    Some(scan_result) => {
+                    Some(Err(error)) => {
+                        // Keep waiting on the same syscall so the user can try again.
+                        self.blocked_on = BlockedOn::Syscall(syscall);
+                        self.write_output(
+                            OutputChannel::Stderr,
+                            format!("Invalid input: {error}. Please try again.\n"),
+                        )
+                        .await;
+                    }
+                    Some(Ok(scan_result)) => {
                         self.blocked_on = BlockedOn::Nothing;
                         match self.current_datapath.as_datapath_ref() {
                             DatapathRef::MIPS(_) => {
@@ -424,12 +455,21 @@ pub async fn execute_syscall_stage(&mut self) {
                 }
             }
             Syscall::ReadDouble => {
-                let scan_result = self.scanner.next_double();
+                let scan_result = self.scanner.read_double();
                 match scan_result {
                     None => {
                         self.blocked_on = BlockedOn::Syscall(syscall);
                     }
-                    Some(scan_result) => {
+                    Some(Err(error)) => {
+                        // Keep waiting on the same syscall so the user can try again.
+                        self.blocked_on = BlockedOn::Syscall(syscall);
+                        self.write_output(
+                            OutputChannel::Stderr,
+                            format!("Invalid input: {error}. Please try again.\n"),
+                        )
+                        .await;
+                    }
+                    Some(Ok(scan_result)) => {
                         self.blocked_on = BlockedOn::Nothing;
                         match self.current_datapath.as_datapath_ref() {
                             DatapathRef::MIPS(_) => {
@@ -494,6 +534,26 @@ pub async fn execute_syscall_stage(&mut self) {
                     }
                 }
             }
+            Syscall::Sbrk(increment) => {
+                let limit = self.get_stack_pointer();
+                let result = self.heap.sbrk(increment as i64, limit);
+                // Like the Unix call, a failed sbrk returns -1.
+                let address = self.unwrap_heap_result(result, u64::MAX).await;
+                self.set_syscall_result(address);
+                self.send_heap_update().await;
+            }
+            Syscall::Malloc(size) => {
+                let limit = self.get_stack_pointer();
+                let result = self.heap.malloc(size, limit);
+                let address = self.unwrap_heap_result(result, 0).await;
+                self.set_syscall_result(address);
+                self.send_heap_update().await;
+            }
+            Syscall::Free(address) => {
+                let result = self.heap.free(address);
+                self.unwrap_heap_result(result, ()).await;
+                self.send_heap_update().await;
+            }
         }
 
         // Now that the syscall is processed, unset the update signal
@@ -514,24 +574,71 @@ pub fn should_send_datapath_update(&self) -> bool {
 
     async fn reset_system(&mut self) {
         self.scanner = Scanner::new();
+        self.heap = Heap::default();
         self.blocked_on = BlockedOn::Nothing;
         self.initialized = false;
-        self.messages = Vec::new();
         self.scope
-            .send(DatapathUpdate::System(SystemUpdate::UpdateMessages(
-                self.messages.clone(),
-            )))
+            .send(DatapathUpdate::System(SystemUpdate::ClearOutput))
             .await
             .unwrap();
         self.updates |= UPDATE_EVERYTHING;
         self.breakpoints = HashSet::default();
     }
 
-    async fn add_message(&mut self, msg: String) {
-        self.messages.push(msg);
+    /// Writes the result of a print syscall to stdout, or the reason it failed to stderr.
+    async fn write_print_result(&mut self, message: Result<String, String>) {
+        match message {
+            Ok(message) => self.write_output(OutputChannel::Stdout, message).await,
+            Err(error) => {
+                self.write_output(OutputChannel::Stderr, format!("Error: {error}\n"))
+                    .await
+            }
+        }
+    }
+
+    /// Returns the value of the stack pointer, which bounds the growth of the heap.
+    fn get_stack_pointer(&self) -> u64 {
+        match self.current_datapath.as_datapath_ref() {
+            DatapathRef::MIPS(datapath) => datapath.registers[GpRegisterType::Sp],
+            DatapathRef::RISCV(datapath) => datapath.registers[RiscGpRegisterType::X2],
+        }
+    }
+
+    /// Stores the return value of a syscall in the architecture's result register.
+    fn set_syscall_result(&mut self, value: u64) {
+        match self.current_datapath.as_datapath_ref() {
+            DatapathRef::MIPS(_) => self.current_datapath.set_register_by_str("v0", value),
+            DatapathRef::RISCV(_) => self.current_datapath.set_register_by_str("x11", value),
+        }
+        self.updates.changed_registers = true;
+    }
+
+    /// Reports a failed heap operation on the console, returning `default` in place of its result.
+    async fn unwrap_heap_result<T>(&mut self, result: Result<T, String>, default: T) -> T {
+        match result {
+            Ok(value) => value,
+            Err(message) => {
+                self.write_output(OutputChannel::Stderr, format!("Error: {message}\n"))
+                    .await;
+                default
+            }
+        }
+    }
+
+    async fn send_heap_update(&mut self) {
+        self.scope
+            .send(DatapathUpdate::System(SystemUpdate::UpdateHeap(
+                self.heap.clone(),
+            )))
+            .await
+            .unwrap();
+    }
+
+    /// Sends only the new text to the UI thread rather than the entire console history.
+    async fn write_output(&mut self, channel: OutputChannel, text: String) {
         self.scope
-            .send(DatapathUpdate::System(SystemUpdate::UpdateMessages(
-                self.messages.clone(),
+            .send(DatapathUpdate::System(SystemUpdate::AppendOutput(
+                channel, text,
             )))
             .await
             .unwrap();
@@ -0,0 +1,102 @@
+//! The console's scrollback, built incrementally from the output sent by the emulator core.
+
+use crate::agent::messages::OutputChannel;
+use std::collections::VecDeque;
+
+/// The default number of lines kept in the console's scrollback.
+pub const MAX_SCROLLBACK_LINES: usize = 1000;
+
+/// A single line of console output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConsoleLine {
+    pub channel: OutputChannel,
+    pub text: String,
+}
+
+/// A bounded buffer of console lines. Output is appended as it arrives, so text without a
+/// trailing newline stays on the current line until more output from the same channel finishes
+/// it. Output from a different channel always starts on a new line. Once the buffer holds more
+/// than its capacity, the oldest lines are dropped.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConsoleBuffer {
+    lines: VecDeque<ConsoleLine>,
+    /// Whether the last line has not yet been terminated by a newline.
+    line_open: bool,
+    capacity: usize,
+    /// The number of lines dropped from the front of the buffer since it was last cleared.
+    dropped_lines: usize,
+}
+
+impl Default for ConsoleBuffer {
+    fn default() -> Self {
+        Self::with_capacity(MAX_SCROLLBACK_LINES)
+    }
+}
+
+impl ConsoleBuffer {
+    /// Constructs an empty buffer holding at most `capacity` lines.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            lines: VecDeque::new(),
+            line_open: false,
+            capacity,
+            dropped_lines: 0,
+        }
+    }
+
+    /// Appends text written to a channel. Each `\n` ends the current line.
+    pub fn append(&mut self, channel: OutputChannel, text: &str) {
+        for (i, segment) in text.split('\n').enumerate() {
+            if i > 0 {
+                // A newline on a line with no text yet is a blank line of its own.
+                if !self.line_open {
+                    self.push_line(channel, String::new());
+                }
+                self.line_open = false;
+            }
+
+            if segment.is_empty() {
+                continue;
+            }
+
+            match self.lines.back_mut() {
+                Some(line) if self.line_open && line.channel == channel => {
+                    line.text.push_str(segment);
+                }
+                _ => self.push_line(channel, segment.to_string()),
+            }
+            self.line_open = true;
+        }
+    }
+
+    /// Removes all lines from the buffer.
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.line_open = false;
+        self.dropped_lines = 0;
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = &ConsoleLine> {
+        self.lines.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    pub fn dropped_lines(&self) -> usize {
+        self.dropped_lines
+    }
+
+    fn push_line(&mut self, channel: OutputChannel, text: String) {
+        self.lines.push_back(ConsoleLine { channel, text });
+        while self.lines.len() > self.capacity {
+            self.lines.pop_front();
+            self.dropped_lines += 1;
+        }
+    }
+}
@@ -1,6 +1,9 @@
+use crate::agent::console_buffer::ConsoleBuffer;
+use crate::agent::heap::Heap;
 use crate::agent::messages::{DatapathUpdate, MipsStateUpdate, RiscStateUpdate, SystemUpdate};
 use crate::emulation_core::architectures::AvailableDatapaths;
 use crate::emulation_core::architectures::AvailableDatapaths::{MIPS, RISCV};
+use crate::emulation_core::interrupt::InterruptController;
 use crate::emulation_core::mips::coprocessor::FpuState;
 use crate::emulation_core::mips::datapath::{DatapathState, Stage};
 use crate::emulation_core::mips::fp_registers::FpRegisters;
@@ -20,7 +23,8 @@ pub struct DatapathReducer {
     pub current_architecture: AvailableDatapaths,
     pub mips: MipsCoreState,
     pub riscv: RiscCoreState,
-    pub messages: Vec<String>,
+    pub output: ConsoleBuffer,
+    pub heap: Heap,
     pub speed: u32,
     pub executing: bool,
     pub initialized: bool,
@@ -35,6 +39,7 @@ pub struct MipsCoreState {
     pub memory: Memory,
     pub current_stage: Stage,
     pub stack: Stack,
+    pub interrupts: InterruptController,
 }
 
 #[derive(Default, PartialEq, Clone)]
@@ -45,6 +50,7 @@ pub struct RiscCoreState {
     pub memory: Memory,
     pub current_stage: RiscStage,
     pub stack: Stack,
+    pub interrupts: InterruptController,
 }
 
 impl Default for DatapathReducer {
@@ -53,7 +59,8 @@ fn default() -> Self {
             current_architecture: MIPS,
             mips: MipsCoreState::default(),
             riscv: RiscCoreState::default(),
-            messages: Vec::new(),
+            output: ConsoleBuffer::default(),
+            heap: Heap::default(),
             speed: 0,
             executing: false,
             initialized: false,
@@ -99,12 +106,28 @@ fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
                         stack,
                         ..self.mips.clone()
                     },
+                    MipsStateUpdate::UpdateInterrupts(interrupts) => MipsCoreState {
+                        interrupts,
+                        ..self.mips.clone()
+                    },
                 },
                 ..(*self).clone()
             },
             DatapathUpdate::System(update) => match update {
-                SystemUpdate::UpdateMessages(messages) => Self {
-                    messages,
+                SystemUpdate::AppendOutput(channel, text) => {
+                    let mut output = self.output.clone();
+                    output.append(channel, &text);
+                    Self {
+                        output,
+                        ..(*self).clone()
+                    }
+                }
+                SystemUpdate::ClearOutput => Self {
+                    output: ConsoleBuffer::default(),
+                    ..(*self).clone()
+                },
+                SystemUpdate::UpdateHeap(heap) => Self {
+                    heap,
                     ..(*self).clone()
                 },
                 SystemUpdate::UpdateExecuting(executing) => Self {
@@ -118,7 +141,8 @@ fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
                 SystemUpdate::UpdateSpeed(speed) => Self {
                     current_architecture: self.current_architecture,
                     mips: self.mips.clone(),
-                    messages: self.messages.clone(),
+                    output: self.output.clone(),
+                    heap: self.heap.clone(),
                     speed,
                     executing: self.executing,
                     initialized: self.initialized,
@@ -148,6 +172,10 @@ fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
                         stack,
                         ..self.riscv.clone()
                     },
+                    RiscStateUpdate::UpdateInterrupts(interrupts) => RiscCoreState {
+                        interrupts,
+                        ..self.riscv.clone()
+                    },
                     RiscStateUpdate::UpdateCoprocessorRegisters(coprocessor_registers) => {
                         RiscCoreState {
                             coprocessor_registers,
@@ -204,6 +232,13 @@ pub fn get_current_stage(&self) -> String {
         }
     }
 
+    pub fn get_interrupts(&self) -> InterruptController {
+        match self.current_architecture {
+            MIPS => self.mips.interrupts,
+            RISCV => self.riscv.interrupts,
+        }
+    }
+
     pub fn get_stack(&self) -> &Stack {
         match self.current_architecture {
             MIPS => &self.mips.stack,
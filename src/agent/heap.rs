@@ -0,0 +1,155 @@
+//! Tracking of the program break and the simple allocator backing the `malloc` and `free` syscalls.
+
+use serde::{Deserialize, Serialize};
+
+/// The alignment of the program break and of every allocation, in bytes. This keeps any
+/// allocation suitable for storing doubles.
+pub const HEAP_ALIGNMENT: u64 = 8;
+
+/// A single successful `sbrk` call that grew the heap.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeapGrowth {
+    /// The program break before the call.
+    pub address: u64,
+    /// The number of bytes the heap grew by.
+    pub size: u64,
+}
+
+/// A block handed out by the `malloc` syscall.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeapAllocation {
+    pub address: u64,
+    pub size: u64,
+    /// Whether the block has not yet been freed.
+    pub live: bool,
+}
+
+/// The heap of the running program. The heap starts directly after the program's text and data
+/// and grows upwards towards the stack.
+///
+/// Allocations are first-fit: `malloc` reuses the first freed block that is large enough and
+/// otherwise grows the heap with `sbrk`. Freed blocks are never split or merged.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Heap {
+    /// The address of the first byte of the heap.
+    pub start: u64,
+    /// The address of the first byte past the end of the heap.
+    pub program_break: u64,
+    pub growths: Vec<HeapGrowth>,
+    pub allocations: Vec<HeapAllocation>,
+}
+
+impl Heap {
+    /// Constructs an empty heap starting at the first aligned address at or after `end_of_program`.
+    pub fn new(end_of_program: u64) -> Self {
+        let start = align(end_of_program);
+        Self {
+            start,
+            program_break: start,
+            ..Default::default()
+        }
+    }
+
+    /// Constructs an empty heap after the highest word of an image loaded into memory from address
+    /// 0. Sections can be placed in any order by `.text`, `.data`, and `.org`, so the heap starts
+    /// after whichever of them ends last rather than after the text or the data.
+    pub fn after_image(image: &[u32]) -> Self {
+        let end_of_image = image.len() as u64 * 4;
+        Self::new(end_of_image)
+    }
+
+    /// Moves the program break by `increment` bytes, rounded up to [`HEAP_ALIGNMENT`], and returns
+    /// the previous program break. The break cannot move below the start of the heap or above
+    /// `limit`, which is generally the current stack pointer.
+    pub fn sbrk(&mut self, increment: i64, limit: u64) -> Result<u64, String> {
+        let old_break = self.program_break;
+        let new_break = if increment >= 0 {
+            old_break.checked_add(align(increment as u64))
+        } else {
+            old_break.checked_sub(increment.unsigned_abs())
+        };
+
+        match new_break {
+            Some(new_break) if new_break < self.start => {
+                Err("sbrk: cannot shrink the heap below its start".to_string())
+            }
+            Some(new_break) if new_break <= limit => {
+                if new_break > old_break {
+                    self.growths.push(HeapGrowth {
+                        address: old_break,
+                        size: new_break - old_break,
+                    });
+                }
+                self.program_break = new_break;
+                Ok(old_break)
+            }
+            _ => Err(format!(
+                "sbrk: growing the heap by {increment} bytes would collide with the stack"
+            )),
+        }
+    }
+
+    /// Allocates a block of at least `size` bytes and returns its address.
+    pub fn malloc(&mut self, size: u64, limit: u64) -> Result<u64, String> {
+        if size == 0 {
+            return Err("malloc: cannot allocate 0 bytes".to_string());
+        }
+        let size = align(size);
+
+        // Reuse the first freed block that is large enough.
+        if let Some(block) = self
+            .allocations
+            .iter_mut()
+            .find(|block| !block.live && block.size >= size)
+        {
+            block.live = true;
+            return Ok(block.address);
+        }
+
+        let address = self
+            .sbrk(size as i64, limit)
+            .map_err(|_| format!("malloc: out of memory allocating {size} bytes"))?;
+        self.allocations.push(HeapAllocation {
+            address,
+            size,
+            live: true,
+        });
+        Ok(address)
+    }
+
+    /// Frees a block previously returned by [`Self::malloc()`]. Freeing address 0 does nothing.
+    pub fn free(&mut self, address: u64) -> Result<(), String> {
+        if address == 0 {
+            return Ok(());
+        }
+
+        match self
+            .allocations
+            .iter_mut()
+            .find(|block| block.address == address)
+        {
+            Some(block) if block.live => {
+                block.live = false;
+                Ok(())
+            }
+            Some(_) => Err(format!("free: double free of address 0x{address:08x}")),
+            None => Err(format!(
+                "free: address 0x{address:08x} was not returned by malloc"
+            )),
+        }
+    }
+
+    /// The number of bytes currently in use by live allocations.
+    pub fn live_bytes(&self) -> u64 {
+        self.allocations
+            .iter()
+            .filter(|block| block.live)
+            .map(|block| block.size)
+            .sum()
+    }
+}
+
+/// Rounds a size or address up to the next multiple of [`HEAP_ALIGNMENT`].
+fn align(value: u64) -> u64 {
+    value.div_ceil(HEAP_ALIGNMENT) * HEAP_ALIGNMENT
+}
@@ -1,3 +1,5 @@
+use crate::agent::heap::Heap;
+use crate::emulation_core::interrupt::InterruptController;
 use crate::emulation_core::mips::coprocessor::FpuState;
 use crate::emulation_core::mips::datapath::DatapathState;
 use crate::emulation_core::mips::fp_registers::FpRegisters;
@@ -38,6 +40,7 @@ pub enum MipsStateUpdate {
     UpdateMemory(Memory),
     UpdateStage(Stage),
     UpdateStack(Stack),
+    UpdateInterrupts(InterruptController),
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -48,12 +51,29 @@ pub enum RiscStateUpdate {
     UpdateMemory(Memory),
     UpdateStage(RiscStage),
     UpdateStack(Stack),
+    UpdateInterrupts(InterruptController),
+}
+
+/// The stream a piece of console output was written to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OutputChannel {
+    /// Output written by the program, such as the print syscalls.
+    #[default]
+    Stdout,
+    /// Errors caused by the program, such as invalid input or invalid strings.
+    Stderr,
+    /// Messages from the emulator itself, such as echoed input.
+    System,
 }
 
 /// Information about the effects of system calls sent from the worker thread to the UI thread.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum SystemUpdate {
-    UpdateMessages(Vec<String>),
+    /// Text to append to the console. Only the new text is sent, and it is not
+    /// implicitly followed by a newline.
+    AppendOutput(OutputChannel, String),
+    ClearOutput,
+    UpdateHeap(Heap),
     UpdateExecuting(bool),
     UpdateInitialized(bool),
     UpdateSpeed(u32),
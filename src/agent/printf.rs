@@ -0,0 +1,233 @@
+//! Formatting for the printf-style syscall, along with the string reading shared by the print syscalls.
+
+use crate::emulation_core::architectures::DatapathRef;
+use crate::emulation_core::mips::gp_registers::GpRegisterType;
+use crate::emulation_core::mips::memory::Memory;
+use crate::emulation_core::riscv::registers::RiscGpRegisterType;
+
+/// Reads a null-terminated string starting at the given address. Reading stops at the first
+/// null byte or at the end of memory.
+pub fn read_null_terminated_string(memory: &Memory, address: u64) -> Result<String, String> {
+    let bytes = memory
+        .memory
+        .iter()
+        .skip(address as usize)
+        .take_while(|byte| **byte != 0)
+        .copied()
+        .collect();
+
+    String::from_utf8(bytes).map_err(|_| "Attempted to print invalid string".to_string())
+}
+
+/// The source of the values consumed by the conversions in a format string. Values are taken
+/// from the argument registers first, then from consecutive 64-bit slots starting at the
+/// stack pointer.
+pub struct PrintfArguments<'a> {
+    registers: Vec<u64>,
+    memory: &'a Memory,
+    stack_pointer: u64,
+    consumed: usize,
+}
+
+impl<'a> PrintfArguments<'a> {
+    pub fn new(registers: Vec<u64>, memory: &'a Memory, stack_pointer: u64) -> Self {
+        Self {
+            registers,
+            memory,
+            stack_pointer,
+            consumed: 0,
+        }
+    }
+
+    /// Returns the next argument. Stack slots that cannot be read are treated as 0.
+    fn next_argument(&mut self) -> u64 {
+        let argument = match self.registers.get(self.consumed) {
+            Some(value) => *value,
+            None => {
+                let slot = (self.consumed - self.registers.len()) as u64;
+                self.memory
+                    .load_double_word(self.stack_pointer + slot * 8)
+                    .unwrap_or(0)
+            }
+        };
+        self.consumed += 1;
+        argument
+    }
+}
+
+/// Formats the string at `format_address` using the arguments of the given datapath.
+///
+/// On MIPS, the syscall number and format string occupy `$a0` and `$a1`, so arguments start at
+/// `$a2`. On RISC-V, they occupy `a0` and `a1`, so arguments start at `a2`.
+pub fn printf(datapath: DatapathRef, format_address: u64) -> Result<String, String> {
+    let (registers, memory, stack_pointer) = match datapath {
+        DatapathRef::MIPS(datapath) => (
+            vec![
+                datapath.registers[GpRegisterType::A2],
+                datapath.registers[GpRegisterType::A3],
+            ],
+            &datapath.memory,
+            datapath.registers[GpRegisterType::Sp],
+        ),
+        DatapathRef::RISCV(datapath) => (
+            [
+                RiscGpRegisterType::X12,
+                RiscGpRegisterType::X13,
+                RiscGpRegisterType::X14,
+                RiscGpRegisterType::X15,
+                RiscGpRegisterType::X16,
+                RiscGpRegisterType::X17,
+            ]
+            .iter()
+            .map(|register| datapath.registers[*register])
+            .collect(),
+            &datapath.memory,
+            datapath.registers[RiscGpRegisterType::X2],
+        ),
+    };
+
+    let format = read_null_terminated_string(memory, format_address)?;
+    let mut arguments = PrintfArguments::new(registers, memory, stack_pointer);
+    format_printf(&format, &mut arguments)
+}
+
+/// Expands a printf-style format string.
+///
+/// Each conversion has the form `%[flags][width][.precision][l]conversion`, where the flags are
+/// `-` (left-align) and `0` (zero-pad). The supported conversions are:
+///
+/// - `%d`, `%u`, `%x`: a signed, unsigned, or hexadecimal integer. These use the lower 32 bits
+///   of the argument unless the `l` length modifier is given.
+/// - `%c`: the character in the lowest byte of the argument.
+/// - `%s`: the null-terminated string at the address in the argument.
+/// - `%f`: a float stored in the lower 32 bits of the argument.
+/// - `%lf`: a double stored in the argument.
+/// - `%%`: a literal `%`.
+pub fn format_printf(format: &str, arguments: &mut PrintfArguments) -> Result<String, String> {
+    let mut output = String::new();
+    let mut chars = format.chars().peekable();
+
+    while let Some(character) = chars.next() {
+        if character != '%' {
+            output.push(character);
+            continue;
+        }
+
+        let mut left_align = false;
+        let mut zero_pad = false;
+        while let Some(flag) = chars.next_if(|c| *c == '-' || *c == '0') {
+            match flag {
+                '-' => left_align = true,
+                _ => zero_pad = true,
+            }
+        }
+
+        let width = read_number(&mut chars).unwrap_or(0);
+        let precision = chars
+            .next_if_eq(&'.')
+            .map(|_| read_number(&mut chars).unwrap_or(0));
+        let long = chars.next_if_eq(&'l').is_some();
+
+        let conversion = chars.next().ok_or_else(|| {
+            "printf: format string ends in the middle of a conversion".to_string()
+        })?;
+
+        let (body, numeric) = match conversion {
+            '%' => {
+                output.push('%');
+                continue;
+            }
+            'd' => {
+                let argument = arguments.next_argument();
+                let value = if long {
+                    argument as i64
+                } else {
+                    argument as i32 as i64
+                };
+                let digits = pad_digits(value.unsigned_abs().to_string(), precision);
+                let sign = if value < 0 { "-" } else { "" };
+                (format!("{sign}{digits}"), true)
+            }
+            'u' => {
+                let argument = arguments.next_argument();
+                let value = if long {
+                    argument
+                } else {
+                    argument as u32 as u64
+                };
+                (pad_digits(value.to_string(), precision), true)
+            }
+            'x' => {
+                let argument = arguments.next_argument();
+                let value = if long {
+                    argument
+                } else {
+                    argument as u32 as u64
+                };
+                (pad_digits(format!("{value:x}"), precision), true)
+            }
+            'c' => ((arguments.next_argument() as u8 as char).to_string(), false),
+            's' => {
+                let address = arguments.next_argument();
+                let string = read_null_terminated_string(arguments.memory, address)?;
+                match precision {
+                    Some(precision) => (string.chars().take(precision).collect(), false),
+                    None => (string, false),
+                }
+            }
+            'f' => {
+                let argument = arguments.next_argument();
+                let value = if long {
+                    f64::from_bits(argument)
+                } else {
+                    f32::from_bits(argument as u32) as f64
+                };
+                (format!("{:.*}", precision.unwrap_or(6), value), true)
+            }
+            other => return Err(format!("printf: unsupported conversion `%{other}`")),
+        };
+
+        output.push_str(&pad(body, width, left_align, zero_pad && numeric));
+    }
+
+    Ok(output)
+}
+
+fn read_number(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<usize> {
+    let mut digits = String::new();
+    while let Some(digit) = chars.next_if(|c| c.is_ascii_digit()) {
+        digits.push(digit);
+    }
+    digits.parse().ok()
+}
+
+/// Left-pads a string of digits with zeros to the precision of an integer conversion.
+fn pad_digits(digits: String, precision: Option<usize>) -> String {
+    match precision {
+        Some(precision) if digits.len() < precision => {
+            format!("{}{digits}", "0".repeat(precision - digits.len()))
+        }
+        _ => digits,
+    }
+}
+
+/// Pads a converted value to the field width. Zero-padding is inserted after any sign.
+fn pad(body: String, width: usize, left_align: bool, zero_pad: bool) -> String {
+    let length = body.chars().count();
+    if length >= width {
+        return body;
+    }
+
+    let padding = width - length;
+    if left_align {
+        format!("{body}{}", " ".repeat(padding))
+    } else if zero_pad {
+        let (sign, rest) = match body.strip_prefix('-') {
+            Some(rest) => ("-", rest),
+            None => ("", body.as_str()),
+        };
+        format!("{sign}{}{rest}", "0".repeat(padding))
+    } else {
+        format!("{}{body}", " ".repeat(padding))
+    }
+}
@@ -1,4 +1,5 @@
 use std::collections::VecDeque;
+use std::fmt;
 
 #[derive(Default)]
 pub struct Scanner {
@@ -18,10 +19,35 @@ enum ScannerState {
     Finished,
 }
 
+/// The reason a typed read from the Scanner was rejected.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScanError {
+    /// The token is not an integer literal.
+    InvalidInt(String),
+    /// The token is not a floating-point literal.
+    InvalidFloat(String),
+    /// The token is a valid literal, but its value does not fit in the type being read.
+    OutOfRange(String),
+}
+
+impl fmt::Display for ScanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScanError::InvalidInt(token) => write!(f, "\"{token}\" is not a valid integer"),
+            ScanError::InvalidFloat(token) => write!(f, "\"{token}\" is not a valid number"),
+            ScanError::OutOfRange(token) => write!(f, "\"{token}\" is out of range"),
+        }
+    }
+}
+
 /// The Scanner is a data structure that pulls information (i.e. strings or ints) from a stream of
 /// characters. To use, feed a string into the Scanner and use the next_x() functions to pull data
 /// out. These functions are designed so they cannot fail, so any input that does not match the type
 /// trying to be read will be ignored.
+///
+/// The read_x() functions are the strict counterparts used by the read syscalls. They read a single
+/// whitespace-delimited token and either return its value or a [`ScanError`] describing why it was
+/// rejected. A rejected token discards the rest of its line so the user can be prompted again.
 impl Scanner {
     /// Constructs a new, empty scanner.
     pub fn new() -> Scanner {
@@ -161,6 +187,142 @@ pub fn next_float(&mut self) -> Option<f32> {
         self.next_double().map(|val| val as f32)
     }
 
+    /// Reads a whitespace-delimited integer token. The token may have a leading `+` or `-` sign
+    /// and may be written in decimal, hexadecimal (`0x`), or binary (`0b`). Negative values are
+    /// returned in two's complement form.
+    ///
+    /// If there are no tokens left in the unscanned input, the function will return None.
+    pub fn read_int(&mut self) -> Option<Result<u64, ScanError>> {
+        let token = self.next_token()?;
+        let result = Self::parse_int(&token);
+        if result.is_err() {
+            self.discard_line();
+        }
+        Some(result)
+    }
+
+    /// Reads a whitespace-delimited floating-point token. In addition to decimal notation, the
+    /// token may use an exponent (`-1.5e3`) or be one of `inf`, `infinity`, or `nan`, in any case
+    /// and with an optional sign.
+    ///
+    /// If there are no tokens left in the unscanned input, the function will return None.
+    pub fn read_double(&mut self) -> Option<Result<f64, ScanError>> {
+        let token = self.next_token()?;
+        let result = Self::parse_double(&token);
+        if result.is_err() {
+            self.discard_line();
+        }
+        Some(result)
+    }
+
+    /// Identical to read_double(), but it returns an f32 instead. Finite values too large to be
+    /// represented as an f32 are rejected.
+    pub fn read_float(&mut self) -> Option<Result<f32, ScanError>> {
+        let token = self.next_token()?;
+        let result = Self::parse_double(&token).and_then(|value| {
+            if value.is_finite() && value.abs() > f32::MAX as f64 {
+                Err(ScanError::OutOfRange(token.clone()))
+            } else {
+                Ok(value as f32)
+            }
+        });
+        if result.is_err() {
+            self.discard_line();
+        }
+        Some(result)
+    }
+
+    /// Parses an integer literal as accepted by read_int().
+    pub fn parse_int(token: &str) -> Result<u64, ScanError> {
+        let invalid = || ScanError::InvalidInt(token.to_string());
+
+        let (negative, unsigned) = match token.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, token.strip_prefix('+').unwrap_or(token)),
+        };
+
+        let lowercase = unsigned.to_ascii_lowercase();
+        let (radix, digits) = if let Some(digits) = lowercase.strip_prefix("0x") {
+            (16, digits)
+        } else if let Some(digits) = lowercase.strip_prefix("0b") {
+            (2, digits)
+        } else {
+            (10, lowercase.as_str())
+        };
+
+        if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+            return Err(invalid());
+        }
+
+        let magnitude = u128::from_str_radix(digits, radix)
+            .map_err(|_| ScanError::OutOfRange(token.to_string()))?;
+
+        if negative {
+            if magnitude > 1 << 63 {
+                return Err(ScanError::OutOfRange(token.to_string()));
+            }
+            Ok((magnitude as u64).wrapping_neg())
+        } else {
+            u64::try_from(magnitude).map_err(|_| ScanError::OutOfRange(token.to_string()))
+        }
+    }
+
+    /// Parses a floating-point literal as accepted by read_double().
+    pub fn parse_double(token: &str) -> Result<f64, ScanError> {
+        let unsigned = token
+            .strip_prefix(|c| c == '-' || c == '+')
+            .unwrap_or(token)
+            .to_ascii_lowercase();
+
+        let is_special = matches!(unsigned.as_str(), "inf" | "infinity" | "nan");
+        let is_numeric = unsigned.starts_with(|c: char| c.is_ascii_digit() || c == '.')
+            && unsigned
+                .chars()
+                .all(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | '+' | '-'));
+        if !is_special && !is_numeric {
+            return Err(ScanError::InvalidFloat(token.to_string()));
+        }
+
+        let value: f64 = token
+            .parse()
+            .map_err(|_| ScanError::InvalidFloat(token.to_string()))?;
+
+        // Rust rounds overly large literals to infinity, but only an explicit
+        // infinity should be accepted.
+        if value.is_infinite() && !is_special {
+            return Err(ScanError::OutOfRange(token.to_string()));
+        }
+
+        Ok(value)
+    }
+
+    /// Returns the next whitespace-delimited token, discarding any whitespace before it. If the
+    /// remainder of the unscanned input is only whitespace, it is consumed and None is returned.
+    fn next_token(&mut self) -> Option<String> {
+        while self.input.front()?.is_whitespace() {
+            self.input.pop_front();
+        }
+
+        let mut token = String::new();
+        while let Some(character) = self.input.front() {
+            if character.is_whitespace() {
+                break;
+            }
+            token.push(self.input.pop_front().unwrap());
+        }
+
+        Some(token)
+    }
+
+    /// Discards the remainder of the current line, including the newline character.
+    fn discard_line(&mut self) {
+        while let Some(character) = self.input.pop_front() {
+            if character == '\n' {
+                break;
+            }
+        }
+    }
+
     /// Returns the remainder of the current line in the Scanner. If the Scanner is empty, this
     /// function will return None.
     pub fn next_line(&mut self) -> Option<String> {
@@ -3,20 +3,36 @@
 use js_sys::Object;
 use monaco::{
     api::TextModel,
-    sys::{editor::IMarkerData, MarkerSeverity},
+    sys::{editor::IMarkerData, IDisposable, MarkerSeverity},
 };
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::rc::Rc;
+use strum::IntoEnumIterator;
 use swim::agent::datapath_communicator::DatapathCommunicator;
 use swim::agent::datapath_reducer::DatapathReducer;
 use swim::agent::EmulationCoreAgent;
+use swim::emulation_core::elf::{load_elf, write_elf, ElfExecutable, ELFDATA2LSB, ELF_MAGIC};
 use swim::emulation_core::mips::datapath::Stage;
+use swim::parser::assembly_cache::AssemblyCache;
+use swim::parser::disassembler::{
+    disassemble, disassemble_with_symbols, read_binary_image, read_hex_image, reproduces_image,
+};
+use swim::parser::image_export::{
+    export_image, Endianness, ExportOptions, ImageFormat, WORD_BYTES,
+};
+use swim::parser::lints::{all_lints, lint_program};
+use swim::parser::listing::create_listing;
 use swim::parser::parser_assembler_main::parser;
-use swim::parser::parser_structs_and_enums::ProgramInfo;
+use swim::parser::parser_structs_and_enums::{Lint, LintSeverity, ProgramInfo};
+use swim::parser::profiles::{built_in_profiles, enforce_profile, parse_profile, Profile};
 use swim::ui::footer::component::Footer;
 use swim::ui::regview::component::Regview;
 use swim::ui::swim_editor::component::SwimEditor;
+use swim::ui::swim_editor::file_tree::{
+    find_active_file, project_files, EditorFile, MAIN_FILE_NAME,
+};
 use swim::{
     emulation_core::{
         architectures::AvailableDatapaths, mips::instruction::MipsInstruction,
@@ -27,9 +43,9 @@
         swim_editor::tab::TabState,
     },
 };
-use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
 use wasm_bindgen_futures::spawn_local;
-use web_sys::HtmlInputElement;
+use web_sys::{HtmlElement, HtmlInputElement};
 use yew::prelude::*;
 use yew::{html, Html, Properties};
 
@@ -42,6 +58,25 @@
 const CONTENT_MIPS: &str = include_str!("../../static/assembly_examples/fibonacci.asm");
 const CONTENT_RISCV: &str = include_str!("../../static/assembly_examples/riscv_fib_recursive.asm");
 
+// The file the disassembly of memory, or of a loaded binary, is opened in.
+const DISASSEMBLY_FILE_NAME: &str = "disassembly.asm";
+
+// The name the assembled program is downloaded under as an ELF executable.
+const ELF_FILE_NAME: &str = "program.elf";
+
+// The addresses an ELF executable can be linked at, along with what it can be used with there.
+const ELF_BASES: [(usize, &str); 3] = [
+    (0, "Linked at 0, for readelf and objdump"),
+    (0x10000, "Linked at 0x10000, for QEMU's user mode"),
+    (0x8000_0000, "Linked at 0x80000000, for Spike"),
+];
+
+// The name the listing of the assembled program is downloaded under.
+const LISTING_FILE_NAME: &str = "program.lst";
+
+// The name exported memory images are downloaded under, before the extension of their format.
+const IMAGE_FILE_STEM: &str = "program";
+
 #[derive(Properties, Clone, PartialEq)]
 struct AppProps {
     communicator: &'static DatapathCommunicator,
@@ -59,6 +94,9 @@ fn app(props: &AppProps) -> Html {
     // when the text model changes.
     //let text_model = use_mut_ref(|| TextModel::create(&code, Some(&language), None).unwrap());
     let text_model = use_state_eq(|| TextModel::create(CONTENT_MIPS, Some("mips"), None).unwrap());
+    // The files of the project other than the main file, and the file that is open in the editor
+    let editor_files = use_state_eq(Vec::<EditorFile>::new);
+    let active_file = use_state_eq(|| 0usize);
 
     // Store the currently executed line in code editor and hex editor
     let editor_curr_line = use_state_eq(|| 0.0);
@@ -79,6 +117,12 @@ fn app(props: &AppProps) -> Html {
     let binary_ref = use_mut_ref(Vec::<u32>::new);
     let labels_ref = use_mut_ref(HashMap::<String, usize>::new);
 
+    // The project as it was last assembled, which is only assembled again once its source changes.
+    let assembly_cache = use_mut_ref(AssemblyCache::default);
+    // Whether the source has been edited since it was last assembled. While it has, the lines highlighted when
+    // stepping through the program are those of the source as it was assembled.
+    let source_changed = use_state_eq(|| false);
+
     let memory_text_model =
         use_state_eq(|| TextModel::create(&memory_text_output, Some("ini"), None).unwrap());
 
@@ -94,6 +138,14 @@ fn app(props: &AppProps) -> Html {
 
     let parser_found_errors = use_state(|| false);
 
+    // The lints checked after assembling, which can each be turned off.
+    let enabled_lints = use_state_eq(all_lints);
+
+    // The instruction-set profiles that can be chosen, including those loaded from a file, and the one programs are
+    // assembled with. Instructions the profile does not allow are errors.
+    let profiles = use_state_eq(built_in_profiles);
+    let active_profile = use_state_eq(Profile::default);
+
     // Start listening for messages from the communicator. This effectively links the worker thread to the main thread
     // and will force updates whenever its internal state changes.
     {
@@ -121,9 +173,54 @@ fn app(props: &AppProps) -> Html {
         (datapath_state.current_architecture, text_model.clone()),
     );
 
+    // Watch every file of the project for edits to tell whether the source has changed since it was assembled.
+    {
+        let assembly_cache = Rc::clone(&assembly_cache);
+        let source_changed = source_changed.clone();
+        use_effect_with_deps(
+            move |(text_model, editor_files, current_architecture)| {
+                let on_change = {
+                    let text_model = text_model.clone();
+                    let editor_files = editor_files.clone();
+                    let current_architecture = *current_architecture;
+                    Closure::wrap(Box::new(move |_: JsValue| {
+                        let assembly_cache = assembly_cache.borrow();
+                        source_changed.set(
+                            assembly_cache.assembly().is_some()
+                                && !assembly_cache.is_current(
+                                    &project_files(&text_model, &editor_files),
+                                    current_architecture,
+                                ),
+                        );
+                    }) as Box<dyn FnMut(JsValue)>)
+                };
+                let disposables: Vec<IDisposable> = std::iter::once(&**text_model)
+                    .chain(editor_files.iter().map(|file| &file.model))
+                    .map(|model| {
+                        model
+                            .as_ref()
+                            .on_did_change_content(on_change.as_ref().unchecked_ref())
+                    })
+                    .collect();
+                move || {
+                    for disposable in &disposables {
+                        disposable.dispose();
+                    }
+                    drop(on_change);
+                }
+            },
+            (
+                text_model.clone(),
+                editor_files.clone(),
+                datapath_state.current_architecture,
+            ),
+        );
+    }
+
     // This is where code is assembled and loaded into the emulation core's memory.
     let on_assemble_clicked = {
         let text_model = text_model.clone();
+        let editor_files = editor_files.clone();
         let memory_curr_instr = memory_curr_instr.clone();
         let datapath_state = datapath_state.clone();
         let parser_text_output = parser_text_output.clone();
@@ -132,93 +229,130 @@ fn app(props: &AppProps) -> Html {
         let breakpoints = breakpoints.clone();
         let communicator = props.communicator;
         let parser_found_errors = parser_found_errors.clone();
+        let enabled_lints = enabled_lints.clone();
+        let active_profile = active_profile.clone();
+        let source_changed = source_changed.clone();
 
         // Clone the value before moving it into the closure
         let pc_limit = pc_limit.clone();
         let program_info_ref = Rc::clone(&program_info_ref);
         let binary_ref = Rc::clone(&binary_ref);
         let labels_ref = Rc::clone(&labels_ref);
+        let assembly_cache = Rc::clone(&assembly_cache);
 
         use_callback(
             move |_,
                   (
                 text_model,
+                editor_files,
                 editor_curr_line,
                 memory_curr_instr,
                 datapath_state,
                 parser_found_errors,
+                enabled_lints,
+                active_profile,
             )| {
                 let text_model = text_model.clone();
                 // parses through the code to assemble the binary and retrieves programinfo for error marking and mouse hover
-                let (program_info, assembled, labels) =
-                    parser(text_model.get_value(), datapath_state.current_architecture);
+                let (mut program_info, assembled, labels) = assemble_project(
+                    &assembly_cache,
+                    &text_model,
+                    &editor_files,
+                    datapath_state.current_architecture,
+                );
+                enforce_profile(
+                    &mut program_info,
+                    active_profile,
+                    datapath_state.current_architecture,
+                );
+                lint_program(
+                    &mut program_info,
+                    &labels,
+                    datapath_state.current_architecture,
+                    enabled_lints,
+                );
                 *program_info_ref.borrow_mut() = program_info.clone();
                 *binary_ref.borrow_mut() = assembled.clone();
                 *labels_ref.borrow_mut() = labels.clone();
                 pc_limit.set(assembled.len() * 4);
-                parser_text_output.set(program_info.console_out_post_assembly);
-
-                let mut markers: Vec<IMarkerData> = vec![];
-
-                // Parse output from parser and create an instance of IMarkerData for each error.
-                for (line_number, line_information) in
-                    program_info.monaco_line_info.iter().enumerate()
-                {
-                    for error in &line_information.errors {
-                        let new_marker: IMarkerData = new_object().into();
-                        new_marker.set_message(&error.message);
-                        new_marker.set_severity(MarkerSeverity::Error);
-                        new_marker.set_start_line_number((line_number + 1) as f64);
-                        new_marker.set_start_column((error.start_end_columns.0 + 1) as f64);
-                        new_marker.set_end_line_number((line_number + 1) as f64);
-                        new_marker.set_end_column((error.start_end_columns.1 + 1) as f64);
-                        markers.push(new_marker);
+                parser_text_output.set(program_info.console_out_post_assembly.clone());
+
+                // Every file of the project gets the markers for the errors and warnings on its own lines.
+                let file_models = std::iter::once((MAIN_FILE_NAME, &*text_model)).chain(
+                    editor_files
+                        .iter()
+                        .map(|file| (file.name.as_str(), &file.model)),
+                );
+                let mut found_errors = false;
+                for (file_name, model) in file_models {
+                    // Parse output from parser and create an instance of IMarkerData for each error.
+                    let marker_jsarray = js_sys::Array::new();
+                    for line_information in program_info.lines_of_file(file_name) {
+                        let line_number = line_information
+                            .source
+                            .as_ref()
+                            .map_or(line_information.line_number, |source| source.line);
+                        for error in &line_information.errors {
+                            let new_marker: IMarkerData = new_object().into();
+                            new_marker.set_message(&error.message);
+                            new_marker.set_severity(MarkerSeverity::Error);
+                            new_marker.set_start_line_number((line_number + 1) as f64);
+                            new_marker.set_start_column((error.start_end_columns.0 + 1) as f64);
+                            new_marker.set_end_line_number((line_number + 1) as f64);
+                            new_marker.set_end_column((error.start_end_columns.1 + 1) as f64);
+                            marker_jsarray.push(&new_marker);
+                            found_errors = true;
+                        }
+                        // Warnings are marked without stopping the program from being loaded.
+                        for warning in &line_information.warnings {
+                            let new_marker: IMarkerData = new_object().into();
+                            new_marker.set_message(&warning.message);
+                            new_marker.set_severity(match warning.lint.severity() {
+                                LintSeverity::Warning => MarkerSeverity::Warning,
+                                LintSeverity::Info => MarkerSeverity::Info,
+                            });
+                            new_marker.set_start_line_number((line_number + 1) as f64);
+                            new_marker.set_start_column((warning.start_end_columns.0 + 1) as f64);
+                            new_marker.set_end_line_number((line_number + 1) as f64);
+                            new_marker.set_end_column((warning.start_end_columns.1 + 1) as f64);
+                            marker_jsarray.push(&new_marker);
+                        }
                     }
-                }
 
-                // Convert Vec<IMarkerData> to Javascript array
-                let marker_jsarray = js_sys::Array::new();
-                for marker in markers {
-                    marker_jsarray.push(&marker);
+                    monaco::sys::editor::set_model_markers(
+                        model.as_ref(),
+                        "owner",
+                        &marker_jsarray,
+                    );
                 }
 
-                monaco::sys::editor::set_model_markers(
-                    text_model.as_ref(),
-                    "owner",
-                    &marker_jsarray,
-                );
-
                 // Reset highlighted line to 0
                 editor_curr_line.set(0.0);
 
-                // Proceed with loading into memory and expand pseudo-instructions if there are no errors.
-                if marker_jsarray.length() == 0 {
+                // Proceed with loading into memory if there are no errors. The source is left as it was
+                // written, and the source map shows what each pseudo-instruction expanded to.
+                if !found_errors {
                     // Send the binary over to the emulation core thread
                     communicator.initialize(program_info.pc_starting_point, assembled);
                     memory_curr_instr.set(datapath_state.get_pc());
                     breakpoints.set(HashSet::default());
-
-                    text_model.set_value(&program_info.updated_monaco_string); // Expands pseudo-instructions to their hardware counterpart.
-
-                    // After adding pseudo instructions, update program info
-                    let (program_info, assembled, labels) =
-                        parser(text_model.get_value(), datapath_state.current_architecture);
-                    *program_info_ref.borrow_mut() = program_info.clone();
-                    *binary_ref.borrow_mut() = assembled.clone();
-                    *labels_ref.borrow_mut() = labels.clone();
                     parser_found_errors.set(false);
                 } else {
                     parser_found_errors.set(true);
                 }
+                source_changed.set(false);
 
                 trigger.force_update();
             },
             (
                 text_model,
+                editor_files,
                 editor_curr_line,
                 memory_curr_instr,
                 datapath_state,
                 parser_found_errors,
+                enabled_lints,
+                active_profile,
             ),
         )
     };
@@ -229,7 +363,6 @@ fn app(props: &AppProps) -> Html {
     // code, the previously executed line is highlighted.
     let on_execute_clicked = {
         let datapath_state = datapath_state.clone();
-        let text_model = text_model.clone();
 
         // Code editor
         let editor_curr_line = editor_curr_line.clone();
@@ -239,24 +372,17 @@ fn app(props: &AppProps) -> Html {
         let communicator = props.communicator;
 
         let program_info_ref = Rc::clone(&program_info_ref);
-        let binary_ref = Rc::clone(&binary_ref);
-        let labels_ref = Rc::clone(&labels_ref);
 
         use_callback(
-            move |_, (editor_curr_line, memory_curr_instr, text_model, datapath_state)| {
-                // Get the current line and convert it to f64
-                let (program_info, assembled, labels) =
-                    parser(text_model.get_value(), datapath_state.current_architecture);
-                *program_info_ref.borrow_mut() = program_info.clone();
-                *binary_ref.borrow_mut() = assembled.clone();
-                *labels_ref.borrow_mut() = labels.clone();
-
-                let list_of_line_numbers = program_info.address_to_line_number;
+            move |_, (editor_curr_line, memory_curr_instr, datapath_state)| {
+                // Highlight the line of the program as it was assembled, even if it has since been edited
                 let index = datapath_state.get_pc() as usize / 4;
-                editor_curr_line.set(match list_of_line_numbers.get(index) {
-                    Some(val) => (val + 1) as f64,
-                    None => 0f64,
-                });
+                editor_curr_line.set(
+                    match program_info_ref.borrow().address_to_line_number.get(index) {
+                        Some(val) => (val + 1) as f64,
+                        None => 0f64,
+                    },
+                );
                 memory_curr_instr.set(datapath_state.get_pc());
 
                 // Execute instruction
@@ -264,18 +390,12 @@ fn app(props: &AppProps) -> Html {
 
                 trigger.force_update();
             },
-            (
-                editor_curr_line,
-                memory_curr_instr,
-                text_model,
-                datapath_state,
-            ),
+            (editor_curr_line, memory_curr_instr, datapath_state),
         )
     };
 
     let on_execute_stage_clicked = {
         let datapath_state = datapath_state.clone();
-        let text_model = text_model.clone();
         let communicator = props.communicator;
 
         // Code editor
@@ -287,11 +407,9 @@ fn app(props: &AppProps) -> Html {
         let trigger = use_force_update();
 
         let program_info_ref = Rc::clone(&program_info_ref);
-        let binary_ref = Rc::clone(&binary_ref);
-        let labels_ref = Rc::clone(&labels_ref);
 
         use_callback(
-            move |_, (editor_curr_line, memory_curr_instr, text_model, datapath_state)| {
+            move |_, (editor_curr_line, memory_curr_instr, datapath_state)| {
                 let is_instruction_decode = match datapath_state.current_architecture {
                     AvailableDatapaths::MIPS => {
                         datapath_state.mips.current_stage == Stage::InstructionDecode
@@ -303,20 +421,13 @@ fn app(props: &AppProps) -> Html {
 
                 if is_instruction_decode {
                     // highlight on InstructionDecode since syscall stops at that stage.
-
-                    // highlight on InstructionDecode since syscall stops at that stage.
-                    let (program_info, assembled, labels) =
-                        parser(text_model.get_value(), datapath_state.current_architecture);
-                    *program_info_ref.borrow_mut() = program_info.clone();
-                    *binary_ref.borrow_mut() = assembled.clone();
-                    *labels_ref.borrow_mut() = labels.clone();
-
-                    let list_of_line_numbers = program_info.address_to_line_number;
                     let index = datapath_state.get_pc() as usize / 4;
-                    editor_curr_line.set(match list_of_line_numbers.get(index) {
-                        Some(val) => (val + 1) as f64,
-                        None => 0f64,
-                    });
+                    editor_curr_line.set(
+                        match program_info_ref.borrow().address_to_line_number.get(index) {
+                            Some(val) => (val + 1) as f64,
+                            None => 0f64,
+                        },
+                    );
                     memory_curr_instr.set(datapath_state.get_pc());
                     communicator.execute_stage();
                 } else {
@@ -325,35 +436,17 @@ fn app(props: &AppProps) -> Html {
 
                 trigger.force_update();
             },
-            (
-                editor_curr_line,
-                memory_curr_instr,
-                text_model,
-                datapath_state,
-            ),
+            (editor_curr_line, memory_curr_instr, datapath_state),
         )
     };
 
     let on_continue_execution = {
         let communicator = props.communicator;
-        let program_info_ref = Rc::clone(&program_info_ref);
-        let binary_ref = Rc::clone(&binary_ref);
-        let labels_ref = Rc::clone(&labels_ref);
-
-        let text_model = text_model.clone();
-
-        let datapath_state = datapath_state.clone();
         use_callback(
-            move |_, (text_model, datapath_state)| {
-                let (program_info, assembled, labels) =
-                    parser(text_model.get_value(), datapath_state.current_architecture);
-                *program_info_ref.borrow_mut() = program_info.clone();
-                *binary_ref.borrow_mut() = assembled.clone();
-                *labels_ref.borrow_mut() = labels.clone();
-
+            move |_, _| {
                 communicator.execute();
             },
-            (text_model, datapath_state),
+            (),
         )
     };
 
@@ -370,6 +463,7 @@ fn app(props: &AppProps) -> Html {
     let on_memory_clicked = {
         // Code editor
         let text_model = text_model.clone();
+        let editor_files = editor_files.clone();
 
         // Hex editor
         let memory_text_model = memory_text_model.clone();
@@ -380,9 +474,11 @@ fn app(props: &AppProps) -> Html {
 
         let program_info_ref = Rc::clone(&program_info_ref);
         let labels_ref = Rc::clone(&labels_ref);
+        let assembly_cache = Rc::clone(&assembly_cache);
+        let source_changed = source_changed.clone();
 
         use_callback(
-            move |_, datapath_state| {
+            move |_, (datapath_state, editor_files)| {
                 let text_model = text_model.clone();
 
                 // Update memory
@@ -449,18 +545,42 @@ fn app(props: &AppProps) -> Html {
                             }
                         }
                         // Memory updated successfully
-                        let (program_info, _assembled, _labels) =
-                            parser(text_model.get_value(), datapath_state.current_architecture);
+                        let (program_info, _assembled, _labels) = assemble_project(
+                            &assembly_cache,
+                            &text_model,
+                            editor_files,
+                            datapath_state.current_architecture,
+                        );
                         let mut lines_beyond_counter = program_info.address_to_line_number.len();
-                        let mut curr_value = text_model.get_value();
-                        let mut add_new_lines = false;
+                        let mut appended_lines = Vec::new();
+                        // A word of a pseudo-instruction can only be changed by writing out what it
+                        // expanded to, so its line is replaced with every instruction of the expansion.
+                        let changed_words: HashMap<usize, String> = changed_lines
+                            .iter()
+                            .map(|line| (line.line_number, line.text.clone()))
+                            .collect();
+                        let mut edited_lines = HashSet::new();
+                        // The edits are made together so that a line replaced by several does not
+                        // move the lines of the other edits.
+                        let curr_model = text_model.as_ref();
+                        let edit_operations_array = js_sys::Array::new();
+                        let before_cursor_state_array = js_sys::Array::new();
                         for line in changed_lines {
                             // Check if we're updating or appending instruction
                             if line.line_number < program_info.address_to_line_number.len() {
-                                let updated_line =
-                                    program_info.address_to_line_number[line.line_number] as f64
-                                        + 1.0;
-                                let curr_model = text_model.as_ref();
+                                let program_line =
+                                    program_info.address_to_line_number[line.line_number];
+                                // Only the main file is edited, since the instruction may come from a library
+                                let Some(source) = program_info.source_location(program_line)
+                                else {
+                                    continue;
+                                };
+                                if source.file != MAIN_FILE_NAME
+                                    || !edited_lines.insert(program_line)
+                                {
+                                    continue;
+                                }
+                                let updated_line = source.line as f64 + 1.0;
 
                                 // Get the current line's contents in the code editor
                                 let line_to_replace = curr_model.get_line_content(updated_line);
@@ -473,6 +593,30 @@ fn app(props: &AppProps) -> Html {
                                         break;
                                     }
                                 }
+                                let indent: String = line_to_replace
+                                    .chars()
+                                    .take_while(|c| c.is_whitespace())
+                                    .collect();
+                                let expansion: Vec<String> = program_info
+                                    .source_map
+                                    .iter()
+                                    .filter(|entry| {
+                                        entry.line_number == program_line
+                                            && entry.expanded_from.is_some()
+                                    })
+                                    .map(|entry| {
+                                        changed_words
+                                            .get(&(entry.address / 4))
+                                            .cloned()
+                                            .unwrap_or_else(|| entry.instruction.clone())
+                                    })
+                                    .collect();
+                                let text = if expansion.is_empty() {
+                                    line.text
+                                } else {
+                                    expansion.join(&format!("\n{indent}"))
+                                };
+
                                 let edit_range = monaco::sys::Range::new(
                                     updated_line,
                                     start_line_column,
@@ -488,32 +632,31 @@ fn app(props: &AppProps) -> Html {
                                 // Create the edit operation using the range and new text
                                 let edit_operations: monaco::sys::editor::IIdentifiedSingleEditOperation = Object::new().unchecked_into();
                                 edit_operations.set_range(&edit_range);
-                                edit_operations.set_text(Some(&line.text));
+                                edit_operations.set_text(Some(&text));
                                 // Append it to JavaScript Array
-                                let edit_operations_array = js_sys::Array::new();
                                 edit_operations_array.push(&edit_operations);
-                                let before_cursor_state_array = js_sys::Array::new();
                                 before_cursor_state_array.push(&before_cursor_state);
-                                // Do the edit!
-                                curr_model.push_edit_operations(
-                                    &before_cursor_state_array,
-                                    &edit_operations_array,
-                                    None,
-                                );
                             } else if line.line_number == lines_beyond_counter {
                                 // Append instruction
-                                if !add_new_lines {
-                                    // If we've added new lines already,
-                                    // start adding new lines by getting a copy of the current text model to append to
-                                    add_new_lines = true;
-                                    curr_value = text_model.get_value();
-                                }
-                                curr_value.push('\n');
-                                curr_value.push_str(&line.text);
+                                appended_lines.push(line.text);
                                 lines_beyond_counter += 1;
                             }
                         }
-                        if add_new_lines {
+                        // Do the edits!
+                        if edit_operations_array.length() > 0 {
+                            curr_model.push_edit_operations(
+                                &before_cursor_state_array,
+                                &edit_operations_array,
+                                None,
+                            );
+                        }
+                        if !appended_lines.is_empty() {
+                            // Start adding new lines by getting a copy of the edited text model to append to
+                            let mut curr_value = text_model.get_value();
+                            for text in appended_lines {
+                                curr_value.push('\n');
+                                curr_value.push_str(&text);
+                            }
                             text_model.set_value(&curr_value);
                         }
                     }
@@ -523,13 +666,19 @@ fn app(props: &AppProps) -> Html {
                 }
 
                 // Update the parsed info for text and data segment views
-                let (program_info, _, _) =
-                    parser(text_model.get_value(), datapath_state.current_architecture);
+                let (program_info, _, _) = assemble_project(
+                    &assembly_cache,
+                    &text_model,
+                    editor_files,
+                    datapath_state.current_architecture,
+                );
                 *program_info_ref.borrow_mut() = program_info;
+                // The source was rewritten to match memory, so it is the program being run
+                source_changed.set(false);
 
                 trigger.force_update();
             },
-            datapath_state,
+            (datapath_state, editor_files),
         )
     };
 
@@ -548,6 +697,8 @@ fn app(props: &AppProps) -> Html {
         // Hex editor
         let memory_curr_instr = memory_curr_instr.clone();
 
+        let source_changed = source_changed.clone();
+
         use_callback(
             move |_, (editor_curr_line, program_info_ref, binary_ref, labels_ref)| {
                 // Set highlighted line to 0
@@ -559,6 +710,7 @@ fn app(props: &AppProps) -> Html {
                 *program_info_ref.borrow_mut() = ProgramInfo::default();
                 *binary_ref.borrow_mut() = vec![];
                 *labels_ref.borrow_mut() = HashMap::<String, usize>::new();
+                source_changed.set(false);
 
                 communicator.reset();
                 breakpoints.set(HashSet::default());
@@ -585,13 +737,184 @@ fn app(props: &AppProps) -> Html {
     // This is the callback to get the file's contents and load it onto the Editor
     let file_picked_callback = {
         let text_model = text_model.clone();
+        let parser_text_output = parser_text_output.clone();
+        let parser_found_errors = parser_found_errors.clone();
+        let communicator = props.communicator;
+        let breakpoints = breakpoints.clone();
+        let source_changed = source_changed.clone();
+        let pc_limit = pc_limit.clone();
+        let program_info_ref = Rc::clone(&program_info_ref);
+        let binary_ref = Rc::clone(&binary_ref);
+        let labels_ref = Rc::clone(&labels_ref);
+        let active_file = active_file.clone();
         use_callback(
-            move |e: Event, _| {
+            move |e: Event, (current_architecture, editor_files)| {
                 let text_model = text_model.clone();
+                let parser_text_output = parser_text_output.clone();
+                let current_architecture = *current_architecture;
+
+                // The disassembly of a loaded binary is opened in its own file and assembled, so
+                // the assembled view, the labels, and the breakpoints are those of the loaded
+                // program. The source the user wrote is left as it was.
+                let load_image = {
+                    let editor_files = editor_files.clone();
+                    let active_file = active_file.clone();
+                    let parser_text_output = parser_text_output.clone();
+                    let parser_found_errors = parser_found_errors.clone();
+                    let breakpoints = breakpoints.clone();
+                    let source_changed = source_changed.clone();
+                    let pc_limit = pc_limit.clone();
+                    let program_info_ref = Rc::clone(&program_info_ref);
+                    let binary_ref = Rc::clone(&binary_ref);
+                    let labels_ref = Rc::clone(&labels_ref);
+                    move |source: String,
+                          entry: usize,
+                          image: Vec<u32>,
+                          symbols: &HashMap<String, usize>,
+                          message: String| {
+                        open_disassembly(
+                            &source,
+                            &editor_files,
+                            &active_file,
+                            current_architecture,
+                        );
+                        let (mut program_info, assembled, mut labels) =
+                            parser(source, current_architecture);
+                        let found_errors = program_info
+                            .monaco_line_info
+                            .iter()
+                            .any(|line| !line.errors.is_empty());
+
+                        // The assembled disassembly is only given to the views, and loaded, if it
+                        // is the same program as the image. Otherwise the image is loaded without
+                        // its source.
+                        let (image, message) = if !found_errors
+                            && reproduces_image(&assembled, &image)
+                        {
+                            // Symbols that could not be written as labels of the disassembly
+                            // are still given to the views.
+                            labels.extend(symbols.clone());
+                            (assembled, message)
+                        } else {
+                            program_info = ProgramInfo::default();
+                            labels = symbols.clone();
+                            (
+                                image,
+                                format!("{message} Its disassembly does not assemble back to the same words, so it is loaded without its source."),
+                            )
+                        };
+                        program_info.pc_starting_point = entry;
+                        *program_info_ref.borrow_mut() = program_info;
+                        *binary_ref.borrow_mut() = image.clone();
+                        *labels_ref.borrow_mut() = labels;
+                        pc_limit.set(image.len() * 4);
+                        parser_text_output.set(message);
+                        communicator.initialize(entry, image);
+                        breakpoints.set(HashSet::default());
+                        parser_found_errors.set(found_errors);
+                        source_changed.set(false);
+                    }
+                };
                 let input: HtmlInputElement = e.target_unchecked_into();
                 // gloo making the code readable and easy to implement
                 let filelist = FileList::from(input.files().unwrap());
                 let file = filelist.first().unwrap();
+                let name = file.name().to_lowercase();
+
+                // Binaries are loaded straight into the datapath, and their disassembly is
+                // shown in the editor so they can be read and stepped through. Files without an
+                // extension are checked for an ELF header, since executables usually have none.
+                let is_binary = name.ends_with(".bin")
+                    || name.ends_with(".hex")
+                    || name.ends_with(".elf")
+                    || !name.contains('.');
+                if is_binary {
+                    let bytes = gloo::file::futures::read_as_bytes(file);
+                    spawn_local(async move {
+                        let Ok(bytes) = bytes.await else {
+                            parser_text_output.set(format!("{name} could not be read."));
+                            return;
+                        };
+                        if bytes.starts_with(&ELF_MAGIC) || name.ends_with(".elf") {
+                            match load_elf(&bytes) {
+                                Ok(elf) if elf.architecture != current_architecture => {
+                                    parser_text_output.set(format!(
+                                        "{name} is a {} executable. Switch the architecture to {} to load it.",
+                                        elf.architecture, elf.architecture
+                                    ));
+                                }
+                                Ok(elf) => {
+                                    // Memory is big-endian, so only the instructions of a
+                                    // little-endian file are reordered, which leaves the bytes of
+                                    // the words in its data reversed.
+                                    let byte_order_note = if bytes[5] == ELFDATA2LSB {
+                                        " Its data is little-endian, so strings read as written but the bytes of each word of data are reversed."
+                                    } else {
+                                        ""
+                                    };
+                                    let base_note = if elf.base != 0 {
+                                        format!(
+                                            " It was linked at 0x{:x} and is moved down to address 0, so any address it builds itself with lui still points where it was linked.",
+                                            elf.base
+                                        )
+                                    } else {
+                                        String::new()
+                                    };
+                                    let message = format!(
+                                        "Loaded {} words and {} symbols from {name}, starting at 0x{:x}.{byte_order_note}{base_note}",
+                                        elf.words.len(),
+                                        elf.symbols.len(),
+                                        elf.entry
+                                    );
+                                    // The symbols become labels of the disassembly, so they are
+                                    // shown in the assembled view and can be used for breakpoints.
+                                    load_image(
+                                        disassemble_with_symbols(
+                                            &elf.words,
+                                            elf.data_start,
+                                            &elf.symbols,
+                                            current_architecture,
+                                        ),
+                                        elf.entry,
+                                        elf.words,
+                                        &elf.symbols,
+                                        message,
+                                    );
+                                }
+                                Err(message) => parser_text_output.set(message),
+                            }
+                            return;
+                        }
+                        if !name.ends_with(".bin") && !name.ends_with(".hex") {
+                            match String::from_utf8(bytes) {
+                                Ok(contents) => text_model.set_value(&contents),
+                                Err(_) => parser_text_output
+                                    .set(format!("{name} is neither text nor an ELF file.")),
+                            }
+                            return;
+                        }
+                        let image = if name.ends_with(".bin") {
+                            read_binary_image(&bytes)
+                        } else {
+                            read_hex_image(&String::from_utf8_lossy(&bytes))
+                        };
+                        match image {
+                            Ok(image) => {
+                                let message = format!("Loaded {} words from {name}.", image.len());
+                                load_image(
+                                    disassemble(&image, None, current_architecture),
+                                    0,
+                                    image,
+                                    &HashMap::new(),
+                                    message,
+                                );
+                            }
+                            Err(message) => parser_text_output.set(message),
+                        }
+                    });
+                    return;
+                }
+
                 let contents = gloo::file::futures::read_as_text(file);
                 spawn_local(async move {
                     let contents = contents.await;
@@ -601,14 +924,216 @@ fn app(props: &AppProps) -> Html {
                     text_model.set_value(&contents);
                 })
             },
+            (datapath_state.current_architecture, editor_files.clone()),
+        )
+    };
+
+    // Disassembles the current memory into a new file of the project, so the program
+    // that is loaded can be read as source even if it was loaded from a binary.
+    let on_disassemble_clicked = {
+        let editor_files = editor_files.clone();
+        let active_file = active_file.clone();
+        let datapath_state = datapath_state.clone();
+        let program_info_ref = Rc::clone(&program_info_ref);
+        let binary_ref = Rc::clone(&binary_ref);
+        use_callback(
+            move |_, (editor_files, datapath_state)| {
+                let image = read_binary_image(&datapath_state.get_memory().memory)
+                    .expect("memory should be no larger than its capacity");
+                let data_start = if binary_ref.borrow().is_empty() {
+                    None
+                } else {
+                    Some(program_info_ref.borrow().data_starting_point)
+                };
+                let source = disassemble(&image, data_start, datapath_state.current_architecture);
+                open_disassembly(
+                    &source,
+                    editor_files,
+                    &active_file,
+                    datapath_state.current_architecture,
+                );
+            },
+            (editor_files, datapath_state),
+        )
+    };
+
+    // Writes the assembled program as an ELF executable linked at the base chosen beside the
+    // button and downloads it, so it can be examined with readelf and objdump or run by QEMU or Spike.
+    let elf_base = use_state_eq(|| 0);
+    let on_elf_base_changed = {
+        let elf_base = elf_base.clone();
+        Callback::from(move |event: Event| {
+            let target = event.target().unwrap().unchecked_into::<HtmlInputElement>();
+            elf_base.set(target.value().parse().unwrap_or(0));
+        })
+    };
+    let on_download_elf_clicked = {
+        let program_info_ref = Rc::clone(&program_info_ref);
+        let binary_ref = Rc::clone(&binary_ref);
+        let labels_ref = Rc::clone(&labels_ref);
+        let parser_text_output = parser_text_output.clone();
+        use_callback(
+            move |_, (current_architecture, base)| {
+                let mut executable = ElfExecutable::from_assembly(
+                    &program_info_ref.borrow(),
+                    &binary_ref.borrow(),
+                    &labels_ref.borrow(),
+                    *current_architecture,
+                );
+                executable.base = *base;
+                match write_elf(&executable) {
+                    Ok(bytes) => {
+                        download_file(ELF_FILE_NAME, &bytes);
+                        parser_text_output.set(if *base == 0 {
+                            format!(
+                                "Downloaded {ELF_FILE_NAME}. Its text is linked at address 0, so QEMU's user mode \
+                                 and Spike cannot run it, but it can be examined with readelf and objdump."
+                            )
+                        } else {
+                            format!("Downloaded {ELF_FILE_NAME}, linked at 0x{base:x}.")
+                        });
+                    }
+                    Err(message) => parser_text_output.set(message),
+                }
+            },
+            (datapath_state.current_architecture, *elf_base),
+        )
+    };
+
+    // Downloads the listing of the assembled program, which gives the address and words of
+    // every line along with a table of its labels.
+    let on_download_listing_clicked = {
+        let program_info_ref = Rc::clone(&program_info_ref);
+        let binary_ref = Rc::clone(&binary_ref);
+        let labels_ref = Rc::clone(&labels_ref);
+        use_callback(
+            move |_, _| {
+                let listing = create_listing(
+                    &program_info_ref.borrow(),
+                    &binary_ref.borrow(),
+                    &labels_ref.borrow(),
+                );
+                download_file(LISTING_FILE_NAME, listing.as_bytes());
+            },
             (),
         )
     };
 
+    // Exports the assembled program as a memory image for a hardware description language
+    // or logic simulator, in the format, endianness, and word width chosen beside the button.
+    let export_options = use_state_eq(ExportOptions::default);
+    let on_export_option_changed = {
+        let export_options = export_options.clone();
+        Callback::from(move |event: Event| {
+            let target = event.target().unwrap().unchecked_into::<HtmlInputElement>();
+            let value = target.value();
+            let mut options = *export_options;
+            match target.name().as_str() {
+                "format" => options.format = value.parse().unwrap_or_default(),
+                "endianness" => options.endianness = value.parse().unwrap_or_default(),
+                _ => options.word_bytes = value.parse().unwrap_or(4),
+            }
+            export_options.set(options);
+        })
+    };
+    let on_export_image_clicked = {
+        let binary_ref = Rc::clone(&binary_ref);
+        let parser_text_output = parser_text_output.clone();
+        use_callback(
+            move |_, export_options| match export_image(&binary_ref.borrow(), **export_options) {
+                Ok(image) => download_file(
+                    &format!("{IMAGE_FILE_STEM}.{}", export_options.format.extension()),
+                    image.as_bytes(),
+                ),
+                Err(message) => parser_text_output.set(message),
+            },
+            export_options.clone(),
+        )
+    };
+
+    // Turns a lint on or off, which takes effect the next time the program is assembled.
+    let on_lint_toggled = {
+        let enabled_lints = enabled_lints.clone();
+        Callback::from(move |event: Event| {
+            let target = event.target().unwrap().unchecked_into::<HtmlInputElement>();
+            let Ok(lint) = target.name().parse::<Lint>() else {
+                return;
+            };
+            let mut lints = (*enabled_lints).clone();
+            if target.checked() {
+                lints.insert(lint);
+            } else {
+                lints.remove(&lint);
+            }
+            enabled_lints.set(lints);
+        })
+    };
+
+    // Chooses the profile programs are assembled with, which takes effect the next time the program is assembled.
+    let on_profile_changed = {
+        let profiles = profiles.clone();
+        let active_profile = active_profile.clone();
+        Callback::from(move |event: Event| {
+            let target = event.target().unwrap().unchecked_into::<HtmlInputElement>();
+            if let Some(profile) = profiles
+                .iter()
+                .find(|profile| profile.name == target.value())
+            {
+                active_profile.set(profile.clone());
+            }
+        })
+    };
+
+    // Loads a profile from a config file and chooses it, replacing any profile with the same name.
+    let on_profile_picked = {
+        let profiles = profiles.clone();
+        let active_profile = active_profile.clone();
+        let parser_text_output = parser_text_output.clone();
+        Callback::from(move |event: Event| {
+            let input: HtmlInputElement = event.target_unchecked_into();
+            let filelist = FileList::from(input.files().unwrap());
+            let Some(file) = filelist.first() else {
+                return;
+            };
+            let name = file.name();
+            let contents = gloo::file::futures::read_as_text(file);
+            let profiles = profiles.clone();
+            let active_profile = active_profile.clone();
+            let parser_text_output = parser_text_output.clone();
+            spawn_local(async move {
+                let profile = match contents.await {
+                    Ok(contents) => parse_profile(&contents),
+                    Err(_) => Err(format!("{name} could not be read.")),
+                };
+                match profile {
+                    Ok(profile) => {
+                        let mut loaded = (*profiles).clone();
+                        loaded.retain(|other| other.name != profile.name);
+                        loaded.push(profile.clone());
+                        profiles.set(loaded);
+                        parser_text_output.set(format!(
+                            "Loaded the {} profile from {name}. {}",
+                            profile.name, profile.description
+                        ));
+                        active_profile.set(profile);
+                    }
+                    Err(message) => parser_text_output.set(message),
+                }
+            });
+        })
+    };
+
+    // A profile for the other architecture is not enforced, so the program is assembled without restrictions.
+    let shown_profile = if active_profile.applies_to(datapath_state.current_architecture) {
+        (*active_profile).clone()
+    } else {
+        Profile::default()
+    };
+
     html! {
         <div class="overflow-hidden">
             // button tied to the input file element, which is hidden to be more clean
-            <input type="file" class="hidden" id="file_input" accept=".txt,.asm,.mips" onchange={file_picked_callback} />
+            <input type="file" class="hidden" id="file_input" accept=".txt,.asm,.mips,.bin,.hex,.elf" onchange={file_picked_callback} />
             <div class="flex flex-row flex-no-wrap h-screen p-2 gap-2">
                 // Left column
                 <div class="flex basis-3/4 flex-col items-stretch min-w-0">
@@ -620,12 +1145,92 @@ fn app(props: &AppProps) -> Html {
                                     <path d="M18 4.30769L14 0H0V28H32V4.30769H18ZM16 11.8462L23 19.3846H18V28H14V19.3846H9L16 11.8462Z" fill="#BBBBBB"/>
                                 </svg>
                             </button>
+                            <button class="opacity-90 disabled:opacity-30 duration-300 border-0 border-r-4 border solid border-primary-300 pr-2 " title="Disassemble memory" onclick={on_disassemble_clicked} disabled={!datapath_state.initialized}>
+                                <svg width="32" height="28" viewBox="0 0 32 28" fill="none" xmlns="http://www.w3.org/2000/svg">
+                                    <path d="M11 6L3 14L11 22M21 6L29 14L21 22" stroke="#BBBBBB" stroke-width="3" stroke-linecap="round" stroke-linejoin="round"/>
+                                </svg>
+                            </button>
+                            <div class="flex flex-row items-center gap-1 border-0 border-r-4 border solid border-primary-300 pr-2">
+                                <select class="bg-primary-600 text-primary-200" name="base" title="Address the ELF executable is linked at" onchange={on_elf_base_changed}>
+                                    {
+                                        ELF_BASES.iter().map(|(base, description)| html! {
+                                            <option value={base.to_string()} selected={*base == *elf_base}>{*description}</option>
+                                        }).collect::<Html>()
+                                    }
+                                </select>
+                                <button class="opacity-90 disabled:opacity-30 duration-300" title={format!("Download ELF executable, linked at 0x{:x}", *elf_base)} onclick={on_download_elf_clicked} disabled={!datapath_state.initialized || *parser_found_errors || binary_ref.borrow().is_empty()}>
+                                    <svg width="32" height="28" viewBox="0 0 32 28" fill="none" xmlns="http://www.w3.org/2000/svg">
+                                        <path d="M16 2V18M16 18L9 11M16 18L23 11M3 20V26H29V20" stroke="#BBBBBB" stroke-width="3" stroke-linecap="round" stroke-linejoin="round"/>
+                                    </svg>
+                                </button>
+                            </div>
+                            <button class="opacity-90 disabled:opacity-30 duration-300 border-0 border-r-4 border solid border-primary-300 pr-2 " title="Download listing" onclick={on_download_listing_clicked} disabled={!datapath_state.initialized || *parser_found_errors || binary_ref.borrow().is_empty()}>
+                                <svg width="32" height="28" viewBox="0 0 32 28" fill="none" xmlns="http://www.w3.org/2000/svg">
+                                    <path d="M6 3H26V25H6V3ZM10 8H22M10 13H22M10 18H17" stroke="#BBBBBB" stroke-width="3" stroke-linecap="round" stroke-linejoin="round"/>
+                                </svg>
+                            </button>
+                            <div class="flex flex-row items-center gap-1 border-0 border-r-4 border solid border-primary-300 pr-2">
+                                <select class="bg-primary-600 text-primary-200" name="format" title="Memory image format" onchange={on_export_option_changed.clone()}>
+                                    {
+                                        ImageFormat::iter().map(|format| html! {
+                                            <option value={format.to_string()} selected={format == export_options.format}>{format.description()}</option>
+                                        }).collect::<Html>()
+                                    }
+                                </select>
+                                <select class="bg-primary-600 text-primary-200" name="endianness" title="Byte order of each word" onchange={on_export_option_changed.clone()}>
+                                    <option value={Endianness::Big.to_string()} selected={export_options.endianness == Endianness::Big}>{"Big-endian"}</option>
+                                    <option value={Endianness::Little.to_string()} selected={export_options.endianness == Endianness::Little}>{"Little-endian"}</option>
+                                </select>
+                                <select class="bg-primary-600 text-primary-200" name="width" title="Word width" onchange={on_export_option_changed}>
+                                    {
+                                        WORD_BYTES.iter().map(|word_bytes| html! {
+                                            <option value={word_bytes.to_string()} selected={*word_bytes == export_options.word_bytes}>{format!("{}-bit", word_bytes * 8)}</option>
+                                        }).collect::<Html>()
+                                    }
+                                </select>
+                                <button class="opacity-90 disabled:opacity-30 duration-300" title="Export memory image" onclick={on_export_image_clicked} disabled={!datapath_state.initialized || *parser_found_errors || binary_ref.borrow().is_empty()}>
+                                    <svg width="32" height="28" viewBox="0 0 32 28" fill="none" xmlns="http://www.w3.org/2000/svg">
+                                        <path d="M4 4H28V24H4V4ZM9 9H13M9 14H13M9 19H13M17 9H23M17 14H23M17 19H23" stroke="#BBBBBB" stroke-width="3" stroke-linecap="round" stroke-linejoin="round"/>
+                                    </svg>
+                                </button>
+                            </div>
+                            <details class="relative border-0 border-r-4 border solid border-primary-300 pr-2 text-primary-200">
+                                <summary class="cursor-pointer" title="Choose the lints checked when assembling">{"Lints"}</summary>
+                                <div class="absolute z-10 flex flex-col gap-1 p-2 bg-primary-600 whitespace-nowrap">
+                                    {
+                                        Lint::iter().map(|lint| html! {
+                                            <label>
+                                                <input type="checkbox" name={lint.to_string()} checked={enabled_lints.contains(&lint)} onchange={on_lint_toggled.clone()} />
+                                                {format!(" {}", lint.description())}
+                                            </label>
+                                        }).collect::<Html>()
+                                    }
+                                </div>
+                            </details>
+                            <div class="flex flex-row items-center gap-1 border-0 border-r-4 border solid border-primary-300 pr-2 text-primary-200">
+                                <select class="bg-primary-600 text-primary-200" name="profile" title={format!("Instruction-set profile: {}", shown_profile.description)} onchange={on_profile_changed}>
+                                    {
+                                        profiles.iter().filter(|profile| profile.applies_to(datapath_state.current_architecture)).map(|profile| html! {
+                                            <option value={profile.name.clone()} selected={profile.name == shown_profile.name}>{profile.name.clone()}</option>
+                                        }).collect::<Html>()
+                                    }
+                                </select>
+                                <label class="cursor-pointer" title="Load an instruction-set profile from a file">
+                                    {"Load"}
+                                    <input type="file" class="hidden" accept=".profile,.txt" onchange={on_profile_picked} />
+                                </label>
+                            </div>
                             <button class="group disabled:opacity-30 duration-300 " title="Assemble" onclick={on_assemble_clicked}>
                                 <svg width="38" height="38" viewBox="0 0 38 38" fill="none" xmlns="http://www.w3.org/2000/svg">
                                     <path class="group-hover:group-enabled:stroke-primary-100 group-hover:group-enabled:fill-primary-100" fill-rule="evenodd" clip-rule="evenodd" d="M34.1794 19.1007C34.1794 23.0891 32.595 26.9142 29.7748 29.7345C26.9545 32.5547 23.1294 34.1392 19.141 34.1392C15.1525 34.1392 11.3274 32.5547 8.50714 29.7345C5.68688 26.9142 4.10247 23.0891 4.10247 19.1007C4.10247 15.1122 5.68688 11.2871 8.50714 8.46686C11.3274 5.6466 15.1525 4.06219 19.141 4.06219C23.1294 4.06219 26.9545 5.6466 29.7748 8.46686C32.595 11.2871 34.1794 15.1122 34.1794 19.1007ZM34.1183 17.5507L36.2416 19.1007L34.1183 20.4552L35.9114 22.4366L33.5523 23.3059L34.941 25.6444L32.4369 25.9915L33.3606 28.6029L30.6009 28.8385L31.2344 31.1941L28.5455 30.894L28.6432 33.3203L26.1295 32.508L25.6847 34.9007L23.4439 33.6234L22.4768 35.8711L20.5932 34.0981L19.141 36.2013L17.6887 34.0981L15.8051 35.8711L14.838 33.532L12.5972 34.9007L12.1524 32.4167L9.63871 33.3203L9.5403 30.8617L7.04749 31.1941L7.48485 28.8063L4.92128 28.6029L5.87082 26.3903L3.34095 25.6444L4.75548 23.7047L2.3705 22.4366L4.18939 20.4552L2.04028 19.1007L4.18939 17.5507L2.3705 15.7648L4.75548 14.7L3.34095 12.5569L5.87082 12.0144L4.92128 9.59843H7.48485L7.04749 7.00721L9.64964 7.44457L9.63871 4.881L12.0656 5.83053L12.5972 3.30066L14.7512 4.7152L15.8051 2.33022L17.6019 4.14911L19.141 2L20.4564 3.97315L22.4768 2.33022L23.4161 4.30201L25.6847 3.30066L26.2272 5.83053L28.6432 4.881V7.44457L31.2344 7.00721L30.6986 9.50002L33.3606 9.59843L32.3127 11.916L34.941 12.5569L33.5523 14.7L35.9114 15.7648L34.1183 17.5507Z" stroke="#BBBBBB" stroke-width="3" stroke-linecap="round" stroke-linejoin="round"/>
                                     <path class="group-hover:group-enabled:stroke-primary-100 group-hover:group-enabled:fill-primary-100" d="M27.4664 24.3107L22.6243 19.1458C22.3246 18.8261 21.8341 18.8261 21.5343 19.1458L21.171 19.5333L18.2164 16.3818L23.0667 11.2081H17.9283L15.6471 13.6413L15.4214 13.4005H14.3314V14.5632L14.5571 14.804L11.2483 18.3334L13.8175 21.0739L17.1263 17.5445L20.0809 20.6961L19.7176 21.0836C19.4178 21.4034 19.4178 21.9266 19.7176 22.2463L24.5597 27.4112C24.8594 27.731 25.3499 27.731 25.6497 27.4112L27.4664 25.4734C27.7662 25.1537 27.7662 24.6305 27.4664 24.3107Z" fill="#BBBBBB"/>
                                 </svg>
                             </button>
+                            if *source_changed {
+                                <span class="text-accent-red-200 whitespace-nowrap" title="The highlighted lines are those of the program as it was assembled. Assemble again to run the edited program.">
+                                    {"Source changed since assembly"}
+                                </span>
+                            }
                             <button class="group hover:stroke-primary-100 disabled:opacity-30 duration-300 " title="Execute" onclick={on_continue_execution} disabled={datapath_state.executing || !datapath_state.initialized || *parser_found_errors}>
                                 <svg width="38" height="38" viewBox="0 0 38 38" fill="none" xmlns="http://www.w3.org/2000/svg">
                                     <path class="fill-accent-red-200 stroke-accent-red-200 group-hover:group-enabled:stroke-accent-red-100 group-hover:group-enabled:fill-accent-red-100" fill-rule="evenodd" clip-rule="evenodd" d="M33.9311 19.1007C33.9311 23.0891 32.3467 26.9142 29.5265 29.7345C26.7062 32.5547 22.8811 34.1392 18.8927 34.1392C14.9042 34.1392 11.0791 32.5547 8.25885 29.7345C5.43859 26.9142 3.85418 23.0891 3.85418 19.1007C3.85418 15.1122 5.43859 11.2871 8.25885 8.46686C11.0791 5.6466 14.9042 4.06219 18.8927 4.06219C22.8811 4.06219 26.7062 5.6466 29.5265 8.46686C32.3467 11.2871 33.9311 15.1122 33.9311 19.1007ZM33.8701 17.5507L35.9933 19.1007L33.8701 20.4552L35.6631 22.4366L33.304 23.3059L34.6927 25.6444L32.1886 25.9915L33.1123 28.6029L30.3526 28.8385L30.9861 31.1941L28.2972 30.894L28.3949 33.3203L25.8812 32.508L25.4364 34.9007L23.1956 33.6234L22.2286 35.8711L20.345 34.0981L18.8927 36.2013L17.4404 34.0981L15.5568 35.8711L14.5897 33.532L12.3489 34.9007L11.9041 32.4167L9.39042 33.3203L9.29201 30.8617L6.7992 31.1941L7.23656 28.8063L4.67299 28.6029L5.62253 26.3903L3.09265 25.6444L4.50719 23.7047L2.12221 22.4366L3.9411 20.4552L1.79199 19.1007L3.9411 17.5507L2.12221 15.7648L4.50719 14.7L3.09265 12.5569L5.62253 12.0144L4.67299 9.59843H7.23656L6.7992 7.00721L9.40135 7.44457L9.39042 4.881L11.8173 5.83053L12.3489 3.30066L14.5029 4.7152L15.5568 2.33022L17.3536 4.14911L18.8927 2L20.2081 3.97315L22.2286 2.33022L23.1678 4.30201L25.4364 3.30066L25.9789 5.83053L28.3949 4.881V7.44457L30.9861 7.00721L30.4504 9.50002L33.1123 9.59843L32.0644 11.916L34.6927 12.5569L33.304 14.7L35.6631 15.7648L33.8701 17.5507Z" stroke-width="3" stroke-linecap="round" stroke-linejoin="round"/>
@@ -666,6 +1271,8 @@ fn app(props: &AppProps) -> Html {
                         <SwimEditor
                             breakpoints={breakpoints.clone()}
                             text_model={text_model}
+                            editor_files={editor_files}
+                            active_file={active_file}
                             lines_content={lines_content}
                             program_info={program_info_ref.borrow().clone()}
                             pc_limit={*pc_limit}
@@ -682,6 +1289,7 @@ fn app(props: &AppProps) -> Html {
                             sp={datapath_state.get_sp()}
                             memory={datapath_state.get_memory().clone()}
                             stack={datapath_state.get_stack().clone()}
+                            heap={datapath_state.heap.clone()}
                             initialized={datapath_state.initialized}
                             executing={datapath_state.executing}
                         />
@@ -703,7 +1311,7 @@ fn app(props: &AppProps) -> Html {
                 </div>
 
                 // Right column
-                <Regview gp={datapath_state.get_dyn_gp_registers()} fp={datapath_state.get_dyn_fp_registers()} pc_limit={*pc_limit} communicator={props.communicator}/>
+                <Regview gp={datapath_state.get_dyn_gp_registers()} fp={datapath_state.get_dyn_fp_registers()} interrupts={datapath_state.get_interrupts()} pc_limit={*pc_limit} communicator={props.communicator}/>
             </div>
             <div class="absolute w-8 top-2 right-2 hover:w-9 duration-300">
                 <a href="https://github.com/SWIM-ucf/SWIM/issues" target="_blank" class="group">
@@ -725,6 +1333,53 @@ fn new_object() -> JsValue {
     js_sys::Object::new().into()
 }
 
+/// Opens disassembled source in its own file of the project, replacing the last
+/// disassembly if there is one, and shows it in the editor.
+fn open_disassembly(
+    source: &str,
+    editor_files: &UseStateHandle<Vec<EditorFile>>,
+    active_file: &UseStateHandle<usize>,
+    current_architecture: AvailableDatapaths,
+) {
+    let mut files = (**editor_files).clone();
+    match files.iter().find(|file| file.name == DISASSEMBLY_FILE_NAME) {
+        Some(file) => file.model.set_value(source),
+        None => {
+            let language = match current_architecture {
+                AvailableDatapaths::MIPS => "mips",
+                AvailableDatapaths::RISCV => "riscv",
+            };
+            files.push(EditorFile {
+                name: DISASSEMBLY_FILE_NAME.to_string(),
+                model: TextModel::create(source, Some(language), None).unwrap(),
+                read_only: false,
+            });
+        }
+    }
+    if let Some(index) = find_active_file(DISASSEMBLY_FILE_NAME, &files) {
+        active_file.set(index);
+    }
+    editor_files.set(files);
+}
+
+/// Assembles the project in the editor, reusing the last assembly if no file has
+/// changed since.
+fn assemble_project(
+    assembly_cache: &RefCell<AssemblyCache>,
+    text_model: &TextModel,
+    editor_files: &[EditorFile],
+    current_architecture: AvailableDatapaths,
+) -> (ProgramInfo, Vec<u32>, HashMap<String, usize>) {
+    let files = project_files(text_model, editor_files);
+    let mut assembly_cache = assembly_cache.borrow_mut();
+    let assembly = assembly_cache.assemble(&files, current_architecture);
+    (
+        assembly.program_info.clone(),
+        assembly.binary.clone(),
+        assembly.labels.clone(),
+    )
+}
+
 /**********************  File I/O Function ***********************/
 pub fn on_upload_file_clicked() {
     let window = web_sys::window().expect("should have a window in this context");
@@ -744,6 +1399,23 @@ pub fn on_upload_file_clicked() {
     });
 }
 
+/// Downloads the bytes as a file with the given name.
+pub fn download_file(name: &str, bytes: &[u8]) {
+    let window = web_sys::window().expect("should have a window in this context");
+    let document = window.document().expect("window should have a document");
+
+    let blob = gloo::file::Blob::new_with_options(bytes, Some("application/octet-stream"));
+    let url = gloo::file::ObjectUrl::from(blob);
+    let link = document
+        .create_element("a")
+        .expect("Creating a link element should succeed.")
+        .dyn_into::<HtmlElement>()
+        .expect("Element should be an HtmlElement");
+    link.set_attribute("href", &url).unwrap();
+    link.set_attribute("download", name).unwrap();
+    link.click();
+}
+
 fn main() {
     // Initialize and leak the communicator to ensure that the thread spawns immediately and the bridge to it lives
     // for the remainder of the program.
@@ -2,6 +2,8 @@
 
 pub mod architectures;
 pub mod datapath;
+pub mod elf;
+pub mod interrupt;
 pub mod line_info;
 pub mod mips;
 pub mod register;
@@ -2,8 +2,8 @@
 
 use crate::emulation_core::architectures::DatapathRef;
 use crate::emulation_core::datapath::Syscall::{
-    Exit, PrintDouble, PrintFloat, PrintInt, PrintString, ReadDouble, ReadFloat, ReadInt,
-    ReadString,
+    Exit, Free, Malloc, PrintDouble, PrintFloat, PrintFormatted, PrintInt, PrintString, ReadDouble,
+    ReadFloat, ReadInt, ReadString, Sbrk,
 };
 use crate::emulation_core::mips::memory::Memory;
 use std::ops::BitOrAssign;
@@ -89,7 +89,7 @@ pub enum Syscall {
     /// Halts the emulator core. This should generally be the default syscall if the syscall number
     /// does not match any of the other variants.
     ///
-    /// Call number: 0
+    /// Call number: 0, as well as 10 and 17, which exit in MARS and RARS
     Exit,
     /// Prints the integer value of the argument register.
     ///
@@ -125,6 +125,26 @@ pub enum Syscall {
     ///
     /// Call number 8
     ReadString(u64),
+    /// Moves the program break by the (signed) number of bytes in the argument and returns the
+    /// previous program break.
+    ///
+    /// Call number: 9
+    Sbrk(u64),
+    /// Allocates a block on the heap of at least the number of bytes in the argument and returns
+    /// its address, or 0 if the allocation failed.
+    ///
+    /// Call number: 13
+    Malloc(u64),
+    /// Frees a block on the heap previously returned by [`Syscall::Malloc`].
+    ///
+    /// Call number: 14
+    Free(u64),
+    /// Prints a printf-style format string, starting at the memory address in the argument and
+    /// ending at a null byte. Values for the conversions are taken from the remaining argument
+    /// registers, then from the stack.
+    ///
+    /// Call number: 12
+    PrintFormatted(u64),
 }
 
 impl Syscall {
@@ -143,6 +163,11 @@ pub fn from_register_data(
             6 => ReadFloat,
             7 => ReadDouble,
             8 => ReadString(integer_arg),
+            9 => Sbrk(integer_arg),
+            // 10 and 17 are left to exit, since programs written for MARS and RARS end with them.
+            12 => PrintFormatted(integer_arg),
+            13 => Malloc(integer_arg),
+            14 => Free(integer_arg),
             _ => Exit,
         }
     }
@@ -158,6 +183,7 @@ pub struct DatapathUpdateSignal {
     pub changed_coprocessor_registers: bool,
     pub changed_memory: bool,
     pub changed_stack: bool,
+    pub changed_interrupts: bool,
     pub hit_syscall: bool,
     pub hit_breakpoint: bool,
 }
@@ -171,6 +197,7 @@ pub struct DatapathUpdateSignal {
     changed_coprocessor_registers: true,
     changed_memory: true,
     changed_stack: true,
+    changed_interrupts: true,
     hit_syscall: false,
     hit_breakpoint: false,
 };
@@ -183,6 +210,7 @@ fn bitor_assign(&mut self, rhs: Self) {
         self.changed_coprocessor_registers |= rhs.changed_coprocessor_registers;
         self.changed_memory |= rhs.changed_memory;
         self.changed_stack |= rhs.changed_stack;
+        self.changed_interrupts |= rhs.changed_interrupts;
         self.hit_syscall |= rhs.hit_syscall;
         self.hit_breakpoint |= rhs.hit_breakpoint;
     }
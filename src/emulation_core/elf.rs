@@ -0,0 +1,1028 @@
+//! Loading and writing of statically linked ELF executables.
+//!
+//! Both ELF32 and ELF64 files of either byte order are read. The `PT_LOAD`
+//! segments are laid out in an image of memory starting at address 0, moved
+//! down if they are linked past the end of memory, and the symbol table is
+//! kept so the program's functions and variables can be shown by name.
+//! Assembled programs are written as ELF64 executables, so they can be
+//! examined with tools such as `readelf` and `objdump`, and linked at a base
+//! such as `0x10000` or `0x8000_0000` so QEMU or Spike can run them.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::emulation_core::mips::constants::{
+    OPCODE_J as MIPS_OPCODE_J, OPCODE_JAL as MIPS_OPCODE_JAL,
+};
+use crate::emulation_core::mips::memory::CAPACITY_BYTES;
+use crate::emulation_core::riscv::constants::{
+    OPCODE_BRANCH as RISCV_OPCODE_BRANCH, OPCODE_JAL as RISCV_OPCODE_JAL,
+};
+use crate::parser::linker::{relocate, relocate_data, PlacedRelocation, RelocationType};
+use crate::parser::parser_structs_and_enums::ProgramInfo;
+
+pub const ELF_MAGIC: [u8; 4] = [0x7f, b'E', b'L', b'F'];
+
+// Identification bytes
+pub const ELFCLASS32: u8 = 1;
+pub const ELFCLASS64: u8 = 2;
+pub const ELFDATA2LSB: u8 = 1;
+pub const ELFDATA2MSB: u8 = 2;
+pub const EV_CURRENT: u8 = 1;
+
+// Header flags
+pub const EF_MIPS_ARCH_64: u32 = 0x6000_0000;
+pub const EF_RISCV_RVC: u32 = 1;
+
+// File types
+pub const ET_REL: u16 = 1;
+pub const ET_EXEC: u16 = 2;
+pub const ET_DYN: u16 = 3;
+
+// Machine types
+pub const EM_MIPS: u16 = 8;
+pub const EM_RISCV: u16 = 243;
+
+// Program header types and flags
+pub const PT_LOAD: u32 = 1;
+pub const PT_DYNAMIC: u32 = 2;
+pub const PT_INTERP: u32 = 3;
+pub const PF_X: u32 = 1;
+pub const PF_W: u32 = 2;
+pub const PF_R: u32 = 4;
+
+// Section header types
+pub const SHT_PROGBITS: u32 = 1;
+pub const SHT_SYMTAB: u32 = 2;
+pub const SHT_STRTAB: u32 = 3;
+pub const SHT_NOBITS: u32 = 8;
+
+// Section header flags
+pub const SHF_WRITE: u64 = 1;
+pub const SHF_ALLOC: u64 = 2;
+pub const SHF_EXECINSTR: u64 = 4;
+
+// Symbol bindings, types, and the undefined section index
+pub const STB_LOCAL: u8 = 0;
+pub const STB_GLOBAL: u8 = 1;
+pub const STT_NOTYPE: u8 = 0;
+pub const STT_OBJECT: u8 = 1;
+pub const STT_FUNC: u8 = 2;
+pub const SHN_UNDEF: u16 = 0;
+
+/// An executable read from an ELF file, ready to be placed in memory.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ElfExecutable {
+    /// The architecture the executable was compiled for.
+    pub architecture: AvailableDatapaths,
+    /// The address the executable is linked at, which is placed at address 0 of
+    /// memory. The entry, words, symbols, and relocations are all relative to
+    /// it, and it is written linked at the same address.
+    pub base: usize,
+    /// The address execution starts at.
+    pub entry: usize,
+    /// The contents of memory from address 0, in words.
+    pub words: Vec<u32>,
+    /// The word address of the first segment that is not executable, if
+    /// there is one after the executable segments.
+    pub data_start: Option<usize>,
+    /// The address of every function, variable, and label in the symbol table.
+    pub symbols: HashMap<String, usize>,
+    /// The symbols that are visible to other files, rather than local to the
+    /// file that defined them.
+    pub global_symbols: HashSet<String>,
+    /// The places in the words that hold an address, which are moved with the
+    /// executable when it is written. An executable read from a file has none,
+    /// since its words already hold the addresses it was linked at.
+    pub relocations: Vec<PlacedRelocation>,
+}
+
+impl ElfExecutable {
+    /// Gathers an assembled program into an executable. The labels become its
+    /// symbols, and those declared with `.globl` or `.global` are made global.
+    pub fn from_assembly(
+        program_info: &ProgramInfo,
+        binary: &[u32],
+        labels: &HashMap<String, usize>,
+        architecture: AvailableDatapaths,
+    ) -> Self {
+        let global_symbols = program_info
+            .monaco_line_info
+            .iter()
+            .filter(|line| {
+                line.tokens.first().is_some_and(|token| {
+                    matches!(&*token.token_name.to_lowercase(), ".globl" | ".global")
+                })
+            })
+            .flat_map(|line| &line.tokens[1..])
+            .map(|token| token.token_name.trim_end_matches(',').to_string())
+            .filter(|name| labels.contains_key(name))
+            .collect();
+
+        ElfExecutable {
+            architecture,
+            base: 0,
+            entry: program_info.pc_starting_point,
+            words: binary.to_vec(),
+            data_start: Some(program_info.data_starting_point)
+                .filter(|data_start| *data_start < binary.len()),
+            symbols: labels.clone(),
+            global_symbols,
+            relocations: program_info.relocations.clone(),
+        }
+    }
+}
+
+/// Reads a statically linked ELF executable for MIPS or RISC-V.
+///
+/// Memory is big-endian, so the instructions of a little-endian executable are
+/// reordered as they are loaded, while its data keeps the order of its bytes.
+/// Strings then read the same as on the machine the executable was built for,
+/// while the halves and words of its data have their bytes reversed. RISC-V
+/// branches and jumps hold the distance to their target, which is rewritten to
+/// the word address SWIM's branches and jumps hold.
+///
+/// An executable whose segments end past the 64 KB of memory, such as one
+/// linked at `0x10000` as the GNU linker does by default, is moved down by the
+/// page its lowest segment starts in, which becomes its base. Jumps are moved
+/// with it, but addresses the program builds itself, such as with `lui`, are
+/// not, so such a program should address its data relative to the PC, as with
+/// `-mcmodel=medany` on RISC-V, or be linked at a lower address.
+pub fn load_elf(bytes: &[u8]) -> Result<ElfExecutable, String> {
+    if bytes.len() < 16 || bytes[0..4] != ELF_MAGIC {
+        return Err("The file is not an ELF file.".to_string());
+    }
+    let reader = ElfReader {
+        bytes,
+        is_64_bit: match bytes[4] {
+            ELFCLASS32 => false,
+            ELFCLASS64 => true,
+            class => return Err(format!("ELF class {class} is not supported.")),
+        },
+        little_endian: match bytes[5] {
+            ELFDATA2LSB => true,
+            ELFDATA2MSB => false,
+            encoding => return Err(format!("ELF data encoding {encoding} is not supported.")),
+        },
+    };
+
+    let architecture = match reader.half(18)? {
+        EM_MIPS => AvailableDatapaths::MIPS,
+        EM_RISCV => AvailableDatapaths::RISCV,
+        machine => {
+            return Err(format!(
+                "ELF files for machine type {machine} are not supported. SWIM runs MIPS ({EM_MIPS}) and RISC-V ({EM_RISCV}) executables."
+            ))
+        }
+    };
+    match reader.half(16)? {
+        ET_EXEC => {}
+        ET_REL => {
+            return Err(
+                "The file is an object file that has not been linked. Link it into an executable first."
+                    .to_string(),
+            )
+        }
+        ET_DYN => {
+            return Err(
+                "The file is a shared library or position-independent executable. Link it with `-static -no-pie`."
+                    .to_string(),
+            )
+        }
+        file_type => return Err(format!("ELF file type {file_type} is not supported.")),
+    }
+
+    let (entry, program_headers, section_headers, flags) = if reader.is_64_bit {
+        (
+            reader.word(24)?,
+            reader.word(32)?,
+            reader.word(40)?,
+            reader.u32(48)?,
+        )
+    } else {
+        (
+            reader.word(24)?,
+            reader.word(28)?,
+            reader.word(32)?,
+            reader.u32(36)?,
+        )
+    };
+    if architecture == AvailableDatapaths::RISCV && flags & EF_RISCV_RVC != 0 {
+        return Err(
+            "The executable uses compressed instructions, which SWIM does not run. Compile it without the C extension, such as with `-march=rv64g`."
+                .to_string(),
+        );
+    }
+    let header_size_offset = if reader.is_64_bit { 52 } else { 40 };
+    let (program_header_size, program_header_count) = (
+        reader.half(header_size_offset + 2)? as usize,
+        reader.half(header_size_offset + 4)? as usize,
+    );
+    let (section_header_size, section_header_count) = (
+        reader.half(header_size_offset + 6)? as usize,
+        reader.half(header_size_offset + 8)? as usize,
+    );
+
+    let mut segments = Vec::new();
+    for i in 0..program_header_count {
+        let segment =
+            reader.program_header(reader.entry(program_headers, i, program_header_size)?)?;
+        match segment.segment_type {
+            PT_LOAD if segment.memory_size > 0 => segments.push(segment),
+            PT_INTERP | PT_DYNAMIC => {
+                return Err(
+                    "The executable is dynamically linked. Link it with `-static`.".to_string(),
+                )
+            }
+            _ => {}
+        }
+    }
+    let sections = (0..section_header_count)
+        .map(|i| reader.section_header(reader.entry(section_headers, i, section_header_size)?))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Segments that do not fit in memory are moved down together, keeping the
+    // distance between them and their offset within a page.
+    let mut end = 0;
+    for segment in &segments {
+        let segment_end = segment
+            .address
+            .checked_add(segment.memory_size)
+            .ok_or_else(|| {
+                format!(
+                    "The segment at 0x{:x} is too large to load.",
+                    segment.address
+                )
+            })?;
+        end = end.max(segment_end);
+    }
+    let Some(start) = segments.iter().map(|segment| segment.address).min() else {
+        return Err("The executable has no segments to load.".to_string());
+    };
+    let base = if end <= CAPACITY_BYTES {
+        0
+    } else {
+        start / SEGMENT_ALIGNMENT * SEGMENT_ALIGNMENT
+    };
+    if end - base > CAPACITY_BYTES {
+        return Err(format!(
+            "The segments from 0x{start:x} to 0x{end:x} do not fit in the {CAPACITY_BYTES} bytes of memory."
+        ));
+    }
+
+    let mut image: Vec<u8> = vec![0; end - base];
+    let mut data_start: Option<usize> = None;
+    let mut code_end = 0;
+    for segment in &segments {
+        let contents = reader.slice(segment.offset, segment.file_size.min(segment.memory_size))?;
+        let address = segment.address - base;
+        image[address..address + contents.len()].copy_from_slice(contents);
+
+        if segment.flags & PF_X != 0 {
+            code_end = code_end.max(address + segment.memory_size);
+        } else {
+            data_start =
+                Some(data_start.map_or(address / 4, |data_start| data_start.min(address / 4)));
+        }
+    }
+
+    // The instructions are found from the executable sections, since read-only
+    // data is often placed in the same segment as the text.
+    let mut code: Vec<(usize, usize)> = sections
+        .iter()
+        .filter(|section| {
+            section.flags & (SHF_ALLOC | SHF_EXECINSTR) == SHF_ALLOC | SHF_EXECINSTR
+                && section.section_type != SHT_NOBITS
+        })
+        .map(|section| {
+            (
+                section.address,
+                section.address.saturating_add(section.size),
+            )
+        })
+        .collect();
+    if code.is_empty() {
+        code = segments
+            .iter()
+            .filter(|segment| segment.flags & PF_X != 0)
+            .map(|segment| {
+                (
+                    segment.address,
+                    segment.address + segment.file_size.min(segment.memory_size),
+                )
+            })
+            .collect();
+    }
+
+    let mut words = Vec::with_capacity(image.len().div_ceil(4));
+    for (i, chunk) in image.chunks(4).enumerate() {
+        let mut word = [0; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        let address = base + i * 4;
+        words.push(
+            if code
+                .iter()
+                .any(|(start, end)| (*start..*end).contains(&address))
+            {
+                let instruction = if reader.little_endian {
+                    u32::from_le_bytes(word)
+                } else {
+                    u32::from_be_bytes(word)
+                };
+                instruction_from_elf(instruction, address, base, architecture)?
+            } else {
+                u32::from_be_bytes(word)
+            },
+        );
+    }
+
+    let mut symbols = HashMap::new();
+    let mut global_symbols = HashSet::new();
+    for table in sections
+        .iter()
+        .filter(|section| section.section_type == SHT_SYMTAB)
+    {
+        let strings = sections.get(table.link as usize).ok_or_else(|| {
+            "The symbol table's names are in a section that does not exist.".to_string()
+        })?;
+        reader.read_symbols(table, strings, &mut symbols, &mut global_symbols)?;
+    }
+    symbols.retain(|_, address| {
+        address
+            .checked_sub(base)
+            .filter(|address| *address < image.len())
+            .map(|moved| *address = moved)
+            .is_some()
+    });
+    global_symbols.retain(|name| symbols.contains_key(name));
+
+    Ok(ElfExecutable {
+        architecture,
+        base,
+        entry: entry.checked_sub(base).ok_or_else(|| {
+            format!("The entry point 0x{entry:x} is before the segments that are loaded.")
+        })?,
+        words,
+        data_start: data_start.filter(|data_start| *data_start * 4 >= code_end),
+        symbols,
+        global_symbols,
+        relocations: Vec::new(),
+    })
+}
+
+/// The word address SWIM's RISC-V branches hold has 12 bits, so they reach the
+/// first 16 KB of memory.
+const BRANCH_TARGET_BITS: u32 = 12;
+
+/// Rewrites an instruction at an address of an executable moved down by base
+/// into the form SWIM runs. RISC-V branches and jumps hold the distance to
+/// their target, which becomes the target's word address, and MIPS jumps are
+/// moved with the executable.
+fn instruction_from_elf(
+    instruction: u32,
+    address: usize,
+    base: usize,
+    architecture: AvailableDatapaths,
+) -> Result<u32, String> {
+    let target_word = |target: i64, bits: u32| {
+        let moved = target - base as i64;
+        if moved < 0 || moved % 4 != 0 || moved / 4 >= 1 << bits {
+            return Err(format!(
+                "The jump at 0x{address:x} goes to 0x{target:x}, which SWIM cannot reach."
+            ));
+        }
+        Ok((moved / 4) as u32)
+    };
+
+    match architecture {
+        AvailableDatapaths::RISCV => match (instruction & 0x7f) as u8 {
+            RISCV_OPCODE_BRANCH => {
+                let target = target_word(
+                    address as i64 + branch_offset(instruction),
+                    BRANCH_TARGET_BITS,
+                )?;
+                let rs1 = (instruction >> 15) & 0x1f;
+                let rs2 = (instruction >> 20) & 0x1f;
+                Ok(target << 20 | rs2 << 15 | instruction & 0x7000 | rs1 << 7 | instruction & 0x7f)
+            }
+            RISCV_OPCODE_JAL => {
+                let target = target_word(address as i64 + jump_offset(instruction), 20)?;
+                Ok(target << 12 | instruction & 0xfff)
+            }
+            _ => Ok(instruction),
+        },
+        AvailableDatapaths::MIPS => match (instruction >> 26) as u8 {
+            MIPS_OPCODE_J | MIPS_OPCODE_JAL if base != 0 => {
+                let region = (address + 4) & 0xf000_0000;
+                let target = region | ((instruction & 0x03ff_ffff) as usize) << 2;
+                Ok(instruction & 0xfc00_0000 | target_word(target as i64, 26)?)
+            }
+            _ => Ok(instruction),
+        },
+    }
+}
+
+/// Rewrites an instruction SWIM runs at an address into the form of an
+/// executable linked at base, the reverse of instruction_from_elf. RISC-V
+/// branches and jumps are given the distance from the address of the
+/// instruction to their target, and MIPS jumps are moved with the executable.
+fn instruction_to_elf(
+    instruction: u32,
+    address: usize,
+    base: usize,
+    architecture: AvailableDatapaths,
+) -> Result<u32, String> {
+    if architecture == AvailableDatapaths::MIPS {
+        return match (instruction >> 26) as u8 {
+            MIPS_OPCODE_J | MIPS_OPCODE_JAL if base != 0 => {
+                let region = (address + 4) & 0xf000_0000;
+                let target = (region | ((instruction & 0x03ff_ffff) as usize) << 2) + base;
+                if target & 0xf000_0000 != (address + base + 4) & 0xf000_0000 {
+                    return Err(format!(
+                        "The jump at 0x{:x} goes to 0x{target:x}, which is outside of the 256 MB it can reach.",
+                        address + base
+                    ));
+                }
+                Ok(instruction & 0xfc00_0000 | (target >> 2) as u32 & 0x03ff_ffff)
+            }
+            _ => Ok(instruction),
+        };
+    }
+    let distance = |target: u32, range: i64| {
+        let distance = target as i64 * 4 - address as i64;
+        if !(-range..range).contains(&distance) {
+            return Err(format!(
+                "The jump at 0x{address:x} goes to 0x{:x}, which is too far for a RISC-V executable.",
+                target * 4
+            ));
+        }
+        Ok(distance as u32)
+    };
+
+    match (instruction & 0x7f) as u8 {
+        RISCV_OPCODE_BRANCH => {
+            let offset = distance(instruction >> 20, 1 << 12)?;
+            let rs1 = (instruction >> 7) & 0x1f;
+            let rs2 = (instruction >> 15) & 0x1f;
+            Ok((offset >> 12 & 1) << 31
+                | (offset >> 5 & 0x3f) << 25
+                | rs2 << 20
+                | rs1 << 15
+                | instruction & 0x7000
+                | (offset >> 1 & 0xf) << 8
+                | (offset >> 11 & 1) << 7
+                | instruction & 0x7f)
+        }
+        RISCV_OPCODE_JAL => {
+            let offset = distance(instruction >> 12, 1 << 20)?;
+            Ok((offset >> 20 & 1) << 31
+                | (offset >> 1 & 0x3ff) << 21
+                | (offset >> 11 & 1) << 20
+                | (offset >> 12 & 0xff) << 12
+                | instruction & 0xfff)
+        }
+        _ => Ok(instruction),
+    }
+}
+
+/// The distance to the target of a standard RISC-V branch, from its B-type
+/// immediate.
+fn branch_offset(instruction: u32) -> i64 {
+    let offset = (instruction >> 31 & 1) << 12
+        | (instruction >> 7 & 1) << 11
+        | (instruction >> 25 & 0x3f) << 5
+        | (instruction >> 8 & 0xf) << 1;
+    ((offset as i64) << 51) >> 51
+}
+
+/// The distance to the target of a standard RISC-V jal, from its J-type
+/// immediate.
+fn jump_offset(instruction: u32) -> i64 {
+    let offset = (instruction >> 31 & 1) << 20
+        | (instruction >> 12 & 0xff) << 12
+        | (instruction >> 20 & 1) << 11
+        | (instruction >> 21 & 0x3ff) << 1;
+    ((offset as i64) << 43) >> 43
+}
+
+/// The alignment of the segments of a written executable, which is the size of
+/// a page so that they can be mapped straight from the file.
+const SEGMENT_ALIGNMENT: usize = 0x1000;
+
+/// Writes an executable as an ELF64 file with a `.text` and `.data` section, a
+/// symbol table, and its entry point.
+///
+/// MIPS executables are big-endian and RISC-V executables are little-endian.
+/// Instructions are written in the executable's byte order and RISC-V branches
+/// and jumps are given the distance to their target, while data keeps the order
+/// of its bytes in memory, the reverse of [`load_elf`], so reading the file
+/// back gives the same image. The memory image is placed in the file after the
+/// first page, so each segment's offset in the file matches its address within
+/// a page. A branch whose target is too far for the standard encoding is
+/// reported as an error.
+///
+/// The executable is linked at its base, which must be a multiple of the page
+/// size. Its segments, sections, entry, and symbols are placed that far up,
+/// and the addresses held at its relocations and by MIPS jumps are moved with
+/// them, so that a program assembled at address 0 can be linked at `0x10000`
+/// for QEMU's user mode or at `0x8000_0000` for Spike. An address that no
+/// longer fits where it is held is reported as an error.
+pub fn write_elf(executable: &ElfExecutable) -> Result<Vec<u8>, String> {
+    let base = executable.base;
+    if base % SEGMENT_ALIGNMENT != 0 {
+        return Err(format!(
+            "An executable cannot be linked at 0x{base:x}, which is not a multiple of the page size, 0x{SEGMENT_ALIGNMENT:x}."
+        ));
+    }
+    let image = relocated_image(executable)?;
+    let little_endian = executable.architecture == AvailableDatapaths::RISCV;
+    let mut writer = ElfWriter {
+        bytes: Vec::new(),
+        little_endian,
+    };
+    let text_end = executable
+        .data_start
+        .unwrap_or(executable.words.len())
+        .min(executable.words.len());
+
+    // Index 0 is the null section, and .data is left out when there is no data.
+    let mut sections: Vec<(&str, usize, usize)> = vec![(".text", 0, text_end)];
+    if text_end < executable.words.len() {
+        sections.push((".data", text_end, executable.words.len()));
+    }
+    let section_index = |address: usize| -> u16 {
+        if address < text_end * 4 || sections.len() == 1 {
+            1
+        } else {
+            2
+        }
+    };
+
+    // The local symbols come before the global ones, and each group is sorted by address.
+    let mut symbols: Vec<(&String, &usize)> = executable.symbols.iter().collect();
+    symbols.sort_by_key(|(name, address)| {
+        (executable.global_symbols.contains(*name), **address, *name)
+    });
+    let first_global = 1 + symbols
+        .iter()
+        .filter(|(name, _)| !executable.global_symbols.contains(*name))
+        .count();
+    let mut symbol_names = vec![0];
+    let mut symbol_table = ElfWriter {
+        bytes: vec![0; 24],
+        little_endian,
+    };
+    for (name, address) in &symbols {
+        let name_offset = symbol_names.len() as u32;
+        symbol_names.extend(name.bytes());
+        symbol_names.push(0);
+
+        let binding = if executable.global_symbols.contains(*name) {
+            STB_GLOBAL
+        } else {
+            STB_LOCAL
+        };
+        // Labels in the text are functions and labels of data are variables. Each one is as
+        // large as the distance to the next label in its section, or to the end of the section.
+        let section = section_index(**address);
+        let symbol_type = if section == 1 { STT_FUNC } else { STT_OBJECT };
+        let section_end = sections[section as usize - 1].2 * 4;
+        let next_address = executable
+            .symbols
+            .values()
+            .filter(|other| *other > *address && section_index(**other) == section)
+            .min()
+            .map_or(section_end, |next_address| *next_address);
+        symbol_table.u32(name_offset);
+        symbol_table.bytes.push(binding << 4 | symbol_type);
+        symbol_table.bytes.push(0);
+        symbol_table.half(section);
+        symbol_table.u64((base + **address) as u64);
+        symbol_table.u64(next_address.saturating_sub(**address) as u64);
+    }
+    let mut section_names = vec![0];
+    let mut name_section = |name: &str| {
+        let offset = section_names.len() as u32;
+        section_names.extend(name.bytes());
+        section_names.push(0);
+        offset
+    };
+    let section_name_offsets: Vec<u32> = sections
+        .iter()
+        .map(|(name, _, _)| name_section(name))
+        .collect();
+    let symtab_name = name_section(".symtab");
+    let strtab_name = name_section(".strtab");
+    let shstrtab_name = name_section(".shstrtab");
+
+    // The header and program headers, then the image, the tables, and the section headers.
+    let image_offset = SEGMENT_ALIGNMENT;
+    let symbol_table_offset = align_to(image_offset + executable.words.len() * 4, 8);
+    let symbol_names_offset = symbol_table_offset + symbol_table.bytes.len();
+    let section_names_offset = symbol_names_offset + symbol_names.len();
+    let section_headers_offset = align_to(section_names_offset + section_names.len(), 8);
+    let section_count = sections.len() + 4;
+
+    writer.bytes.extend(ELF_MAGIC);
+    writer.bytes.push(ELFCLASS64);
+    writer.bytes.push(if little_endian {
+        ELFDATA2LSB
+    } else {
+        ELFDATA2MSB
+    });
+    writer.bytes.push(EV_CURRENT);
+    writer.bytes.resize(16, 0);
+    writer.half(ET_EXEC);
+    writer.half(match executable.architecture {
+        AvailableDatapaths::MIPS => EM_MIPS,
+        AvailableDatapaths::RISCV => EM_RISCV,
+    });
+    writer.u32(EV_CURRENT as u32);
+    writer.u64((base + executable.entry) as u64);
+    writer.u64(64);
+    writer.u64(section_headers_offset as u64);
+    writer.u32(match executable.architecture {
+        AvailableDatapaths::MIPS => EF_MIPS_ARCH_64,
+        AvailableDatapaths::RISCV => 0,
+    });
+    writer.half(64);
+    writer.half(56);
+    writer.half(sections.len() as u16);
+    writer.half(64);
+    writer.half(section_count as u16);
+    writer.half(section_count as u16 - 1);
+
+    for (name, start, end) in &sections {
+        writer.u32(PT_LOAD);
+        writer.u32(if *name == ".text" {
+            PF_R | PF_X
+        } else {
+            PF_R | PF_W
+        });
+        writer.u64((image_offset + start * 4) as u64);
+        writer.u64((base + start * 4) as u64);
+        writer.u64((base + start * 4) as u64);
+        writer.u64(((end - start) * 4) as u64);
+        writer.u64(((end - start) * 4) as u64);
+        writer.u64(SEGMENT_ALIGNMENT as u64);
+    }
+
+    writer.bytes.resize(image_offset, 0);
+    for (i, word) in image.chunks(4).enumerate() {
+        if i < text_end {
+            let word = u32::from_be_bytes(word.try_into().unwrap());
+            writer.u32(instruction_to_elf(
+                word,
+                i * 4,
+                base,
+                executable.architecture,
+            )?);
+        } else {
+            writer.bytes.extend(word);
+        }
+    }
+    writer.bytes.resize(symbol_table_offset, 0);
+    writer.bytes.extend(&symbol_table.bytes);
+    writer.bytes.extend(&symbol_names);
+    writer.bytes.extend(&section_names);
+    writer.bytes.resize(section_headers_offset, 0);
+
+    writer.bytes.resize(writer.bytes.len() + 64, 0);
+    for ((name, start, end), name_offset) in sections.iter().zip(section_name_offsets) {
+        let flags = if *name == ".text" {
+            SHF_ALLOC | SHF_EXECINSTR
+        } else {
+            SHF_ALLOC | SHF_WRITE
+        };
+        writer.section_header(
+            name_offset,
+            SHT_PROGBITS,
+            flags,
+            (
+                base + start * 4,
+                image_offset + start * 4,
+                (end - start) * 4,
+            ),
+            (0, 0),
+            (4, 0),
+        );
+    }
+    let strtab_index = sections.len() as u32 + 2;
+    writer.section_header(
+        symtab_name,
+        SHT_SYMTAB,
+        0,
+        (0, symbol_table_offset, symbol_table.bytes.len()),
+        (strtab_index, first_global as u32),
+        (8, 24),
+    );
+    writer.section_header(
+        strtab_name,
+        SHT_STRTAB,
+        0,
+        (0, symbol_names_offset, symbol_names.len()),
+        (0, 0),
+        (1, 0),
+    );
+    writer.section_header(
+        shstrtab_name,
+        SHT_STRTAB,
+        0,
+        (0, section_names_offset, section_names.len()),
+        (0, 0),
+        (1, 0),
+    );
+    Ok(writer.bytes)
+}
+
+/// Takes the bytes of an executable's words with the addresses held at its
+/// relocations moved to its base. Branches and jumps are left to
+/// instruction_to_elf, and distances between two places of the executable stay
+/// the same wherever it is linked.
+fn relocated_image(executable: &ElfExecutable) -> Result<Vec<u8>, String> {
+    let base = executable.base;
+    let mut image: Vec<u8> = executable
+        .words
+        .iter()
+        .flat_map(|word| word.to_be_bytes())
+        .collect();
+    for relocation in &executable.relocations {
+        let address = relocation.address;
+        let value = relocation.value + base as i64;
+        let moved = match relocation.relocation_type {
+            RelocationType::Mips26 | RelocationType::RiscvBranch | RelocationType::RiscvJal => {
+                continue
+            }
+            relocation_type if !relocation_type.is_absolute() => continue,
+            RelocationType::Data(size) => image
+                .get_mut(address..address + size)
+                .is_some_and(|bytes| relocate_data(bytes, value)),
+            relocation_type => {
+                let word = image.get(address..address + 4).and_then(|bytes| {
+                    let word = u32::from_be_bytes(bytes.try_into().unwrap());
+                    relocate(word, relocation_type, value, base + address)
+                });
+                if let Some(word) = word {
+                    image[address..address + 4].copy_from_slice(&word.to_be_bytes());
+                }
+                word.is_some()
+            }
+        };
+        if !moved {
+            return Err(format!(
+                "The address 0x{value:x} held at 0x{:x} does not fit there once the executable is linked at 0x{base:x}.",
+                base + address
+            ));
+        }
+    }
+    Ok(image)
+}
+
+fn align_to(offset: usize, alignment: usize) -> usize {
+    offset.div_ceil(alignment) * alignment
+}
+
+/// Writes the fields of an ELF64 file in the file's byte order.
+struct ElfWriter {
+    bytes: Vec<u8>,
+    little_endian: bool,
+}
+
+impl ElfWriter {
+    fn half(&mut self, value: u16) {
+        self.bytes.extend(if self.little_endian {
+            value.to_le_bytes()
+        } else {
+            value.to_be_bytes()
+        });
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.bytes.extend(if self.little_endian {
+            value.to_le_bytes()
+        } else {
+            value.to_be_bytes()
+        });
+    }
+
+    fn u64(&mut self, value: u64) {
+        self.bytes.extend(if self.little_endian {
+            value.to_le_bytes()
+        } else {
+            value.to_be_bytes()
+        });
+    }
+
+    /// Writes a section header. `placement` is the section's address, offset
+    /// in the file, and size, `link_info` is its link and info fields, and
+    /// `alignment_entry_size` is its alignment and the size of each entry of a
+    /// table.
+    fn section_header(
+        &mut self,
+        name: u32,
+        section_type: u32,
+        flags: u64,
+        placement: (usize, usize, usize),
+        link_info: (u32, u32),
+        alignment_entry_size: (u64, u64),
+    ) {
+        let (address, offset, size) = placement;
+        self.u32(name);
+        self.u32(section_type);
+        self.u64(flags);
+        self.u64(address as u64);
+        self.u64(offset as u64);
+        self.u64(size as u64);
+        self.u32(link_info.0);
+        self.u32(link_info.1);
+        self.u64(alignment_entry_size.0);
+        self.u64(alignment_entry_size.1);
+    }
+}
+
+struct ProgramHeader {
+    segment_type: u32,
+    flags: u32,
+    offset: usize,
+    address: usize,
+    file_size: usize,
+    memory_size: usize,
+}
+
+struct SectionHeader {
+    section_type: u32,
+    flags: u64,
+    address: usize,
+    offset: usize,
+    size: usize,
+    link: u32,
+    entry_size: usize,
+}
+
+/// Reads the fields of an ELF file in the file's class and byte order.
+struct ElfReader<'a> {
+    bytes: &'a [u8],
+    is_64_bit: bool,
+    little_endian: bool,
+}
+
+impl<'a> ElfReader<'a> {
+    fn slice(&self, offset: usize, length: usize) -> Result<&'a [u8], String> {
+        offset
+            .checked_add(length)
+            .and_then(|end| self.bytes.get(offset..end))
+            .ok_or_else(|| "The ELF file ends before all of its contents.".to_string())
+    }
+
+    /// Finds the offset of an entry of a table of entries of the given size,
+    /// such as the program headers.
+    fn entry(&self, table: usize, index: usize, size: usize) -> Result<usize, String> {
+        index
+            .checked_mul(size)
+            .and_then(|offset| offset.checked_add(table))
+            .ok_or_else(|| "The ELF file ends before all of its contents.".to_string())
+    }
+
+    fn byte(&self, offset: usize) -> Result<u8, String> {
+        Ok(self.slice(offset, 1)?[0])
+    }
+
+    fn half(&self, offset: usize) -> Result<u16, String> {
+        let bytes = self.slice(offset, 2)?.try_into().unwrap();
+        Ok(if self.little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    }
+
+    fn u32(&self, offset: usize) -> Result<u32, String> {
+        let bytes = self.slice(offset, 4)?.try_into().unwrap();
+        Ok(if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    /// Reads an address, offset, or size, which is 4 bytes in ELF32 and 8 bytes
+    /// in ELF64.
+    fn word(&self, offset: usize) -> Result<usize, String> {
+        if !self.is_64_bit {
+            return Ok(self.u32(offset)? as usize);
+        }
+        let bytes = self.slice(offset, 8)?.try_into().unwrap();
+        let value = if self.little_endian {
+            u64::from_le_bytes(bytes)
+        } else {
+            u64::from_be_bytes(bytes)
+        };
+        usize::try_from(value).map_err(|_| format!("0x{value:x} is too large to load."))
+    }
+
+    fn program_header(&self, header: usize) -> Result<ProgramHeader, String> {
+        if self.is_64_bit {
+            Ok(ProgramHeader {
+                segment_type: self.u32(header)?,
+                flags: self.u32(header + 4)?,
+                offset: self.word(header + 8)?,
+                address: self.word(header + 16)?,
+                file_size: self.word(header + 32)?,
+                memory_size: self.word(header + 40)?,
+            })
+        } else {
+            Ok(ProgramHeader {
+                segment_type: self.u32(header)?,
+                offset: self.word(header + 4)?,
+                address: self.word(header + 8)?,
+                file_size: self.word(header + 16)?,
+                memory_size: self.word(header + 20)?,
+                flags: self.u32(header + 24)?,
+            })
+        }
+    }
+
+    fn section_header(&self, header: usize) -> Result<SectionHeader, String> {
+        if self.is_64_bit {
+            Ok(SectionHeader {
+                section_type: self.u32(header + 4)?,
+                flags: self.word(header + 8)? as u64,
+                address: self.word(header + 16)?,
+                offset: self.word(header + 24)?,
+                size: self.word(header + 32)?,
+                link: self.u32(header + 40)?,
+                entry_size: self.word(header + 56)?,
+            })
+        } else {
+            Ok(SectionHeader {
+                section_type: self.u32(header + 4)?,
+                flags: self.word(header + 8)? as u64,
+                address: self.word(header + 12)?,
+                offset: self.word(header + 16)?,
+                size: self.word(header + 20)?,
+                link: self.u32(header + 24)?,
+                entry_size: self.word(header + 36)?,
+            })
+        }
+    }
+
+    /// Adds the defined functions, variables, and labels of a symbol table to
+    /// symbols, and the global ones among them to global_symbols. Section and
+    /// file symbols are left out.
+    fn read_symbols(
+        &self,
+        table: &SectionHeader,
+        strings: &SectionHeader,
+        symbols: &mut HashMap<String, usize>,
+        global_symbols: &mut HashSet<String>,
+    ) -> Result<(), String> {
+        let entry_size = if table.entry_size == 0 {
+            if self.is_64_bit {
+                24
+            } else {
+                16
+            }
+        } else {
+            table.entry_size
+        };
+        let names = self.slice(strings.offset, strings.size)?;
+        let end = table
+            .offset
+            .checked_add(table.size)
+            .ok_or_else(|| "The ELF file ends before all of its contents.".to_string())?;
+
+        for symbol in (table.offset..end).step_by(entry_size) {
+            let (name, info, section, value) = if self.is_64_bit {
+                (
+                    self.u32(symbol)?,
+                    self.byte(symbol + 4)?,
+                    self.half(symbol + 6)?,
+                    self.word(symbol + 8)?,
+                )
+            } else {
+                (
+                    self.u32(symbol)?,
+                    self.byte(symbol + 12)?,
+                    self.half(symbol + 14)?,
+                    self.word(symbol + 4)?,
+                )
+            };
+            if section == SHN_UNDEF || !matches!(info & 0xf, STT_NOTYPE | STT_OBJECT | STT_FUNC) {
+                continue;
+            }
+            let Some(name) = names.get(name as usize..).and_then(|names| {
+                let end = names.iter().position(|byte| *byte == 0)?;
+                std::str::from_utf8(&names[..end]).ok()
+            }) else {
+                continue;
+            };
+            if !name.is_empty() {
+                symbols.insert(name.to_string(), value);
+                if info >> 4 == STB_GLOBAL {
+                    global_symbols.insert(name.to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,225 @@
+//! A cycle-counting timer and a small interrupt controller shared by the datapaths.
+//!
+//! The timer follows the MIPS CP0 `Count`/`Compare` and RISC-V `mtime`/`mtimecmp`
+//! model: the count advances by one for every instruction the datapath completes,
+//! and the timer interrupt is pending for as long as the count has reached a
+//! non-zero compare value. Writing a new compare value acknowledges the interrupt.
+//!
+//! Neither datapath implements the privileged register instructions, so both
+//! expose the timer and controller to programs through the same memory-mapped
+//! window starting at [`MMIO_BASE`]. Each register is 64 bits wide; word-sized
+//! loads return the lower 32 bits, and word-sized stores zero-extend.
+//!
+//! # Taking an Interrupt
+//!
+//! At every instruction boundary the datapath asks the controller whether an
+//! interrupt should be taken. This happens when the global enable bit is set and
+//! at least one line is both pending and enabled. When taken, the controller
+//! saves the PC of the next instruction to the return address register, records
+//! the line in the cause register, clears the global enable bit, and the datapath
+//! jumps to the address in the vector register.
+//!
+//! A handler returns by acknowledging the interrupt, setting the global enable
+//! bit again, and jumping to the saved return address.
+
+use serde::{Deserialize, Serialize};
+
+/// The first address of the memory-mapped interrupt controller window.
+pub const MMIO_BASE: u64 = 0x0200_0000;
+
+/// The first address past the end of the memory-mapped interrupt controller window.
+pub const MMIO_END: u64 = 0x0201_0000;
+
+/// Timer compare register (`Compare` on MIPS, `mtimecmp` on RISC-V).
+/// A value of 0 disarms the timer.
+pub const TIMECMP_ADDRESS: u64 = 0x0200_4000;
+
+/// Timer count register (`Count` on MIPS, `mtime` on RISC-V).
+pub const TIME_ADDRESS: u64 = 0x0200_BFF8;
+
+/// Bitmask of the enabled interrupt lines.
+pub const INTERRUPT_ENABLE_ADDRESS: u64 = 0x0200_C000;
+
+/// Bitmask of the pending interrupt lines. Writing a 1 to a bit clears it.
+pub const INTERRUPT_PENDING_ADDRESS: u64 = 0x0200_C008;
+
+/// Global interrupt enable. Bit 0 enables interrupts.
+pub const INTERRUPT_STATUS_ADDRESS: u64 = 0x0200_C010;
+
+/// The address of the interrupt handler.
+pub const INTERRUPT_VECTOR_ADDRESS: u64 = 0x0200_C018;
+
+/// The address of the instruction to return to after handling an interrupt.
+pub const INTERRUPT_RETURN_ADDRESS: u64 = 0x0200_C020;
+
+/// The line number of the most recently taken interrupt.
+pub const INTERRUPT_CAUSE_ADDRESS: u64 = 0x0200_C028;
+
+/// The interrupt line used by the timer. This matches both the MIPS `IP7`
+/// timer line and the RISC-V machine timer interrupt (`MTIP`) bit.
+pub const TIMER_INTERRUPT: u32 = 7;
+
+/// A timer counting emulated cycles.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Timer {
+    /// The number of instructions completed since the timer was reset.
+    pub count: u64,
+
+    /// The count at which the timer interrupt becomes pending. 0 disarms the timer.
+    pub compare: u64,
+}
+
+impl Timer {
+    /// Advance the timer by a single cycle.
+    pub fn tick(&mut self) {
+        self.count = self.count.wrapping_add(1);
+    }
+
+    /// Whether the timer is currently requesting an interrupt.
+    pub fn is_firing(&self) -> bool {
+        self.compare != 0 && self.count >= self.compare
+    }
+}
+
+/// The interrupt controller checked by the datapath between instructions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InterruptController {
+    pub timer: Timer,
+
+    /// Bitmask of the interrupt lines allowed to interrupt the program.
+    pub enabled: u32,
+
+    /// Bitmask of the interrupt lines currently requesting an interrupt.
+    pub pending: u32,
+
+    /// Global interrupt enable. Cleared automatically when an interrupt is taken.
+    pub global_enable: bool,
+
+    /// The address jumped to when an interrupt is taken.
+    pub vector: u64,
+
+    /// The address of the instruction that was interrupted.
+    pub return_address: u64,
+
+    /// The line of the most recently taken interrupt.
+    pub cause: u32,
+}
+
+impl InterruptController {
+    /// Advance the timer by one cycle and update the timer interrupt line.
+    pub fn tick(&mut self) {
+        self.timer.tick();
+        self.update_timer_line();
+    }
+
+    /// Mark an interrupt line as pending.
+    pub fn raise(&mut self, line: u32) {
+        self.pending |= 1 << line;
+    }
+
+    /// Clear a pending interrupt line.
+    pub fn clear(&mut self, line: u32) {
+        self.pending &= !(1 << line);
+    }
+
+    /// Whether a given interrupt line is pending.
+    pub fn is_pending(&self, line: u32) -> bool {
+        self.pending & (1 << line) != 0
+    }
+
+    /// Whether a given interrupt line is enabled.
+    pub fn is_enabled(&self, line: u32) -> bool {
+        self.enabled & (1 << line) != 0
+    }
+
+    /// Returns the line of the interrupt that would be taken at the next
+    /// instruction boundary, if any. Higher lines have higher priority.
+    pub fn next_interrupt(&self) -> Option<u32> {
+        let active = self.pending & self.enabled;
+        if !self.global_enable || active == 0 {
+            return None;
+        }
+
+        Some(31 - active.leading_zeros())
+    }
+
+    /// Take the highest-priority active interrupt, if any. `pc` is the address
+    /// of the next instruction that would have executed. Returns the address
+    /// the datapath should jump to.
+    pub fn take_interrupt(&mut self, pc: u64) -> Option<u64> {
+        let line = self.next_interrupt()?;
+
+        self.return_address = pc;
+        self.cause = line;
+        self.global_enable = false;
+
+        Some(self.vector)
+    }
+
+    /// Whether an address falls inside the memory-mapped window.
+    pub fn contains_address(address: u64) -> bool {
+        (MMIO_BASE..MMIO_END).contains(&address)
+    }
+
+    /// Read a memory-mapped register. Returns [`None`] if the address does
+    /// not belong to the controller. Unused addresses inside the window read as 0.
+    pub fn mmio_read(&self, address: u64) -> Option<u64> {
+        if !Self::contains_address(address) {
+            return None;
+        }
+
+        Some(match address {
+            TIMECMP_ADDRESS => self.timer.compare,
+            TIME_ADDRESS => self.timer.count,
+            INTERRUPT_ENABLE_ADDRESS => self.enabled as u64,
+            INTERRUPT_PENDING_ADDRESS => self.pending as u64,
+            INTERRUPT_STATUS_ADDRESS => self.global_enable as u64,
+            INTERRUPT_VECTOR_ADDRESS => self.vector,
+            INTERRUPT_RETURN_ADDRESS => self.return_address,
+            INTERRUPT_CAUSE_ADDRESS => self.cause as u64,
+            _ => 0,
+        })
+    }
+
+    /// Write a memory-mapped register. Returns `false` if the address does not
+    /// belong to the controller. Writes to unused addresses inside the window
+    /// are ignored.
+    pub fn mmio_write(&mut self, address: u64, data: u64) -> bool {
+        if !Self::contains_address(address) {
+            return false;
+        }
+
+        match address {
+            TIMECMP_ADDRESS => {
+                self.timer.compare = data;
+                self.update_timer_line();
+            }
+            TIME_ADDRESS => {
+                self.timer.count = data;
+                self.update_timer_line();
+            }
+            INTERRUPT_ENABLE_ADDRESS => self.enabled = data as u32,
+            INTERRUPT_PENDING_ADDRESS => {
+                self.pending &= !(data as u32);
+                // The timer line is level-triggered and stays pending until
+                // the compare register is moved past the count.
+                self.update_timer_line();
+            }
+            INTERRUPT_STATUS_ADDRESS => self.global_enable = data & 1 == 1,
+            INTERRUPT_VECTOR_ADDRESS => self.vector = data,
+            INTERRUPT_RETURN_ADDRESS => self.return_address = data,
+            INTERRUPT_CAUSE_ADDRESS => self.cause = data as u32,
+            _ => (),
+        }
+
+        true
+    }
+
+    fn update_timer_line(&mut self) {
+        if self.timer.is_firing() {
+            self.raise(TIMER_INTERRUPT);
+        } else {
+            self.clear(TIMER_INTERRUPT);
+        }
+    }
+}
@@ -37,6 +37,9 @@
 //! - Unlike the MIPS64 version 6 specification for the `jal` and `jalr` instructions,
 //!   `PC + 4` is stored in `GPR[31]`, *not* `PC + 8`, as there is no implementation of
 //!   branch delay slots.
+//! - The CP0 `Count`, `Compare`, `Status`, `Cause`, and `EPC` registers are not
+//!   accessed with `mfc0`/`mtc0`. Instead, the timer and interrupt controller are
+//!   memory-mapped. (See [`crate::emulation_core::interrupt`].)
 //!
 //! # Notes on `is_halted`
 //!
@@ -55,6 +58,7 @@
 use super::{coprocessor::MipsFpCoprocessor, gp_registers::GpRegisters, memory::Memory};
 use crate::emulation_core::architectures::DatapathRef;
 use crate::emulation_core::datapath::{DatapathUpdateSignal, Syscall};
+use crate::emulation_core::interrupt::InterruptController;
 use crate::emulation_core::mips::fp_registers::FpRegisterType;
 use crate::emulation_core::mips::gp_registers::GpRegisterType::{A0, A1};
 use crate::emulation_core::stack::{Stack, StackFrame};
@@ -78,6 +82,9 @@ pub struct MipsDatapath {
     /// The stack of instructions that have been executed
     pub stack: Stack,
 
+    /// The timer and interrupt controller, checked between instructions.
+    pub interrupts: InterruptController,
+
     /// Boolean value that states whether the datapath has halted.
     ///
     /// This is set in the event of any `syscall` instruction. To unset this,
@@ -219,6 +226,7 @@ fn default() -> Self {
             state: DatapathState::default(),
             current_stage: Stage::default(),
             stack: Stack::default(),
+            interrupts: InterruptController::default(),
             is_halted: true,
         };
 
@@ -523,6 +531,10 @@ fn stage_writeback(&mut self) -> DatapathUpdateSignal {
             }
         }
 
+        // The instruction is complete, so this is the boundary at which
+        // the timer advances and pending interrupts are taken.
+        self.check_interrupts();
+
         DatapathUpdateSignal {
             changed_state: true,
             changed_coprocessor_state: true,
@@ -530,10 +542,23 @@ fn stage_writeback(&mut self) -> DatapathUpdateSignal {
             changed_coprocessor_registers: self.coprocessor.signals.fpu_reg_write
                 == FpuRegWrite::YesWrite,
             changed_stack,
+            changed_interrupts: true,
             ..Default::default()
         }
     }
 
+    // ==================== Interrupt Handling ====================
+    /// Advance the timer by one cycle and, if an enabled interrupt is
+    /// pending, redirect the PC to the interrupt vector. The address of the
+    /// instruction that would have executed next is saved in the controller.
+    fn check_interrupts(&mut self) {
+        self.interrupts.tick();
+
+        if let Some(vector) = self.interrupts.take_interrupt(self.registers.pc) {
+            self.registers.pc = vector;
+        }
+    }
+
     // ================== Instruction Fetch (IF) ==================
     /// Load the raw binary instruction from memory and into the
     /// datapath. If there is an error with loading the word, assume
@@ -1264,6 +1289,16 @@ fn calc_cpu_branch_signal(&mut self) {
     fn memory_read(&mut self) {
         let address = self.state.alu_result;
 
+        // Accesses to the interrupt controller's window never reach memory.
+        if let Some(data) = self.interrupts.mmio_read(address) {
+            // Words are sign-extended as lw requires, the same as on RISC-V.
+            self.state.memory_data = match self.signals.reg_width {
+                RegWidth::Word => data as u32 as i32 as i64 as u64,
+                RegWidth::DoubleWord => data,
+            };
+            return;
+        }
+
         // Load memory, first choosing the correct load function by the
         // RegWidth control signal, then reading the result from this
         // memory access.
@@ -1284,6 +1319,15 @@ fn memory_write(&mut self) {
             MemWriteSrc::FloatingPointUnit => self.coprocessor.get_fp_register_to_memory(),
         };
 
+        // Accesses to the interrupt controller's window never reach memory.
+        let mmio_data = match self.signals.reg_width {
+            RegWidth::Word => self.state.write_data as u32 as u64,
+            RegWidth::DoubleWord => self.state.write_data,
+        };
+        if self.interrupts.mmio_write(address, mmio_data) {
+            return;
+        }
+
         // Choose the correct store function based on the RegWidth
         // control signal.
         match self.signals.reg_width {
@@ -500,14 +500,11 @@ pub fn get_string_version(
                 let str_rs = find_register_name(i_type.rs).unwrap_or("##");
                 let str_rt = find_register_name(i_type.rt).unwrap_or("##");
 
-                // Check if immediate is negative
-                let mut str_immediate = format!("{}", i_type.immediate);
-                if i_type.immediate & 0x800 != 0 {
-                    str_immediate = format!(
-                        "-{}",
-                        (!(i_type.immediate) + 1) & 0b00000000000000000000111111111111
-                    );
-                }
+                // The immediate is a signed 16-bit value
+                let str_immediate = format!("{}", i_type.immediate as i16);
+
+                // Branches are relative to the instruction after the branch
+                let branch_target = branch_target(instruction_number, i_type.immediate);
 
                 match i_type.op {
                     OPCODE_ADDI => {
@@ -565,7 +562,7 @@ pub fn get_string_version(
                     OPCODE_BEQ => {
                         let mut str_label = String::new();
                         for label in labels {
-                            if label.1 == (i_type.immediate as usize) + instruction_number * 4 {
+                            if Some(label.1) == branch_target {
                                 str_label = label.0;
                             }
                         }
@@ -575,7 +572,7 @@ pub fn get_string_version(
                     OPCODE_BNE => {
                         let mut str_label = String::new();
                         for label in labels {
-                            if label.1 == (i_type.immediate as usize) + instruction_number * 4 {
+                            if Some(label.1) == branch_target {
                                 str_label = label.0;
                             }
                         }
@@ -732,12 +729,17 @@ pub fn get_string_version(
             MipsInstruction::FpuBranchType(fpu_branch_type) => {
                 // FPU Branching instructions:
                 // bc1t, bc1f
-                // TODO add support for labels
 
                 match fpu_branch_type.bcc1 {
                     SUB_BC => {
-                        let str_offset = fpu_branch_type.offset.to_string();
-                        let str_offset = str_offset.as_str();
+                        let branch_target =
+                            branch_target(instruction_number, fpu_branch_type.offset);
+                        let mut str_offset = fpu_branch_type.offset.to_string();
+                        for label in labels {
+                            if Some(label.1) == branch_target {
+                                str_offset = label.0;
+                            }
+                        }
 
                         match fpu_branch_type.tf {
                             1 => {
@@ -811,6 +813,13 @@ pub fn get_string_version(
     }
 }
 
+/// Returns the byte address a branch at the given instruction number jumps to, given
+/// its offset in instructions from the instruction after the branch.
+pub fn branch_target(instruction_number: usize, offset: u16) -> Option<usize> {
+    let target = (instruction_number as i64 + 1 + offset as i16 as i64) * 4;
+    usize::try_from(target).ok()
+}
+
 pub fn find_register_name(binary: u8) -> Option<&'static str> {
     for register in GP_REGISTERS {
         if register.binary == binary {
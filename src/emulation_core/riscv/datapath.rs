@@ -26,6 +26,7 @@
 use super::{super::mips::memory::Memory, registers::RiscGpRegisters};
 use crate::emulation_core::architectures::DatapathRef;
 use crate::emulation_core::datapath::{DatapathUpdateSignal, Syscall};
+use crate::emulation_core::interrupt::InterruptController;
 use crate::emulation_core::riscv::registers::RiscGpRegisterType::{X10, X11};
 use crate::emulation_core::stack::Stack;
 use crate::emulation_core::stack::StackFrame;
@@ -47,6 +48,9 @@ pub struct RiscDatapath {
     /// The currently-active stage in the datapath.
     pub current_stage: RiscStage,
 
+    /// The timer and interrupt controller, checked between instructions.
+    pub interrupts: InterruptController,
+
     /// Boolean value that states whether the datapath has halted.
     ///
     /// This is set in the event of any `syscall` instruction. To unset this,
@@ -196,6 +200,7 @@ fn default() -> Self {
             datapath_signals: DatapathSignals::default(),
             state: RiscDatapathState::default(),
             current_stage: RiscStage::default(),
+            interrupts: InterruptController::default(),
             is_halted: true,
         };
 
@@ -489,6 +494,10 @@ fn stage_writeback(&mut self) -> DatapathUpdateSignal {
             }
         }
 
+        // The instruction is complete, so this is the boundary at which
+        // the timer advances and pending interrupts are taken.
+        self.check_interrupts();
+
         DatapathUpdateSignal {
             changed_state: true,
             changed_coprocessor_state: true,
@@ -496,10 +505,23 @@ fn stage_writeback(&mut self) -> DatapathUpdateSignal {
             changed_coprocessor_registers: self.coprocessor.signals.fpu_reg_write
                 == FpuRegWrite::YesWrite,
             changed_stack,
+            changed_interrupts: true,
             ..Default::default()
         }
     }
 
+    // ==================== Interrupt Handling ====================
+    /// Advance the timer by one cycle and, if an enabled interrupt is
+    /// pending, redirect the PC to the interrupt vector. The address of the
+    /// instruction that would have executed next is saved in the controller.
+    fn check_interrupts(&mut self) {
+        self.interrupts.tick();
+
+        if let Some(vector) = self.interrupts.take_interrupt(self.registers.pc) {
+            self.registers.pc = vector;
+        }
+    }
+
     // ================== Instruction Fetch (IF) ==================
     /// Load the raw binary instruction from memory and into the
     /// datapath. If there is an error with loading the word, assume
@@ -1098,6 +1120,21 @@ fn calc_cpu_branch_signal(&mut self) {
     fn memory_read(&mut self) {
         let address = self.state.alu_result;
 
+        // Accesses to the interrupt controller's window never reach memory.
+        if let Some(data) = self.interrupts.mmio_read(address) {
+            self.state.memory_data = match self.signals.read_write {
+                ReadWrite::LoadByte => data as u8 as i8 as i64 as u64,
+                ReadWrite::LoadByteUnsigned => data as u8 as u64,
+                ReadWrite::LoadHalf => data as u16 as i16 as i64 as u64,
+                ReadWrite::LoadHalfUnsigned => data as u16 as u64,
+                ReadWrite::LoadWord => data as u32 as i32 as i64 as u64,
+                ReadWrite::LoadWordUnsigned => data as u32 as u64,
+                ReadWrite::LoadDouble => data,
+                _ => 0,
+            };
+            return;
+        }
+
         // Load memory, first choosing the correct load function by the
         // RegWidth control signal, then reading the result from this
         // memory access.
@@ -1124,6 +1161,17 @@ fn memory_write(&mut self) {
             MemWriteSrc::FloatingPointUnit => self.coprocessor.get_fp_register_to_memory(),
         };
 
+        // Accesses to the interrupt controller's window never reach memory.
+        let mmio_data = match self.signals.read_write {
+            ReadWrite::StoreByte => self.state.write_data as u8 as u64,
+            ReadWrite::StoreHalf => self.state.write_data as u16 as u64,
+            ReadWrite::StoreWord => self.state.write_data as u32 as u64,
+            _ => self.state.write_data,
+        };
+        if self.interrupts.mmio_write(address, mmio_data) {
+            return;
+        }
+
         // Choose the correct store function based on the RegWidth
         // control signal.
         match self.signals.read_write {
@@ -1,7 +1,23 @@
 //! The parser for the project.
 
 pub mod assembling;
+pub mod assembly_cache;
+pub mod completion;
+pub mod disassembler;
+pub mod expressions;
+pub mod fix_its;
+pub mod image_export;
+pub mod layout;
+pub mod linker;
+pub mod lints;
+pub mod listing;
+pub mod local_labels;
+pub mod macro_parsing;
+pub mod navigation;
 pub mod parser_assembler_main;
 pub mod parser_structs_and_enums;
 pub mod parsing;
+pub mod profiles;
+pub mod project;
 pub mod pseudo_instruction_parsing;
+pub mod source_map;
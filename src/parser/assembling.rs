@@ -1,9 +1,17 @@
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::parser::expressions::{
+    decode_string, evaluate_expression, is_symbol_char, read_char, read_relocation,
+    references_labels, ExpressionError, Relocation,
+};
+use crate::parser::layout::place;
+use crate::parser::local_labels::read_local_label_reference;
 use crate::parser::parser_assembler_main::append_binary;
 use crate::parser::parser_structs_and_enums::ErrorType::{
     ImmediateOutOfBounds, ImproperlyFormattedASCII, ImproperlyFormattedChar,
     IncorrectNumberOfOperands, IncorrectRegisterTypeFP, IncorrectRegisterTypeGP,
-    InvalidMemorySyntax, LabelNotFound, NonASCIIChar, NonASCIIString, NonFloatImmediate,
-    NonIntImmediate, UnrecognizedDataType, UnrecognizedFPRegister, UnrecognizedGPRegister,
+    InvalidMemorySyntax, InvalidRelocation, LabelNotFound, LocalLabelNotFound, NonASCIIChar,
+    NonASCIIString, NonFloatImmediate, NonIntImmediate, UnrecognizedDataType,
+    UnrecognizedFPRegister, UnrecognizedGPRegister,
 };
 use crate::parser::parser_structs_and_enums::OperandType::{
     Immediate, LabelAbsolute, LabelRelative, MemoryAddress, RegisterFP, RegisterGP, ShiftAmount,
@@ -11,10 +19,11 @@
 };
 use crate::parser::parser_structs_and_enums::RegisterType::{FloatingPoint, GeneralPurpose};
 use crate::parser::parser_structs_and_enums::TokenType::{
-    Byte, Float, Half, Space, Word, ASCII, ASCIIZ,
+    Byte, Dword, Float, Half, Space, Word, ASCII, ASCIIZ,
 };
 use crate::parser::parser_structs_and_enums::{
-    Data, Error, Instruction, OperandType, RegisterType, TokenType, FP_REGISTERS, GP_REGISTERS,
+    Data, Error, Instruction, OperandType, RegisterType, Token, TokenType, FP_REGISTERS,
+    GP_REGISTERS,
 };
 use std::collections::HashMap;
 
@@ -36,6 +45,7 @@ pub fn read_operands(
             token_causing_error: instruction.operator.token_name.clone(),
             start_end_columns: instruction.operator.start_end_columns,
             message: "".to_string(),
+            fix_its: Vec::new(),
         });
         return instruction;
     }
@@ -204,6 +214,7 @@ pub fn read_operands_riscv(
             token_causing_error: instruction.operator.token_name.clone(),
             start_end_columns: instruction.operator.start_end_columns,
             message: "".to_string(),
+            fix_its: Vec::new(),
         });
         return instruction;
     }
@@ -374,6 +385,32 @@ pub fn read_operands_riscv(
     instruction
 }
 
+///Creates the error for a label that is not defined. A local label reference such as `1f` is given an error of its own,
+/// since the label may well be defined, just not in the direction the reference looks for it.
+fn label_not_found(label: &str, start_end_columns: (usize, usize)) -> Error {
+    let Some((number, forward)) = read_local_label_reference(label) else {
+        return Error {
+            error_name: LabelNotFound,
+            token_causing_error: label.to_string(),
+            start_end_columns,
+            message: "".to_string(),
+            fix_its: Vec::new(),
+        };
+    };
+    let message = if forward {
+        format!("There is no following label {number}. `{label}` refers to the first `{number}:` after this line.\n")
+    } else {
+        format!("There is no preceding label {number}. `{label}` refers to the last `{number}:` on or before this line.\n")
+    };
+    Error {
+        error_name: LocalLabelNotFound,
+        token_causing_error: label.to_string(),
+        start_end_columns,
+        message,
+        fix_its: Vec::new(),
+    }
+}
+
 ///Returns distance to a labeled instruction relative to the instruction after the current instruction.
 /// The value represents instruction numbers NOT bytes.
 pub fn read_label_relative(
@@ -385,15 +422,7 @@ pub fn read_label_relative(
     let result = labels.get(given_label);
 
     if result.is_none() {
-        return (
-            0,
-            Some(Error {
-                error_name: LabelNotFound,
-                token_causing_error: given_label.to_string(),
-                start_end_columns,
-                message: "".to_string(),
-            }),
-        );
+        return (0, Some(label_not_found(given_label, start_end_columns)));
     }
     let mut offset = *result.unwrap() as i32;
     offset -= (current_instruction_number as i32 + 1) << 2;
@@ -411,15 +440,7 @@ pub fn read_label_absolute(
 ) -> (u32, Option<Error>) {
     let result = labels.get(given_label);
     if result.is_none() {
-        return (
-            0,
-            Some(Error {
-                error_name: LabelNotFound,
-                token_causing_error: given_label.to_string(),
-                start_end_columns,
-                message: "".to_string(),
-            }),
-        );
+        return (0, Some(label_not_found(given_label, start_end_columns)));
     }
     ((*result.unwrap() >> 2) as u32, None)
 }
@@ -432,8 +453,8 @@ pub fn read_memory_address(
 ) -> (u32, u32, Option<Vec<Error>>) {
     //the indices of the open and close parentheses are checked.
     //If either are missing or they are in the wrong order, an error is returned
-    let open_index = orig_string.find('(');
-    let close_index = orig_string.find(')');
+    let open_index = orig_string.rfind('(');
+    let close_index = orig_string.rfind(')');
     if close_index.is_none() || open_index.is_none() || close_index < open_index {
         return (
             0,
@@ -443,6 +464,7 @@ pub fn read_memory_address(
                 token_causing_error: orig_string.to_string(),
                 start_end_columns,
                 message: "".to_string(),
+                fix_its: Vec::new(),
             }]),
         );
     }
@@ -462,6 +484,7 @@ pub fn read_memory_address(
                 token_causing_error: orig_string.to_string(),
                 start_end_columns,
                 message: "".to_string(),
+                fix_its: Vec::new(),
             }]),
         );
     }
@@ -501,8 +524,8 @@ pub fn read_memory_address_riscv(
 ) -> (u32, u32, Option<Vec<Error>>) {
     //the indices of the open and close parentheses are checked.
     //If either are missing or they are in the wrong order, an error is returned
-    let open_index = orig_string.find('(');
-    let close_index = orig_string.find(')');
+    let open_index = orig_string.rfind('(');
+    let close_index = orig_string.rfind(')');
     if close_index.is_none() || open_index.is_none() || close_index < open_index {
         return (
             0,
@@ -512,6 +535,7 @@ pub fn read_memory_address_riscv(
                 token_causing_error: orig_string.to_string(),
                 start_end_columns,
                 message: "".to_string(),
+                fix_its: Vec::new(),
             }]),
         );
     }
@@ -531,6 +555,7 @@ pub fn read_memory_address_riscv(
                 token_causing_error: orig_string.to_string(),
                 start_end_columns,
                 message: "".to_string(),
+                fix_its: Vec::new(),
             }]),
         );
     }
@@ -584,6 +609,7 @@ pub fn read_register(
                             token_causing_error: register.to_string(),
                             start_end_columns,
                             message: "".to_string(),
+                            fix_its: Vec::new(),
                         }),
                     )
                 } else {
@@ -594,6 +620,7 @@ pub fn read_register(
                             token_causing_error: register.to_string(),
                             start_end_columns,
                             message: "".to_string(),
+                            fix_its: Vec::new(),
                         }),
                     )
                 }
@@ -613,6 +640,7 @@ pub fn read_register(
                             token_causing_error: register.to_string(),
                             start_end_columns,
                             message: "".to_string(),
+                            fix_its: Vec::new(),
                         }),
                     )
                 } else {
@@ -623,6 +651,7 @@ pub fn read_register(
                             token_causing_error: register.to_string(),
                             start_end_columns,
                             message: "".to_string(),
+                            fix_its: Vec::new(),
                         }),
                     )
                 }
@@ -653,6 +682,7 @@ pub fn read_register_riscv(
                             token_causing_error: register.to_string(),
                             start_end_columns,
                             message: "".to_string(),
+                            fix_its: Vec::new(),
                         }),
                     )
                 } else {
@@ -663,6 +693,7 @@ pub fn read_register_riscv(
                             token_causing_error: register.to_string(),
                             start_end_columns,
                             message: "".to_string(),
+                            fix_its: Vec::new(),
                         }),
                     )
                 }
@@ -682,6 +713,7 @@ pub fn read_register_riscv(
                             token_causing_error: register.to_string(),
                             start_end_columns,
                             message: "".to_string(),
+                            fix_its: Vec::new(),
                         }),
                     )
                 } else {
@@ -692,6 +724,7 @@ pub fn read_register_riscv(
                             token_causing_error: register.to_string(),
                             start_end_columns,
                             message: "".to_string(),
+                            fix_its: Vec::new(),
                         }),
                     )
                 }
@@ -753,35 +786,60 @@ pub fn match_fp_register_riscv(given_string: &str) -> Option<u8> {
 ///This function takes a string representation of an immediate value and the number of bits available to represent it
 /// and attempts to translate it to an actual integer. If the value cannot be cast to int or is too big to be represented
 /// by the available bits, an error is returned.
+///Reads a `.word` written in the text section, which places its value in memory as it is written rather than
+/// assembling an instruction. Words that do not decode to an instruction can then sit between the instructions.
+pub fn read_raw_word(instruction: &mut Instruction) {
+    if instruction.operands.len() != 1 {
+        instruction.errors.push(Error {
+            error_name: IncorrectNumberOfOperands,
+            token_causing_error: instruction.operator.token_name.clone(),
+            start_end_columns: instruction.operator.start_end_columns,
+            message: "".to_string(),
+            fix_its: Vec::new(),
+        });
+        return;
+    }
+
+    instruction.operands[0].token_type = TokenType::Immediate;
+    let (value, error) = read_immediate(
+        &instruction.operands[0].token_name,
+        instruction.operands[0].start_end_columns,
+        32,
+    );
+    instruction.binary = value;
+    if let Some(error) = error {
+        instruction.errors.push(error);
+    }
+}
+
 pub fn read_immediate(
     given_text: &str,
     start_end_columns: (usize, usize),
     num_bits: u32,
 ) -> (u32, Option<Error>) {
-    //attempts to cast the text into a large int
-    let mut parse_results = given_text.parse::<i64>();
-
-    //if that results in an error, try to read it as hex
-    if parse_results.is_err() {
-        let removed_prefix = given_text.strip_prefix("0x");
-        if let Some(removed_prefix) = removed_prefix {
-            parse_results = i64::from_str_radix(removed_prefix, 16);
+    //the text may be a constant expression, but labels are not known here so any label makes the immediate invalid
+    let int_representation = match evaluate_expression(given_text, &HashMap::new()) {
+        Ok(int_representation) => int_representation,
+        Err(error) => {
+            let (error_name, message) = match error {
+                ExpressionError::NonASCII(_) => (NonASCIIChar, error.literal_message()),
+                ExpressionError::InvalidEscape(_) => {
+                    (ImproperlyFormattedChar, error.literal_message())
+                }
+                _ => (NonIntImmediate, "".to_string()),
+            };
+            return (
+                0,
+                Some(Error {
+                    error_name,
+                    token_causing_error: given_text.to_string(),
+                    start_end_columns,
+                    message,
+                    fix_its: Vec::new(),
+                }),
+            );
         }
-    }
-
-    //if there was an error typecasting, the function returns with an error to add to the instruction or data
-    if parse_results.is_err() {
-        return (
-            0,
-            Some(Error {
-                error_name: NonIntImmediate,
-                token_causing_error: given_text.to_string(),
-                start_end_columns,
-                message: "".to_string(),
-            }),
-        );
-    }
-    let int_representation = parse_results.unwrap();
+    };
 
     //finds the max and min values of a signed integer with specified number of bits
     let max_value = i64::pow(2, num_bits);
@@ -795,6 +853,7 @@ pub fn read_immediate(
                 token_causing_error: given_text.to_string(),
                 start_end_columns,
                 message: "".to_string(),
+                fix_its: Vec::new(),
             }),
         );
     }
@@ -802,35 +861,67 @@ pub fn read_immediate(
     (int_representation as u32, None)
 }
 
+///Reads a string such as `"Hello\n"` into the bytes it stands for, decoding its escape sequences.
+fn read_string(value: &Token) -> Result<Vec<u8>, Error> {
+    let text = value
+        .token_name
+        .strip_prefix('\"')
+        .and_then(|text| text.strip_suffix('\"'))
+        .filter(|text| !text.is_empty());
+    let result = match text {
+        Some(text) => decode_string(text),
+        None => Err(ExpressionError::Syntax),
+    };
+    result.map_err(|error| Error {
+        error_name: match error {
+            ExpressionError::NonASCII(_) => NonASCIIString,
+            _ => ImproperlyFormattedASCII,
+        },
+        token_causing_error: value.token_name.clone(),
+        start_end_columns: value.start_end_columns,
+        message: match error {
+            ExpressionError::Syntax => {
+                "Token recognized as ASCII does not start and or end with double quotes (\").\n"
+                    .to_string()
+            }
+            _ => error.literal_message(),
+        },
+        fix_its: Vec::new(),
+    })
+}
+
 ///Takes the data list and finds the actual values for each data entry that will be put into memory
 pub fn assemble_data_binary(data_list: &mut [Data]) -> Vec<u8> {
+    assemble_data_binary_at(data_list, 0)
+}
+
+///Assembles the data in the same way as assemble_data_binary for a data section starting at base_address, which
+/// placement directives such as `.align` and `.org` need to know where the data will be in memory.
+pub fn assemble_data_binary_at(data_list: &mut [Data], base_address: usize) -> Vec<u8> {
     let mut vec_of_data: Vec<u8> = Vec::new();
     for datum in data_list.iter_mut() {
         datum.data_number = vec_of_data.len();
         match &*datum.data_type.token_name.to_lowercase() {
+            ".align" | ".balign" | ".p2align" | ".org" | ".data" => {
+                //the gap up to where the next data is placed is filled with zeros
+                match place(
+                    &datum.data_type,
+                    datum.data_entries.first(),
+                    base_address + vec_of_data.len(),
+                    false,
+                ) {
+                    Ok(address) => vec_of_data.resize(address - base_address, 0),
+                    Err(error) => datum.errors.push(error),
+                }
+                datum.data_number = vec_of_data.len();
+            }
             ".ascii" => {
                 //pushes a string of characters to memory
                 for value in datum.data_entries.iter_mut() {
                     value.token_type = ASCII;
-                    let chars = value.token_name.as_bytes();
-                    if chars[0] != b'\"' || chars[chars.len() - 1] != b'\"' || chars.len() <= 2 {
-                        datum.errors.push(Error {
-                            error_name: ImproperlyFormattedASCII,
-                            token_causing_error: value.token_name.to_string(),
-                            start_end_columns: value.start_end_columns,
-                            message: "".to_string(),
-                        });
-                    } else if !chars.is_ascii() {
-                        datum.errors.push(Error {
-                            error_name: NonASCIIString,
-                            token_causing_error: value.token_name.to_string(),
-                            start_end_columns: value.start_end_columns,
-                            message: "".to_string(),
-                        })
-                    } else {
-                        for char in chars.iter().take(chars.len() - 1).skip(1) {
-                            vec_of_data.push(*char);
-                        }
+                    match read_string(value) {
+                        Ok(bytes) => vec_of_data.extend(bytes),
+                        Err(error) => datum.errors.push(error),
                     }
                 }
             }
@@ -838,25 +929,9 @@ pub fn assemble_data_binary(data_list: &mut [Data]) -> Vec<u8> {
                 //same as ascii but pushes a \0 to memory as well
                 for value in datum.data_entries.iter_mut() {
                     value.token_type = ASCIIZ;
-                    let chars = value.token_name.as_bytes();
-                    if chars[0] != b'\"' || chars[chars.len() - 1] != b'\"' || chars.len() <= 2 {
-                        datum.errors.push(Error {
-                            error_name: ImproperlyFormattedASCII,
-                            token_causing_error: value.token_name.to_string(),
-                            start_end_columns: value.start_end_columns,
-                            message: "".to_string(),
-                        });
-                    } else if !chars.is_ascii() {
-                        datum.errors.push(Error {
-                            error_name: NonASCIIString,
-                            token_causing_error: value.token_name.to_string(),
-                            start_end_columns: value.start_end_columns,
-                            message: "".to_string(),
-                        })
-                    } else {
-                        for char in chars.iter().take(chars.len() - 1).skip(1) {
-                            vec_of_data.push(*char);
-                        }
+                    match read_string(value) {
+                        Ok(bytes) => vec_of_data.extend(bytes),
+                        Err(error) => datum.errors.push(error),
                     }
                 }
                 vec_of_data.push(0);
@@ -866,27 +941,25 @@ pub fn assemble_data_binary(data_list: &mut [Data]) -> Vec<u8> {
                     value.token_type = Byte;
                     //this if block handles chars
                     if value.token_name.starts_with('\'') {
-                        if !value.token_name.is_ascii() {
-                            datum.errors.push(Error {
-                                error_name: NonASCIIChar,
-                                token_causing_error: value.token_name.to_string(),
-                                start_end_columns: value.start_end_columns,
-                                message: "".to_string(),
-                            });
-                        } else if value.token_name.len() != 3 || !value.token_name.ends_with('\'') {
-                            datum.errors.push(Error {
-                                error_name: ImproperlyFormattedChar,
-                                token_causing_error: value.token_name.clone().to_string(),
+                        match read_char(&value.token_name) {
+                            Ok(byte) => vec_of_data.push(byte),
+                            Err(error) => datum.errors.push(Error {
+                                error_name: match error {
+                                    ExpressionError::NonASCII(_) => NonASCIIChar,
+                                    _ => ImproperlyFormattedChar,
+                                },
+                                token_causing_error: value.token_name.clone(),
                                 start_end_columns: value.start_end_columns,
-                                message: "".to_string(),
-                            });
-                        } else {
-                            let mut chars = value.token_name.chars();
-                            chars.next();
-                            let char = chars.next().unwrap();
-                            vec_of_data.push(char as u8);
+                                message: error.literal_message(),
+                                fix_its: Vec::new(),
+                            }),
                         }
                     } else {
+                        //values that reference labels are filled in once the labels are known
+                        if references_labels(&value.token_name) {
+                            vec_of_data.push(0);
+                            continue;
+                        }
                         //otherwise we can assume it is an int
                         let immediate_results =
                             read_immediate(&value.token_name, value.start_end_columns, 8);
@@ -897,6 +970,29 @@ pub fn assemble_data_binary(data_list: &mut [Data]) -> Vec<u8> {
                     }
                 }
             }
+            ".dword" | ".quad" => {
+                //pushes the given 64 bit values
+                for value in datum.data_entries.iter_mut() {
+                    value.token_type = Dword;
+                    if references_labels(&value.token_name) {
+                        vec_of_data.extend([0; 8]);
+                        continue;
+                    }
+                    match evaluate_expression(&value.token_name, &HashMap::new()) {
+                        Ok(dword) => vec_of_data.extend(dword.to_be_bytes()),
+                        Err(_) => {
+                            vec_of_data.extend([0; 8]);
+                            datum.errors.push(Error {
+                                error_name: NonIntImmediate,
+                                token_causing_error: value.token_name.to_string(),
+                                start_end_columns: value.start_end_columns,
+                                message: "".to_string(),
+                                fix_its: Vec::new(),
+                            });
+                        }
+                    }
+                }
+            }
             ".double" => {
                 //pushes the given 64 bit float values
                 for value in datum.data_entries.iter_mut() {
@@ -919,6 +1015,7 @@ pub fn assemble_data_binary(data_list: &mut [Data]) -> Vec<u8> {
                             token_causing_error: value.token_name.to_string(),
                             start_end_columns: value.start_end_columns,
                             message: "".to_string(),
+                            fix_its: Vec::new(),
                         }),
                     }
                 }
@@ -941,6 +1038,7 @@ pub fn assemble_data_binary(data_list: &mut [Data]) -> Vec<u8> {
                             token_causing_error: value.token_name.to_string(),
                             start_end_columns: value.start_end_columns,
                             message: "".to_string(),
+                            fix_its: Vec::new(),
                         }),
                     }
                 }
@@ -949,6 +1047,10 @@ pub fn assemble_data_binary(data_list: &mut [Data]) -> Vec<u8> {
                 //half words are 16 bits each. The vec is of u32s so 2 half words are in each u32
                 for value in datum.data_entries.iter_mut() {
                     value.token_type = Half;
+                    if references_labels(&value.token_name) {
+                        vec_of_data.extend([0; 2]);
+                        continue;
+                    }
                     let immediate_results =
                         read_immediate(&value.token_name, value.start_end_columns, 16);
 
@@ -979,6 +1081,10 @@ pub fn assemble_data_binary(data_list: &mut [Data]) -> Vec<u8> {
             ".word" => {
                 for value in datum.data_entries.iter_mut() {
                     value.token_type = Word;
+                    if references_labels(&value.token_name) {
+                        vec_of_data.extend([0; 4]);
+                        continue;
+                    }
                     let immediate_results =
                         read_immediate(&value.token_name, value.start_end_columns, 32);
                     if immediate_results.1.is_some() {
@@ -1004,8 +1110,229 @@ pub fn assemble_data_binary(data_list: &mut [Data]) -> Vec<u8> {
                 token_causing_error: datum.data_type.token_name.to_string(),
                 start_end_columns: datum.data_type.start_end_columns,
                 message: "".to_string(),
+                fix_its: Vec::new(),
             }),
         }
     }
     vec_of_data
 }
+
+///Replaces operands using a relocation operator, such as `%hi(label)` or `%lo(label)(t0)`, with the selected bits of
+/// the value. This runs before the other label expressions are resolved since the text inside the operator may itself
+/// be an expression of labels.
+pub fn resolve_relocation_operators(
+    instructions: &mut [Instruction],
+    labels: &HashMap<String, usize>,
+    arch: AvailableDatapaths,
+) {
+    //%pcrel_lo reads the operand of an auipc, so the original operands are kept while they are replaced
+    let original_instructions = instructions.to_vec();
+    for instruction in instructions.iter_mut() {
+        let address = (instruction.instruction_number << 2) as i64;
+        for operand in instruction.operands.iter_mut() {
+            let Some((relocation, expression, rest)) = read_relocation(&operand.token_name) else {
+                continue;
+            };
+            let rest = rest.to_string();
+
+            let value = match relocation {
+                Relocation::PcrelHi => {
+                    evaluate_expression(expression, labels).map(|target| target - address)
+                }
+                Relocation::PcrelLo => pcrel_lo_distance(
+                    expression,
+                    instruction.instruction_number,
+                    &original_instructions,
+                    labels,
+                ),
+                _ => evaluate_expression(expression, labels),
+            };
+
+            let error = match value.map(|value| select_relocation_bits(relocation, value, arch)) {
+                Ok(Some(bits)) => {
+                    operand.token_name = format!("{bits}{rest}");
+                    continue;
+                }
+                Err(ExpressionError::UnknownSymbol(symbol)) => {
+                    label_not_found(&symbol, operand.start_end_columns)
+                }
+                _ => Error {
+                    error_name: InvalidRelocation,
+                    token_causing_error: operand.token_name.clone(),
+                    start_end_columns: operand.start_end_columns,
+                    message: "".to_string(),
+                    fix_its: Vec::new(),
+                },
+            };
+            instruction.errors.push(error);
+            //the operand is replaced so the same problem is not reported again when it is read
+            operand.token_name = format!("0{rest}");
+        }
+    }
+}
+
+///Takes the bits of a value selected by a relocation operator. `%hi` and `%higher` round up when the bits below them
+/// would be sign-extended to a negative value so that adding the lower bits gives back the original value. Returns None if
+/// the operator is not available for the architecture.
+fn select_relocation_bits(
+    relocation: Relocation,
+    value: i64,
+    arch: AvailableDatapaths,
+) -> Option<i64> {
+    match (arch, relocation) {
+        (AvailableDatapaths::MIPS, Relocation::Hi) => {
+            Some((value.wrapping_add(0x8000) >> 16) & 0xFFFF)
+        }
+        (AvailableDatapaths::MIPS, Relocation::Lo) => Some((value << 48) >> 48),
+        (AvailableDatapaths::MIPS, Relocation::Higher) => {
+            Some((value.wrapping_add(0x8000_8000) >> 32) & 0xFFFF)
+        }
+        (AvailableDatapaths::MIPS, Relocation::Highest) => {
+            Some((value.wrapping_add(0x8000_8000_8000) >> 48) & 0xFFFF)
+        }
+        (AvailableDatapaths::RISCV, Relocation::Hi | Relocation::PcrelHi) => {
+            Some((value.wrapping_add(0x800) >> 12) & 0xF_FFFF)
+        }
+        (AvailableDatapaths::RISCV, Relocation::Lo | Relocation::PcrelLo) => {
+            Some((value << 52) >> 52)
+        }
+        _ => None,
+    }
+}
+
+///Finds the distance that `%pcrel_lo(symbol)` takes the lower bits of. As with the GNU assembler, the symbol is the
+/// label of an auipc using `%pcrel_hi(target)`. The target itself may also be given when the instruction directly follows
+/// that auipc, which is how la and loads or stores of a label are expanded.
+fn pcrel_lo_distance(
+    expression: &str,
+    instruction_number: usize,
+    instructions: &[Instruction],
+    labels: &HashMap<String, usize>,
+) -> Result<i64, ExpressionError> {
+    let pcrel_hi_target = |auipc: &Instruction| -> Option<String> {
+        if auipc.operator.token_name != "auipc" {
+            return None;
+        }
+        match read_relocation(&auipc.operands.get(1)?.token_name) {
+            Some((Relocation::PcrelHi, target, _)) => Some(target.trim().to_string()),
+            _ => None,
+        }
+    };
+    let instruction_at = |address: usize| {
+        instructions
+            .iter()
+            .find(|i| i.instruction_number << 2 == address)
+    };
+
+    if let Some(address) = labels.get(expression.trim()) {
+        if let Some(target) = instruction_at(*address).and_then(pcrel_hi_target) {
+            return Ok(evaluate_expression(&target, labels)? - *address as i64);
+        }
+    }
+
+    let previous_address = (instruction_number << 2).wrapping_sub(4);
+    match instruction_at(previous_address).and_then(pcrel_hi_target) {
+        Some(target) if instruction_number > 0 && target == expression.trim() => {
+            Ok(evaluate_expression(&target, labels)? - previous_address as i64)
+        }
+        _ => Err(ExpressionError::Syntax),
+    }
+}
+
+///Replaces operands that are constant expressions involving labels, such as `label + 8` or `end - start`, with their
+/// values. Operands that are just a label are left for the instruction to read as a label. For memory operands, only the
+/// offset before the base register is evaluated.
+pub fn resolve_instruction_label_expressions(
+    instructions: &mut [Instruction],
+    labels: &HashMap<String, usize>,
+) {
+    for instruction in instructions.iter_mut() {
+        for operand in instruction.operands.iter_mut() {
+            //an operand such as `4 * (end - start)` is evaluated as a whole before it is treated as offset(base)
+            let (expression, base) = match operand.token_name.rfind('(') {
+                Some(index)
+                    if index > 0
+                        && operand.token_name.ends_with(')')
+                        && evaluate_expression(&operand.token_name, labels).is_err() =>
+                {
+                    operand.token_name.split_at(index)
+                }
+                _ => (operand.token_name.as_str(), ""),
+            };
+            let is_label = expression
+                .chars()
+                .all(|char| is_symbol_char(char) || char.is_ascii_digit());
+            if is_label || !references_labels(expression) {
+                continue;
+            }
+
+            match evaluate_expression(expression, labels) {
+                Ok(result) => operand.token_name = format!("{result}{base}"),
+                Err(ExpressionError::UnknownSymbol(symbol)) => {
+                    instruction
+                        .errors
+                        .push(label_not_found(&symbol, operand.start_end_columns));
+                    //the operand is replaced so the same problem is not reported again when it is read
+                    operand.token_name = format!("0{base}");
+                }
+                Err(_) => {}
+            }
+        }
+    }
+}
+
+///Fills in the values of .byte, .half, and .word entries that reference labels, such as `.word end - start`. These are
+/// left as zeros by assemble_data_binary since the addresses of labels are not known until after the data is laid out.
+pub fn resolve_data_label_expressions(
+    data_list: &mut [Data],
+    vec_of_data: &mut [u8],
+    labels: &HashMap<String, usize>,
+) {
+    for datum in data_list.iter_mut() {
+        let (size, num_bits) = match &*datum.data_type.token_name.to_lowercase() {
+            ".byte" => (1, 8),
+            ".half" => (2, 16),
+            ".word" => (4, 32),
+            ".dword" | ".quad" => (8, 64),
+            _ => continue,
+        };
+
+        for (i, value) in datum.data_entries.iter().enumerate() {
+            if !references_labels(&value.token_name) {
+                continue;
+            }
+
+            match evaluate_expression(&value.token_name, labels) {
+                Ok(result) => {
+                    //every value fits in 64 bits
+                    let max_value = i64::checked_pow(2, num_bits).unwrap_or(i64::MAX);
+                    if result > max_value || result < -max_value - 1 {
+                        datum.errors.push(Error {
+                            error_name: ImmediateOutOfBounds,
+                            token_causing_error: value.token_name.clone(),
+                            start_end_columns: value.start_end_columns,
+                            message: "".to_string(),
+                            fix_its: Vec::new(),
+                        });
+                        continue;
+                    }
+                    //entries are big-endian, starting with the most significant byte
+                    let address = datum.data_number + i * size;
+                    for byte in 0..size {
+                        vec_of_data[address + byte] = (result >> (8 * (size - 1 - byte))) as u8;
+                    }
+                }
+                Err(ExpressionError::UnknownSymbol(symbol)) => datum
+                    .errors
+                    .push(label_not_found(&symbol, value.start_end_columns)),
+                Err(_) => datum.errors.push(Error {
+                    error_name: NonIntImmediate,
+                    token_causing_error: value.token_name.clone(),
+                    start_end_columns: value.start_end_columns,
+                    message: "".to_string(),
+                    fix_its: Vec::new(),
+                }),
+            }
+        }
+    }
+}
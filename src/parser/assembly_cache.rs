@@ -0,0 +1,132 @@
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::parser::parser_structs_and_enums::{MonacoLineInfo, ProgramInfo};
+use crate::parser::parsing::{tokenize_line, tokenize_program};
+use crate::parser::project::{parse_tokenized_project, ProjectFile};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+///Identifies a version of the source of a project. The version changes whenever a file of the project is edited, renamed,
+/// added, or removed.
+pub fn source_version(files: &[ProjectFile]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    files.hash(&mut hasher);
+    hasher.finish()
+}
+
+///A project as it was last assembled.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Assembly {
+    ///The version of the source that was assembled, given by source_version.
+    pub version: u64,
+    pub arch: AvailableDatapaths,
+    pub program_info: ProgramInfo,
+    pub binary: Vec<u32>,
+    pub labels: HashMap<String, usize>,
+}
+
+///Keeps the result of assembling a project so that it is only assembled again once its source changes. When it is
+/// assembled again, only the lines which changed are tokenized again.
+#[derive(Clone, Debug, Default)]
+pub struct AssemblyCache {
+    assembly: Option<Assembly>,
+    ///The lines of each file as they were tokenized when the project was last assembled, by the name of the file.
+    file_lines: HashMap<String, Vec<MonacoLineInfo>>,
+    ///The number of lines that had to be tokenized when the project was last assembled.
+    tokenized_lines: usize,
+}
+
+impl AssemblyCache {
+    ///Returns the project as assembled from the given files, assembling it only if the files or architecture have
+    /// changed since it was last assembled.
+    pub fn assemble(&mut self, files: &[ProjectFile], arch: AvailableDatapaths) -> &Assembly {
+        let version = source_version(files);
+        let assembly = match self.assembly.take() {
+            Some(assembly) if assembly.version == version && assembly.arch == arch => assembly,
+            _ => self.reassemble(files, arch, version),
+        };
+        self.assembly.insert(assembly)
+    }
+
+    ///Returns the project as it was last assembled, even if its source has since changed.
+    pub fn assembly(&self) -> Option<&Assembly> {
+        self.assembly.as_ref()
+    }
+
+    ///Returns true if the project was last assembled from exactly these files for this architecture. Once the source
+    /// changes, the lines of the last assembly no longer match the editor until it is assembled again.
+    pub fn is_current(&self, files: &[ProjectFile], arch: AvailableDatapaths) -> bool {
+        self.assembly.as_ref().is_some_and(|assembly| {
+            assembly.arch == arch && assembly.version == source_version(files)
+        })
+    }
+
+    ///Returns the number of lines that had to be tokenized when the project was last assembled.
+    pub fn tokenized_lines(&self) -> usize {
+        self.tokenized_lines
+    }
+
+    fn reassemble(
+        &mut self,
+        files: &[ProjectFile],
+        arch: AvailableDatapaths,
+        version: u64,
+    ) -> Assembly {
+        self.tokenized_lines = 0;
+        let file_lines: Vec<Vec<MonacoLineInfo>> = files
+            .iter()
+            .map(|file| {
+                retokenize_file(
+                    self.file_lines.get(&file.name),
+                    &file.contents,
+                    &mut self.tokenized_lines,
+                )
+            })
+            .collect();
+        let (program_info, binary, labels) = parse_tokenized_project(files, &file_lines, arch);
+        self.file_lines = files
+            .iter()
+            .map(|file| file.name.clone())
+            .zip(file_lines)
+            .collect();
+        Assembly {
+            version,
+            arch,
+            program_info,
+            binary,
+            labels,
+        }
+    }
+}
+
+///Tokenizes a file, reusing the tokens of every line whose text is the same as a line of the file when it was last
+/// tokenized. The number of lines that had to be tokenized is added to tokenized_lines.
+fn retokenize_file(
+    previous_lines: Option<&Vec<MonacoLineInfo>>,
+    contents: &str,
+    tokenized_lines: &mut usize,
+) -> Vec<MonacoLineInfo> {
+    if contents.lines().next().is_none() {
+        return tokenize_program(String::new());
+    }
+
+    let previous: HashMap<&str, &MonacoLineInfo> = previous_lines
+        .into_iter()
+        .flatten()
+        .map(|line| (line.updated_monaco_string.as_str(), line))
+        .collect();
+    contents
+        .lines()
+        .enumerate()
+        .map(|(line_number, text)| match previous.get(text) {
+            Some(line) => MonacoLineInfo {
+                line_number,
+                ..(*line).clone()
+            },
+            None => {
+                *tokenized_lines += 1;
+                tokenize_line(line_number, text)
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,393 @@
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::parser::expressions::is_symbol_char;
+use crate::parser::parser_assembler_main::parser;
+use crate::parser::parser_structs_and_enums::{
+    TokenType, FP_REGISTERS, GP_REGISTERS, PSEUDO_INSTRUCTIONS_MIPS, PSEUDO_INSTRUCTIONS_RISCV,
+    RISCV_FP_REGISTERS, RISCV_GP_REGISTERS, SUPPORTED_INSTRUCTIONS_MIPS,
+    SUPPORTED_INSTRUCTIONS_RISCV,
+};
+use std::sync::OnceLock;
+
+///The kind of value written for an operand of an instruction.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OperandKind {
+    RegisterGP,
+    RegisterFP,
+    Immediate,
+    ///A memory address written as `offset(base)`, or as a label for the loads and stores that accept one.
+    MemoryAddress,
+    Label,
+}
+
+///An instruction or pseudo-instruction as it is offered when completing the operator of a line.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InstructionSyntax {
+    pub mnemonic: String,
+    ///How the instruction is written, such as `lw rt, offset(base)`.
+    pub syntax: String,
+    ///The description shown when hovering over the instruction, in markdown.
+    pub description: String,
+    ///The name of each operand in the syntax and the kind of value it is written as.
+    pub operands: Vec<(String, OperandKind)>,
+    pub is_pseudo: bool,
+}
+
+impl InstructionSyntax {
+    ///Writes the instruction as a Monaco snippet with a placeholder for each operand, so that tab moves from one operand
+    /// to the next. A memory address has placeholders for both its offset and its base.
+    pub fn snippet(&self) -> String {
+        let mut placeholder = 0;
+        let mut next_placeholder = |name: &str| {
+            placeholder += 1;
+            format!("${{{placeholder}:{}}}", escape_snippet(name))
+        };
+        let operands: Vec<String> = self
+            .operands
+            .iter()
+            .map(|(name, _)| match name.split_once('(') {
+                Some((offset, base)) => format!(
+                    "{}({})",
+                    next_placeholder(offset),
+                    next_placeholder(base.trim_end_matches(')'))
+                ),
+                None => next_placeholder(name),
+            })
+            .collect();
+        if operands.is_empty() {
+            self.mnemonic.clone()
+        } else {
+            format!("{} {}", self.mnemonic, operands.join(", "))
+        }
+    }
+}
+
+///What a completion inserts, which decides the icon it is shown with.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CompletionKind {
+    Instruction,
+    PseudoInstruction,
+    Register,
+    Label,
+}
+
+///A single suggestion for the text at the cursor.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Completion {
+    ///The text the suggestion is shown and filtered as.
+    pub label: String,
+    pub kind: CompletionKind,
+    ///A short description shown beside the suggestion, such as the syntax of an instruction.
+    pub detail: String,
+    ///A longer description in markdown.
+    pub documentation: String,
+    ///The text inserted, which is a snippet for instructions.
+    pub insert_text: String,
+    pub is_snippet: bool,
+}
+
+///The suggestions for the text at the cursor, which replace the line from start_column up to the cursor.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Completions {
+    pub start_column: usize,
+    pub items: Vec<Completion>,
+}
+
+///Returns the instructions and pseudo-instructions of an architecture with their syntax. The syntax and description of
+/// each instruction are the ones the parser shows when hovering over it, and the kinds of its operands are the ones the
+/// assembler reads them as.
+pub fn instruction_syntaxes(arch: AvailableDatapaths) -> &'static [InstructionSyntax] {
+    static MIPS: OnceLock<Vec<InstructionSyntax>> = OnceLock::new();
+    static RISCV: OnceLock<Vec<InstructionSyntax>> = OnceLock::new();
+    match arch {
+        AvailableDatapaths::MIPS => MIPS.get_or_init(|| {
+            let instructions = SUPPORTED_INSTRUCTIONS_MIPS.iter().chain(&["syscall"]);
+            read_instruction_syntaxes(instructions, &PSEUDO_INSTRUCTIONS_MIPS, arch)
+        }),
+        AvailableDatapaths::RISCV => RISCV.get_or_init(|| {
+            read_instruction_syntaxes(
+                SUPPORTED_INSTRUCTIONS_RISCV.iter(),
+                &PSEUDO_INSTRUCTIONS_RISCV,
+                arch,
+            )
+        }),
+    }
+}
+
+///Reads the syntax of each instruction by assembling it on its own. Each instruction is first assembled without operands
+/// to find its syntax, and then with as many operands as its syntax has to find the kind of each operand.
+fn read_instruction_syntaxes<'a>(
+    instructions: impl Iterator<Item = &'a &'a str>,
+    pseudo_instructions: &[&str],
+    arch: AvailableDatapaths,
+) -> Vec<InstructionSyntax> {
+    let mut syntaxes = Vec::new();
+    let mnemonics = instructions
+        .map(|mnemonic| (mnemonic, false))
+        .chain(pseudo_instructions.iter().map(|mnemonic| (mnemonic, true)));
+    for (mnemonic, is_pseudo) in mnemonics {
+        if syntaxes
+            .iter()
+            .any(|syntax: &InstructionSyntax| syntax.mnemonic == *mnemonic)
+        {
+            continue;
+        }
+        let (program_info, _, _) = parser(mnemonic.to_string(), arch);
+        let hover = &program_info.monaco_line_info[0].mouse_hover_string;
+        let description = hover
+            .split("\n\n**Binary:**")
+            .next()
+            .unwrap_or_default()
+            .trim_end()
+            .to_string();
+        let syntax = read_syntax(hover).unwrap_or_else(|| mnemonic.to_string());
+        let names: Vec<String> = syntax
+            .split_once(' ')
+            .map(|(_, operands)| {
+                operands
+                    .split(',')
+                    .map(|operand| operand.trim().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        //pseudo-instructions are expanded before their operands are read, so their kinds are read from the syntax
+        let assembled_kinds = if is_pseudo || names.is_empty() {
+            Vec::new()
+        } else {
+            let placeholders = vec!["_"; names.len()].join(", ");
+            let (program_info, _, _) = parser(format!("{mnemonic} {placeholders}"), arch);
+            program_info
+                .instructions
+                .first()
+                .map(|instruction| {
+                    instruction
+                        .operands
+                        .iter()
+                        .map(|operand| match operand.token_type {
+                            TokenType::RegisterGP => Some(OperandKind::RegisterGP),
+                            TokenType::RegisterFP => Some(OperandKind::RegisterFP),
+                            TokenType::Immediate => Some(OperandKind::Immediate),
+                            TokenType::MemoryAddress => Some(OperandKind::MemoryAddress),
+                            TokenType::LabelOperand => Some(OperandKind::Label),
+                            _ => None,
+                        })
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        let operands = names
+            .into_iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let kind = assembled_kinds
+                    .get(i)
+                    .copied()
+                    .flatten()
+                    .unwrap_or_else(|| operand_kind_of_name(&name));
+                (name, kind)
+            })
+            .collect();
+
+        syntaxes.push(InstructionSyntax {
+            mnemonic: mnemonic.to_string(),
+            syntax,
+            description,
+            operands,
+            is_pseudo,
+        });
+    }
+    syntaxes
+}
+
+///Reads the syntax from the hover text of an instruction, which is written as "**Syntax:** `add rd, rs, rt`" for
+/// instructions and on the line before "=>" for pseudo-instructions.
+fn read_syntax(hover: &str) -> Option<String> {
+    if let Some(syntax) = hover.strip_prefix("**Syntax:** `") {
+        return syntax.split('`').next().map(str::to_string);
+    }
+    hover
+        .lines()
+        .find_map(|line| line.strip_suffix(" =>"))
+        .map(|syntax| syntax.trim().to_string())
+}
+
+///Guesses the kind of an operand from its name in the syntax of an instruction, such as `rt`, `fs`, or `target`.
+fn operand_kind_of_name(name: &str) -> OperandKind {
+    if name.contains('(') {
+        OperandKind::MemoryAddress
+    } else if matches!(name, "target" | "label" | "offset" | "symbol") {
+        OperandKind::Label
+    } else if name.starts_with("fr") || (name.starts_with('f') && name.len() == 2) {
+        OperandKind::RegisterFP
+    } else if name.starts_with('r') || name.starts_with('$') || name == "base" {
+        OperandKind::RegisterGP
+    } else {
+        OperandKind::Immediate
+    }
+}
+
+///Escapes the characters that have a meaning in Monaco snippets.
+fn escape_snippet(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('$', "\\$")
+        .replace('}', "\\}")
+}
+
+///Suggests what may be written at a column of a line. The operator of a line is completed with the instructions of the
+/// architecture. Each operand after it is completed with the registers of the right kind, or the labels of the program
+/// where the instruction accepts a label.
+pub fn complete(
+    line: &str,
+    column: usize,
+    labels: &[String],
+    arch: AvailableDatapaths,
+) -> Completions {
+    let before_cursor: Vec<char> = line.chars().take(column).collect();
+    let column = before_cursor.len();
+
+    //nothing is suggested in comments or strings
+    let mut quote = None;
+    for char in &before_cursor {
+        match quote {
+            Some(open) if *char == open => quote = None,
+            Some(_) => {}
+            None if *char == '"' || *char == '\'' => quote = Some(*char),
+            None if *char == '#' => return Completions::default(),
+            None => {}
+        }
+    }
+    if quote.is_some() {
+        return Completions::default();
+    }
+
+    //skips the labels at the start of the line
+    let mut i = 0;
+    loop {
+        while i < column && before_cursor[i].is_whitespace() {
+            i += 1;
+        }
+        let start = i;
+        while i < column && (is_symbol_char(before_cursor[i]) || before_cursor[i].is_ascii_digit())
+        {
+            i += 1;
+        }
+        if i < column && before_cursor[i] == ':' && i > start {
+            i += 1;
+            continue;
+        }
+        i = start;
+        break;
+    }
+
+    let operator_start = i;
+    while i < column && !before_cursor[i].is_whitespace() {
+        i += 1;
+    }
+    let operator: String = before_cursor[operator_start..i].iter().collect();
+    if i == column {
+        if operator.starts_with('.') {
+            return Completions::default();
+        }
+        return Completions {
+            start_column: operator_start,
+            items: instruction_completions(arch),
+        };
+    }
+
+    let Some(instruction) = instruction_syntaxes(arch)
+        .iter()
+        .find(|syntax| syntax.mnemonic == operator.to_lowercase())
+    else {
+        return Completions::default();
+    };
+    let operands = &before_cursor[i..];
+    let operand_index = operands.iter().filter(|char| **char == ',').count();
+    let current_start = before_cursor
+        .iter()
+        .rposition(|char| char.is_whitespace() || *char == ',' || *char == '(')
+        .map_or(0, |position| position + 1);
+    let in_parentheses = operands
+        .iter()
+        .rev()
+        .take_while(|char| **char != ',')
+        .any(|char| *char == '(');
+
+    let items = match instruction.operands.get(operand_index) {
+        Some((_, OperandKind::RegisterGP)) => register_completions(arch, false),
+        Some((_, OperandKind::RegisterFP)) => register_completions(arch, true),
+        Some((_, OperandKind::MemoryAddress)) if in_parentheses => {
+            register_completions(arch, false)
+        }
+        Some((_, OperandKind::MemoryAddress | OperandKind::Label)) => label_completions(labels),
+        _ => Vec::new(),
+    };
+    Completions {
+        start_column: current_start,
+        items,
+    }
+}
+
+fn instruction_completions(arch: AvailableDatapaths) -> Vec<Completion> {
+    instruction_syntaxes(arch)
+        .iter()
+        .map(|instruction| Completion {
+            label: instruction.mnemonic.clone(),
+            kind: if instruction.is_pseudo {
+                CompletionKind::PseudoInstruction
+            } else {
+                CompletionKind::Instruction
+            },
+            detail: instruction.syntax.clone(),
+            documentation: instruction.description.clone(),
+            insert_text: instruction.snippet(),
+            is_snippet: true,
+        })
+        .collect()
+}
+
+///Suggests the registers of an architecture by the name they are usually written as, with their other names as detail.
+fn register_completions(arch: AvailableDatapaths, floating_point: bool) -> Vec<Completion> {
+    let registers: Vec<(&str, String)> = match (arch, floating_point) {
+        (AvailableDatapaths::MIPS, false) => GP_REGISTERS
+            .iter()
+            .map(|register| (register.names[0], register.names[2].to_string()))
+            .collect(),
+        (AvailableDatapaths::MIPS, true) => FP_REGISTERS
+            .iter()
+            .map(|register| (register.name, String::new()))
+            .collect(),
+        (AvailableDatapaths::RISCV, false) => RISCV_GP_REGISTERS
+            .iter()
+            .map(|register| (register.names[1], register.names[0].to_string()))
+            .collect(),
+        (AvailableDatapaths::RISCV, true) => RISCV_FP_REGISTERS
+            .iter()
+            .map(|register| (register.names[1], register.names[0].to_string()))
+            .collect(),
+    };
+    registers
+        .into_iter()
+        .map(|(name, detail)| Completion {
+            label: name.to_string(),
+            kind: CompletionKind::Register,
+            detail,
+            documentation: String::new(),
+            insert_text: name.to_string(),
+            is_snippet: false,
+        })
+        .collect()
+}
+
+fn label_completions(labels: &[String]) -> Vec<Completion> {
+    labels
+        .iter()
+        .map(|label| Completion {
+            label: label.clone(),
+            kind: CompletionKind::Label,
+            detail: "label".to_string(),
+            documentation: String::new(),
+            insert_text: label.clone(),
+            is_snippet: false,
+        })
+        .collect()
+}
@@ -0,0 +1,390 @@
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::emulation_core::mips::constants::{OPCODE_BEQ, OPCODE_BNE};
+use crate::emulation_core::mips::instruction::{branch_target, MipsInstruction};
+use crate::emulation_core::mips::memory::CAPACITY_BYTES;
+use crate::emulation_core::riscv::constants::{OPCODE_BRANCH, OPCODE_JAL};
+use crate::emulation_core::riscv::instruction::RiscInstruction;
+use crate::parser::parser_assembler_main::parser;
+use std::collections::{BTreeMap, HashMap};
+
+///Reads a raw binary image, such as an uploaded `.bin` file or the bytes of memory, into words. Words are stored
+/// big-endian as they are in memory, and a partial word at the end is padded with zeros. Images larger than memory are
+/// rejected, since they could not be loaded.
+pub fn read_binary_image(bytes: &[u8]) -> Result<Vec<u32>, String> {
+    if bytes.len() > CAPACITY_BYTES {
+        return Err(image_too_large(bytes.len()));
+    }
+    Ok(bytes
+        .chunks(4)
+        .map(|chunk| {
+            let mut word = [0; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            u32::from_be_bytes(word)
+        })
+        .collect())
+}
+
+///Reads an image written as text, with any number of hexadecimal words on each line separated by spaces or commas.
+/// The `0x` prefix is optional, an address ending in a colon at the start of a line is skipped, and anything after a
+/// `#` is a comment. Images larger than memory are rejected, since they could not be loaded.
+pub fn read_hex_image(text: &str) -> Result<Vec<u32>, String> {
+    let mut words = Vec::new();
+    for (line_number, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default();
+        let tokens = line
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|token| !token.is_empty());
+        for (i, token) in tokens.enumerate() {
+            if i == 0 && token.ends_with(':') {
+                continue;
+            }
+            let digits = token
+                .strip_prefix("0x")
+                .or_else(|| token.strip_prefix("0X"))
+                .unwrap_or(token);
+            match u32::from_str_radix(digits, 16) {
+                Ok(word) if digits.len() <= 8 && !digits.starts_with('+') => words.push(word),
+                _ => {
+                    return Err(format!(
+                        "`{token}` on line {} is not a hexadecimal word.",
+                        line_number + 1
+                    ))
+                }
+            }
+        }
+    }
+    if words.len() * 4 > CAPACITY_BYTES {
+        return Err(image_too_large(words.len() * 4));
+    }
+    Ok(words)
+}
+
+///The error for an image of the given number of bytes that does not fit in memory.
+fn image_too_large(size: usize) -> String {
+    format!(
+        "The image is {size} bytes, which does not fit in the {CAPACITY_BYTES} bytes of memory."
+    )
+}
+
+///Turns an image of words back into source that assembles to the same image. The words before data_start, or every
+/// word if there is no data section, are decoded as instructions and every branch and jump target within them is given
+/// a label. Words that do not decode to an instruction, or whose instruction does not assemble back to the same word,
+/// are written as `.word`. Zeros at the end of the image are left out since memory starts as zeros.
+pub fn disassemble(image: &[u32], data_start: Option<usize>, arch: AvailableDatapaths) -> String {
+    disassemble_with_symbols(image, data_start, &HashMap::new(), arch)
+}
+
+///Disassembles an image the same way as [`disassemble`], naming the addresses of the symbols, such as those of an ELF
+/// executable, with the symbols in place of synthesized labels. Symbols that are not word aligned or are not valid
+/// label names are left out.
+pub fn disassemble_with_symbols(
+    image: &[u32],
+    data_start: Option<usize>,
+    symbols: &HashMap<String, usize>,
+    arch: AvailableDatapaths,
+) -> String {
+    let length = image
+        .iter()
+        .rposition(|word| *word != 0)
+        .map_or(0, |i| i + 1);
+    let image = &image[..length];
+
+    let mut symbols: Vec<(usize, &str)> = symbols
+        .iter()
+        .filter(|(name, address)| *address % 4 == 0 && is_label_name(name))
+        .map(|(name, address)| (*address, name.as_str()))
+        .collect();
+    symbols.sort();
+
+    //the data section is only kept if the program still assembles to the same image with it, since the instruction
+    // added to the end of a program by the parser may need the space the data starts at
+    if let Some(data_start) = data_start.filter(|data_start| *data_start < image.len()) {
+        let (source, reproduced) =
+            Disassembly::new(&image[..data_start], &image[data_start..], &symbols, arch).verify();
+        if reproduced {
+            return source;
+        }
+    }
+    Disassembly::new(image, &[], &symbols, arch).verify().0
+}
+
+///Returns true if a binary, such as that of a disassembly assembled again, holds the same words as an image up to the
+/// last word of the image that is not zero, so it can be loaded in place of the image. The zeros at the end of an image
+/// are left out of its disassembly, so the words after them, such as the syscall the parser adds to the end of a MIPS
+/// program, are not compared.
+pub fn reproduces_image(binary: &[u32], image: &[u32]) -> bool {
+    let length = image
+        .iter()
+        .rposition(|word| *word != 0)
+        .map_or(0, |i| i + 1);
+    image[..length]
+        .iter()
+        .enumerate()
+        .all(|(address, word)| binary.get(address) == Some(word))
+}
+
+///Returns true if the name can be written as a label.
+fn is_label_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|char| char.is_ascii_alphanumeric() || char == '_' || char == '.')
+}
+
+///The fewest zeros in a row that are skipped over rather than written out word by word.
+const MIN_SKIPPED_ZEROS: usize = 8;
+
+///The image being disassembled and the way each of its words is currently written.
+struct Disassembly<'a> {
+    arch: AvailableDatapaths,
+    text: &'a [u32],
+    data: &'a [u32],
+    ///The labels of the text by byte address, which are the symbols and the synthesized labels of the branch and jump
+    /// targets.
+    labels: BTreeMap<usize, Vec<String>>,
+    ///The label of the data at each byte address that has a symbol.
+    data_labels: BTreeMap<usize, String>,
+    ///The instruction each word of the text decodes to, or None if it is written as `.word`.
+    instructions: Vec<Option<String>>,
+}
+
+impl<'a> Disassembly<'a> {
+    fn new(
+        text: &'a [u32],
+        data: &'a [u32],
+        symbols: &[(usize, &str)],
+        arch: AvailableDatapaths,
+    ) -> Self {
+        let text_end = text.len() * 4;
+        let data_end = text_end + data.len() * 4;
+        let mut labels: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+        let mut data_labels = BTreeMap::new();
+        for (address, name) in symbols {
+            if *address < text_end {
+                labels.entry(*address).or_default().push(name.to_string());
+            } else if *address < data_end {
+                data_labels.entry(*address).or_insert(name.to_string());
+            }
+        }
+        for (instruction_number, word) in text.iter().enumerate() {
+            if let Some(target) = jump_target(*word, instruction_number, arch) {
+                if target < text_end {
+                    labels
+                        .entry(target)
+                        .or_insert_with(|| vec![format!("loc_{target:04x}")]);
+                }
+            }
+        }
+
+        //instructions refer to the first label of their target
+        let label_addresses: HashMap<String, usize> = labels
+            .iter()
+            .map(|(address, names)| (names[0].clone(), *address))
+            .collect();
+        let instructions = text
+            .iter()
+            .enumerate()
+            .map(|(instruction_number, word)| {
+                decode(*word, instruction_number, &label_addresses, arch)
+            })
+            .collect();
+
+        Disassembly {
+            arch,
+            text,
+            data,
+            labels,
+            data_labels,
+            instructions,
+        }
+    }
+
+    ///Writes the source of the image, returning it with the line each word of the text is written on. Long runs of
+    /// zeros are skipped over with `.org` in the text and `.space` in the data, so their words have no line.
+    fn render(&self) -> (String, Vec<Option<usize>>) {
+        let mut lines = vec![".text".to_string()];
+        let mut word_lines = Vec::new();
+        let mut instruction_number = 0;
+        while instruction_number < self.text.len() {
+            let zeros_end = zero_run_end(self.text, instruction_number, |i| {
+                self.labels.contains_key(&(i * 4))
+            });
+            //a run of zeros at the end of the text is kept, since .org only moves the instruction after it
+            if zeros_end - instruction_number >= MIN_SKIPPED_ZEROS && zeros_end < self.text.len() {
+                lines.push(format!("        .org 0x{:x}", zeros_end * 4));
+                word_lines.resize(zeros_end, None);
+                instruction_number = zeros_end;
+                continue;
+            }
+
+            for label in self
+                .labels
+                .get(&(instruction_number * 4))
+                .into_iter()
+                .flatten()
+            {
+                lines.push(format!("{label}:"));
+            }
+            word_lines.push(Some(lines.len()));
+            lines.push(match &self.instructions[instruction_number] {
+                Some(instruction) => format!("        {instruction}"),
+                None => format!("        .word 0x{:08x}", self.text[instruction_number]),
+            });
+            instruction_number += 1;
+        }
+
+        let data_start = self.text.len();
+        if !self.data.is_empty() {
+            lines.push(format!(".data 0x{:x}", data_start * 4));
+        }
+        //data is split into lines at every symbol so that each symbol labels its own line
+        let is_labeled = |i: usize| self.data_labels.contains_key(&((data_start + i) * 4));
+        let mut i = 0;
+        while i < self.data.len() {
+            let address = (data_start + i) * 4;
+            let label = self
+                .data_labels
+                .get(&address)
+                .cloned()
+                .unwrap_or_else(|| format!("data_{address:04x}"));
+            let zeros_end = zero_run_end(self.data, i, |j| j > i && is_labeled(j));
+            if zeros_end - i >= MIN_SKIPPED_ZEROS {
+                lines.push(format!("{label}: .space {}", (zeros_end - i) * 4));
+                i = zeros_end;
+                continue;
+            }
+
+            //a line holds up to four words, ending early where a run of zeros to skip starts
+            let mut end = i + 1;
+            while end < (i + 4).min(self.data.len())
+                && !is_labeled(end)
+                && zero_run_end(self.data, end, |j| j > end && is_labeled(j)) - end
+                    < MIN_SKIPPED_ZEROS
+            {
+                end += 1;
+            }
+            let words: Vec<String> = self.data[i..end]
+                .iter()
+                .map(|word| format!("0x{word:08x}"))
+                .collect();
+            lines.push(format!("{label}: .word {}", words.join(", ")));
+            i = end;
+        }
+
+        let mut source = lines.join("\n");
+        source.push('\n');
+        (source, word_lines)
+    }
+
+    ///Assembles the source until every instruction assembles back to its own word, writing the instructions that do
+    /// not as `.word`. Returns the source and whether it assembles to the whole image.
+    fn verify(mut self) -> (String, bool) {
+        loop {
+            let (source, word_lines) = self.render();
+            let (program_info, binary, _) = parser(source.clone(), self.arch);
+
+            //an instruction that assembles to more than one word moves every word after it, so words are only compared
+            // up to the first one that did not come from its own line, and the instruction before it is written as a word
+            let moved = (0..self.text.len()).find(|instruction_number| {
+                word_lines[*instruction_number].is_some_and(|line| {
+                    program_info.address_to_line_number.get(*instruction_number) != Some(&line)
+                })
+            });
+            let mut mismatched: Vec<usize> = self
+                .text
+                .iter()
+                .enumerate()
+                .filter(|(instruction_number, word)| {
+                    let Some(line) = word_lines[*instruction_number] else {
+                        return false;
+                    };
+                    let has_errors = !program_info.monaco_line_info[line].errors.is_empty();
+                    let compared = moved.map_or(true, |moved| *instruction_number < moved);
+                    has_errors || (compared && binary.get(*instruction_number) != Some(word))
+                })
+                .map(|(instruction_number, _)| instruction_number)
+                .collect();
+            if let Some(moved) = moved {
+                mismatched.push(moved.saturating_sub(1));
+            }
+
+            let mut changed = false;
+            for instruction_number in mismatched {
+                changed |= self.instructions[instruction_number].take().is_some();
+            }
+            if changed {
+                continue;
+            }
+
+            let data_start = self.text.len();
+            let reproduced = program_info
+                .monaco_line_info
+                .iter()
+                .all(|line| line.errors.is_empty())
+                && binary.get(..data_start) == Some(self.text)
+                && binary.get(data_start..data_start + self.data.len()) == Some(self.data);
+            return (source, reproduced);
+        }
+    }
+}
+
+///Returns the index after the run of zero words starting at start, stopping early at any word that is_kept.
+fn zero_run_end(words: &[u32], start: usize, is_kept: impl Fn(usize) -> bool) -> usize {
+    let mut end = start;
+    while end < words.len() && words[end] == 0 && !is_kept(end) {
+        end += 1;
+    }
+    end
+}
+
+///Returns the byte address a branch or jump instruction goes to. MIPS branches are relative to the next instruction,
+/// while SWIM's RISC-V branches and jumps, like MIPS jumps, hold the word address of their target.
+fn jump_target(word: u32, instruction_number: usize, arch: AvailableDatapaths) -> Option<usize> {
+    match arch {
+        AvailableDatapaths::MIPS => match MipsInstruction::try_from(word).ok()? {
+            MipsInstruction::IType(i_type)
+                if i_type.op == OPCODE_BEQ || i_type.op == OPCODE_BNE =>
+            {
+                branch_target(instruction_number, i_type.immediate)
+            }
+            MipsInstruction::FpuBranchType(fpu_branch_type) => {
+                branch_target(instruction_number, fpu_branch_type.offset)
+            }
+            MipsInstruction::JType(j_type) => Some(j_type.addr as usize * 4),
+            _ => None,
+        },
+        AvailableDatapaths::RISCV => match RiscInstruction::try_from(word).ok()? {
+            RiscInstruction::BType(b_type) if b_type.op == OPCODE_BRANCH => {
+                Some(b_type.imm as usize * 4)
+            }
+            RiscInstruction::JType(j_type) if j_type.op == OPCODE_JAL => {
+                Some(j_type.imm as usize * 4)
+            }
+            _ => None,
+        },
+    }
+}
+
+///Decodes a word into the text of its instruction, or None if it is not an instruction SWIM can write.
+fn decode(
+    word: u32,
+    instruction_number: usize,
+    labels: &HashMap<String, usize>,
+    arch: AvailableDatapaths,
+) -> Option<String> {
+    let instruction = match arch {
+        AvailableDatapaths::MIPS => {
+            //undecodable words would otherwise be written as nop
+            MipsInstruction::try_from(word).ok()?;
+            MipsInstruction::get_string_version(word, labels.clone(), instruction_number).ok()?
+        }
+        AvailableDatapaths::RISCV => {
+            RiscInstruction::get_string_version(word, labels.clone()).ok()?
+        }
+    };
+    if instruction.is_empty() || instruction.contains("##") {
+        return None;
+    }
+    Some(instruction)
+}
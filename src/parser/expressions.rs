@@ -0,0 +1,436 @@
+use crate::parser::local_labels::read_local_label_reference;
+use std::collections::HashMap;
+
+///The reasons a constant expression cannot be evaluated.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ExpressionError {
+    ///The expression is not well formed.
+    Syntax,
+    ///The expression references a symbol that is not a known label.
+    UnknownSymbol(String),
+    DivisionByZero,
+    ///A character literal or string holds a character that takes up more than one byte in UTF-8.
+    NonASCII(char),
+    ///A character literal or string holds an escape sequence that is not recognized, such as `\q`.
+    InvalidEscape(String),
+}
+
+impl ExpressionError {
+    ///Explains why a character literal or string cannot be read. Other errors are explained where they are reported.
+    pub fn literal_message(&self) -> String {
+        match self {
+            ExpressionError::NonASCII(char) => format!(
+                "'{char}' is encoded in UTF-8 as {} bytes, so it cannot be stored as a single ASCII character. Other bytes can be written with an escape sequence such as \\xe9.\n",
+                char.len_utf8()
+            ),
+            ExpressionError::InvalidEscape(escape) => format!(
+                "{escape} is not a recognized escape sequence. The recognized escape sequences are \\a, \\b, \\f, \\n, \\r, \\t, \\v, \\\\, \\', \\\", \\?, \\xNN, and octal values such as \\0 or \\101.\n"
+            ),
+            _ => "Token recognized as a char must hold a single character or escape sequence between single quotes (').\n".to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum ExpressionToken {
+    Number(i64),
+    Symbol(String),
+    Operator(&'static str),
+    OpenParenthesis,
+    CloseParenthesis,
+}
+
+const OPERATORS: [&str; 11] = ["<<", ">>", "+", "-", "*", "/", "%", "&", "|", "^", "~"];
+
+///Evaluates a constant expression such as `(end - start) / 4` or `'a' + 1`.
+///
+///Terms are decimal, hexadecimal (`0x`), or binary (`0b`) integers, character literals, and label names, which evaluate
+/// to the address of the label. The operators, from lowest to highest precedence, are `|`, `^`, `&`, `<<` and `>>`,
+/// `+` and `-`, then `*`, `/`, and `%`, and finally the unary `-`, `+`, and `~`. Parentheses group sub-expressions.
+pub fn evaluate_expression(
+    expression: &str,
+    labels: &HashMap<String, usize>,
+) -> Result<i64, ExpressionError> {
+    let tokens = lex_expression(expression)?;
+    let mut parser = ExpressionParser {
+        tokens,
+        position: 0,
+        labels,
+    };
+    let value = parser.parse_binary(0)?;
+    if parser.position != parser.tokens.len() {
+        return Err(ExpressionError::Syntax);
+    }
+    Ok(value)
+}
+
+///Returns true if the expression can only be evaluated once the addresses of labels are known.
+pub fn references_labels(expression: &str) -> bool {
+    matches!(
+        evaluate_expression(expression, &HashMap::new()),
+        Err(ExpressionError::UnknownSymbol(_))
+    )
+}
+
+///Returns the names of the symbols an expression refers to, in the order they are written. An expression that cannot be
+/// read refers to no symbols.
+pub fn expression_symbols(expression: &str) -> Vec<String> {
+    let Ok(tokens) = lex_expression(expression) else {
+        return Vec::new();
+    };
+    tokens
+        .into_iter()
+        .filter_map(|token| match token {
+            ExpressionToken::Symbol(name) => Some(name),
+            _ => None,
+        })
+        .collect()
+}
+
+fn lex_expression(expression: &str) -> Result<Vec<ExpressionToken>, ExpressionError> {
+    let chars: Vec<char> = expression.chars().collect();
+    let mut tokens: Vec<ExpressionToken> = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let char = chars[i];
+        if char.is_whitespace() {
+            i += 1;
+        } else if char == '(' {
+            tokens.push(ExpressionToken::OpenParenthesis);
+            i += 1;
+        } else if char == ')' {
+            tokens.push(ExpressionToken::CloseParenthesis);
+            i += 1;
+        } else if char == '\'' {
+            let (value, length) = read_char_literal(&chars[i..])?;
+            tokens.push(ExpressionToken::Number(value));
+            i += length;
+        } else if char.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && chars[i].is_ascii_alphanumeric() {
+                i += 1;
+            }
+            let number: String = chars[start..i].iter().collect();
+            //a local label reference such as `1f` is a symbol rather than a number
+            if read_local_label_reference(&number).is_some() && parse_number(&number).is_err() {
+                tokens.push(ExpressionToken::Symbol(number));
+            } else {
+                tokens.push(ExpressionToken::Number(parse_number(&number)?));
+            }
+        } else if is_symbol_char(char) {
+            let start = i;
+            while i < chars.len() && (is_symbol_char(chars[i]) || chars[i].is_ascii_digit()) {
+                i += 1;
+            }
+            tokens.push(ExpressionToken::Symbol(chars[start..i].iter().collect()));
+        } else {
+            let rest: String = chars[i..].iter().collect();
+            let operator = OPERATORS
+                .iter()
+                .find(|operator| rest.starts_with(**operator))
+                .ok_or(ExpressionError::Syntax)?;
+            tokens.push(ExpressionToken::Operator(operator));
+            i += operator.len();
+        }
+    }
+    Ok(tokens)
+}
+
+///Returns true for the characters that can start a symbol name.
+pub fn is_symbol_char(char: char) -> bool {
+    char.is_ascii_alphabetic() || char == '_' || char == '.'
+}
+
+fn parse_number(number: &str) -> Result<i64, ExpressionError> {
+    let result = if let Some(hex) = number.strip_prefix("0x") {
+        i64::from_str_radix(hex, 16)
+    } else if let Some(binary) = number.strip_prefix("0b") {
+        i64::from_str_radix(binary, 2)
+    } else {
+        number.parse::<i64>()
+    };
+    result.map_err(|_| ExpressionError::Syntax)
+}
+
+///Reads a character literal such as `'a'` or `'\n'` from the start of the given characters. Returns the value of the
+/// character and the number of characters the literal takes up.
+fn read_char_literal(chars: &[char]) -> Result<(i64, usize), ExpressionError> {
+    let (value, length) = match chars.get(1) {
+        Some('\\') => {
+            let (value, length) = read_escape_sequence(&chars[1..])?;
+            (value, length + 2)
+        }
+        Some(char) if *char != '\'' => (ascii_byte(*char)?, 3),
+        _ => return Err(ExpressionError::Syntax),
+    };
+
+    if chars.get(length - 1) != Some(&'\'') {
+        return Err(ExpressionError::Syntax);
+    }
+    Ok((value as i64, length))
+}
+
+///Reads a token that is a single character literal, such as `'a'` or `'\0'`, returning the byte it stands for.
+pub fn read_char(literal: &str) -> Result<u8, ExpressionError> {
+    let chars: Vec<char> = literal.chars().collect();
+    if chars.first() != Some(&'\'') {
+        return Err(ExpressionError::Syntax);
+    }
+    let (value, length) = read_char_literal(&chars)?;
+    if length != chars.len() {
+        return Err(ExpressionError::Syntax);
+    }
+    Ok(value as u8)
+}
+
+///Decodes the text of a string, which is written without its surrounding quotes, into the bytes it stands for. Every
+/// escape sequence is replaced with the byte it stands for.
+pub fn decode_string(text: &str) -> Result<Vec<u8>, ExpressionError> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut bytes: Vec<u8> = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] == '\\' {
+            let (value, length) = read_escape_sequence(&chars[i..])?;
+            bytes.push(value);
+            i += length;
+        } else {
+            bytes.push(ascii_byte(chars[i])?);
+            i += 1;
+        }
+    }
+    Ok(bytes)
+}
+
+///Reads the C escape sequence at the start of the given characters, which start with the backslash. Returns the byte
+/// the sequence stands for and the number of characters it takes up.
+///
+///Besides the single character escapes, `\xNN` gives a byte in hexadecimal and `\NNN` gives a byte in octal with one to
+/// three digits, so `\0` is the null byte.
+fn read_escape_sequence(chars: &[char]) -> Result<(u8, usize), ExpressionError> {
+    let invalid = |length: usize| {
+        ExpressionError::InvalidEscape(chars[..length.min(chars.len())].iter().collect())
+    };
+    let value = match chars.get(1) {
+        Some('a') => 0x07,
+        Some('b') => 0x08,
+        Some('f') => 0x0c,
+        Some('n') => b'\n',
+        Some('r') => b'\r',
+        Some('t') => b'\t',
+        Some('v') => 0x0b,
+        Some('\\') => b'\\',
+        Some('\'') => b'\'',
+        Some('\"') => b'\"',
+        Some('?') => b'?',
+        Some('x') => {
+            let digits = chars[2..]
+                .iter()
+                .take(2)
+                .take_while(|char| char.is_ascii_hexdigit())
+                .count();
+            let hex: String = chars[2..2 + digits].iter().collect();
+            let value = u8::from_str_radix(&hex, 16).map_err(|_| invalid(2))?;
+            return Ok((value, digits + 2));
+        }
+        Some('0'..='7') => {
+            let digits = chars[1..]
+                .iter()
+                .take(3)
+                .take_while(|char| ('0'..='7').contains(char))
+                .count();
+            let octal: String = chars[1..1 + digits].iter().collect();
+            let value = u8::from_str_radix(&octal, 8).map_err(|_| invalid(digits + 1))?;
+            return Ok((value, digits + 1));
+        }
+        _ => return Err(invalid(2)),
+    };
+    Ok((value, 2))
+}
+
+///Returns the byte of an ASCII character. Any other character takes up more than one byte in UTF-8.
+fn ascii_byte(char: char) -> Result<u8, ExpressionError> {
+    if char.is_ascii() {
+        Ok(char as u8)
+    } else {
+        Err(ExpressionError::NonASCII(char))
+    }
+}
+
+struct ExpressionParser<'a> {
+    tokens: Vec<ExpressionToken>,
+    position: usize,
+    labels: &'a HashMap<String, usize>,
+}
+
+///The binary operators grouped by precedence, from lowest to highest.
+const PRECEDENCE: [&[&str]; 6] = [
+    &["|"],
+    &["^"],
+    &["&"],
+    &["<<", ">>"],
+    &["+", "-"],
+    &["*", "/", "%"],
+];
+
+impl ExpressionParser<'_> {
+    fn parse_binary(&mut self, level: usize) -> Result<i64, ExpressionError> {
+        if level == PRECEDENCE.len() {
+            return self.parse_unary();
+        }
+
+        let mut value = self.parse_binary(level + 1)?;
+        while let Some(ExpressionToken::Operator(operator)) = self.tokens.get(self.position) {
+            let operator = *operator;
+            if !PRECEDENCE[level].contains(&operator) {
+                break;
+            }
+            self.position += 1;
+            let right = self.parse_binary(level + 1)?;
+            value = match operator {
+                "|" => value | right,
+                "^" => value ^ right,
+                "&" => value & right,
+                "<<" => value.wrapping_shl(right as u32),
+                ">>" => value.wrapping_shr(right as u32),
+                "+" => value.wrapping_add(right),
+                "-" => value.wrapping_sub(right),
+                "*" => value.wrapping_mul(right),
+                "/" | "%" if right == 0 => return Err(ExpressionError::DivisionByZero),
+                "/" => value.wrapping_div(right),
+                _ => value.wrapping_rem(right),
+            };
+        }
+        Ok(value)
+    }
+
+    fn parse_unary(&mut self) -> Result<i64, ExpressionError> {
+        match self.tokens.get(self.position).cloned() {
+            Some(ExpressionToken::Operator("-")) => {
+                self.position += 1;
+                Ok(self.parse_unary()?.wrapping_neg())
+            }
+            Some(ExpressionToken::Operator("+")) => {
+                self.position += 1;
+                self.parse_unary()
+            }
+            Some(ExpressionToken::Operator("~")) => {
+                self.position += 1;
+                Ok(!self.parse_unary()?)
+            }
+            Some(ExpressionToken::Number(value)) => {
+                self.position += 1;
+                Ok(value)
+            }
+            Some(ExpressionToken::Symbol(symbol)) => {
+                self.position += 1;
+                match self.labels.get(&symbol) {
+                    Some(address) => Ok(*address as i64),
+                    None => Err(ExpressionError::UnknownSymbol(symbol)),
+                }
+            }
+            Some(ExpressionToken::OpenParenthesis) => {
+                self.position += 1;
+                let value = self.parse_binary(0)?;
+                if self.tokens.get(self.position) != Some(&ExpressionToken::CloseParenthesis) {
+                    return Err(ExpressionError::Syntax);
+                }
+                self.position += 1;
+                Ok(value)
+            }
+            _ => Err(ExpressionError::Syntax),
+        }
+    }
+}
+
+///Replaces every symbol in the text that names a constant with the constant's value. Strings and character literals
+/// are left as written.
+pub fn substitute_constants(text: &str, constants: &HashMap<String, String>) -> String {
+    if constants.is_empty() || text.starts_with('\"') || text.starts_with('\'') {
+        return text.to_string();
+    }
+
+    let chars: Vec<char> = text.chars().collect();
+    let mut substituted = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        //registers such as $t0 are read as a single word so that a constant named t0 does not replace part of them
+        let is_word_char =
+            |char: char| is_symbol_char(char) || char.is_ascii_digit() || char == '$';
+        if is_word_char(chars[i]) {
+            let start = i;
+            while i < chars.len() && is_word_char(chars[i]) {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            match constants.get(&word) {
+                Some(value) => substituted.push_str(value),
+                None => substituted.push_str(&word),
+            }
+        } else {
+            substituted.push(chars[i]);
+            i += 1;
+        }
+    }
+    substituted
+}
+
+///Determines the text a constant is replaced with. Values which can already be evaluated are replaced by the result, other
+/// expressions are wrapped in parentheses so they keep their meaning when substituted, and anything else, such as a
+/// register name, is substituted as written.
+pub fn constant_value(value: &str) -> String {
+    match evaluate_expression(value, &HashMap::new()) {
+        Ok(result) => result.to_string(),
+        Err(ExpressionError::UnknownSymbol(_))
+            if value
+                .contains(|char: char| char.is_whitespace() || "+-*/%<>&|^~".contains(char)) =>
+        {
+            format!("({value})")
+        }
+        _ => value.to_string(),
+    }
+}
+
+///The relocation operators, which select part of an address so it can be built up by a sequence of instructions.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Relocation {
+    ///`%hi`, the upper bits of a value, adjusted for the sign extension of `%lo`.
+    Hi,
+    ///`%lo`, the sign-extended lower bits of a value.
+    Lo,
+    ///`%higher`, bits 32 to 47 of a value on MIPS64.
+    Higher,
+    ///`%highest`, bits 48 to 63 of a value on MIPS64.
+    Highest,
+    ///`%pcrel_hi`, the upper bits of the distance from the current instruction on RISC-V.
+    PcrelHi,
+    ///`%pcrel_lo`, the lower bits of the distance calculated by the matching `%pcrel_hi` on RISC-V.
+    PcrelLo,
+}
+
+///Splits an operand such as `%lo(label)(t0)` into its relocation operator, the expression the operator is applied to,
+/// and the text following it. Returns None if the operand does not start with a relocation operator.
+pub fn read_relocation(operand: &str) -> Option<(Relocation, &str, &str)> {
+    let (name, arguments) = operand.strip_prefix('%')?.split_once('(')?;
+    let relocation = match name.trim() {
+        "hi" => Relocation::Hi,
+        "lo" => Relocation::Lo,
+        "higher" => Relocation::Higher,
+        "highest" => Relocation::Highest,
+        "pcrel_hi" => Relocation::PcrelHi,
+        "pcrel_lo" => Relocation::PcrelLo,
+        _ => return None,
+    };
+
+    //the expression ends at the parenthesis that closes the one following the operator
+    let mut depth = 1;
+    for (i, char) in arguments.char_indices() {
+        match char {
+            '(' => depth += 1,
+            ')' if depth == 1 => return Some((relocation, &arguments[..i], &arguments[i + 1..])),
+            ')' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
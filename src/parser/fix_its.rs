@@ -0,0 +1,63 @@
+use crate::parser::parser_structs_and_enums::{FixIt, ProgramInfo};
+
+///Finds the fix-its of the errors found on the lines of a file, with the line of the file each one edits. Lines of a
+/// program assembled on its own with `parser` are not from any file of a project, so they are found whatever file is
+/// given. A fix-it found more than once, such as on a file which is included twice, is only returned once.
+pub fn fix_its_in_file<'a>(program_info: &'a ProgramInfo, file: &str) -> Vec<(usize, &'a FixIt)> {
+    let mut fix_its: Vec<(usize, &FixIt)> = Vec::new();
+    for line in &program_info.monaco_line_info {
+        let line_number = match &line.source {
+            Some(source) if source.file != file => continue,
+            Some(source) => source.line,
+            None => line.line_number,
+        };
+        for fix_it in line.errors.iter().flat_map(|error| &error.fix_its) {
+            if !fix_its.contains(&(line_number, fix_it)) {
+                fix_its.push((line_number, fix_it));
+            }
+        }
+    }
+    fix_its
+}
+
+///Applies a fix-it to the line it was found on, returning the corrected line.
+pub fn apply_fix_it(line: &str, fix_it: &FixIt) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let (start, end) = fix_it.start_end_columns;
+    let start = start.min(chars.len());
+    let end = end.clamp(start, chars.len());
+    let mut fixed: String = chars[..start].iter().collect();
+    fixed.push_str(&fix_it.replacement);
+    fixed.extend(&chars[end..]);
+    fixed
+}
+
+///Applies fix-its to the contents of a file, returning the corrected contents. The fix-its of each line are applied from
+/// the last column to the first so that each edit leaves the columns of the others in place, and a fix-it which overlaps
+/// one that was already applied is skipped.
+pub fn apply_fix_its(contents: &str, fix_its: &[(usize, &FixIt)]) -> String {
+    let mut lines: Vec<String> = contents.lines().map(str::to_string).collect();
+    for (line_number, line) in lines.iter_mut().enumerate() {
+        let mut line_fix_its: Vec<&FixIt> = fix_its
+            .iter()
+            .filter(|(line, _)| *line == line_number)
+            .map(|(_, fix_it)| *fix_it)
+            .collect();
+        line_fix_its.sort_by_key(|fix_it| std::cmp::Reverse(fix_it.start_end_columns));
+
+        let mut applied_from = usize::MAX;
+        for fix_it in line_fix_its {
+            if fix_it.start_end_columns.1 > applied_from {
+                continue;
+            }
+            *line = apply_fix_it(line, fix_it);
+            applied_from = fix_it.start_end_columns.0;
+        }
+    }
+
+    let mut fixed = lines.join("\n");
+    if contents.ends_with('\n') {
+        fixed.push('\n');
+    }
+    fixed
+}
@@ -0,0 +1,224 @@
+use strum_macros::{Display, EnumIter, EnumString};
+
+///The file formats an assembled program can be exported in for hardware description languages and logic simulators.
+#[derive(Clone, Copy, Debug, Default, Display, EnumIter, EnumString, Eq, PartialEq)]
+pub enum ImageFormat {
+    ///Hexadecimal words for Verilog's `$readmemh`.
+    #[default]
+    #[strum(serialize = "readmemh")]
+    ReadMemH,
+    ///Binary words for Verilog's `$readmemb`.
+    #[strum(serialize = "readmemb")]
+    ReadMemB,
+    ///The `v2.0 raw` image loaded into Logisim's RAM and ROM components.
+    #[strum(serialize = "logisim")]
+    LogisimRaw,
+    ///Intel HEX records.
+    #[strum(serialize = "ihex")]
+    IntelHex,
+    ///Motorola S-records.
+    #[strum(serialize = "srec")]
+    SRecord,
+}
+
+impl ImageFormat {
+    ///The name of the format as it is shown to the user.
+    pub fn description(&self) -> &'static str {
+        match self {
+            ImageFormat::ReadMemH => "Verilog $readmemh",
+            ImageFormat::ReadMemB => "Verilog $readmemb",
+            ImageFormat::LogisimRaw => "Logisim v2.0 raw",
+            ImageFormat::IntelHex => "Intel HEX",
+            ImageFormat::SRecord => "Motorola S-record",
+        }
+    }
+
+    ///The extension of a file in the format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::ReadMemH => "memh",
+            ImageFormat::ReadMemB => "memb",
+            ImageFormat::LogisimRaw => "img",
+            ImageFormat::IntelHex => "ihex",
+            ImageFormat::SRecord => "srec",
+        }
+    }
+}
+
+///The order of the bytes within each word of an exported image.
+#[derive(Clone, Copy, Debug, Default, Display, EnumIter, EnumString, Eq, PartialEq)]
+pub enum Endianness {
+    ///The most significant byte first, which is the order of SWIM's memory.
+    #[default]
+    #[strum(serialize = "big")]
+    Big,
+    #[strum(serialize = "little")]
+    Little,
+}
+
+///How an assembled program is written out as a memory image.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ExportOptions {
+    pub format: ImageFormat,
+    pub endianness: Endianness,
+    ///The number of bytes in each word of the memory being loaded, which is 1, 2, 4, or 8.
+    pub word_bytes: usize,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        ExportOptions {
+            format: ImageFormat::default(),
+            endianness: Endianness::default(),
+            word_bytes: 4,
+        }
+    }
+}
+
+///The word widths in bytes that an image can be exported with.
+pub const WORD_BYTES: [usize; 4] = [1, 2, 4, 8];
+
+///The most bytes written in a single Intel HEX or S-record record.
+const BYTES_PER_RECORD: usize = 16;
+
+///The most values written on a single line of a Logisim image.
+const VALUES_PER_LOGISIM_LINE: usize = 8;
+
+///Writes the words of an assembled program, such as those made by `create_binary_vec`, as a memory image. The image
+/// starts at address 0. Words of the memory are formed from the bytes of the program in order, so a little-endian
+/// image reverses the bytes within each word, and a word width other than 4 bytes splits or joins the program's
+/// words. The last word is padded with zeros if the program does not fill it.
+pub fn export_image(binary: &[u32], options: ExportOptions) -> Result<String, String> {
+    if !WORD_BYTES.contains(&options.word_bytes) {
+        return Err(format!(
+            "A word of {} bytes cannot be exported. Words must be 1, 2, 4, or 8 bytes.",
+            options.word_bytes
+        ));
+    }
+
+    let mut bytes: Vec<u8> = binary.iter().flat_map(|word| word.to_be_bytes()).collect();
+    bytes.resize(
+        bytes.len().div_ceil(options.word_bytes) * options.word_bytes,
+        0,
+    );
+    if options.endianness == Endianness::Little {
+        for word in bytes.chunks_mut(options.word_bytes) {
+            word.reverse();
+        }
+    }
+
+    Ok(match options.format {
+        ImageFormat::ReadMemH => write_readmem(&bytes, options.word_bytes, false),
+        ImageFormat::ReadMemB => write_readmem(&bytes, options.word_bytes, true),
+        ImageFormat::LogisimRaw => write_logisim(&bytes, options.word_bytes),
+        ImageFormat::IntelHex => write_intel_hex(&bytes),
+        ImageFormat::SRecord => write_s_record(&bytes),
+    })
+}
+
+///Reads the bytes of a word, most significant first, into a number.
+fn word_value(word: &[u8]) -> u64 {
+    word.iter()
+        .fold(0, |value, byte| (value << 8) | *byte as u64)
+}
+
+///Writes one word per line in hexadecimal or binary, as read by `$readmemh` and `$readmemb`.
+fn write_readmem(bytes: &[u8], word_bytes: usize, binary: bool) -> String {
+    let mut image = format!(
+        "// {} words of {} bits, starting at address 0\n",
+        bytes.len() / word_bytes,
+        word_bytes * 8
+    );
+    for word in bytes.chunks(word_bytes) {
+        let value = word_value(word);
+        if binary {
+            image.push_str(&format!("{value:0width$b}\n", width = word_bytes * 8));
+        } else {
+            image.push_str(&format!("{value:0width$x}\n", width = word_bytes * 2));
+        }
+    }
+    image
+}
+
+///Writes the words in hexadecimal after the `v2.0 raw` header, writing four or more of the same word in a row as
+/// `count*word`.
+fn write_logisim(bytes: &[u8], word_bytes: usize) -> String {
+    let words: Vec<u64> = bytes.chunks(word_bytes).map(word_value).collect();
+    let mut values = Vec::new();
+    let mut i = 0;
+    while i < words.len() {
+        let run = words[i..]
+            .iter()
+            .take_while(|word| **word == words[i])
+            .count();
+        if run >= 4 {
+            values.push(format!("{run}*{:x}", words[i]));
+            i += run;
+        } else {
+            values.push(format!("{:x}", words[i]));
+            i += 1;
+        }
+    }
+
+    let mut image = "v2.0 raw\n".to_string();
+    for line in values.chunks(VALUES_PER_LOGISIM_LINE) {
+        image.push_str(&line.join(" "));
+        image.push('\n');
+    }
+    image
+}
+
+///Writes a record of bytes as uppercase hexadecimal.
+fn hex_bytes(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{byte:02X}"))
+        .collect::<Vec<String>>()
+        .join("")
+}
+
+///Writes data records of up to 16 bytes and an end of file record in the Intel HEX format.
+fn write_intel_hex(bytes: &[u8]) -> String {
+    let intel_record = |address: usize, record_type: u8, data: &[u8]| {
+        let mut record = vec![
+            data.len() as u8,
+            (address >> 8) as u8,
+            address as u8,
+            record_type,
+        ];
+        record.extend(data);
+        let checksum = record
+            .iter()
+            .fold(0u8, |sum, byte| sum.wrapping_add(*byte))
+            .wrapping_neg();
+        record.push(checksum);
+        format!(":{}\n", hex_bytes(&record))
+    };
+
+    let mut image = String::new();
+    for (i, data) in bytes.chunks(BYTES_PER_RECORD).enumerate() {
+        image.push_str(&intel_record(i * BYTES_PER_RECORD, 0x00, data));
+    }
+    image.push_str(&intel_record(0, 0x01, &[]));
+    image
+}
+
+///Writes a header record, data records of up to 16 bytes with 16-bit addresses, and a termination record in the
+/// Motorola S-record format.
+fn write_s_record(bytes: &[u8]) -> String {
+    let s_record = |record_type: u8, address: usize, data: &[u8]| {
+        //the count includes the address and the checksum
+        let mut record = vec![(data.len() + 3) as u8, (address >> 8) as u8, address as u8];
+        record.extend(data);
+        let checksum = !record.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+        record.push(checksum);
+        format!("S{record_type}{}\n", hex_bytes(&record))
+    };
+
+    let mut image = s_record(0, 0, b"SWIM");
+    for (i, data) in bytes.chunks(BYTES_PER_RECORD).enumerate() {
+        image.push_str(&s_record(1, i * BYTES_PER_RECORD, data));
+    }
+    image.push_str(&s_record(9, 0, &[]));
+    image
+}
@@ -0,0 +1,156 @@
+use crate::emulation_core::mips::memory::CAPACITY_BYTES;
+use crate::parser::expressions::evaluate_expression;
+use crate::parser::parser_structs_and_enums::ErrorType::{InvalidPlacement, NonIntImmediate};
+use crate::parser::parser_structs_and_enums::{Error, Instruction, MonacoLineInfo, Token};
+use std::collections::HashMap;
+
+///The directives which only reserve zeroed space, and so are the only ones allowed in the .bss section.
+pub const BSS_DIRECTIVES: [&str; 5] = [".space", ".align", ".balign", ".p2align", ".org"];
+
+///A directive in the text section that changes where the following instructions are placed, such as `.text 0x400`,
+/// `.org 0x800`, or `.align 3`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TextPlacement {
+    pub directive: Token,
+    ///The address or alignment given to the directive, if any.
+    pub argument: Option<Token>,
+    ///The line the directive was written on.
+    pub line_number: usize,
+    ///The line of the first instruction the directive places. Instructions expanded from a pseudo-instruction or macro
+    /// share a line, so the directive applies to the first of them.
+    pub first_instruction_line: usize,
+}
+
+///Returns true for the directives that move where the next instruction or data is placed without committing anything
+/// to memory themselves.
+pub fn is_placement_directive(name: &str) -> bool {
+    matches!(
+        &*name.to_lowercase(),
+        ".align" | ".balign" | ".p2align" | ".org"
+    )
+}
+
+///Returns the address of the next instruction or data after a placement directive, which is given the address it would
+/// otherwise have been placed at. `.align n` and `.p2align n` align to 2^n bytes and `.balign n` aligns to n bytes.
+/// `.org`, `.text`, and `.data` move to the absolute address they are given, which cannot be before anything already
+/// placed in the section. Addresses in the text section must stay aligned to a word.
+pub fn place(
+    directive: &Token,
+    argument: Option<&Token>,
+    address: usize,
+    in_text: bool,
+) -> Result<usize, Error> {
+    let name = directive.token_name.to_lowercase();
+    let Some(argument) = argument else {
+        return Err(placement_error(
+            directive,
+            format!("{name} must be given a value.\n"),
+        ));
+    };
+    let value = evaluate_expression(&argument.token_name, &HashMap::new()).map_err(|_| Error {
+        error_name: NonIntImmediate,
+        token_causing_error: argument.token_name.clone(),
+        start_end_columns: argument.start_end_columns,
+        message: "".to_string(),
+        fix_its: Vec::new(),
+    })?;
+
+    let placed = match &*name {
+        ".align" | ".p2align" | ".balign" => {
+            let alignment = if name == ".balign" {
+                value
+            } else if (0..=16).contains(&value) {
+                1 << value
+            } else {
+                0
+            };
+            if !(1..=1 << 16).contains(&alignment) || alignment & (alignment - 1) != 0 {
+                return Err(placement_error(
+                    argument,
+                    format!("{name} must be given a power of two up to 2^16 bytes, or the power itself for .align and .p2align.\n"),
+                ));
+            }
+            address.div_ceil(alignment as usize) * alignment as usize
+        }
+        _ => {
+            if value < 0 || value as usize >= CAPACITY_BYTES {
+                return Err(placement_error(
+                    argument,
+                    format!("The address must be within the {CAPACITY_BYTES} bytes of memory.\n"),
+                ));
+            }
+            if (value as usize) < address {
+                return Err(placement_error(
+                    argument,
+                    format!("The address must not be before 0x{address:x}, the address of the next instruction or data already placed.\n"),
+                ));
+            }
+            value as usize
+        }
+    };
+
+    if in_text && placed % 4 != 0 {
+        return Err(placement_error(
+            argument,
+            "Instructions must be placed at an address that is a multiple of 4.\n".to_string(),
+        ));
+    }
+    Ok(placed)
+}
+
+fn placement_error(token: &Token, message: String) -> Error {
+    Error {
+        error_name: InvalidPlacement,
+        token_causing_error: token.token_name.clone(),
+        start_end_columns: token.start_end_columns,
+        message,
+        fix_its: Vec::new(),
+    }
+}
+
+///Gives every instruction its address, in words, as its instruction number. Instructions are placed one after another
+/// from address 0 unless a placement directive in the text section moves them. Errors in the placement directives are
+/// added to the lines they were written on.
+pub fn assign_text_addresses(
+    instructions: &mut [Instruction],
+    placements: &[TextPlacement],
+    monaco_line_info: &mut [MonacoLineInfo],
+) {
+    let mut address = 0;
+    let mut placements = placements.iter().peekable();
+    for instruction in instructions {
+        while let Some(placement) = placements
+            .next_if(|placement| instruction.line_number >= placement.first_instruction_line)
+        {
+            match place(
+                &placement.directive,
+                placement.argument.as_ref(),
+                address,
+                true,
+            ) {
+                Ok(placed) => address = placed,
+                Err(error) => monaco_line_info[placement.line_number].errors.push(error),
+            }
+        }
+        instruction.instruction_number = address >> 2;
+        address += 4;
+    }
+}
+
+///Returns the address the data section starts at, which directly follows the last instruction.
+pub fn data_base_address(instructions: &[Instruction]) -> usize {
+    instructions
+        .last()
+        .map_or(0, |instruction| (instruction.instruction_number + 1) << 2)
+}
+
+///Builds the list of which line each word of the text section came from. Words left empty between instructions placed
+/// by a directive are attributed to the instruction that follows them.
+pub fn map_addresses_to_lines(instructions: &[Instruction]) -> Vec<usize> {
+    let mut address_to_line_number = Vec::new();
+    for instruction in instructions {
+        address_to_line_number.resize(instruction.instruction_number, instruction.line_number);
+        address_to_line_number.push(instruction.line_number);
+    }
+    address_to_line_number
+}
@@ -0,0 +1,938 @@
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::parser::assembling::assemble_data_binary_at;
+use crate::parser::expressions::{
+    evaluate_expression, expression_symbols, is_symbol_char, read_relocation, Relocation,
+};
+use crate::parser::layout::{data_base_address, is_placement_directive, map_addresses_to_lines};
+use crate::parser::parser_assembler_main::{
+    assemble_laid_out_program, create_binary_vec, determine_pc_starting_point, lay_out_program,
+};
+use crate::parser::parser_structs_and_enums::ErrorType::{
+    DuplicateGlobalSymbol, ImmediateOutOfBounds, LabelNotFound, NonIntImmediate, UndefinedSymbol,
+};
+use crate::parser::parser_structs_and_enums::{
+    Data, Error, Instruction, MonacoLineInfo, ProgramInfo,
+};
+use crate::parser::parsing::{create_label_map_at, declared_symbols};
+use crate::parser::project::{file_with_includes, ProjectFile};
+use crate::parser::source_map::create_source_map;
+use std::collections::HashMap;
+
+///The sections of a unit that symbols are defined in and relocations are made to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Section {
+    Text,
+    Data,
+}
+
+///A label defined by a unit.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnitSymbol {
+    pub section: Section,
+    ///The distance in bytes from the start of the section to the label.
+    pub offset: usize,
+    ///True if the label is exported with .globl or .global, so the other units of the program can refer to it.
+    pub global: bool,
+    pub line_number: usize,
+    pub start_end_columns: (usize, usize),
+}
+
+///A place where a unit refers to a symbol that it does not define.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SymbolReference {
+    pub symbol: String,
+    pub line_number: usize,
+    pub start_end_columns: (usize, usize),
+}
+
+///The ways the address of a symbol is written into an instruction or data entry.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RelocationType {
+    ///The 16-bit word offset of a MIPS branch.
+    MipsPc16,
+    ///The 26-bit word address of a MIPS j or jal.
+    Mips26,
+    ///The upper 16 bits of an address, from lui or `%hi`.
+    MipsHi16,
+    ///The lower 16 bits of an address, from a load or store of a label or `%lo`.
+    MipsLo16,
+    ///Bits 32 to 47 of an address, from `%higher`.
+    MipsHigher,
+    ///Bits 48 to 63 of an address, from `%highest`.
+    MipsHighest,
+    ///The target of a RISC-V conditional branch.
+    RiscvBranch,
+    ///The target of a RISC-V jal.
+    RiscvJal,
+    ///The upper 20 bits of an address, from lui or `%hi`.
+    RiscvHi20,
+    ///The lower 12 bits of an address in an I-type instruction, from `%lo`.
+    RiscvLo12I,
+    ///The lower 12 bits of an address in a store, from `%lo`.
+    RiscvLo12S,
+    ///The upper 20 bits of the distance to an address, from auipc or `%pcrel_hi`.
+    RiscvPcrelHi20,
+    ///The lower 12 bits of the distance found by a `%pcrel_hi`, in an I-type instruction.
+    RiscvPcrelLo12I,
+    ///The lower 12 bits of the distance found by a `%pcrel_hi`, in a store.
+    RiscvPcrelLo12S,
+    ///A whole value in the 16-bit immediate or memory offset of a MIPS instruction, such as `addi $t0, $zero, label + 4`.
+    Mips16,
+    ///A whole value in the 12-bit immediate or memory offset of a RISC-V I-type instruction.
+    Riscv12I,
+    ///A whole value in the 12-bit offset of a RISC-V store.
+    Riscv12S,
+    ///A whole value in the 20-bit immediate of a RISC-V lui or auipc.
+    Riscv20,
+    ///A data entry of the given number of bytes, such as `.word label`.
+    Data(usize),
+}
+
+impl RelocationType {
+    ///Returns true if the value written for the relocation depends on where the program is placed, rather than only on
+    /// the distance from the instruction to the symbol.
+    pub fn is_absolute(self) -> bool {
+        !matches!(
+            self,
+            RelocationType::MipsPc16
+                | RelocationType::RiscvPcrelHi20
+                | RelocationType::RiscvPcrelLo12I
+                | RelocationType::RiscvPcrelLo12S
+        )
+    }
+}
+
+///A place in a unit that holds the address of a symbol, which changes once the unit is placed in the linked program.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RelocationRecord {
+    pub section: Section,
+    ///The distance in bytes from the start of the section to the instruction or data entry.
+    pub offset: usize,
+    pub relocation_type: RelocationType,
+    pub symbol: String,
+    ///The value added to the address of the symbol, such as 8 for `label + 8`.
+    pub addend: i64,
+    pub line_number: usize,
+    pub start_end_columns: (usize, usize),
+}
+
+///A place in an assembled program that holds the address of a symbol, so the program can be moved to another address.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PlacedRelocation {
+    ///The address of the instruction or data entry in memory.
+    pub address: usize,
+    pub relocation_type: RelocationType,
+    ///The address of the symbol plus the addend, which is written at the relocation.
+    pub value: i64,
+}
+
+///A file of a project assembled on its own, with its text starting at address 0 and its data directly following it.
+/// Symbols it does not define are given the address 0 until the unit is linked, when its relocations are written into
+/// its text and data.
+#[derive(Clone, Debug)]
+pub struct ObjectUnit {
+    pub file: String,
+    ///The lines, instructions, and data of the unit as assembled on its own.
+    pub program_info: ProgramInfo,
+    pub text: Vec<u32>,
+    pub data: Vec<u8>,
+    pub symbols: HashMap<String, UnitSymbol>,
+    pub undefined_symbols: Vec<SymbolReference>,
+    pub relocations: Vec<RelocationRecord>,
+}
+
+///How far every symbol is moved to find which expressions depend on where a unit is placed.
+const RELOCATION_TEST_DISTANCE: usize = 0x1000;
+
+///Assembles the files of a project given by linked_files as separate units and links them, with the first as the main
+/// file of the program. The lines of each file are given by tokenize_files.
+pub fn link_files(
+    files: &[ProjectFile],
+    file_lines: &[Vec<MonacoLineInfo>],
+    linked_files: &[usize],
+    arch: AvailableDatapaths,
+) -> (ProgramInfo, Vec<u32>, HashMap<String, usize>) {
+    let units: Vec<ObjectUnit> = linked_files
+        .iter()
+        .map(|file| assemble_unit(files, file_lines, *file, arch))
+        .collect();
+    link(&units)
+}
+
+///Assembles a file of a project, along with the files it includes, as a unit to be linked. On MIPS, only the main file
+/// of the project, the first file, has a syscall added to its end since the program does not end in the other files.
+pub fn assemble_unit(
+    files: &[ProjectFile],
+    file_lines: &[Vec<MonacoLineInfo>],
+    file: usize,
+    arch: AvailableDatapaths,
+) -> ObjectUnit {
+    let laid_out = lay_out_program(file_with_includes(files, file_lines, file), arch, file == 0);
+    let text_size = data_base_address(&laid_out.instructions);
+
+    //the symbols the unit does not define are those that cannot be found when it is assembled alone
+    let mut alone = laid_out.clone();
+    let (_, defined) = assemble_laid_out_program(&mut alone, text_size, &HashMap::new(), arch);
+    let undefined_symbols = find_undefined_symbols(&alone, &defined);
+    let placeholders: HashMap<String, usize> = undefined_symbols
+        .iter()
+        .map(|reference| (reference.symbol.clone(), 0))
+        .collect();
+
+    let mut program_info = laid_out.clone();
+    let (data, labels) =
+        assemble_laid_out_program(&mut program_info, text_size, &placeholders, arch);
+    let (text, _) = create_binary_vec(program_info.instructions.clone(), Vec::new());
+
+    let globals: Vec<String> = declared_symbols(&laid_out.monaco_line_info)
+        .into_iter()
+        .filter(|(_, _, is_extern)| !is_extern)
+        .map(|(symbol, _, _)| symbol.token_name)
+        .collect();
+    let mut symbols = HashMap::new();
+    for (name, line_number, start_end_columns) in label_definitions(&laid_out) {
+        let Some(address) = labels.get(&name) else {
+            continue;
+        };
+        let (section, offset) = if *address < text_size {
+            (Section::Text, *address)
+        } else {
+            (Section::Data, address - text_size)
+        };
+        let global = globals.contains(&name);
+        symbols.insert(
+            name,
+            UnitSymbol {
+                section,
+                offset,
+                global,
+                line_number,
+                start_end_columns,
+            },
+        );
+    }
+
+    let mut addresses = placeholders;
+    addresses.extend(labels.clone());
+    let relocations = find_relocations(
+        &laid_out,
+        &program_info.data,
+        (&labels, &addresses),
+        text_size,
+        arch,
+    );
+
+    ObjectUnit {
+        file: files[file].name.clone(),
+        program_info,
+        text,
+        data,
+        symbols,
+        undefined_symbols,
+        relocations,
+    }
+}
+
+///Finds the relocations of a program assembled as a whole, from the program before its symbols are resolved and the
+/// data of the assembled program. data_base is the address its data starts at.
+pub fn program_relocations(
+    laid_out: &ProgramInfo,
+    data: &[Data],
+    labels: &HashMap<String, usize>,
+    data_base: usize,
+    arch: AvailableDatapaths,
+) -> Vec<PlacedRelocation> {
+    find_relocations(laid_out, data, (labels, labels), data_base, arch)
+        .into_iter()
+        .map(|relocation| PlacedRelocation {
+            address: match relocation.section {
+                Section::Text => relocation.offset,
+                Section::Data => data_base + relocation.offset,
+            },
+            relocation_type: relocation.relocation_type,
+            value: labels[&relocation.symbol] as i64 + relocation.addend,
+        })
+        .collect()
+}
+
+///Links units assembled by assemble_unit into a single program. The text of every unit is placed in order starting at
+/// address 0, followed by the data of every unit in the same order, with the data of each unit after the first starting
+/// on an 8-byte boundary. `.org` and `.text` addresses in a unit are relative to the start of its text.
+///
+///The text and data of each unit are copied to their place, with the final address of every symbol written into them at
+/// each of the unit's relocations. Symbols that are not exported by any unit and symbols exported by more than one unit
+/// are reported on the lines that use or define them, as are addresses that no longer fit where they are written.
+pub fn link(units: &[ObjectUnit]) -> (ProgramInfo, Vec<u32>, HashMap<String, usize>) {
+    let mut placed_units: Vec<ProgramInfo> = Vec::new();
+    let mut text_bases = Vec::new();
+    let mut text_end = 0;
+    for unit in units {
+        let mut program_info = unit.program_info.clone();
+        for instruction in &mut program_info.instructions {
+            instruction.instruction_number += text_end >> 2;
+        }
+        text_bases.push(text_end);
+        text_end = text_end.max(data_base_address(&program_info.instructions));
+        placed_units.push(program_info);
+    }
+
+    //the data of each unit is laid out again at its place, since .align, .org, and .data depend on the address it
+    // starts at, which moves each of its entries to where they are copied
+    let mut data_bases = Vec::new();
+    let mut data_sizes = Vec::new();
+    let mut unit_labels = Vec::new();
+    let mut data_base = text_end;
+    for program_info in &mut placed_units {
+        let mut placed_data = program_info.data.clone();
+        let data_size = assemble_data_binary_at(&mut placed_data, data_base).len();
+        unit_labels.push(create_label_map_at(
+            &mut program_info.instructions.clone(),
+            &mut placed_data.clone(),
+            data_base,
+        ));
+        for (datum, placed) in program_info.data.iter_mut().zip(placed_data) {
+            datum.data_number = placed.data_number;
+        }
+        data_bases.push(data_base);
+        data_sizes.push(data_size);
+        if data_size > 0 {
+            data_base = (data_base + data_size).next_multiple_of(8);
+        }
+    }
+
+    //the errors found by the linker, along with the unit and line they are reported on
+    let mut link_errors: Vec<(usize, usize, Error)> = Vec::new();
+
+    let mut globals: HashMap<String, usize> = HashMap::new();
+    let mut global_units: HashMap<String, usize> = HashMap::new();
+    for (i, unit) in units.iter().enumerate() {
+        let mut exported: Vec<(&String, &UnitSymbol)> = unit
+            .symbols
+            .iter()
+            .filter(|(_, symbol)| symbol.global)
+            .collect();
+        exported.sort_by_key(|(_, symbol)| symbol.line_number);
+        for (name, symbol) in exported {
+            if let Some(other) = global_units.get(name) {
+                link_errors.push((
+                    i,
+                    symbol.line_number,
+                    Error {
+                        error_name: DuplicateGlobalSymbol,
+                        token_causing_error: name.clone(),
+                        start_end_columns: symbol.start_end_columns,
+                        message: format!(
+                            "`{name}` is also exported by {}. A symbol exported with .globl can only be defined in one file of the project.\n",
+                            units[*other].file
+                        ),
+                        fix_its: Vec::new(),
+                    },
+                ));
+                continue;
+            }
+            global_units.insert(name.clone(), i);
+            globals.insert(name.clone(), unit_labels[i][name]);
+        }
+    }
+
+    let mut linked = ProgramInfo::default();
+    let mut relocations = Vec::new();
+    let mut vec_of_data: Vec<u8> = Vec::new();
+    let mut console_out = String::new();
+    for (i, (unit, mut program_info)) in units.iter().zip(placed_units).enumerate() {
+        for reference in &unit.undefined_symbols {
+            if globals.contains_key(&reference.symbol) {
+                continue;
+            }
+            let message = match units
+                .iter()
+                .find(|other| other.symbols.contains_key(&reference.symbol))
+            {
+                Some(other) => format!(
+                    "`{}` is defined in {} but is not exported with .globl, so it cannot be used in another file.\n",
+                    reference.symbol, other.file
+                ),
+                None => format!(
+                    "`{}` is not defined in this file, and no other file of the project exports it with .globl.\n",
+                    reference.symbol
+                ),
+            };
+            link_errors.push((
+                i,
+                reference.line_number,
+                Error {
+                    error_name: UndefinedSymbol,
+                    token_causing_error: reference.symbol.clone(),
+                    start_end_columns: reference.start_end_columns,
+                    message,
+                    fix_its: Vec::new(),
+                },
+            ));
+        }
+
+        //each content entry keeps its bytes, which do not depend on where it is placed, at its new place
+        let mut data = vec![0; data_sizes[i]];
+        let mut data_places = Vec::new();
+        for (datum, placed) in unit.program_info.data.iter().zip(&program_info.data) {
+            let length = datum_length(datum);
+            let (from, to) = (datum.data_number, placed.data_number);
+            if length > 0 && from + length <= unit.data.len() && to + length <= data.len() {
+                data[to..to + length].copy_from_slice(&unit.data[from..from + length]);
+                data_places.push((from, to, length));
+            }
+        }
+
+        let mut text = unit.text.clone();
+        for relocation in &unit.relocations {
+            //symbols that are not defined were reported above and are left at 0
+            let Some(address) = unit_labels[i]
+                .get(&relocation.symbol)
+                .or_else(|| globals.get(&relocation.symbol))
+            else {
+                continue;
+            };
+            let value = *address as i64 + relocation.addend;
+            let (place, relocated) = match relocation.section {
+                Section::Text => {
+                    let index = relocation.offset >> 2;
+                    let place = text_bases[i] + relocation.offset;
+                    let word = text
+                        .get(index)
+                        .and_then(|word| relocate(*word, relocation.relocation_type, value, place));
+                    if let Some(word) = word {
+                        text[index] = word;
+                    }
+                    (place, word.is_some())
+                }
+                Section::Data => {
+                    let RelocationType::Data(size) = relocation.relocation_type else {
+                        continue;
+                    };
+                    let Some((from, to, _)) = data_places.iter().find(|(from, _, length)| {
+                        (*from..from + length).contains(&relocation.offset)
+                    }) else {
+                        continue;
+                    };
+                    let offset = to + (relocation.offset - from);
+                    let relocated = data
+                        .get_mut(offset..offset + size)
+                        .is_some_and(|bytes| relocate_data(bytes, value));
+                    (data_bases[i] + offset, relocated)
+                }
+            };
+            if relocated {
+                relocations.push(PlacedRelocation {
+                    address: place,
+                    relocation_type: relocation.relocation_type,
+                    value,
+                });
+            } else {
+                link_errors.push((
+                    i,
+                    relocation.line_number,
+                    Error {
+                        error_name: ImmediateOutOfBounds,
+                        token_causing_error: relocation.symbol.clone(),
+                        start_end_columns: relocation.start_end_columns,
+                        message: format!(
+                            "`{}` is at 0x{address:x} once the files are linked, which does not fit in this {}.\n",
+                            relocation.symbol,
+                            match relocation.section {
+                                Section::Text => "instruction",
+                                Section::Data => "data entry",
+                            }
+                        ),
+                        fix_its: Vec::new(),
+                    },
+                ));
+            }
+        }
+        for instruction in &mut program_info.instructions {
+            let index = instruction.instruction_number - (text_bases[i] >> 2);
+            instruction.binary = text[index];
+        }
+        if !data.is_empty() {
+            vec_of_data.resize(data_bases[i] - text_end, 0);
+            vec_of_data.extend(data);
+        }
+
+        let assembly_failed = program_info
+            .instructions
+            .iter()
+            .any(|instruction| !instruction.errors.is_empty())
+            || program_info
+                .data
+                .iter()
+                .any(|datum| !datum.errors.is_empty());
+        if assembly_failed {
+            console_out.push_str(&program_info.console_out_post_assembly);
+        }
+        for (_, line_number, error) in link_errors.iter().filter(|(unit, _, _)| *unit == i) {
+            let line = &mut program_info.monaco_line_info[*line_number];
+            line.errors.push(error.clone());
+            console_out.push_str(&format!(
+                "{} on {} with token \"{}\"\n{}\n",
+                error.error_name,
+                line.location(),
+                error.token_causing_error,
+                error.message
+            ));
+        }
+
+        append_unit(&mut linked, program_info, data_bases[i] - text_end);
+    }
+
+    for line in &linked.monaco_line_info {
+        linked
+            .updated_monaco_string
+            .push_str(&format!("{}\n", line.updated_monaco_string));
+    }
+    linked.console_out_post_assembly = if console_out.is_empty() {
+        "Program assembled successfully!".to_string()
+    } else {
+        console_out
+    };
+    linked.relocations = relocations;
+    linked.address_to_line_number = map_addresses_to_lines(&linked.instructions);
+    linked.source_map = create_source_map(&linked.instructions, &linked.monaco_line_info);
+
+    //the program starts at a main or start label of the main file, or one exported by another file
+    let mut labels = unit_labels.first().cloned().unwrap_or_default();
+    for (name, address) in &globals {
+        labels.entry(name.clone()).or_insert(*address);
+    }
+    let text_starting_point = linked
+        .instructions
+        .first()
+        .map_or(0, |instruction| instruction.instruction_number << 2);
+    linked.pc_starting_point = determine_pc_starting_point(labels.clone()).max(text_starting_point);
+    for unit_labels in unit_labels.iter().skip(1) {
+        for (name, address) in unit_labels {
+            labels.entry(name.clone()).or_insert(*address);
+        }
+    }
+
+    let (binary, data_starting_point) = create_binary_vec(linked.instructions.clone(), vec_of_data);
+    linked.data_starting_point = data_starting_point;
+
+    (linked, binary, labels)
+}
+
+///Adds the lines, instructions, and data of a unit to the end of the linked program, moving their line numbers past the
+/// lines already in it. data_offset is where the data of the unit starts within the data of the linked program.
+fn append_unit(linked: &mut ProgramInfo, mut program_info: ProgramInfo, data_offset: usize) {
+    let line_offset = linked.monaco_line_info.len();
+    for line in &mut program_info.monaco_line_info {
+        line.line_number += line_offset;
+    }
+    for instruction in &mut program_info.instructions {
+        instruction.line_number += line_offset;
+        if let Some(macro_line_number) = &mut instruction.macro_line_number {
+            *macro_line_number += line_offset;
+        }
+        for label in &mut instruction.labels {
+            label.token_line += line_offset;
+        }
+    }
+    for datum in &mut program_info.data {
+        datum.line_number += line_offset;
+        if let Some(macro_line_number) = &mut datum.macro_line_number {
+            *macro_line_number += line_offset;
+        }
+        datum.data_number += data_offset;
+    }
+
+    linked
+        .monaco_line_info
+        .extend(program_info.monaco_line_info);
+    linked.instructions.extend(program_info.instructions);
+    linked.data.extend(program_info.data);
+}
+
+///Finds the symbols a unit assembled without the symbols of other units could not find, once for each line using them.
+/// A load or store of a symbol that is not found on MIPS reads it as an immediate instead, so a name given where a number
+/// is expected is also taken as a symbol unless the unit defines it.
+fn find_undefined_symbols(
+    program_info: &ProgramInfo,
+    defined: &HashMap<String, usize>,
+) -> Vec<SymbolReference> {
+    let errors = program_info
+        .instructions
+        .iter()
+        .flat_map(|instruction| {
+            instruction
+                .errors
+                .iter()
+                .map(|error| (instruction.line_number, error))
+        })
+        .chain(
+            program_info
+                .data
+                .iter()
+                .flat_map(|datum| datum.errors.iter().map(|error| (datum.line_number, error))),
+        );
+
+    let mut references: Vec<SymbolReference> = Vec::new();
+    for (line_number, error) in errors {
+        let symbol = &error.token_causing_error;
+        let is_reference = match error.error_name {
+            LabelNotFound => true,
+            NonIntImmediate => {
+                symbol.starts_with(is_symbol_char)
+                    && symbol
+                        .chars()
+                        .all(|char| is_symbol_char(char) || char.is_ascii_digit())
+                    && !defined.contains_key(symbol)
+            }
+            _ => false,
+        };
+        if !is_reference
+            || references.iter().any(|reference| {
+                reference.symbol == *symbol && reference.line_number == line_number
+            })
+        {
+            continue;
+        }
+        references.push(SymbolReference {
+            symbol: symbol.clone(),
+            line_number,
+            start_end_columns: error.start_end_columns,
+        });
+    }
+    references
+}
+
+///Lists the name, line, and columns of every label defined by a program.
+fn label_definitions(program_info: &ProgramInfo) -> Vec<(String, usize, (usize, usize))> {
+    let mut definitions = Vec::new();
+    for instruction in &program_info.instructions {
+        for label in &instruction.labels {
+            definitions.push((
+                label.token.token_name.clone(),
+                label.token_line,
+                label.token.start_end_columns,
+            ));
+        }
+    }
+    for datum in &program_info.data {
+        if !datum.label.token_name.is_empty() {
+            definitions.push((
+                datum.label.token_name.clone(),
+                datum.line_number,
+                datum.label.start_end_columns,
+            ));
+        }
+    }
+    definitions
+}
+
+///Finds the operands and data entries of a unit whose values depend on where a symbol is placed. The operands are read
+/// from the unit before its symbols are resolved, and the data entries from the assembled unit, which knows where each
+/// entry is. labels holds the symbols the unit defines and addresses also holds those it does not.
+fn find_relocations(
+    laid_out: &ProgramInfo,
+    data: &[Data],
+    (labels, addresses): (&HashMap<String, usize>, &HashMap<String, usize>),
+    text_size: usize,
+    arch: AvailableDatapaths,
+) -> Vec<RelocationRecord> {
+    let moved_addresses: HashMap<String, usize> = addresses
+        .iter()
+        .map(|(name, address)| (name.clone(), address + RELOCATION_TEST_DISTANCE))
+        .collect();
+    let addresses = (addresses, &moved_addresses);
+    let mut relocations = Vec::new();
+    let instructions = &laid_out.instructions;
+    for (index, instruction) in instructions.iter().enumerate() {
+        let operator = instruction.operator.token_name.to_lowercase();
+        let offset = instruction.instruction_number << 2;
+        //a load or store of a label on MIPS is a lui of the label into $at followed by the load or store from $at, which
+        // is given the lower 16 bits of the address
+        let loads_label = arch == AvailableDatapaths::MIPS
+            && operator == "lui"
+            && instructions.get(index + 1).is_some_and(|next| {
+                ["lw", "sw", "lwc1", "swc1"].contains(&&*next.operator.token_name)
+            });
+        for operand in &instruction.operands {
+            let paired = loads_label && addresses.0.contains_key(&operand.token_name);
+            let (relocation_type, mut expression) = match read_relocation(&operand.token_name) {
+                Some((relocation, expression, _)) => {
+                    let Some(relocation_type) =
+                        relocation_operator_type(relocation, &operator, arch)
+                    else {
+                        continue;
+                    };
+                    (relocation_type, expression.to_string())
+                }
+                None if paired => (RelocationType::MipsHi16, operand.token_name.clone()),
+                None => (
+                    instruction_relocation_type(&operator, arch),
+                    memory_offset(&operand.token_name, addresses.0).to_string(),
+                ),
+            };
+            //`%pcrel_lo` of the label of an auipc takes the lower bits of the distance that auipc finds, which is kept as
+            // a distance from the instruction before this one, as when it directly follows the auipc
+            let mut distance = 0;
+            if matches!(
+                relocation_type,
+                RelocationType::RiscvPcrelLo12I | RelocationType::RiscvPcrelLo12S
+            ) {
+                if let Some((target, auipc_address)) =
+                    labelled_pcrel_hi(&expression, labels, instructions)
+                {
+                    expression = target;
+                    distance = offset as i64 - 4 - auipc_address as i64;
+                }
+            }
+            let Some((symbol, addend)) = relocated_symbol(&expression, addresses) else {
+                continue;
+            };
+            //the distance between two instructions of the unit does not change when it is placed
+            if !relocation_type.is_absolute()
+                && labels
+                    .get(&symbol)
+                    .is_some_and(|address| *address < text_size)
+            {
+                continue;
+            }
+            let relocation = RelocationRecord {
+                section: Section::Text,
+                offset,
+                relocation_type,
+                symbol,
+                addend: addend + distance,
+                line_number: instruction.line_number,
+                start_end_columns: operand.start_end_columns,
+            };
+            if paired {
+                relocations.push(relocation.clone());
+                relocations.push(RelocationRecord {
+                    offset: offset + 4,
+                    relocation_type: RelocationType::MipsLo16,
+                    line_number: instructions[index + 1].line_number,
+                    ..relocation
+                });
+            } else {
+                relocations.push(relocation);
+            }
+        }
+    }
+
+    for datum in data {
+        let size = match &*datum.data_type.token_name.to_lowercase() {
+            ".byte" => 1,
+            ".half" => 2,
+            ".word" => 4,
+            ".dword" | ".quad" => 8,
+            _ => continue,
+        };
+        for (i, entry) in datum.data_entries.iter().enumerate() {
+            let Some((symbol, addend)) = relocated_symbol(&entry.token_name, addresses) else {
+                continue;
+            };
+            relocations.push(RelocationRecord {
+                section: Section::Data,
+                offset: datum.data_number + i * size,
+                relocation_type: RelocationType::Data(size),
+                symbol,
+                addend,
+                line_number: datum.line_number,
+                start_end_columns: entry.start_end_columns,
+            });
+        }
+    }
+    relocations
+}
+
+///Finds the target of the auipc that `%pcrel_lo(label)` refers to by its label, along with the address of the auipc.
+fn labelled_pcrel_hi(
+    expression: &str,
+    labels: &HashMap<String, usize>,
+    instructions: &[Instruction],
+) -> Option<(String, usize)> {
+    let address = *labels.get(expression.trim())?;
+    let auipc = instructions
+        .iter()
+        .find(|instruction| instruction.instruction_number << 2 == address)?;
+    if auipc.operator.token_name != "auipc" {
+        return None;
+    }
+    match read_relocation(&auipc.operands.get(1)?.token_name) {
+        Some((Relocation::PcrelHi, target, _)) => Some((target.trim().to_string(), address)),
+        _ => None,
+    }
+}
+
+///Finds the first symbol of an expression and the value added to its address, or None if the expression does not
+/// depend on where its symbols are placed, as with `end - start`. The expression is evaluated with the addresses of the
+/// symbols and again with every symbol moved by RELOCATION_TEST_DISTANCE.
+fn relocated_symbol(
+    expression: &str,
+    (addresses, moved_addresses): (&HashMap<String, usize>, &HashMap<String, usize>),
+) -> Option<(String, i64)> {
+    let symbol = expression_symbols(expression)
+        .into_iter()
+        .find(|symbol| addresses.contains_key(symbol))?;
+    let value = evaluate_expression(expression, addresses).ok()?;
+    if evaluate_expression(expression, moved_addresses).ok()? == value {
+        return None;
+    }
+    Some((symbol.clone(), value - addresses[&symbol] as i64))
+}
+
+///Takes the offset of a memory operand such as `label+4(t0)`, or the whole operand if it is not a memory operand.
+fn memory_offset<'a>(operand: &'a str, addresses: &HashMap<String, usize>) -> &'a str {
+    match operand.rfind('(') {
+        Some(index)
+            if index > 0
+                && operand.ends_with(')')
+                && evaluate_expression(operand, addresses).is_err() =>
+        {
+            &operand[..index]
+        }
+        _ => operand,
+    }
+}
+
+///The relocation made to an operand using a relocation operator, or None if the operator is not available for the
+/// architecture, which the assembler reports.
+fn relocation_operator_type(
+    relocation: Relocation,
+    operator: &str,
+    arch: AvailableDatapaths,
+) -> Option<RelocationType> {
+    let is_store = is_riscv_store(operator);
+    match (arch, relocation) {
+        (AvailableDatapaths::MIPS, Relocation::Hi) => Some(RelocationType::MipsHi16),
+        (AvailableDatapaths::MIPS, Relocation::Lo) => Some(RelocationType::MipsLo16),
+        (AvailableDatapaths::MIPS, Relocation::Higher) => Some(RelocationType::MipsHigher),
+        (AvailableDatapaths::MIPS, Relocation::Highest) => Some(RelocationType::MipsHighest),
+        (AvailableDatapaths::RISCV, Relocation::Hi) => Some(RelocationType::RiscvHi20),
+        (AvailableDatapaths::RISCV, Relocation::Lo) if is_store => Some(RelocationType::RiscvLo12S),
+        (AvailableDatapaths::RISCV, Relocation::Lo) => Some(RelocationType::RiscvLo12I),
+        (AvailableDatapaths::RISCV, Relocation::PcrelHi) => Some(RelocationType::RiscvPcrelHi20),
+        (AvailableDatapaths::RISCV, Relocation::PcrelLo) if is_store => {
+            Some(RelocationType::RiscvPcrelLo12S)
+        }
+        (AvailableDatapaths::RISCV, Relocation::PcrelLo) => Some(RelocationType::RiscvPcrelLo12I),
+        _ => None,
+    }
+}
+
+///The relocation made to an operand that is a symbol or an expression of symbols, which depends on the instruction.
+/// Branches and jumps hold the address of their target, and the other instructions hold the whole value in their
+/// immediate or memory offset.
+fn instruction_relocation_type(operator: &str, arch: AvailableDatapaths) -> RelocationType {
+    match (arch, operator) {
+        (_, ".word") => RelocationType::Data(4),
+        (AvailableDatapaths::MIPS, "beq" | "bne" | "b" | "bc1t" | "bc1f") => {
+            RelocationType::MipsPc16
+        }
+        (AvailableDatapaths::MIPS, "j" | "jal") => RelocationType::Mips26,
+        (AvailableDatapaths::MIPS, _) => RelocationType::Mips16,
+        (AvailableDatapaths::RISCV, "beq" | "bne" | "blt" | "bge" | "bltu" | "bgeu") => {
+            RelocationType::RiscvBranch
+        }
+        (AvailableDatapaths::RISCV, "jal") => RelocationType::RiscvJal,
+        (AvailableDatapaths::RISCV, "lui" | "auipc") => RelocationType::Riscv20,
+        (AvailableDatapaths::RISCV, _) if is_riscv_store(operator) => RelocationType::Riscv12S,
+        (AvailableDatapaths::RISCV, _) => RelocationType::Riscv12I,
+    }
+}
+
+///Returns true for the RISC-V stores, whose offset is split between two fields of the instruction.
+fn is_riscv_store(operator: &str) -> bool {
+    matches!(operator, "sb" | "sh" | "sw" | "sd" | "fsw" | "fsd")
+}
+
+///Writes the value of a relocation, the address of its symbol plus its addend, into the instruction at address. The
+/// upper and lower bits of an address are taken the same way as by the relocation operators of the assembler. Returns
+/// None if the value does not fit in the instruction. `.word label` in the text section replaces the whole word.
+pub fn relocate(
+    instruction: u32,
+    relocation_type: RelocationType,
+    value: i64,
+    address: usize,
+) -> Option<u32> {
+    let address = address as i64;
+    let with_bits = |mask: u32, bits: u32| Some(instruction & !mask | bits & mask);
+    let upper_20 = |value: i64| with_bits(0xFFFF_F000, (((value + 0x800) >> 12) as u32) << 12);
+    let lower_12_i = |value: i64| with_bits(0xFFF0_0000, (value as u32) << 20);
+    let lower_12_s = |value: i64| {
+        let value = value as u32;
+        with_bits(0xFE00_0F80, (value & 0xFE0) << 20 | (value & 0x1F) << 7)
+    };
+
+    match relocation_type {
+        RelocationType::MipsPc16 => {
+            let distance = value - (address + 4);
+            if distance % 4 != 0 || i16::try_from(distance >> 2).is_err() {
+                return None;
+            }
+            with_bits(0xFFFF, (distance >> 2) as u32)
+        }
+        RelocationType::Mips26 => {
+            //the target keeps the upper 4 bits of the address after the jump
+            if value % 4 != 0 || value < 0 || value >> 28 != (address + 4) >> 28 {
+                return None;
+            }
+            with_bits(0x03FF_FFFF, (value >> 2) as u32)
+        }
+        RelocationType::MipsHi16 => with_bits(0xFFFF, ((value + 0x8000) >> 16) as u32),
+        RelocationType::MipsLo16 => with_bits(0xFFFF, value as u32),
+        RelocationType::MipsHigher => with_bits(0xFFFF, ((value + 0x8000_8000) >> 32) as u32),
+        RelocationType::MipsHighest => with_bits(0xFFFF, ((value + 0x8000_8000_8000) >> 48) as u32),
+        RelocationType::RiscvBranch => {
+            //SWIM's branches hold the word address of their target in the 12 bits of their offset
+            if value % 4 != 0 || !(0..1 << 14).contains(&value) {
+                return None;
+            }
+            with_bits(0xFFF0_0000, ((value >> 2) as u32) << 20)
+        }
+        RelocationType::RiscvJal => {
+            //as do its jumps, in the 20 bits of their offset
+            if value % 4 != 0 || !(0..1 << 22).contains(&value) {
+                return None;
+            }
+            with_bits(0xFFFF_F000, ((value >> 2) as u32) << 12)
+        }
+        RelocationType::RiscvHi20 => upper_20(value),
+        RelocationType::RiscvLo12I => lower_12_i(value),
+        RelocationType::RiscvLo12S => lower_12_s(value),
+        RelocationType::RiscvPcrelHi20 => upper_20(value - address),
+        RelocationType::RiscvPcrelLo12I => lower_12_i(value - (address - 4)),
+        RelocationType::RiscvPcrelLo12S => lower_12_s(value - (address - 4)),
+        RelocationType::Mips16 if fits(value, 16) => with_bits(0xFFFF, value as u32),
+        RelocationType::Riscv12I if fits(value, 12) => lower_12_i(value),
+        RelocationType::Riscv12S if fits(value, 12) => lower_12_s(value),
+        RelocationType::Riscv20 if fits(value, 20) => with_bits(0xFFFF_F000, (value as u32) << 12),
+        RelocationType::Data(_) if fits(value, 32) => Some(value as u32),
+        _ => None,
+    }
+}
+
+///Writes the value of a data relocation into the bytes of its data entry, most significant byte first. Returns false if
+/// the value does not fit in the entry.
+pub fn relocate_data(bytes: &mut [u8], value: i64) -> bool {
+    let size = bytes.len();
+    if size > 8 || (size < 8 && !fits(value, size as u32 * 8)) {
+        return false;
+    }
+    bytes.copy_from_slice(&value.to_be_bytes()[8 - size..]);
+    true
+}
+
+///Returns true if a value can be written in the given number of bits as either a signed or an unsigned number.
+fn fits(value: i64, bits: u32) -> bool {
+    (-(1 << (bits - 1))..1 << bits).contains(&value)
+}
+
+///The number of bytes a data entry writes, which does not depend on where it is placed. The directives that only move
+/// where the next entry is placed write none of their own.
+fn datum_length(datum: &Data) -> usize {
+    let name = &datum.data_type.token_name;
+    if is_placement_directive(name) || name.eq_ignore_ascii_case(".data") {
+        return 0;
+    }
+    assemble_data_binary_at(&mut [datum.clone()], 0).len()
+}
@@ -0,0 +1,627 @@
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::emulation_core::datapath::Syscall;
+use crate::parser::assembling::{match_gp_register, match_gp_register_riscv};
+use crate::parser::expressions::{evaluate_expression, expression_symbols};
+use crate::parser::local_labels::is_local_label_name;
+use crate::parser::parser_structs_and_enums::{
+    Data, Instruction, Lint, MonacoLineInfo, ProgramInfo, TokenType, Warning, GP_REGISTERS,
+    RISCV_GP_REGISTERS,
+};
+use crate::parser::parsing::declared_symbols;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use strum::IntoEnumIterator;
+
+///The registers a function must restore before it returns if it changes them: $s0 to $s7 and $fp on MIPS.
+const SAVED_REGISTERS_MIPS: [u8; 9] = [16, 17, 18, 19, 20, 21, 22, 23, 30];
+///The registers a function must restore before it returns if it changes them: s0 to s11 on RISC-V.
+const SAVED_REGISTERS_RISCV: [u8; 12] = [8, 9, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27];
+
+///The labels the program starts at, which are not expected to be referred to.
+const ENTRY_LABELS: [&str; 3] = ["main", "start", "_start"];
+
+///Returns every lint, which is the set of lints checked unless some are turned off.
+pub fn all_lints() -> HashSet<Lint> {
+    Lint::iter().collect()
+}
+
+///Checks an assembled program for likely mistakes with the enabled lints. Each warning is added to the line it is found
+/// on and described in the console output after any errors.
+pub fn lint_program(
+    program_info: &mut ProgramInfo,
+    labels: &HashMap<String, usize>,
+    arch: AvailableDatapaths,
+    enabled_lints: &HashSet<Lint>,
+) {
+    let program = LintedProgram {
+        instructions: &program_info.instructions,
+        lines: &program_info.monaco_line_info,
+        labels,
+        arch,
+        data_base: program_info.data_starting_point << 2,
+    };
+
+    let mut warnings: Vec<(usize, Warning)> = Vec::new();
+    for lint in Lint::iter().filter(|lint| enabled_lints.contains(lint)) {
+        match lint {
+            Lint::WriteToZeroRegister => program.check_writes_to_zero(&mut warnings),
+            Lint::UnusedLabel => program.check_unused_labels(&program_info.data, &mut warnings),
+            Lint::FallThroughIntoData => {
+                if !program_info.data.is_empty() {
+                    program.check_fall_through(&mut warnings);
+                }
+            }
+            Lint::BranchToData => program.check_branches_to_data(&mut warnings),
+            Lint::UnrestoredSavedRegister => program.check_saved_registers(&mut warnings),
+            Lint::SignExtendedImmediate => program.check_sign_extension(&mut warnings),
+            Lint::MissingExit => program.check_exit(program_info.pc_starting_point, &mut warnings),
+        }
+    }
+    warnings.sort_by_key(|(line_number, _)| *line_number);
+
+    let mut console_out = String::new();
+    for (line_number, warning) in warnings {
+        let line = &mut program_info.monaco_line_info[line_number];
+        console_out.push_str(&format!(
+            "{}: {} on {} with token \"{}\"\n{}\n",
+            warning.lint.severity(),
+            warning.lint,
+            line.location(),
+            warning.token_causing_warning,
+            warning.message
+        ));
+        line.warnings.push(warning);
+    }
+    if !console_out.is_empty() {
+        program_info
+            .console_out_post_assembly
+            .push_str(&format!("\n\n{console_out}"));
+    }
+}
+
+///The parts of an assembled program the lints look at.
+struct LintedProgram<'a> {
+    instructions: &'a [Instruction],
+    lines: &'a [MonacoLineInfo],
+    labels: &'a HashMap<String, usize>,
+    arch: AvailableDatapaths,
+    data_base: usize,
+}
+
+impl LintedProgram<'_> {
+    ///Reads the number of a general purpose register.
+    fn gp_register(&self, name: &str) -> Option<u8> {
+        match self.arch {
+            AvailableDatapaths::MIPS => match_gp_register(name),
+            AvailableDatapaths::RISCV => match_gp_register_riscv(name),
+        }
+    }
+
+    ///The name of a general purpose register as it is shown in a message.
+    fn register_name(&self, register: u8) -> &'static str {
+        match self.arch {
+            AvailableDatapaths::MIPS => GP_REGISTERS[register as usize].names[0],
+            AvailableDatapaths::RISCV => RISCV_GP_REGISTERS[register as usize].names[1],
+        }
+    }
+
+    ///Returns true if the operator of an instruction was written on its line, rather than the instruction being expanded
+    /// from a pseudo-instruction or macro.
+    fn is_written(&self, instruction: &Instruction) -> bool {
+        instruction.macro_line_number.is_none()
+            && self.lines[instruction.line_number]
+                .tokens
+                .iter()
+                .any(|token| {
+                    token
+                        .token_name
+                        .eq_ignore_ascii_case(&instruction.operator.token_name)
+                })
+    }
+
+    ///Finds the general purpose register an instruction writes its result to, if it has one.
+    fn destination_register(&self, instruction: &Instruction) -> Option<u8> {
+        let operator = instruction.operator.token_name.to_lowercase();
+        let has_destination = match self.arch {
+            AvailableDatapaths::MIPS => {
+                !matches!(
+                    &*operator,
+                    "sw" | "sh"
+                        | "sb"
+                        | "sd"
+                        | "swc1"
+                        | "beq"
+                        | "bne"
+                        | "b"
+                        | "bc1t"
+                        | "bc1f"
+                        | "j"
+                        | "jal"
+                        | "jr"
+                        | "jalr"
+                        | "mtc1"
+                        | "dmtc1"
+                        | "syscall"
+                        | "nop"
+                        | ".word"
+                ) && !operator.starts_with("c.")
+            }
+            AvailableDatapaths::RISCV => {
+                !matches!(
+                    &*operator,
+                    "sb" | "sh"
+                        | "sw"
+                        | "sd"
+                        | "fsw"
+                        | "fsd"
+                        | "beq"
+                        | "bne"
+                        | "blt"
+                        | "bge"
+                        | "bltu"
+                        | "bgeu"
+                        | "jal"
+                        | "jalr"
+                        | "ecall"
+                        | "ebreak"
+                        | "fence"
+                        | ".word"
+                ) && !operator.starts_with("csr")
+            }
+        };
+        if !has_destination {
+            return None;
+        }
+        self.gp_register(&instruction.operands.first()?.token_name)
+    }
+
+    ///Finds the label an instruction branches or jumps to, along with true if the branch is always taken. Calls are not
+    /// branches since they return to the following instruction.
+    fn branch_target<'i>(&self, instruction: &'i Instruction) -> Option<(&'i str, bool)> {
+        let operator = instruction.operator.token_name.to_lowercase();
+        let target = instruction.operands.last()?.token_name.as_str();
+        let always_taken = match (self.arch, &*operator) {
+            (AvailableDatapaths::MIPS, "j" | "b") => true,
+            (AvailableDatapaths::MIPS, "beq" | "bne" | "bc1t" | "bc1f") => false,
+            (AvailableDatapaths::RISCV, "jal") if self.call_target(instruction).is_none() => true,
+            (AvailableDatapaths::RISCV, "beq" | "bne" | "blt" | "bge" | "bltu" | "bgeu") => false,
+            _ => return None,
+        };
+        Some((target, always_taken))
+    }
+
+    ///Finds the label of the function an instruction calls, if it is a call.
+    fn call_target<'i>(&self, instruction: &'i Instruction) -> Option<&'i str> {
+        let operator = instruction.operator.token_name.to_lowercase();
+        let target = instruction.operands.last()?.token_name.as_str();
+        match (self.arch, &*operator) {
+            (AvailableDatapaths::MIPS, "jal") => Some(target),
+            //jal without a destination register links through ra
+            (AvailableDatapaths::RISCV, "jal")
+                if instruction.operands.len() == 1
+                    || self.gp_register(&instruction.operands[0].token_name) != Some(0) =>
+            {
+                Some(target)
+            }
+            _ => None,
+        }
+    }
+
+    ///Returns true if an instruction returns from a function, as `jr $ra` does on MIPS and `jalr x0, ra, 0` on RISC-V.
+    fn is_return(&self, instruction: &Instruction) -> bool {
+        let operator = instruction.operator.token_name.to_lowercase();
+        let register = |index: usize| {
+            instruction
+                .operands
+                .get(index)
+                .and_then(|operand| self.gp_register(&operand.token_name))
+        };
+        match self.arch {
+            AvailableDatapaths::MIPS => operator == "jr" && register(0) == Some(31),
+            AvailableDatapaths::RISCV => {
+                operator == "jalr" && register(0) == Some(0) && register(1) == Some(1)
+            }
+        }
+    }
+
+    ///Returns true if execution never continues to the instruction after this one.
+    fn ends_path(&self, instruction: &Instruction) -> bool {
+        let operator = instruction.operator.token_name.to_lowercase();
+        match self.arch {
+            AvailableDatapaths::MIPS => matches!(&*operator, "j" | "b" | "jr"),
+            AvailableDatapaths::RISCV => {
+                matches!(&*operator, "jal" | "jalr")
+                    && instruction
+                        .operands
+                        .first()
+                        .and_then(|operand| self.gp_register(&operand.token_name))
+                        == Some(0)
+            }
+        }
+    }
+
+    fn is_syscall(&self, instruction: &Instruction) -> bool {
+        let operator = instruction.operator.token_name.to_lowercase();
+        matches!(&*operator, "syscall" | "ecall")
+    }
+
+    ///Warns about written instructions whose result is thrown away by writing it to $zero or x0.
+    fn check_writes_to_zero(&self, warnings: &mut Vec<(usize, Warning)>) {
+        //nop is encoded as an instruction writing to the zero register
+        let nop = match self.arch {
+            AvailableDatapaths::MIPS => 0,
+            AvailableDatapaths::RISCV => 0x00000013,
+        };
+        for instruction in self.instructions {
+            if !self.is_written(instruction)
+                || instruction.binary == nop
+                || self.destination_register(instruction) != Some(0)
+            {
+                continue;
+            }
+            let operand = &instruction.operands[0];
+            warnings.push((
+                instruction.line_number,
+                Warning {
+                    lint: Lint::WriteToZeroRegister,
+                    token_causing_warning: operand.token_name.clone(),
+                    start_end_columns: operand.start_end_columns,
+                    message: format!(
+                        "The result of {} is written to {}, which always holds 0, so it is thrown away.\n",
+                        instruction.operator.token_name, operand.token_name
+                    ),
+                },
+            ));
+        }
+    }
+
+    ///Gives information about labels that no line of the program refers to.
+    fn check_unused_labels(&self, data: &[Data], warnings: &mut Vec<(usize, Warning)>) {
+        let mut referenced: HashSet<String> = self
+            .lines
+            .iter()
+            .flat_map(|line| &line.tokens)
+            //the definition of a label is not a use of it
+            .filter(|token| {
+                token.token_type != TokenType::Label && !token.token_name.ends_with(':')
+            })
+            .flat_map(|token| expression_symbols(&token.token_name))
+            .collect();
+        referenced.extend(
+            declared_symbols(self.lines)
+                .into_iter()
+                .map(|(symbol, _, _)| symbol.token_name),
+        );
+
+        let instruction_labels = self
+            .instructions
+            .iter()
+            .filter(|instruction| instruction.macro_line_number.is_none())
+            .flat_map(|instruction| {
+                instruction
+                    .labels
+                    .iter()
+                    .map(|label| (&label.token, label.token_line))
+            });
+        let data_labels = data
+            .iter()
+            .filter(|datum| datum.macro_line_number.is_none() && !datum.label.token_name.is_empty())
+            .map(|datum| (&datum.label, datum.line_number));
+        for (label, line_number) in instruction_labels.chain(data_labels) {
+            //local labels such as `1:` are referred to as `1b` or `1f`, and are often placed only to mark a spot
+            if referenced.contains(&label.token_name)
+                || ENTRY_LABELS.contains(&label.token_name.as_str())
+                || is_local_label_name(&label.token_name)
+            {
+                continue;
+            }
+            warnings.push((
+                line_number,
+                Warning {
+                    lint: Lint::UnusedLabel,
+                    token_causing_warning: label.token_name.clone(),
+                    start_end_columns: label.start_end_columns,
+                    message: format!("The label {} is never used.\n", label.token_name),
+                },
+            ));
+        }
+    }
+
+    ///Warns when the last instruction neither ends the program nor jumps elsewhere, so the data that follows it would be
+    /// run as instructions.
+    fn check_fall_through(&self, warnings: &mut Vec<(usize, Warning)>) {
+        let Some(last) = self.instructions.last() else {
+            return;
+        };
+        if self.is_syscall(last) || self.ends_path(last) {
+            return;
+        }
+        warnings.push((
+            last.line_number,
+            Warning {
+                lint: Lint::FallThroughIntoData,
+                token_causing_warning: last.operator.token_name.clone(),
+                start_end_columns: last.operator.start_end_columns,
+                message: "This is the last instruction of the program, so execution continues into the data that follows it. End the program with an exit syscall or a jump.\n".to_string(),
+            },
+        ));
+    }
+
+    ///Warns about branches, jumps, and calls to labels of data.
+    fn check_branches_to_data(&self, warnings: &mut Vec<(usize, Warning)>) {
+        for instruction in self.instructions {
+            let target = self
+                .branch_target(instruction)
+                .map(|(target, _)| target)
+                .or(self.call_target(instruction));
+            let Some(target) = target else {
+                continue;
+            };
+            if !self
+                .labels
+                .get(target)
+                .is_some_and(|address| *address >= self.data_base)
+            {
+                continue;
+            }
+            let operand = instruction.operands.last().unwrap();
+            warnings.push((
+                instruction.line_number,
+                Warning {
+                    lint: Lint::BranchToData,
+                    token_causing_warning: target.to_string(),
+                    start_end_columns: operand.start_end_columns,
+                    message: format!(
+                        "{target} labels data rather than an instruction, so the data would be run as instructions.\n"
+                    ),
+                },
+            ));
+        }
+    }
+
+    ///Warns about returns from functions that can be reached after changing a callee-saved register without loading it
+    /// back. Each function starts at a label that is called, and every path through it is followed until it returns.
+    /// Calls within a function are assumed to keep the callee-saved registers.
+    fn check_saved_registers(&self, warnings: &mut Vec<(usize, Warning)>) {
+        let saved_registers: &[u8] = match self.arch {
+            AvailableDatapaths::MIPS => &SAVED_REGISTERS_MIPS,
+            AvailableDatapaths::RISCV => &SAVED_REGISTERS_RISCV,
+        };
+        let index_of: HashMap<usize, usize> = self
+            .instructions
+            .iter()
+            .enumerate()
+            .map(|(i, instruction)| (instruction.instruction_number << 2, i))
+            .collect();
+        let label_index = |label: &str| {
+            self.labels
+                .get(label)
+                .and_then(|address| index_of.get(address).copied())
+        };
+
+        let mut function_entries: Vec<usize> = self
+            .instructions
+            .iter()
+            .filter_map(|instruction| label_index(self.call_target(instruction)?))
+            .collect();
+        function_entries.sort();
+        function_entries.dedup();
+
+        //the callee-saved registers that may have been changed before reaching each instruction, with the line changing them
+        let mut reported: HashSet<(usize, u8)> = HashSet::new();
+        for entry in function_entries {
+            let mut changed: HashMap<usize, BTreeMap<u8, usize>> = HashMap::new();
+            let mut worklist = vec![(entry, BTreeMap::new())];
+            while let Some((i, mut registers)) = worklist.pop() {
+                let state = changed.entry(i).or_default();
+                let before = state.len();
+                for (register, line_number) in &registers {
+                    state.entry(*register).or_insert(*line_number);
+                }
+                if state.len() == before && before > 0 {
+                    continue;
+                }
+                registers = state.clone();
+
+                let instruction = &self.instructions[i];
+                if self.is_return(instruction) {
+                    for (register, line_number) in &registers {
+                        if reported.insert((i, *register)) {
+                            warnings.push((
+                                instruction.line_number,
+                                self.unrestored_register_warning(
+                                    instruction,
+                                    *register,
+                                    *line_number,
+                                ),
+                            ));
+                        }
+                    }
+                    continue;
+                }
+
+                if let Some(register) = self.destination_register(instruction) {
+                    if saved_registers.contains(&register) {
+                        if is_load(instruction) {
+                            registers.remove(&register);
+                        } else {
+                            registers.insert(register, instruction.line_number);
+                        }
+                    }
+                }
+
+                let mut successors = Vec::new();
+                match self.branch_target(instruction) {
+                    Some((target, always_taken)) => {
+                        successors.extend(label_index(target));
+                        if !always_taken {
+                            successors.push(i + 1);
+                        }
+                    }
+                    None if !self.ends_path(instruction) => successors.push(i + 1),
+                    None => {}
+                }
+                for successor in successors {
+                    if successor < self.instructions.len() {
+                        worklist.push((successor, registers.clone()));
+                    }
+                }
+            }
+        }
+    }
+
+    fn unrestored_register_warning(
+        &self,
+        instruction: &Instruction,
+        register: u8,
+        changed_on: usize,
+    ) -> Warning {
+        let name = self.register_name(register);
+        Warning {
+            lint: Lint::UnrestoredSavedRegister,
+            token_causing_warning: instruction.operator.token_name.clone(),
+            start_end_columns: instruction.operator.start_end_columns,
+            message: format!(
+                "The function can return without restoring {name}, which is changed on {}. Callee-saved registers should be stored on the stack before they are changed and loaded back before returning.\n",
+                self.lines[changed_on].location()
+            ),
+        }
+    }
+
+    ///Warns about immediates written as positive values that do not fit in a signed immediate, so they are sign-extended
+    /// to negative values.
+    fn check_sign_extension(&self, warnings: &mut Vec<(usize, Warning)>) {
+        for instruction in self.instructions {
+            let operator = instruction.operator.token_name.to_lowercase();
+            let (bits, suggestion) = match (self.arch, &*operator) {
+                (
+                    AvailableDatapaths::MIPS,
+                    "addi" | "addiu" | "daddi" | "daddiu" | "slti" | "sltiu",
+                ) => (16, "Use li to load the value"),
+                (
+                    AvailableDatapaths::RISCV,
+                    "addi" | "addiw" | "slti" | "sltiu" | "xori" | "ori" | "andi",
+                ) => (12, "Use li to load the value"),
+                _ => continue,
+            };
+            let Some(operand) = instruction.operands.get(2) else {
+                continue;
+            };
+            let Ok(value) = evaluate_expression(&operand.token_name, &HashMap::new()) else {
+                continue;
+            };
+            if !self.is_written(instruction) || value < 1 << (bits - 1) || value >= 1 << bits {
+                continue;
+            }
+            warnings.push((
+                instruction.line_number,
+                Warning {
+                    lint: Lint::SignExtendedImmediate,
+                    token_causing_warning: operand.token_name.clone(),
+                    start_end_columns: operand.start_end_columns,
+                    message: format!(
+                        "{} does not fit in the {bits}-bit signed immediate of {operator}, so it is sign-extended to {}. {suggestion} instead.\n",
+                        operand.token_name,
+                        value - (1 << bits)
+                    ),
+                },
+            ));
+        }
+    }
+
+    ///Warns when main does not end with the exit syscall. main runs from where the program starts up to the first function
+    /// that is called after it. Exit is any call number the emulator core does not give another syscall, so it is only known
+    /// to be missing when the last syscall is given one of those numbers.
+    fn check_exit(&self, pc_starting_point: usize, warnings: &mut Vec<(usize, Warning)>) {
+        let Some(start) = self
+            .instructions
+            .iter()
+            .position(|instruction| instruction.instruction_number << 2 >= pc_starting_point)
+        else {
+            return;
+        };
+        let function_start = self
+            .instructions
+            .iter()
+            .filter_map(|instruction| self.labels.get(self.call_target(instruction)?))
+            .filter_map(|address| {
+                self.instructions
+                    .iter()
+                    .position(|instruction| instruction.instruction_number << 2 == *address)
+            })
+            .filter(|index| *index > start)
+            .min()
+            .unwrap_or(self.instructions.len());
+        let main = &self.instructions[start..function_start];
+        let Some(last) = main.last() else {
+            return;
+        };
+
+        //a jump at the end of main leaves it, such as to loop forever
+        if self.ends_path(last) && !self.is_return(last) {
+            return;
+        }
+
+        let (argument_register, syscall) = match self.arch {
+            AvailableDatapaths::MIPS => (4, "syscall"),
+            AvailableDatapaths::RISCV => (10, "ecall"),
+        };
+        let message = if self.is_syscall(last) {
+            let call_number = main
+                .iter()
+                .rev()
+                .skip(1)
+                .find(|instruction| {
+                    self.destination_register(instruction) == Some(argument_register)
+                })
+                .and_then(|instruction| self.constant_result(instruction));
+            let is_exit = |call_number: i64| {
+                Syscall::from_register_data(call_number as u64, 0, 0.0, 0.0) == Syscall::Exit
+            };
+            match call_number {
+                Some(call_number) if !is_exit(call_number) => format!(
+                    "The last {syscall} of main is call number {call_number} rather than exit. Set {} to 0 before a final {syscall} to end the program.\n",
+                    self.register_name(argument_register)
+                ),
+                _ => return,
+            }
+        } else {
+            format!(
+                "main ends without an exit {syscall}, so execution continues past it. Set {} to 0 and end main with {syscall}.\n",
+                self.register_name(argument_register)
+            )
+        };
+        warnings.push((
+            last.line_number,
+            Warning {
+                lint: Lint::MissingExit,
+                token_causing_warning: last.operator.token_name.clone(),
+                start_end_columns: last.operator.start_end_columns,
+                message,
+            },
+        ));
+    }
+
+    ///Finds the value an instruction writes to its destination when it is a constant, as with `addi $a0, $zero, 1` or the
+    /// expansion of `li`.
+    fn constant_result(&self, instruction: &Instruction) -> Option<i64> {
+        let operator = instruction.operator.token_name.to_lowercase();
+        if !matches!(
+            &*operator,
+            "addi" | "addiu" | "daddi" | "daddiu" | "ori" | "xori"
+        ) {
+            return None;
+        }
+        if self.gp_register(&instruction.operands.get(1)?.token_name) != Some(0) {
+            return None;
+        }
+        evaluate_expression(&instruction.operands.get(2)?.token_name, &HashMap::new()).ok()
+    }
+}
+
+///Returns true if an instruction loads a value from memory into a general purpose register.
+fn is_load(instruction: &Instruction) -> bool {
+    matches!(
+        &*instruction.operator.token_name.to_lowercase(),
+        "lb" | "lh" | "lw" | "ld" | "lbu" | "lhu" | "lwu"
+    )
+}
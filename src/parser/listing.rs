@@ -0,0 +1,203 @@
+use crate::parser::assembling::assemble_data_binary_at;
+use crate::parser::parser_structs_and_enums::{Data, Instruction, MonacoLineInfo, ProgramInfo};
+use crate::parser::source_map::assembled_text;
+use std::collections::HashMap;
+
+///The most bytes of a single line of data written in a listing. The rest are summarized on one line so that large
+/// buffers such as `.space 4096` do not fill the listing with zeros.
+const LISTED_DATA_BYTES: usize = 16;
+
+///The width of the columns before the source text, which are left blank on lines that are not given a value.
+const LINE_COLUMN_WIDTH: usize = 5;
+const ADDRESS_COLUMN_WIDTH: usize = 8;
+const WORD_COLUMN_WIDTH: usize = 8;
+
+///Creates a listing of an assembled program, such as one returned by `parser` or `parse_project`. Every source line is
+/// listed with its address, the words it was assembled to, and the text it was written as. A pseudo-instruction or macro
+/// invocation is followed by each instruction it expanded to, with the address and word of that instruction. The listing
+/// ends with a table of the labels of the program with their addresses and sizes.
+pub fn create_listing(
+    program_info: &ProgramInfo,
+    binary: &[u32],
+    labels: &HashMap<String, usize>,
+) -> String {
+    let data_base = program_info.data_starting_point << 2;
+    let mut listing = format!(
+        "{:>LINE_COLUMN_WIDTH$}  {:<ADDRESS_COLUMN_WIDTH$}  {:<WORD_COLUMN_WIDTH$}  Source\n",
+        "Line", "Address", "Word"
+    );
+
+    let mut current_file = None;
+    for line in &program_info.monaco_line_info {
+        //lines of a project are grouped by the file they were written in
+        let file = line.source.as_ref().map(|source| &source.file);
+        if file != current_file {
+            if let Some(file) = file {
+                listing.push_str(&format!("\nFile: {file}\n"));
+                current_file = Some(file);
+            }
+        }
+        let line_number = line
+            .source
+            .as_ref()
+            .map_or(line.line_number, |source| source.line)
+            + 1;
+        let source_text = source_text(line);
+
+        let instructions: Vec<&Instruction> = program_info
+            .instructions
+            .iter()
+            .filter(|instruction| instruction.line_number == line.line_number)
+            .collect();
+        let data_bytes = program_info
+            .data
+            .iter()
+            .filter(|datum| datum.line_number == line.line_number)
+            .map(|datum| {
+                let address = data_base + datum.data_number;
+                (address, data_size(datum, address))
+            })
+            .reduce(|(first_address, _), (address, size)| {
+                (first_address, address + size - first_address)
+            });
+
+        if let [instruction] = instructions[..] {
+            if !is_expanded(instruction, line) {
+                listing.push_str(&listing_row(
+                    Some(line_number),
+                    Some((
+                        instruction.instruction_number << 2,
+                        format!("{:08x}", instruction.binary),
+                    )),
+                    &source_text,
+                ));
+                continue;
+            }
+        }
+
+        if !instructions.is_empty() {
+            listing.push_str(&listing_row(Some(line_number), None, &source_text));
+            for instruction in instructions {
+                listing.push_str(&listing_row(
+                    None,
+                    Some((
+                        instruction.instruction_number << 2,
+                        format!("{:08x}", instruction.binary),
+                    )),
+                    &format!("    > {}", assembled_text(instruction)),
+                ));
+            }
+        } else if let Some((address, size)) = data_bytes.filter(|(_, size)| *size > 0) {
+            let bytes = read_bytes(binary, address, size.min(LISTED_DATA_BYTES));
+            for (i, word) in bytes.chunks(4).enumerate() {
+                let word = word
+                    .iter()
+                    .map(|byte| format!("{byte:02x}"))
+                    .collect::<Vec<String>>()
+                    .join("");
+                listing.push_str(&listing_row(
+                    Some(line_number).filter(|_| i == 0),
+                    Some((address + i * 4, word)),
+                    if i == 0 { &source_text } else { "" },
+                ));
+            }
+            if size > LISTED_DATA_BYTES {
+                listing.push_str(&listing_row(
+                    None,
+                    None,
+                    &format!("    ({} more bytes)", size - LISTED_DATA_BYTES),
+                ));
+            }
+        } else {
+            listing.push_str(&listing_row(Some(line_number), None, &source_text));
+        }
+    }
+
+    listing.push_str(&symbol_table(program_info, labels));
+    listing
+}
+
+///Writes a row of the listing, leaving any column without a value blank.
+fn listing_row(line_number: Option<usize>, word: Option<(usize, String)>, text: &str) -> String {
+    let line_number = line_number.map_or(String::new(), |line_number| line_number.to_string());
+    let (address, word) = word.map_or((String::new(), String::new()), |(address, word)| {
+        (format!("{address:08x}"), word)
+    });
+    format!(
+        "{line_number:>LINE_COLUMN_WIDTH$}  {address:<ADDRESS_COLUMN_WIDTH$}  {word:<WORD_COLUMN_WIDTH$}  {text}"
+    )
+    .trim_end()
+    .to_string()
+        + "\n"
+}
+
+///Takes the text of a line as it was written, without the note marking an expanded pseudo-instruction.
+fn source_text(line: &MonacoLineInfo) -> String {
+    let text = line
+        .updated_monaco_string
+        .lines()
+        .next()
+        .unwrap_or_default();
+    text.replacen("#Pseudo-Instruction: ", "", 1)
+        .trim()
+        .to_string()
+}
+
+///Returns true if the only instruction of a line is not the instruction written on it, as with `li` expanded to `addi`
+/// or a macro invocation with a single instruction in its body.
+fn is_expanded(instruction: &Instruction, line: &MonacoLineInfo) -> bool {
+    instruction.macro_line_number.is_some()
+        || !line.tokens.iter().any(|token| {
+            token
+                .token_name
+                .eq_ignore_ascii_case(&instruction.operator.token_name)
+        })
+}
+
+///Finds the number of bytes a line of data takes, including any padding added by `.align`.
+fn data_size(datum: &Data, address: usize) -> usize {
+    assemble_data_binary_at(&mut [datum.clone()], address).len()
+}
+
+///Reads bytes from the words of an assembled program, which are stored with the most significant byte first.
+fn read_bytes(binary: &[u32], address: usize, size: usize) -> Vec<u8> {
+    (address..address + size)
+        .map(|address| {
+            binary
+                .get(address >> 2)
+                .map_or(0, |word| word.to_be_bytes()[address & 3])
+        })
+        .collect()
+}
+
+///Writes the labels of a program in order of address. The size of a label in the text is the distance to the next label
+/// or the end of the text, and the size of a label of data is the number of bytes of the data it labels.
+fn symbol_table(program_info: &ProgramInfo, labels: &HashMap<String, usize>) -> String {
+    let data_base = program_info.data_starting_point << 2;
+    let mut symbols: Vec<(&String, &usize)> = labels.iter().collect();
+    symbols.sort_by(|a, b| a.1.cmp(b.1).then(a.0.cmp(b.0)));
+
+    let mut table = format!(
+        "\nSymbols\n{:<ADDRESS_COLUMN_WIDTH$}  {:>8}  {:<7}  Name\n",
+        "Address", "Size", "Section"
+    );
+    for (name, address) in symbols {
+        let (section, size) = if *address < data_base {
+            let next_address = labels
+                .values()
+                .filter(|other| *other > address && **other < data_base)
+                .min()
+                .unwrap_or(&data_base);
+            ("text", next_address - address)
+        } else {
+            let size = program_info
+                .data
+                .iter()
+                .find(|datum| datum.label.token_name == **name)
+                .map_or(0, |datum| data_size(datum, *address));
+            ("data", size)
+        };
+        table.push_str(&format!("{address:08x}  {size:>8}  {section:<7}  {name}\n"));
+    }
+    table
+}
@@ -0,0 +1,144 @@
+use crate::parser::expressions::is_symbol_char;
+use crate::parser::parser_structs_and_enums::{Data, Instruction};
+
+///Returns true if a label is a GNU-style local label such as `1`, which is named by a number and can be defined any
+/// number of times.
+pub fn is_local_label(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|char| char.is_ascii_digit())
+}
+
+///Reads a reference to a local label such as `1b` or `2f`, returning the number of the label and true if the reference
+/// is to the nearest following definition rather than the nearest preceding one.
+pub fn read_local_label_reference(reference: &str) -> Option<(&str, bool)> {
+    let forward = match reference.chars().last()? {
+        'f' => true,
+        'b' => false,
+        _ => return None,
+    };
+    let label = &reference[..reference.len() - 1];
+    is_local_label(label).then_some((label, forward))
+}
+
+///The name given to a definition of a local label, where instance counts the definitions of the label from 1. As with
+/// GNU `as`, the name cannot be confused with the number of the label, so the second `1:` of a program is `.L1.2`.
+pub fn local_label_name(label: &str, instance: usize) -> String {
+    format!(".L{label}.{instance}")
+}
+
+///Returns true if the name of a label is one given to a definition of a local label by local_label_name.
+pub fn is_local_label_name(name: &str) -> bool {
+    name.strip_prefix(".L")
+        .and_then(|rest| rest.split_once('.'))
+        .is_some_and(|(label, instance)| is_local_label(label) && is_local_label(instance))
+}
+
+///Gives every definition of a local label its own name from local_label_name, then replaces every reference such as `1b`
+/// or `1f` in the operands of the instructions and the entries of the data with the name of the definition it refers to.
+/// `1b` refers to the nearest `1:` on or before the line of the reference and `1f` to the nearest one after it.
+///
+///A reference with no definition to refer to is left as written, so it is reported once the labels are looked up.
+pub fn resolve_local_labels(instructions: &mut [Instruction], data: &mut [Data]) {
+    //the line of every definition, in the order the labels are visited below
+    let mut definition_lines: Vec<(usize, String)> = Vec::new();
+    for instruction in instructions.iter() {
+        for label in &instruction.labels {
+            if is_local_label(&label.token.token_name) {
+                definition_lines.push((label.token_line, label.token.token_name.clone()));
+            }
+        }
+    }
+    for datum in data.iter() {
+        if is_local_label(&datum.label.token_name) {
+            definition_lines.push((datum.line_number, datum.label.token_name.clone()));
+        }
+    }
+    if definition_lines.is_empty() {
+        return;
+    }
+
+    //each label is counted in the order it is written, wherever it is placed in memory
+    let mut order: Vec<usize> = (0..definition_lines.len()).collect();
+    order.sort_by_key(|index| definition_lines[*index].0);
+    let mut names = vec![String::new(); definition_lines.len()];
+    let mut definitions: Vec<(usize, &str, String)> = Vec::new();
+    for index in order {
+        let (line_number, label) = &definition_lines[index];
+        let instance = definitions
+            .iter()
+            .filter(|(_, other, _)| other == label)
+            .count()
+            + 1;
+        names[index] = local_label_name(label, instance);
+        definitions.push((*line_number, label, names[index].clone()));
+    }
+
+    let mut names = names.into_iter();
+    for instruction in instructions.iter_mut() {
+        for label in &mut instruction.labels {
+            if is_local_label(&label.token.token_name) {
+                label.token.token_name = names.next().unwrap_or_default();
+            }
+        }
+        for operand in &mut instruction.operands {
+            operand.token_name =
+                substitute_references(&operand.token_name, instruction.line_number, &definitions);
+        }
+    }
+    for datum in data.iter_mut() {
+        if is_local_label(&datum.label.token_name) {
+            datum.label.token_name = names.next().unwrap_or_default();
+        }
+        for entry in &mut datum.data_entries {
+            entry.token_name =
+                substitute_references(&entry.token_name, datum.line_number, &definitions);
+        }
+    }
+}
+
+///Replaces every local label reference in the text of an operand on the given line with the name of the definition it
+/// refers to. The definitions are given in the order they are written. Strings and character literals are left as
+/// written.
+fn substitute_references(
+    text: &str,
+    line_number: usize,
+    definitions: &[(usize, &str, String)],
+) -> String {
+    if text.starts_with('\"') || text.starts_with('\'') {
+        return text.to_string();
+    }
+
+    let chars: Vec<char> = text.chars().collect();
+    let mut substituted = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        //registers such as $1 are read as a single word so they are not mistaken for references
+        let is_word_char =
+            |char: char| is_symbol_char(char) || char.is_ascii_digit() || char == '$';
+        if !is_word_char(chars[i]) {
+            substituted.push(chars[i]);
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        while i < chars.len() && is_word_char(chars[i]) {
+            i += 1;
+        }
+        let word: String = chars[start..i].iter().collect();
+        let definition = match read_local_label_reference(&word) {
+            Some((label, true)) => definitions
+                .iter()
+                .find(|(line, other, _)| *other == label && *line > line_number),
+            Some((label, false)) => definitions
+                .iter()
+                .rev()
+                .find(|(line, other, _)| *other == label && *line <= line_number),
+            None => None,
+        };
+        match definition {
+            Some((_, _, name)) => substituted.push_str(name),
+            None => substituted.push_str(&word),
+        }
+    }
+    substituted
+}
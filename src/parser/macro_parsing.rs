@@ -0,0 +1,496 @@
+use crate::parser::parser_structs_and_enums::ErrorType::{
+    ImproperlyFormattedMacro, IncorrectNumberOfOperands, RecursiveMacroExpansion,
+};
+use crate::parser::parser_structs_and_enums::TokenType::{Directive, Label, Operator};
+use crate::parser::parser_structs_and_enums::{Data, Error, Instruction, MonacoLineInfo, Token};
+use std::collections::HashMap;
+
+///The deepest macro invocations can be nested before expansion stops. This catches macros that invoke themselves.
+pub const MAX_MACRO_DEPTH: usize = 32;
+
+///A macro read from a `.macro` definition. Both the MARS syntax, `.macro name(%a, %b)` ... `.end_macro`,
+/// and the GNU syntax, `.macro name a, b` ... `.endm`, are supported.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MacroDefinition {
+    pub name: String,
+    ///The parameters as they are referenced in the body: `%a` for MARS parameters and `\a` for GNU parameters.
+    pub parameters: Vec<String>,
+    ///The lines between the header and the end of the macro.
+    pub body: Vec<MonacoLineInfo>,
+    pub line_number: usize,
+}
+
+///Macros are looked up by name. Multiple macros may share a name as long as they take a different number of parameters.
+pub type MacroMap = HashMap<String, Vec<MacroDefinition>>;
+
+///Returns true if the line begins a macro definition.
+pub fn is_macro_start(tokens: &[Token]) -> bool {
+    !tokens.is_empty() && tokens[0].token_name.to_lowercase() == ".macro"
+}
+
+///Returns true if the line ends a macro definition.
+pub fn is_macro_end(tokens: &[Token]) -> bool {
+    !tokens.is_empty()
+        && matches!(
+            &*tokens[0].token_name.to_lowercase(),
+            ".end_macro" | ".endm"
+        )
+}
+
+///Reads the macro definition starting at `start` and adds it to the map of macros. Returns the index of the first line
+/// after the definition. Errors in the header or a missing end to the definition are added to the lines themselves.
+pub fn read_macro_definition(
+    lines: &mut [MonacoLineInfo],
+    start: usize,
+    macros: &mut MacroMap,
+) -> usize {
+    lines[start].tokens[0].token_type = Directive;
+
+    //find the end of the definition
+    let mut end = start + 1;
+    while end < lines.len() && !is_macro_end(&lines[end].tokens) {
+        if is_macro_start(&lines[end].tokens) {
+            lines[end].errors.push(Error {
+                error_name: ImproperlyFormattedMacro,
+                token_causing_error: lines[end].tokens[0].token_name.clone(),
+                start_end_columns: lines[end].tokens[0].start_end_columns,
+                message: "Macros cannot be defined inside of another macro.\n".to_string(),
+                fix_its: Vec::new(),
+            });
+        }
+        end += 1;
+    }
+    if end == lines.len() {
+        lines[start].errors.push(Error {
+            error_name: ImproperlyFormattedMacro,
+            token_causing_error: lines[start].tokens[0].token_name.clone(),
+            start_end_columns: lines[start].tokens[0].start_end_columns,
+            message: "Macro definition is not closed by .end_macro or .endm.\n".to_string(),
+            fix_its: Vec::new(),
+        });
+        return end;
+    }
+    lines[end].tokens[0].token_type = Directive;
+
+    match read_macro_header(&lines[start]) {
+        Ok((name, parameters)) => {
+            for line in &mut lines[start + 1..end] {
+                line.mouse_hover_string = format!("Line of the body of macro `{name}`.");
+            }
+            lines[start].tokens[1].token_type = Operator;
+            lines[start].mouse_hover_string = format!(
+                "Definition of macro `{}` with {} parameter(s).",
+                name,
+                parameters.len()
+            );
+
+            let definitions = macros.entry(name.clone()).or_default();
+            //a later definition with the same number of parameters replaces the earlier one
+            definitions.retain(|definition| definition.parameters.len() != parameters.len());
+            definitions.push(MacroDefinition {
+                name,
+                parameters,
+                body: lines[start + 1..end].to_vec(),
+                line_number: start,
+            });
+        }
+        Err(error) => lines[start].errors.push(error),
+    }
+
+    end + 1
+}
+
+///Reads the name and parameters from the first line of a macro definition.
+fn read_macro_header(line: &MonacoLineInfo) -> Result<(String, Vec<String>), Error> {
+    let directive = &line.tokens[0];
+    let improperly_formatted = |message: &str| Error {
+        error_name: ImproperlyFormattedMacro,
+        token_causing_error: directive.token_name.clone(),
+        start_end_columns: (
+            directive.start_end_columns.0,
+            line.tokens.last().unwrap().start_end_columns.1,
+        ),
+        message: message.to_string(),
+        fix_its: Vec::new(),
+    };
+
+    let (name, arguments, closed) = match split_name_and_arguments(&line.tokens[1..]) {
+        Some(header) => header,
+        None => {
+            return Err(improperly_formatted(
+                "Macro definition is missing a name.\n",
+            ))
+        }
+    };
+    if !closed {
+        return Err(improperly_formatted(
+            "Macro parameters are missing a closing parenthesis.\n",
+        ));
+    }
+
+    let mut parameters: Vec<String> = Vec::new();
+    for argument in arguments {
+        let parameter = if let Some(stripped) = argument.strip_prefix('%') {
+            if !is_identifier(stripped) {
+                return Err(improperly_formatted(&format!(
+                    "`{argument}` is not a valid macro parameter.\n"
+                )));
+            }
+            argument.clone()
+        } else if is_identifier(&argument) {
+            //GNU parameters are referenced in the body with a backslash
+            format!("\\{argument}")
+        } else {
+            return Err(improperly_formatted(&format!(
+                "`{argument}` is not a valid macro parameter.\n"
+            )));
+        };
+
+        if parameters.contains(&parameter) {
+            return Err(improperly_formatted(&format!(
+                "Macro parameter `{argument}` is declared more than once.\n"
+            )));
+        }
+        parameters.push(parameter);
+    }
+
+    Ok((name, parameters))
+}
+
+///Splits the tokens following a macro name into the name and the arguments. MARS arguments are wrapped in parentheses
+/// and separated by commas, while GNU arguments are separated by commas or spaces. The returned bool is false if an
+/// opening parenthesis around the arguments is not closed.
+fn split_name_and_arguments(tokens: &[Token]) -> Option<(String, Vec<String>, bool)> {
+    let text = tokens
+        .iter()
+        .map(|token| token.token_name.clone())
+        .collect::<Vec<String>>()
+        .join(" ");
+
+    let name: String = text
+        .chars()
+        .take_while(|char| *char != '(' && *char != ',' && *char != ' ')
+        .collect();
+    if name.is_empty() {
+        return None;
+    }
+
+    let rest = text[name.len()..].trim();
+    Some(match rest.strip_prefix('(') {
+        Some(inner) => match inner.strip_suffix(')') {
+            Some(inner) => (name, split_arguments(inner, false), true),
+            None => (name, split_arguments(inner, false), false),
+        },
+        None => (name, split_arguments(rest, true), true),
+    })
+}
+
+///Splits macro arguments on commas that are not inside parentheses, and on spaces if `split_on_spaces` is set and there
+/// are no commas.
+fn split_arguments(text: &str, split_on_spaces: bool) -> Vec<String> {
+    let mut arguments: Vec<String> = Vec::new();
+    let mut argument = String::new();
+    let mut depth = 0;
+    for char in text.chars() {
+        match char {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => {}
+        }
+        if char == ',' && depth == 0 {
+            arguments.push(argument.trim().to_string());
+            argument = String::new();
+        } else {
+            argument.push(char);
+        }
+    }
+    arguments.push(argument.trim().to_string());
+
+    if split_on_spaces && arguments.len() == 1 {
+        return arguments[0]
+            .split(' ')
+            .filter(|argument| !argument.is_empty())
+            .map(|argument| argument.to_string())
+            .collect();
+    }
+
+    arguments.retain(|argument| !argument.is_empty());
+    arguments
+}
+
+fn is_identifier(string: &str) -> bool {
+    !string.is_empty()
+        && !string.starts_with(|char: char| char.is_ascii_digit())
+        && string
+            .chars()
+            .all(|char| char.is_ascii_alphanumeric() || char == '_' || char == '.')
+}
+
+///Returns the number of labels at the start of the tokens.
+pub fn count_leading_labels(tokens: &[Token]) -> usize {
+    tokens
+        .iter()
+        .take_while(|token| token.token_name.ends_with(':'))
+        .count()
+}
+
+///If the tokens after any labels on the line invoke a known macro, returns the name of the macro and the arguments given.
+pub fn read_macro_invocation(tokens: &[Token], macros: &MacroMap) -> Option<(String, Vec<String>)> {
+    let tokens = &tokens[count_leading_labels(tokens)..];
+    let (name, arguments, _) = split_name_and_arguments(tokens)?;
+    if !macros.contains_key(&name) {
+        return None;
+    }
+
+    Some((name, arguments))
+}
+
+///Expands an invocation of a macro into copies of the lines in the macro's body. The line_number of each copy is the line of the
+/// macro body it came from. Parameters are replaced by the arguments and labels defined in the body are given a suffix unique to
+/// this expansion so that the macro can be invoked more than once. Invocations of other macros within the body are expanded
+/// in turn. The columns of a returned error are left for the caller to fill in.
+pub fn expand_macro(
+    name: &str,
+    arguments: &[String],
+    macros: &MacroMap,
+    expansion_count: &mut usize,
+    depth: usize,
+) -> Result<Vec<MonacoLineInfo>, Error> {
+    if depth >= MAX_MACRO_DEPTH {
+        return Err(Error {
+            error_name: RecursiveMacroExpansion,
+            token_causing_error: name.to_string(),
+            start_end_columns: (0, 0),
+            message: format!("Expansion of macro `{name}` is nested more than {MAX_MACRO_DEPTH} levels deep. Check that the macro does not invoke itself.\n"),
+            fix_its: Vec::new(),
+        });
+    }
+
+    let definitions = &macros[name];
+    let definition = match definitions
+        .iter()
+        .find(|definition| definition.parameters.len() == arguments.len())
+    {
+        Some(definition) => definition,
+        None => {
+            let expected = definitions
+                .iter()
+                .map(|definition| definition.parameters.len().to_string())
+                .collect::<Vec<String>>()
+                .join(" or ");
+            return Err(Error {
+                error_name: IncorrectNumberOfOperands,
+                token_causing_error: name.to_string(),
+                start_end_columns: (0, 0),
+                message: format!(
+                    "Macro `{}` expects {} argument(s) but was given {}.\n",
+                    name,
+                    expected,
+                    arguments.len()
+                ),
+                fix_its: Vec::new(),
+            });
+        }
+    };
+
+    let suffix = format!("_M{expansion_count}");
+    *expansion_count += 1;
+
+    //labels defined in the body are local to each expansion
+    let mut local_labels: Vec<String> = Vec::new();
+    for line in &definition.body {
+        for token in &line.tokens[..count_leading_labels(&line.tokens)] {
+            local_labels.push(
+                token
+                    .token_name
+                    .trim_end_matches(',')
+                    .trim_end_matches(':')
+                    .to_string(),
+            );
+        }
+    }
+
+    //substitute longer parameters first so that a parameter which is a prefix of another is not substituted into it
+    let mut substitutions: Vec<(&String, &String)> =
+        definition.parameters.iter().zip(arguments).collect();
+    substitutions.sort_by_key(|(parameter, _)| std::cmp::Reverse(parameter.len()));
+
+    let mut expansion: Vec<MonacoLineInfo> = Vec::new();
+    for body_line in &definition.body {
+        let mut line = MonacoLineInfo {
+            line_number: body_line.line_number,
+            tokens: body_line.tokens.clone(),
+            ..Default::default()
+        };
+        for token in &mut line.tokens {
+            for (parameter, argument) in &substitutions {
+                token.token_name = token.token_name.replace(*parameter, argument);
+            }
+            rename_local_label(token, &local_labels, &suffix);
+        }
+
+        match read_macro_invocation(&line.tokens, macros) {
+            Some((inner_name, inner_arguments)) => {
+                let label_count = count_leading_labels(&line.tokens);
+                let mut inner = expand_macro(
+                    &inner_name,
+                    &inner_arguments,
+                    macros,
+                    expansion_count,
+                    depth + 1,
+                )?;
+                //any labels on the invocation are kept on a line of their own so they refer to the start of the expansion
+                if label_count > 0 {
+                    line.tokens.truncate(label_count);
+                    expansion.push(line);
+                }
+                expansion.append(&mut inner);
+            }
+            None => expansion.push(line),
+        }
+    }
+
+    Ok(expansion)
+}
+
+///Appends the expansion suffix to a token which defines or references a label local to the macro.
+fn rename_local_label(token: &mut Token, local_labels: &[String], suffix: &str) {
+    let trailing: String = token
+        .token_name
+        .chars()
+        .rev()
+        .take_while(|char| *char == ':' || *char == ',')
+        .collect::<String>()
+        .chars()
+        .rev()
+        .collect();
+    let base = &token.token_name[..token.token_name.len() - trailing.len()];
+
+    if local_labels.iter().any(|label| label == base) {
+        token.token_name = format!("{base}{suffix}{trailing}");
+    } else if let Some(open) = base.find('(') {
+        //memory operands such as offset(label) may also reference a local label
+        let inner = base[open + 1..].trim_end_matches(')');
+        if local_labels.iter().any(|label| label == inner) && base.ends_with(')') {
+            token.token_name = format!("{}({inner}{suffix}){trailing}", &base[..open]);
+        }
+    }
+}
+
+///Returns the columns of the invocation on a line, excluding any labels before it.
+pub fn invocation_columns(line: &MonacoLineInfo) -> (usize, usize) {
+    let tokens: Vec<&Token> = line
+        .tokens
+        .iter()
+        .filter(|token| token.token_type != Label)
+        .collect();
+    match (tokens.first(), tokens.last()) {
+        (Some(first), Some(last)) => (first.start_end_columns.0, last.start_end_columns.1),
+        _ => (0, 0),
+    }
+}
+
+///Describes a line of a macro body for messages, such as "line 2 of the macro body".
+pub fn macro_body_location(line: &MonacoLineInfo) -> String {
+    match &line.source {
+        Some(source) => format!(
+            "line {} of the macro body in {}",
+            source.line + 1,
+            source.file
+        ),
+        None => format!("line {} of the macro body", line.line_number + 1),
+    }
+}
+
+///Adds an error found in a line of a macro expansion to both the line of the macro body and the line of the invocation.
+/// The error on the body keeps its columns while the error on the invocation covers the whole invocation.
+pub fn add_macro_error(
+    monaco_line_info: &mut [MonacoLineInfo],
+    invocation_line: usize,
+    macro_line: usize,
+    error: Error,
+) {
+    //the columns of an error in an expansion may not match the text of either line, so neither can be fixed in place
+    let mut body_error = error.clone();
+    body_error.fix_its.clear();
+    body_error.message.push_str(&format!(
+        "Found when expanding the macro invoked on {}.\n",
+        monaco_line_info[invocation_line].location()
+    ));
+    if !monaco_line_info[macro_line].errors.contains(&body_error) {
+        monaco_line_info[macro_line].errors.push(body_error);
+    }
+
+    let mut invocation_error = error;
+    invocation_error.fix_its.clear();
+    invocation_error.start_end_columns = invocation_columns(&monaco_line_info[invocation_line]);
+    invocation_error.message.push_str(&format!(
+        "Found in {}.\n",
+        macro_body_location(&monaco_line_info[macro_line])
+    ));
+    monaco_line_info[invocation_line]
+        .errors
+        .push(invocation_error);
+}
+
+///Replaces the mouse hover of each line that invokes a macro with the instructions and data the invocation expanded to.
+pub fn update_macro_mouse_hover(
+    instructions: &[Instruction],
+    data: &[Data],
+    monaco_line_info: &mut [MonacoLineInfo],
+) {
+    let mut expansions: HashMap<usize, Vec<String>> = HashMap::new();
+    for instruction in instructions {
+        if let Some(macro_line_number) = instruction.macro_line_number {
+            expansions
+                .entry(instruction.line_number)
+                .or_default()
+                .push(format!(
+                    "{:<24} # {}: 0x{:08x}",
+                    instruction.recreate_string(),
+                    monaco_line_info[macro_line_number].location(),
+                    instruction.binary
+                ));
+        }
+    }
+    for datum in data {
+        if let Some(macro_line_number) = datum.macro_line_number {
+            expansions
+                .entry(datum.line_number)
+                .or_default()
+                .push(format!(
+                    "{:<24} # {}",
+                    datum.recreate_string(),
+                    monaco_line_info[macro_line_number].location()
+                ));
+        }
+    }
+
+    for (line_number, lines) in expansions {
+        let line = &mut monaco_line_info[line_number];
+        let name = line.tokens[count_label_tokens(&line.tokens)..]
+            .first()
+            .map(|token| {
+                token
+                    .token_name
+                    .split('(')
+                    .next()
+                    .unwrap_or_default()
+                    .to_string()
+            })
+            .unwrap_or_default();
+        line.mouse_hover_string = format!(
+            "`{}` is a macro.\n\n```\n{}\n```\n\n",
+            name,
+            lines.join("\n")
+        );
+    }
+}
+
+///Counts labels at the start of a line which has already had its label colons removed.
+fn count_label_tokens(tokens: &[Token]) -> usize {
+    tokens
+        .iter()
+        .take_while(|token| token.token_type == Label)
+        .count()
+}
@@ -0,0 +1,328 @@
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::parser::assembling::{
+    match_fp_register, match_fp_register_riscv, match_gp_register, match_gp_register_riscv,
+};
+use crate::parser::expressions::is_symbol_char;
+use crate::parser::linker::Section;
+use crate::parser::parser_structs_and_enums::{
+    ProgramInfo, SUPPORTED_INSTRUCTIONS_MIPS, SUPPORTED_INSTRUCTIONS_RISCV,
+};
+use crate::parser::parsing::declared_symbols;
+use crate::parser::project::ProjectFile;
+use std::collections::HashSet;
+
+///Where a label is written in a file of a project. Lines and columns start from 0 and the end column is not included.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LabelLocation {
+    pub file: String,
+    pub line: usize,
+    pub start_end_columns: (usize, usize),
+}
+
+///A label defined in a file of a project.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LabelDefinition {
+    pub name: String,
+    pub section: Section,
+    pub location: LabelLocation,
+}
+
+///Every label of an assembled project along with every place a symbol is written, which is used to move between the
+/// definition and uses of a label in the editor and to rename labels.
+#[derive(Clone, Debug, Default)]
+pub struct LabelIndex {
+    definitions: Vec<LabelDefinition>,
+    ///The labels exported with .globl or .global, which can be referred to from any file.
+    globals: HashSet<String>,
+    ///Every symbol written in the files, including the definitions of labels.
+    occurrences: Vec<(String, LabelLocation)>,
+}
+
+impl LabelIndex {
+    ///Builds the index of a project from the files it was assembled from and the program info returned by
+    /// `parse_project`. The labels of the program are those the assembler placed, so labels created by expanding a macro
+    /// are left out since they are not written in any file.
+    pub fn new(files: &[ProjectFile], program_info: &ProgramInfo) -> LabelIndex {
+        let file_lines: Vec<(&str, Vec<&str>)> = files
+            .iter()
+            .map(|file| (file.name.as_str(), file.contents.lines().collect()))
+            .collect();
+        let text_at = |location: &LabelLocation| {
+            let (_, lines) = file_lines.iter().find(|(name, _)| *name == location.file)?;
+            let line: Vec<char> = lines.get(location.line)?.chars().collect();
+            let (start, end) = location.start_end_columns;
+            Some(line.get(start..end)?.iter().collect::<String>())
+        };
+        let location_of = |line_number: usize, start_end_columns: (usize, usize)| {
+            let (file, line) = match &program_info.monaco_line_info.get(line_number)?.source {
+                Some(source) => (source.file.clone(), source.line),
+                None => (files.first()?.name.clone(), line_number),
+            };
+            Some(LabelLocation {
+                file,
+                line,
+                start_end_columns,
+            })
+        };
+
+        let instruction_labels = program_info.instructions.iter().flat_map(|instruction| {
+            instruction
+                .labels
+                .iter()
+                .map(|label| (&label.token, label.token_line, Section::Text))
+        });
+        let data_labels = program_info
+            .data
+            .iter()
+            .filter(|datum| !datum.label.token_name.is_empty())
+            .map(|datum| (&datum.label, datum.line_number, Section::Data));
+        let mut definitions: Vec<LabelDefinition> = Vec::new();
+        for (token, line_number, section) in instruction_labels.chain(data_labels) {
+            let Some(location) = location_of(line_number, token.start_end_columns) else {
+                continue;
+            };
+            //a file included more than once defines its labels once in the editor
+            if text_at(&location).as_deref() != Some(token.token_name.as_str())
+                || definitions
+                    .iter()
+                    .any(|definition| definition.location == location)
+            {
+                continue;
+            }
+            definitions.push(LabelDefinition {
+                name: token.token_name.clone(),
+                section,
+                location,
+            });
+        }
+        definitions.sort_by_key(|definition| {
+            (
+                files
+                    .iter()
+                    .position(|file| file.name == definition.location.file),
+                definition.location.line,
+                definition.location.start_end_columns,
+            )
+        });
+
+        let globals = declared_symbols(&program_info.monaco_line_info)
+            .into_iter()
+            .filter(|(_, _, is_extern)| !is_extern)
+            .map(|(symbol, _, _)| symbol.token_name)
+            .collect();
+
+        let mut occurrences = Vec::new();
+        for (file, lines) in &file_lines {
+            for (line, text) in lines.iter().enumerate() {
+                for (name, start_end_columns) in line_symbols(text) {
+                    occurrences.push((
+                        name,
+                        LabelLocation {
+                            file: file.to_string(),
+                            line,
+                            start_end_columns,
+                        },
+                    ));
+                }
+            }
+        }
+
+        LabelIndex {
+            definitions,
+            globals,
+            occurrences,
+        }
+    }
+
+    ///Finds the definition a symbol written in a file refers to. A label defined in the same file is used first, then a
+    /// label exported from another file, and then a label of a file which is included.
+    pub fn resolve(&self, file: &str, name: &str) -> Option<&LabelDefinition> {
+        let named = || {
+            self.definitions
+                .iter()
+                .filter(move |definition| definition.name == name)
+        };
+        named()
+            .find(|definition| definition.location.file == file)
+            .or_else(|| named().find(|_| self.globals.contains(name)))
+            .or_else(|| named().next())
+    }
+
+    ///Finds the label written at a column of a line of a file. The column just after the label also finds it, since that
+    /// is where the cursor is after typing it.
+    pub fn label_at(&self, file: &str, line: usize, column: usize) -> Option<&str> {
+        self.occurrence_at(file, line, column).map(|(name, _)| name)
+    }
+
+    ///Finds the label written at a column of a line of a file along with where it is written on the line.
+    pub fn occurrence_at(
+        &self,
+        file: &str,
+        line: usize,
+        column: usize,
+    ) -> Option<(&str, &LabelLocation)> {
+        self.occurrences
+            .iter()
+            .find(|(name, location)| {
+                location.file == file
+                    && location.line == line
+                    && (location.start_end_columns.0..=location.start_end_columns.1)
+                        .contains(&column)
+                    && self.resolve(file, name).is_some()
+            })
+            .map(|(name, location)| (name.as_str(), location))
+    }
+
+    ///Finds the definition of the label written at a column of a line of a file.
+    pub fn find_definition(
+        &self,
+        file: &str,
+        line: usize,
+        column: usize,
+    ) -> Option<&LabelLocation> {
+        let name = self.label_at(file, line, column)?;
+        self.resolve(file, name)
+            .map(|definition| &definition.location)
+    }
+
+    ///Finds every place the label written at a column of a line of a file is written, including its definition and any
+    /// files it is exported to. Labels of the same name which are local to other files are left out.
+    pub fn find_references(&self, file: &str, line: usize, column: usize) -> Vec<&LabelLocation> {
+        let Some(name) = self.label_at(file, line, column) else {
+            return Vec::new();
+        };
+        let definition = self.resolve(file, name);
+        self.occurrences
+            .iter()
+            .filter(|(other, location)| {
+                other == name && self.resolve(&location.file, name) == definition
+            })
+            .map(|(_, location)| location)
+            .collect()
+    }
+
+    ///Finds every place that is changed to rename the label written at a column of a line of a file. The new name must be
+    /// a valid label which is not a register or instruction and is not already written in any file the label is used in,
+    /// so that renaming can never change what another symbol refers to.
+    pub fn rename_label(
+        &self,
+        file: &str,
+        line: usize,
+        column: usize,
+        new_name: &str,
+        arch: AvailableDatapaths,
+    ) -> Result<Vec<&LabelLocation>, String> {
+        let Some(name) = self.label_at(file, line, column) else {
+            return Err("Only labels can be renamed.".to_string());
+        };
+        let mut chars = new_name.chars();
+        let is_valid_name = chars.next().is_some_and(is_symbol_char)
+            && chars.all(|char| is_symbol_char(char) || char.is_ascii_digit());
+        if !is_valid_name {
+            return Err(format!(
+                "`{new_name}` is not a valid label. Labels start with a letter, `_`, or `.` and may only contain letters, digits, `_`, and `.`."
+            ));
+        }
+        let is_register = match arch {
+            AvailableDatapaths::MIPS => {
+                match_gp_register(new_name).is_some() || match_fp_register(new_name).is_some()
+            }
+            AvailableDatapaths::RISCV => {
+                match_gp_register_riscv(new_name).is_some()
+                    || match_fp_register_riscv(new_name).is_some()
+            }
+        };
+        let is_instruction = match arch {
+            AvailableDatapaths::MIPS => {
+                SUPPORTED_INSTRUCTIONS_MIPS.contains(&&*new_name.to_lowercase())
+            }
+            AvailableDatapaths::RISCV => {
+                SUPPORTED_INSTRUCTIONS_RISCV.contains(&&*new_name.to_lowercase())
+            }
+        };
+        if is_register || is_instruction {
+            return Err(format!(
+                "`{new_name}` is the name of a {}, so it cannot be used as a label.",
+                if is_register {
+                    "register"
+                } else {
+                    "instruction"
+                }
+            ));
+        }
+
+        let references = self.find_references(file, line, column);
+        if new_name == name {
+            return Ok(references);
+        }
+        let files: HashSet<&str> = references
+            .iter()
+            .map(|location| location.file.as_str())
+            .collect();
+        if let Some((_, location)) = self
+            .occurrences
+            .iter()
+            .find(|(other, location)| other == new_name && files.contains(location.file.as_str()))
+        {
+            return Err(format!(
+                "`{new_name}` is already used on line {} of {}.",
+                location.line + 1,
+                location.file
+            ));
+        }
+        Ok(references)
+    }
+
+    ///Lists the labels defined in a file, with the labels of the text before those of the data. Labels of each section
+    /// are in the order they are written.
+    pub fn outline(&self, file: &str) -> Vec<&LabelDefinition> {
+        let mut labels: Vec<&LabelDefinition> = self
+            .definitions
+            .iter()
+            .filter(|definition| definition.location.file == file)
+            .collect();
+        labels.sort_by_key(|definition| definition.section == Section::Data);
+        labels
+    }
+}
+
+///Finds the symbols written on a line of a file with their columns. The instruction or directive of the line is not a
+/// symbol, nor are registers, relocations such as `%hi`, numbers, strings, characters, and comments.
+fn line_symbols(line: &str) -> Vec<(String, (usize, usize))> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut symbols = Vec::new();
+    let mut read_operator = false;
+    let mut i = 0;
+    while i < chars.len() {
+        let char = chars[i];
+        if char == '#' {
+            break;
+        } else if char == '"' || char == '\'' {
+            //skips to the closing quote, passing over escaped characters
+            i += 1;
+            while i < chars.len() && chars[i] != char {
+                i += if chars[i] == '\\' { 2 } else { 1 };
+            }
+            i += 1;
+        } else if char.is_ascii_digit() || char == '$' || char == '%' {
+            i += 1;
+            while i < chars.len() && (is_symbol_char(chars[i]) || chars[i].is_ascii_digit()) {
+                i += 1;
+            }
+        } else if is_symbol_char(char) {
+            let start = i;
+            while i < chars.len() && (is_symbol_char(chars[i]) || chars[i].is_ascii_digit()) {
+                i += 1;
+            }
+            let is_label = !read_operator && chars.get(i) == Some(&':');
+            if is_label || read_operator {
+                symbols.push((chars[start..i].iter().collect(), (start, i)));
+            } else {
+                read_operator = true;
+            }
+        } else {
+            i += 1;
+        }
+    }
+    symbols
+}
@@ -1,5 +1,13 @@
 use crate::emulation_core::architectures::AvailableDatapaths;
-use crate::parser::assembling::{assemble_data_binary, read_operands, read_operands_riscv};
+use crate::parser::assembling::{
+    assemble_data_binary_at, read_operands, read_operands_riscv, read_raw_word,
+    resolve_data_label_expressions, resolve_instruction_label_expressions,
+    resolve_relocation_operators,
+};
+use crate::parser::layout::{assign_text_addresses, data_base_address, map_addresses_to_lines};
+use crate::parser::linker::program_relocations;
+use crate::parser::local_labels::resolve_local_labels;
+use crate::parser::macro_parsing::update_macro_mouse_hover;
 use crate::parser::parser_structs_and_enums::ErrorType::*;
 use crate::parser::parser_structs_and_enums::OperandType::*;
 use crate::parser::parser_structs_and_enums::ProgramInfo;
@@ -9,6 +17,7 @@
     complete_lw_sw_pseudo_instructions, expand_pseudo_instructions_and_assign_instruction_numbers,
     expand_pseudo_instructions_and_assign_instruction_numbers_riscv,
 };
+use crate::parser::source_map::create_source_map;
 use std::collections::HashMap;
 
 ///Parser is the starting function of the parser / assembler process. It takes a string representation of a MIPS/RISC-V
@@ -17,124 +26,176 @@ pub fn parser(
     file_string: String,
     arch: AvailableDatapaths,
 ) -> (ProgramInfo, Vec<u32>, HashMap<String, usize>) {
-    match arch {
-        AvailableDatapaths::MIPS => {
-            let mut program_info = ProgramInfo {
-                monaco_line_info: tokenize_program(file_string),
-                ..Default::default()
-            };
+    assemble_lines(tokenize_program(file_string), arch)
+}
 
-            (program_info.instructions, program_info.data) =
-                separate_data_and_text(&mut program_info.monaco_line_info);
+///Builds the binary of a program that has already been tokenized, such as the lines of a project joined together by
+/// tokenize_project.
+pub fn assemble_lines(
+    monaco_line_info: Vec<MonacoLineInfo>,
+    arch: AvailableDatapaths,
+) -> (ProgramInfo, Vec<u32>, HashMap<String, usize>) {
+    let mut program_info = lay_out_program(monaco_line_info, arch, true);
+    let laid_out = program_info.clone();
+    let data_base = data_base_address(&program_info.instructions);
+    let (vec_of_data, labels) =
+        assemble_laid_out_program(&mut program_info, data_base, &HashMap::new(), arch);
+    program_info.relocations =
+        program_relocations(&laid_out, &program_info.data, &labels, data_base, arch);
+
+    let (binary, data_starting_point) =
+        create_binary_vec(program_info.instructions.clone(), vec_of_data);
+
+    for entry in &program_info.monaco_line_info {
+        program_info
+            .updated_monaco_string
+            .push_str(&format!("{}\n", entry.updated_monaco_string));
+    }
 
-            expand_pseudo_instructions_and_assign_instruction_numbers(
-                &mut program_info.instructions,
-                &program_info.data,
-                &mut program_info.monaco_line_info,
-            );
+    program_info.address_to_line_number = map_addresses_to_lines(&program_info.instructions);
+    program_info.source_map =
+        create_source_map(&program_info.instructions, &program_info.monaco_line_info);
 
-            let vec_of_data = assemble_data_binary(&mut program_info.data);
+    //without a main or start label the program starts at its first instruction, which .text or .org may move
+    let text_starting_point = program_info
+        .instructions
+        .first()
+        .map_or(0, |instruction| instruction.instruction_number << 2);
+    program_info.pc_starting_point =
+        determine_pc_starting_point(labels.clone()).max(text_starting_point);
+    program_info.data_starting_point = data_starting_point;
 
-            let labels: HashMap<String, usize> =
-                create_label_map(&mut program_info.instructions, &mut program_info.data);
-            let labels_clone = labels.clone();
+    (program_info, binary, labels)
+}
 
-            complete_lw_sw_pseudo_instructions(
-                &mut program_info.instructions,
-                &labels,
-                &mut program_info.monaco_line_info,
-            );
+///Separates the lines of a program into its instructions and data, expands pseudo-instructions, and places every
+/// instruction at its address. On MIPS, a syscall is added to the end of a program that does not already end with one
+/// unless ends_program is false, as it is for the files linked after the first since the program does not end there.
+pub fn lay_out_program(
+    monaco_line_info: Vec<MonacoLineInfo>,
+    arch: AvailableDatapaths,
+    ends_program: bool,
+) -> ProgramInfo {
+    let mut program_info = ProgramInfo {
+        monaco_line_info,
+        ..Default::default()
+    };
 
-            read_instructions(
-                &mut program_info.instructions,
-                &labels,
-                &mut program_info.monaco_line_info,
-            );
+    let text_placements;
+    (
+        program_info.instructions,
+        program_info.data,
+        text_placements,
+    ) = separate_sections(&mut program_info.monaco_line_info);
+    resolve_local_labels(&mut program_info.instructions, &mut program_info.data);
 
-            program_info.console_out_post_assembly = suggest_error_corrections(
+    match arch {
+        AvailableDatapaths::MIPS => {
+            //a syscall at the end keeps another from being added, so one is held there while pseudo-instructions are expanded
+            if !ends_program {
+                let line_number = program_info
+                    .instructions
+                    .last()
+                    .map_or(0, |instruction| instruction.line_number);
+                program_info.instructions.push(Instruction {
+                    operator: Token {
+                        token_name: "syscall".to_string(),
+                        start_end_columns: (0, 0),
+                        token_type: TokenType::Operator,
+                    },
+                    line_number,
+                    ..Default::default()
+                });
+            }
+            expand_pseudo_instructions_and_assign_instruction_numbers(
                 &mut program_info.instructions,
-                &mut program_info.data,
-                &labels,
+                &program_info.data,
                 &mut program_info.monaco_line_info,
-                arch,
             );
-
-            let (binary, data_starting_point) =
-                create_binary_vec(program_info.instructions.clone(), vec_of_data);
-
-            for entry in &program_info.monaco_line_info {
-                program_info
-                    .updated_monaco_string
-                    .push_str(&format!("{}\n", entry.updated_monaco_string));
-            }
-
-            for instruction in program_info.instructions.clone() {
-                program_info
-                    .address_to_line_number
-                    .push(instruction.line_number);
+            if !ends_program {
+                program_info.instructions.pop();
             }
-
-            program_info.pc_starting_point = determine_pc_starting_point(labels);
-            program_info.data_starting_point = data_starting_point;
-
-            (program_info.clone(), binary, labels_clone)
         }
         AvailableDatapaths::RISCV => {
-            let mut program_info = ProgramInfo {
-                monaco_line_info: tokenize_program(file_string),
-                ..Default::default()
-            };
-
-            (program_info.instructions, program_info.data) =
-                separate_data_and_text(&mut program_info.monaco_line_info);
-
             expand_pseudo_instructions_and_assign_instruction_numbers_riscv(
                 &mut program_info.instructions,
                 &program_info.data,
                 &mut program_info.monaco_line_info,
             );
+        }
+    }
 
-            let vec_of_data = assemble_data_binary(&mut program_info.data);
+    assign_text_addresses(
+        &mut program_info.instructions,
+        &text_placements,
+        &mut program_info.monaco_line_info,
+    );
 
-            let labels: HashMap<String, usize> =
-                create_label_map(&mut program_info.instructions, &mut program_info.data);
-            let labels_clone = labels.clone();
+    program_info
+}
 
-            read_instructions_riscv(
+///Assembles the instructions and data of a program placed by lay_out_program, with the data starting at data_base. Where
+/// the program refers to a label it does not define, the label is looked up in external_labels, which holds the labels
+/// exported by the other files of a linked program. Returns the bytes of the data and the labels the program defines.
+pub fn assemble_laid_out_program(
+    program_info: &mut ProgramInfo,
+    data_base: usize,
+    external_labels: &HashMap<String, usize>,
+    arch: AvailableDatapaths,
+) -> (Vec<u8>, HashMap<String, usize>) {
+    let mut vec_of_data = assemble_data_binary_at(&mut program_info.data, data_base);
+
+    let labels: HashMap<String, usize> = create_label_map_at(
+        &mut program_info.instructions,
+        &mut program_info.data,
+        data_base,
+    );
+    //labels defined in the program take the place of any external label of the same name
+    let mut all_labels = external_labels.clone();
+    all_labels.extend(labels.clone());
+
+    resolve_relocation_operators(&mut program_info.instructions, &all_labels, arch);
+    resolve_instruction_label_expressions(&mut program_info.instructions, &all_labels);
+    resolve_data_label_expressions(&mut program_info.data, &mut vec_of_data, &all_labels);
+
+    match arch {
+        AvailableDatapaths::MIPS => {
+            complete_lw_sw_pseudo_instructions(
                 &mut program_info.instructions,
-                &labels,
+                &all_labels,
                 &mut program_info.monaco_line_info,
             );
 
-            program_info.console_out_post_assembly = suggest_error_corrections(
+            read_instructions(
                 &mut program_info.instructions,
-                &mut program_info.data,
-                &labels,
+                &all_labels,
+                &mut program_info.monaco_line_info,
+            );
+        }
+        AvailableDatapaths::RISCV => {
+            read_instructions_riscv(
+                &mut program_info.instructions,
+                &all_labels,
                 &mut program_info.monaco_line_info,
-                arch,
             );
-
-            let (binary, data_starting_point) =
-                create_binary_vec(program_info.instructions.clone(), vec_of_data);
-
-            for entry in &program_info.monaco_line_info {
-                program_info
-                    .updated_monaco_string
-                    .push_str(&format!("{}\n", entry.updated_monaco_string));
-            }
-
-            for instruction in program_info.instructions.clone() {
-                program_info
-                    .address_to_line_number
-                    .push(instruction.line_number);
-            }
-
-            program_info.pc_starting_point = determine_pc_starting_point(labels);
-            program_info.data_starting_point = data_starting_point;
-
-            (program_info.clone(), binary, labels_clone)
         }
     }
+
+    program_info.console_out_post_assembly = suggest_error_corrections(
+        &mut program_info.instructions,
+        &mut program_info.data,
+        &all_labels,
+        &mut program_info.monaco_line_info,
+        arch,
+    );
+
+    update_macro_mouse_hover(
+        &program_info.instructions,
+        &program_info.data,
+        &mut program_info.monaco_line_info,
+    );
+
+    (vec_of_data, labels)
 }
 
 ///Takes the vector of MIPS instructions and assembles the binary for them.
@@ -1132,6 +1193,7 @@ pub fn read_instructions(
                             token_causing_error: "$zero".to_string(),
                             start_end_columns: instruction.operands[0].start_end_columns,
                             message: "".to_string(),
+                            fix_its: Vec::new(),
                         })
                     }
                     read_operands(instruction, vec![RegisterGP, RegisterGP], vec![2, 1], None);
@@ -1526,6 +1588,15 @@ pub fn read_instructions(
                     monaco_line_info[instruction.line_number].mouse_hover_string = info.to_string();
                 }
             }
+            ".word" => {
+                read_raw_word(instruction);
+
+                let info = InstructionDescription {
+                    syntax: ".word value".to_string(),
+                    description: "Places the 32-bit value in memory between the instructions as it is written, without assembling an instruction.".to_string(),
+                };
+                monaco_line_info[instruction.line_number].mouse_hover_string = info.to_string();
+            }
 
             _ => {
                 if UNSUPPORTED_INSTRUCTIONS_MIPS.contains(&&*instruction.operator.token_name) {
@@ -1534,6 +1605,7 @@ pub fn read_instructions(
                         token_causing_error: instruction.operator.token_name.to_string(),
                         start_end_columns: instruction.operator.start_end_columns,
                         message: "\n\n".to_string(),
+                        fix_its: Vec::new(),
                     })
                 } else {
                     instruction.errors.push(Error {
@@ -1541,6 +1613,7 @@ pub fn read_instructions(
                         token_causing_error: instruction.operator.token_name.clone(),
                         start_end_columns: instruction.operator.start_end_columns,
                         message: "\n\n".to_string(),
+                        fix_its: Vec::new(),
                     });
                 }
             }
@@ -4844,6 +4917,15 @@ pub fn read_instructions_riscv(
                     monaco_line_info[instruction.line_number].mouse_hover_string = info.to_string();
                 }
             }
+            ".word" => {
+                read_raw_word(instruction);
+
+                let info = InstructionDescription {
+                    syntax: ".word value".to_string(),
+                    description: "Places the 32-bit value in memory between the instructions as it is written, without assembling an instruction.".to_string(),
+                };
+                monaco_line_info[instruction.line_number].mouse_hover_string = info.to_string();
+            }
             _ => {
                 if UNSUPPORTED_INSTRUCTIONS_RISCV.contains(&&*instruction.operator.token_name) {
                     instruction.errors.push(Error {
@@ -4851,6 +4933,7 @@ pub fn read_instructions_riscv(
                         token_causing_error: instruction.operator.token_name.to_string(),
                         start_end_columns: instruction.operator.start_end_columns,
                         message: "\n\n".to_string(),
+                        fix_its: Vec::new(),
                     })
                 } else {
                     instruction.errors.push(Error {
@@ -4858,6 +4941,7 @@ pub fn read_instructions_riscv(
                         token_causing_error: instruction.operator.token_name.clone(),
                         start_end_columns: instruction.operator.start_end_columns,
                         message: "\n\n".to_string(),
+                        fix_its: Vec::new(),
                     });
                 }
             }
@@ -5014,9 +5098,10 @@ pub fn create_binary_vec(
     instructions: Vec<Instruction>,
     mut vec_of_data: Vec<u8>,
 ) -> (Vec<u32>, usize) {
-    //push all instructions
+    //push all instructions, filling any gap left by a placement directive such as .org with zeros
     let mut binary: Vec<u32> = Vec::new();
     for instruction in instructions {
+        binary.resize(instruction.instruction_number, 0);
         binary.push(instruction.binary);
     }
 
@@ -3,19 +3,87 @@
 use std::fmt::Formatter;
 use std::string::ToString;
 
+use crate::parser::linker::PlacedRelocation;
 use gloo_console::log;
+use strum_macros::{Display, EnumIter, EnumString};
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 ///Wrapper for all information gathered in the Parser/Assembler about the written program.
 pub struct ProgramInfo {
     pub monaco_line_info: Vec<MonacoLineInfo>,
     pub address_to_line_number: Vec<usize>,
+    ///The line and, for pseudo-instructions and macros, the text each instruction of the text section was assembled
+    /// from, in the order of their addresses.
+    pub source_map: Vec<SourceMapEntry>,
     pub updated_monaco_string: String,
     pub console_out_post_assembly: String,
     pub instructions: Vec<Instruction>,
     pub data: Vec<Data>,
     pub pc_starting_point: usize,
     pub data_starting_point: usize,
+    ///The places in memory that hold the address of a symbol, which are moved when the program is written at another
+    /// address.
+    pub relocations: Vec<PlacedRelocation>,
+}
+
+impl ProgramInfo {
+    ///Returns the file and line in the editor that a line of the assembled program came from. Only programs assembled
+    /// from a project have a source location for their lines.
+    pub fn source_location(&self, line: usize) -> Option<&SourceLocation> {
+        self.monaco_line_info.get(line)?.source.as_ref()
+    }
+
+    ///Returns the lines of the assembled program that came from the given file of a project. A file which is included more
+    /// than once has its lines returned once for every time it was included.
+    pub fn lines_of_file<'a>(&'a self, file: &'a str) -> impl Iterator<Item = &'a MonacoLineInfo> {
+        self.monaco_line_info.iter().filter(move |line| {
+            line.source
+                .as_ref()
+                .is_some_and(|source| source.file == file)
+        })
+    }
+
+    ///Builds the updated text for a single file of a project, in the same way updated_monaco_string is built for the whole
+    /// program. Returns None if the file was not part of the assembled program.
+    pub fn updated_file_string(&self, file: &str) -> Option<String> {
+        let mut updated_string = String::new();
+        let mut next_line = 0;
+        for line in self.lines_of_file(file) {
+            //only the first time a file is included is used
+            let source_line = line.source.as_ref().map_or(0, |source| source.line);
+            if source_line != next_line {
+                break;
+            }
+            updated_string.push_str(&format!("{}\n", line.updated_monaco_string));
+            next_line += 1;
+        }
+
+        if next_line == 0 {
+            None
+        } else {
+            Some(updated_string)
+        }
+    }
+}
+
+///The file of a project and the line within it that a line of the assembled program came from.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SourceLocation {
+    pub file: String,
+    pub line: usize,
+}
+
+///Where a word of the text section came from, so that it can be traced back to the source as it was written.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SourceMapEntry {
+    pub address: usize,
+    ///The line of the program the word was assembled from, the same line address_to_line_number gives for it.
+    pub line_number: usize,
+    ///The hardware instruction in the word, as it was assembled.
+    pub instruction: String,
+    ///The pseudo-instruction or macro invocation written on the line, if the word is one of the instructions it was
+    /// expanded to.
+    pub expanded_from: Option<String>,
 }
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
@@ -27,11 +95,31 @@ pub struct MonacoLineInfo {
     pub line_number: usize,
     pub error_start_end_columns: Vec<(usize, usize)>,
     pub errors: Vec<Error>,
+    ///Likely mistakes found by the lints, which do not keep the program from being assembled.
+    pub warnings: Vec<Warning>,
+    ///The file and line the line was written on when the program is assembled from a project of several files.
+    pub source: Option<SourceLocation>,
 }
 
 impl MonacoLineInfo {
+    ///Describes where the line was written for messages, such as "line 4" or "line 4 of io.asm" for lines of a project.
+    pub fn location(&self) -> String {
+        match &self.source {
+            Some(source) => format!("line {} of {}", source.line + 1, source.file),
+            None => format!("line {}", self.line_number + 1),
+        }
+    }
+
     ///This function puts the translation from a pseudo-instruction into the updated monaco string
     pub fn update_pseudo_string(&mut self, expansion: Vec<&mut Instruction>) {
+        //pseudo-instructions inside a macro body are left as written since the line in the editor is the macro invocation
+        if expansion
+            .iter()
+            .any(|instruction| instruction.macro_line_number.is_some())
+        {
+            return;
+        }
+
         let (prefix, index) = self.get_tab_space_offset();
 
         self.updated_monaco_string
@@ -110,9 +198,16 @@ pub struct Instruction {
     pub line_number: usize,
     pub errors: Vec<Error>,
     pub labels: Vec<LabelInstance>,
+    ///If the instruction was expanded from a macro, the line of the macro body it came from. line_number is the line of the invocation.
+    pub macro_line_number: Option<usize>,
 }
 
 impl Instruction {
+    ///Returns true if the instruction is the syscall added to the end of a MIPS program, which is not written anywhere.
+    pub fn is_added_syscall(&self) -> bool {
+        self.operator.token_name == "syscall" && self.operator.start_end_columns == (0, 0)
+    }
+
     ///Takes the operator, operands, and label(optional) associated with an instruction and recreates the string version
     pub fn recreate_string(&self) -> String {
         let mut recreated_string = "".to_string();
@@ -149,13 +244,18 @@ pub struct Data {
     pub label: Token,
     pub data_type: Token,
     pub data_entries: Vec<Token>,
+    ///If the data was expanded from a macro, the line of the macro body it came from. line_number is the line of the invocation.
+    pub macro_line_number: Option<usize>,
 }
 
 impl Data {
     ///Takes the operator, operands, and label(optional) associated with an instruction and recreates the string version
     pub fn recreate_string(&self) -> String {
         let mut recreated_string = "".to_string();
-        recreated_string.push_str(&format!("{}: ", self.label.clone().token_name));
+        //directives such as .align do not have a label
+        if !self.label.token_name.is_empty() {
+            recreated_string.push_str(&format!("{}: ", self.label.clone().token_name));
+        }
         recreated_string.push_str(&self.data_type.token_name.to_string());
 
         for token in &self.data_entries {
@@ -187,6 +287,68 @@ pub struct Error {
     pub token_causing_error: String,
     pub start_end_columns: (usize, usize),
     pub message: String,
+    ///Edits of the line the error is on which would correct it, such as replacing a misspelled register.
+    pub fix_its: Vec<FixIt>,
+}
+
+///An edit of a line which corrects an error. The columns replaced start from 0 and the end column is not included, so a
+/// fix-it which inserts text has the same start and end column.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FixIt {
+    ///A short description of the edit, such as "Replace with `$t0`".
+    pub title: String,
+    pub start_end_columns: (usize, usize),
+    pub replacement: String,
+}
+
+///A likely mistake found by a lint. Unlike an error, a warning does not keep the program from being assembled.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Warning {
+    pub lint: Lint,
+    pub token_causing_warning: String,
+    pub start_end_columns: (usize, usize),
+    pub message: String,
+}
+
+///The checks for likely mistakes in a program which can each be turned on or off.
+#[derive(Clone, Copy, Debug, Display, EnumIter, EnumString, Eq, Hash, PartialEq)]
+pub enum Lint {
+    WriteToZeroRegister, //The destination of an instruction is $zero or x0, so the result is thrown away
+    UnusedLabel,         //A label is never referred to
+    FallThroughIntoData, //The last instruction does not end the program or jump, so execution continues into the data
+    BranchToData,        //A branch or jump targets a label of data rather than an instruction
+    UnrestoredSavedRegister, //A function returns without restoring a callee-saved register it changed
+    SignExtendedImmediate, //An immediate written as a positive value is sign-extended to a negative one
+    MissingExit,           //main does not end with an exit syscall
+}
+
+///How serious the mistakes found by a lint are, which decides how they are marked in the editor.
+#[derive(Clone, Copy, Debug, Display, Eq, PartialEq)]
+pub enum LintSeverity {
+    Warning,
+    Info,
+}
+
+impl Lint {
+    pub fn severity(&self) -> LintSeverity {
+        match self {
+            Lint::UnusedLabel => LintSeverity::Info,
+            _ => LintSeverity::Warning,
+        }
+    }
+
+    ///The description of the lint as it is shown to the user when turning it on or off.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Lint::WriteToZeroRegister => "Writes to the zero register",
+            Lint::UnusedLabel => "Unused labels",
+            Lint::FallThroughIntoData => "Code falling through into data",
+            Lint::BranchToData => "Branches to data",
+            Lint::UnrestoredSavedRegister => "Callee-saved registers not restored",
+            Lint::SignExtendedImmediate => "Sign-extended immediates",
+            Lint::MissingExit => "Missing exit syscall",
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
@@ -202,6 +364,7 @@ pub enum TokenType {
     Operator,
     Half,
     Word,
+    Dword,
     ASCIIZ,
     ASCII,
     DataType,
@@ -231,14 +394,26 @@ pub enum ErrorType {
     LabelAssignmentError, //A label is specified but it is not followed by anything committed to memory
     LabelMultipleDefinition, //The given label name is already used elsewhere in the project
     LabelNotFound,        //The given label operand does not match a given label
+    LocalLabelNotFound, //A local label reference such as 1b or 1f has no definition of the label before or after it
     ImproperlyFormattedLabel, //Label assignment recognized but does not end in a colon.
     ImproperlyFormattedData, //Line of data does not contain the proper number of tokens
-    ImproperlyFormattedASCII, //Token recognized as ASCII does not start and or end with "
-    ImproperlyFormattedChar, //Token recognized as a char does not end with ' or is larger than a single char
+    ImproperlyFormattedASCII, //Token recognized as ASCII does not start and or end with " or holds an unrecognized escape sequence
+    ImproperlyFormattedChar, //Token recognized as a char does not end with ', is larger than a single char, or holds an unrecognized escape sequence
     NonASCIIString, //One or multiple characters within the given string cannot be represented in ASCII
     NonASCIIChar,   //The given char cannot be represented in ASCII
     JALRRDRegisterZero, //The destination address for JALR cannot be the zero register
     IncorrectImmediateValue, //Ensure immediate value for li is valid
+    ImproperlyFormattedMacro, //Macro definition is missing a name, has malformed parameters, or is not closed by .end_macro/.endm
+    RecursiveMacroExpansion, //Macro invocations are nested too deeply, generally because a macro invokes itself
+    ImproperlyFormattedConstant, //.eqv, .equ, or .set is missing the name or value of the constant
+    InvalidRelocation, //The relocation operator is not available for the architecture or %pcrel_lo has no matching %pcrel_hi
+    IncludedFileNotFound, //.include does not name a file in the project
+    RecursiveInclude,  //A file includes itself, either directly or through other included files
+    InvalidPlacement, //The address or alignment given to .org, .align, .text, or .data cannot be used
+    InitializedBssData, //Data with initial values is placed in the .bss section, which only reserves zeroed space
+    UndefinedSymbol, //A symbol used by a file is neither defined in it nor exported with .globl by another file of the project
+    DuplicateGlobalSymbol, //A symbol exported with .globl is defined by more than one file of the project
+    ProfileViolation, //The instruction is not allowed by the instruction-set profile the program is assembled with
 }
 
 impl fmt::Display for ErrorType {
@@ -401,6 +576,20 @@ pub enum OperandType {
     "fcvt.s.lu",
 ];
 
+///The pseudo-instructions the parser expands for MIPS, which are not in SUPPORTED_INSTRUCTIONS_MIPS since they are not
+/// encoded as themselves.
+pub const PSEUDO_INSTRUCTIONS_MIPS: [&str; 21] = [
+    "li", "move", "seq", "sne", "sle", "sleu", "sgt", "sgtu", "sge", "sgeu", "la", "subi", "dsubi",
+    "dsubiu", "muli", "dmuli", "dmuliu", "divi", "ddivi", "ddiviu", "exit",
+];
+
+///The pseudo-instructions the parser expands for RISC-V.
+pub const PSEUDO_INSTRUCTIONS_RISCV: [&str; 32] = [
+    "nop", "li", "la", "lla", "call", "tail", "mv", "not", "neg", "negw", "sext.w", "seqz", "snez",
+    "sltz", "sgtz", "beqz", "bnez", "blez", "bgez", "bltz", "bgtz", "bgt", "ble", "bgtu", "bleu",
+    "j", "jr", "ret", "fmv.s", "fabs.s", "fneg.s", "exit",
+];
+
 pub const UNSUPPORTED_INSTRUCTIONS_MIPS: [&str; 408] = [
     "abs.d",
     "abs.ps",
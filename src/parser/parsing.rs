@@ -1,10 +1,19 @@
 use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::parser::expressions::{constant_value, is_symbol_char, substitute_constants};
+use crate::parser::layout::{
+    data_base_address, is_placement_directive, TextPlacement, BSS_DIRECTIVES,
+};
+use crate::parser::macro_parsing::{
+    add_macro_error, count_leading_labels, expand_macro, invocation_columns, is_macro_end,
+    is_macro_start, macro_body_location, read_macro_definition, read_macro_invocation, MacroMap,
+};
 use crate::parser::parser_structs_and_enums::ErrorType::*;
 use crate::parser::parser_structs_and_enums::TokenType::{Directive, Label, Operator, Unknown};
 use crate::parser::parser_structs_and_enums::{
-    Data, Error, Instruction, LabelInstance, MonacoLineInfo, Token, FP_REGISTERS, GP_REGISTERS,
-    RISCV_FP_REGISTERS, RISCV_GP_REGISTERS, SUPPORTED_INSTRUCTIONS_MIPS,
+    Data, Error, FixIt, Instruction, LabelInstance, MonacoLineInfo, Token, FP_REGISTERS,
+    GP_REGISTERS, RISCV_FP_REGISTERS, RISCV_GP_REGISTERS, SUPPORTED_INSTRUCTIONS_MIPS,
 };
+use crate::parser::project::{included_file_not_found, is_include};
 use levenshtein::levenshtein;
 use std::collections::HashMap;
 
@@ -13,119 +22,116 @@
 ///Takes the initial string of the program given by the editor and turns it into a vector of Line,
 /// a struct that holds tokens and the original line number.
 pub fn tokenize_program(program: String) -> Vec<MonacoLineInfo> {
-    let mut monaco_line_info_vec: Vec<MonacoLineInfo> = Vec::new();
+    let mut monaco_line_info_vec: Vec<MonacoLineInfo> = program
+        .lines()
+        .enumerate()
+        .map(|(i, line_of_program)| tokenize_line(i, line_of_program))
+        .collect();
+
+    //creates an empty monaco line if there is nothing in Monaco.
+    if monaco_line_info_vec.is_empty() {
+        monaco_line_info_vec.push(MonacoLineInfo {
+            mouse_hover_string: "".to_string(),
+            updated_monaco_string: "".to_string(),
+            tokens: vec![],
+            line_number: 0,
+            error_start_end_columns: vec![],
+            errors: vec![],
+            warnings: vec![],
+            source: None,
+        })
+    }
 
+    monaco_line_info_vec
+}
+
+///Splits a single line of the program into tokens. Every line is tokenized on its own, so a line that has not changed
+/// since the program was last tokenized has the same tokens.
+pub fn tokenize_line(line_number: usize, line_of_program: &str) -> MonacoLineInfo {
     let mut token: Token = Token {
         token_name: "".to_string(),
         start_end_columns: (0, 0),
         token_type: Unknown,
     };
 
-    for (i, line_of_program) in program.lines().enumerate() {
-        let mut line = MonacoLineInfo {
-            mouse_hover_string: "".to_string(),
-            updated_monaco_string: line_of_program.to_string(),
-            tokens: vec![],
-            line_number: i,
-            error_start_end_columns: vec![],
-            errors: vec![],
-        };
+    let mut line = MonacoLineInfo {
+        mouse_hover_string: "".to_string(),
+        updated_monaco_string: line_of_program.to_string(),
+        tokens: vec![],
+        line_number,
+        error_start_end_columns: vec![],
+        errors: vec![],
+        warnings: vec![],
+        source: None,
+    };
 
-        let mut is_string = false;
-        let mut check_escape = false;
-        //iterates through every character on each line of the program
-        for (j, char) in line_of_program.chars().enumerate() {
-            token.start_end_columns.1 = j + 1;
-            if char == '#' {
-                if j > 0 {
-                    token.start_end_columns.1 -= 1;
-                }
-                break;
-            };
-            //is string is a flag to handle strings and read them in as a single token
-            if is_string {
-                if char == '\\' {
-                    check_escape = true;
-                    continue;
-                }
-                if check_escape {
-                    match char {
-                        'n' => {
-                            token.token_name.push('\n');
-                        }
-                        't' => {
-                            token.token_name.push('\t');
-                        }
-                        '\\' => {
-                            token.token_name.push('\\');
-                        }
-                        '\"' => {
-                            token.token_name.push('\"');
-                        }
-                        '\'' => {
-                            token.token_name.push('\'');
-                        }
-                        _ => {
-                            token.token_name.push('\\');
-                            token.token_name.push(char);
-                        }
-                    }
-                    check_escape = false;
-                } else if char == '\"' {
-                    token.token_name.push('\"');
-                    is_string = false;
+    //the quote that opened the string or char being read, if any
+    let mut quote: Option<char> = None;
+    let mut check_escape = false;
+    //iterates through every character on each line of the program
+    for (j, char) in line_of_program.chars().enumerate() {
+        token.start_end_columns.1 = j + 1;
+        if char == '#' && quote.is_none() {
+            if j > 0 {
+                token.start_end_columns.1 -= 1;
+            }
+            break;
+        };
+        //strings and chars are read in as part of a single token. Escape sequences are kept as written and decoded by
+        // the assembler, but an escaped quote does not end the string or char
+        if let Some(opening_quote) = quote {
+            token.token_name.push(char);
+            if check_escape {
+                check_escape = false;
+            } else if char == '\\' {
+                check_escape = true;
+            } else if char == opening_quote {
+                quote = None;
+            }
+        } else if char == '\''
+            && !token
+                .token_name
+                .ends_with(|previous: char| previous.is_alphanumeric() || previous == '_')
+        {
+            //an apostrophe within a word, such as in a label, does not start a char
+            if token.token_name.is_empty() {
+                token.start_end_columns.0 = j;
+            }
+            token.token_name.push(char);
+            quote = Some(char);
+        } else if char == '\"' {
+            if !token.token_name.is_empty() {
+                line.tokens.push(token.clone());
+            }
+            token.token_name = '\"'.to_string();
+            token.start_end_columns.0 = j;
+            quote = Some(char);
+        } else if char != ' ' {
+            if token.token_name.is_empty() {
+                token.start_end_columns.0 = j;
+            }
+            token.token_name.push(char);
+            if char == ',' {
+                if token.token_name.len() == 1 {
+                    let length = line.tokens.len();
+                    line.tokens[length - 1].token_name.push(char);
                 } else {
-                    token.token_name.push(char);
-                }
-            } else if char == '\"' {
-                if !token.token_name.is_empty() {
+                    token.start_end_columns.1 -= 1;
                     line.tokens.push(token.clone());
                 }
-                token.token_name = '\"'.to_string();
-                token.start_end_columns.0 = j;
-                is_string = true;
-            } else if char != ' ' {
-                if token.token_name.is_empty() {
-                    token.start_end_columns.0 = j;
-                }
-                token.token_name.push(char);
-                if char == ',' {
-                    if token.token_name.len() == 1 {
-                        let length = line.tokens.len();
-                        line.tokens[length - 1].token_name.push(char);
-                    } else {
-                        token.start_end_columns.1 -= 1;
-                        line.tokens.push(token.clone());
-                    }
-                    token.token_name = "".to_string();
-                }
-            } else if !token.token_name.is_empty() {
-                token.start_end_columns.1 -= 1;
-                line.tokens.push(token.clone());
                 token.token_name = "".to_string();
             }
-        }
-        if !token.token_name.is_empty() {
+        } else if !token.token_name.is_empty() {
+            token.start_end_columns.1 -= 1;
             line.tokens.push(token.clone());
             token.token_name = "".to_string();
         }
-
-        monaco_line_info_vec.push(line);
     }
-
-    //creates an empty monaco line if there is nothing in Monaco.
-    if monaco_line_info_vec.is_empty() {
-        monaco_line_info_vec.push(MonacoLineInfo {
-            mouse_hover_string: "".to_string(),
-            updated_monaco_string: "".to_string(),
-            tokens: vec![],
-            line_number: 0,
-            error_start_end_columns: vec![],
-            errors: vec![],
-        })
+    if !token.token_name.is_empty() {
+        line.tokens.push(token);
     }
 
-    monaco_line_info_vec
+    line
 }
 
 ///Checks the name of every token on a line and makes sure that labels, directives, and operators do not end in commas while
@@ -150,6 +156,7 @@ pub fn remove_commas(line: &mut MonacoLineInfo) {
                 token_causing_error: line.tokens[i].clone().token_name,
                 start_end_columns: line.tokens[i].start_end_columns,
                 message: "".to_string(),
+                fix_its: vec![unnecessary_comma_fix_it(line.tokens[i].start_end_columns)],
             });
         }
         i += 1;
@@ -161,6 +168,7 @@ pub fn remove_commas(line: &mut MonacoLineInfo) {
             token_causing_error: line.tokens[i].clone().token_name,
             start_end_columns: line.tokens[i].start_end_columns,
             message: "".to_string(),
+            fix_its: vec![unnecessary_comma_fix_it(line.tokens[i].start_end_columns)],
         });
     }
     i += 1;
@@ -173,6 +181,7 @@ pub fn remove_commas(line: &mut MonacoLineInfo) {
                 token_causing_error: line.tokens[i].clone().token_name,
                 start_end_columns: line.tokens[i].start_end_columns,
                 message: "".to_string(),
+                fix_its: vec![missing_comma_fix_it(line.tokens[i].start_end_columns)],
             });
         }
         i += 1;
@@ -185,6 +194,7 @@ pub fn remove_commas(line: &mut MonacoLineInfo) {
             token_causing_error: line.tokens[i].clone().token_name,
             start_end_columns: line.tokens[i].start_end_columns,
             message: "".to_string(),
+            fix_its: vec![unnecessary_comma_fix_it(line.tokens[i].start_end_columns)],
         });
     }
 }
@@ -192,154 +202,516 @@ pub fn remove_commas(line: &mut MonacoLineInfo) {
 ///This function takes the vector of lines created by tokenize program and turns them into instructions
 ///assigning labels, operators, operands, and line numbers and data assigning labels, data types, and values
 pub fn separate_data_and_text(lines: &mut [MonacoLineInfo]) -> (Vec<Instruction>, Vec<Data>) {
-    let mut instruction_list: Vec<Instruction> = Vec::new();
-    let mut data_list: Vec<Data> = Vec::new();
-    let mut labels: Vec<LabelInstance> = Vec::new();
+    let (instructions, data, _) = separate_sections(lines);
+    (instructions, data)
+}
+
+///Separates the lines in the same way as separate_data_and_text, and also returns the directives that change where
+/// instructions are placed in the text section.
+pub fn separate_sections(
+    lines: &mut [MonacoLineInfo],
+) -> (Vec<Instruction>, Vec<Data>, Vec<TextPlacement>) {
+    let mut separated = SeparatedLines {
+        is_text: true,
+        data_section: ".data".to_string(),
+        ..Default::default()
+    };
+    let mut macros: MacroMap = HashMap::new();
+    let mut expansion_count = 0;
 
-    let mut is_text = true;
     let mut i = 0;
     while i < lines.len() {
         if lines[i].tokens.is_empty() {
             i += 1;
             continue;
         }
-        //check commas and remove them
-        remove_commas(&mut lines[i]);
 
-        //handle transitions between .data and .text
-        if lines[i].tokens[0].token_name.to_lowercase() == ".text"
-            || lines[i].tokens[0].token_name.to_lowercase() == ".data"
-        {
-            lines[i].tokens[0].token_type = Directive;
-            while let Some(last) = labels.pop() {
-                lines[last.token_line].errors.push(Error {
-                    error_name: LabelAssignmentError,
-                    token_causing_error: last.token.token_name,
-                    start_end_columns: last.token.start_end_columns,
-                    message: "".to_string(),
-                });
-            }
-            is_text = lines[i].tokens[0].token_name.to_lowercase() == ".text";
-            i += 1;
+        //macro definitions are read and then skipped over
+        if is_macro_start(&lines[i].tokens) {
+            i = read_macro_definition(lines, i, &mut macros);
             continue;
         }
-        let mut j = 0;
-        //add all labels to the label stack
-        while lines[i].tokens.len() > j && lines[i].tokens[j].token_name.ends_with(':') {
-            lines[i].tokens[j].token_name.pop();
-            lines[i].tokens[j].start_end_columns.1 -= 1;
-            lines[i].tokens[j].token_type = Label;
-            labels.push(LabelInstance {
-                token: lines[i].tokens[j].clone(),
-                token_line: i,
+        if is_macro_end(&lines[i].tokens) {
+            lines[i].errors.push(Error {
+                error_name: ImproperlyFormattedMacro,
+                token_causing_error: lines[i].tokens[0].token_name.clone(),
+                start_end_columns: lines[i].tokens[0].start_end_columns,
+                message: "The end of a macro was found without a matching .macro.\n".to_string(),
+                fix_its: Vec::new(),
             });
-            j += 1;
+            i += 1;
+            continue;
         }
-        //make sure there are still tokens remaining on the line
-        if lines[i].tokens.len() == j {
+
+        //macro invocations are replaced by the lines of the macro body
+        if let Some((name, arguments)) = read_macro_invocation(&lines[i].tokens, &macros) {
+            read_labels(&mut lines[i], i, &mut separated.labels);
+            match expand_macro(&name, &arguments, &macros, &mut expansion_count, 0) {
+                Ok(expansion) => {
+                    for mut expanded_line in expansion {
+                        let macro_line = expanded_line.line_number;
+                        separated.separate_line(&mut expanded_line, i, Some(macro_line));
+                        for error in expanded_line.errors {
+                            add_macro_error(lines, i, macro_line, error);
+                        }
+                        let note = format!("\n\nExpanded on {}.", lines[i].location());
+                        if !lines[macro_line].mouse_hover_string.contains(&note) {
+                            lines[macro_line].mouse_hover_string.push_str(&note);
+                        }
+                    }
+                }
+                Err(mut error) => {
+                    error.start_end_columns = invocation_columns(&lines[i]);
+                    lines[i].errors.push(error);
+                }
+            }
             i += 1;
             continue;
         }
+
+        let mut line = std::mem::take(&mut lines[i]);
+        separated.separate_line(&mut line, i, None);
+        lines[i] = line;
+        i += 1;
+    }
+
+    separated.finish(lines)
+}
+
+///The instructions, data, and unassigned labels found so far while separating the lines of a program.
+#[derive(Default)]
+struct SeparatedLines {
+    instructions: Vec<Instruction>,
+    data: Vec<Data>,
+    labels: Vec<LabelInstance>,
+    is_text: bool,
+    ///Labels which were not followed by anything committed to memory before the section changed.
+    unassigned_labels: Vec<LabelInstance>,
+    ///The values of the symbolic constants defined so far, by name.
+    constants: HashMap<String, String>,
+    ///The name of the data section being read, such as .data, .rodata, or .bss.
+    data_section: String,
+    ///The placement directives found in the text section, with the index of the instruction that follows each.
+    text_placements: Vec<(usize, TextPlacement)>,
+}
+
+impl SeparatedLines {
+    ///Reads a single line into an instruction or data. line_number is the line the resulting instruction or data is
+    /// attributed to, which differs from the line's own line_number when the line comes from the body of a macro.
+    fn separate_line(
+        &mut self,
+        line: &mut MonacoLineInfo,
+        line_number: usize,
+        macro_line_number: Option<usize>,
+    ) {
+        if line.tokens.is_empty() {
+            return;
+        }
+        //includes are replaced when a project is tokenized, so any that remain do not name a file in the project
+        if is_include(&line.tokens) {
+            line.tokens[0].token_type = Directive;
+            line.errors.push(included_file_not_found(line));
+            return;
+        }
+        //symbolic constants are recorded and then substituted into every line that follows
+        if is_constant_definition(&line.tokens) {
+            self.define_constant(line);
+            return;
+        }
+        //symbols declared global or external are only used when linking, so the line is otherwise ignored
+        if is_symbol_declaration(&line.tokens) {
+            declare_symbols(line);
+            return;
+        }
+        let label_count = count_leading_labels(&line.tokens);
+        for token in &mut line.tokens[label_count..] {
+            token.token_name = substitute_constants(&token.token_name, &self.constants);
+        }
+        merge_expression_tokens(line);
+        //check commas and remove them
+        remove_commas(line);
+
+        //handle transitions between .data, .text, and the other sections
+        if let Some(section) = read_section(&line.tokens) {
+            line.tokens[0].token_type = Directive;
+            while let Some(last) = self.labels.pop() {
+                self.unassigned_labels.push(last);
+            }
+            self.is_text = section == ".text" || section.starts_with(".text.");
+            if !self.is_text {
+                self.data_section = section;
+            }
+
+            //.text and .data may be given the address the section continues from
+            if line.tokens[0].token_name.to_lowercase() != ".section" && line.tokens.len() > 1 {
+                line.tokens[1].token_type = Directive;
+                self.place(line, 0, line_number, macro_line_number);
+            }
+            return;
+        }
+        //add all labels to the label stack
+        let mut j = read_labels(line, line.line_number, &mut self.labels);
+        //make sure there are still tokens remaining on the line
+        if line.tokens.len() == j {
+            return;
+        }
+        //directives such as .align move the next instruction or data, so any labels are left for it
+        if is_placement_directive(&line.tokens[j].token_name) {
+            self.place(line, j, line_number, macro_line_number);
+            return;
+        }
         //this chunk handles how we read .text
-        if is_text {
+        if self.is_text {
             let mut instruction = Instruction {
-                line_number: i,
+                line_number,
+                macro_line_number,
                 ..Default::default()
             };
             //push all incomplete labels to reference this instruction
-            while let Some(label) = labels.pop() {
+            while let Some(label) = self.labels.pop() {
                 instruction.labels.push(label);
             }
             //the next token is the operator
-            lines[i].tokens[j].token_type = Operator;
-            instruction.operator = lines[i].tokens[j].clone();
+            line.tokens[j].token_type = Operator;
+            instruction.operator = line.tokens[j].clone();
             j += 1;
             //any remaining tokens are the operands
-            while lines[i].tokens.len() > j {
-                instruction.operands.push(lines[i].tokens[j].clone());
+            while line.tokens.len() > j {
+                instruction.operands.push(line.tokens[j].clone());
                 j += 1;
             }
-            instruction_list.push(instruction);
+            self.instructions.push(instruction);
 
             //this chunk handles how we read .data
         } else {
             let mut data = Data {
-                line_number: i,
+                line_number,
+                macro_line_number,
                 ..Default::default()
             };
-            if labels.is_empty() {
+            if self.labels.is_empty() {
                 //if labels is empty, generate an error but assume the first token on the line was supposed to be the label
                 //Remove commas should have already generated a MissingComma error so we replace that with a more accurate error.
-                lines[i].errors.pop();
-                let start_end = lines[i].tokens[j].start_end_columns;
-                let token_name = lines[i].tokens[j].token_name.clone();
-                lines[i].errors.push(Error {
+                line.errors.pop();
+                let start_end = line.tokens[j].start_end_columns;
+                let token_name = line.tokens[j].token_name.clone();
+                line.errors.push(Error {
                     error_name: ImproperlyFormattedLabel,
                     token_causing_error: token_name,
                     start_end_columns: start_end,
                     message: "".to_string(),
+                    fix_its: Vec::new(),
                 });
-                data.label = lines[i].tokens[j].clone();
+                data.label = line.tokens[j].clone();
                 j += 1;
             } else {
-                data.label = labels.pop().unwrap().token;
+                data.label = self.labels.pop().unwrap().token;
             }
             //continue to the next line if there are no other tokens on the line.
-            if lines[i].tokens.len() == j {
-                i += 1;
-                continue;
+            if line.tokens.len() == j {
+                return;
             }
             //any other labels in the stack are pushed to the data_list to be initialized as empty words in the assemble data function
-            for label in &labels {
-                data_list.push(Data {
+            for label in &self.labels {
+                self.data.push(Data {
                     line_number: label.token_line,
                     label: label.token.clone(),
                     ..Default::default()
                 });
             }
-            labels = Vec::new();
+            self.labels = Vec::new();
             //the next token should be the data type directive
-            data.data_type = lines[i].tokens[j].clone();
+            data.data_type = line.tokens[j].clone();
+            if self.data_section == ".bss"
+                && !BSS_DIRECTIVES.contains(&&*data.data_type.token_name.to_lowercase())
+            {
+                data.errors.push(Error {
+                    error_name: InitializedBssData,
+                    token_causing_error: data.data_type.token_name.clone(),
+                    start_end_columns: data.data_type.start_end_columns,
+                    message: "The .bss section only reserves space that starts as zero, so data with initial values must be placed in .data instead. Use .space to reserve space in .bss.\n".to_string(),
+                    fix_its: Vec::new(),
+                });
+            }
             j += 1;
             //any remaining tokens should be data entries
-            while lines[i].tokens.len() > j {
-                data.data_entries.push(lines[i].tokens[j].clone());
+            while line.tokens.len() > j {
+                data.data_entries.push(line.tokens[j].clone());
                 j += 1;
             }
-            data_list.push(data);
+            self.data.push(data);
         }
-        i += 1;
     }
 
-    //handle any unfinished labels
-    if is_text {
-        //unfinished labels in text cause an error
-        for label in labels {
+    ///Records the constant defined by a .eqv, .equ, or .set line. The value may refer to constants defined before it.
+    fn define_constant(&mut self, line: &mut MonacoLineInfo) {
+        line.tokens[0].token_type = Directive;
+        //.set is also used for assembler options such as `.set noreorder`, which SWIM ignores
+        if line.tokens.len() == 2 && line.tokens[0].token_name.to_lowercase() == ".set" {
+            return;
+        }
+
+        let name = line
+            .tokens
+            .get(1)
+            .map(|token| token.token_name.trim_end_matches(',').to_string())
+            .unwrap_or_default();
+        let value = line.tokens[2.min(line.tokens.len())..]
+            .iter()
+            .map(|token| token.token_name.clone())
+            .collect::<Vec<String>>()
+            .join(" ");
+
+        let is_valid_name = name.starts_with(is_symbol_char)
+            && name
+                .chars()
+                .all(|char| is_symbol_char(char) || char.is_ascii_digit());
+        if !is_valid_name || value.is_empty() {
+            let last = line.tokens.last().unwrap().start_end_columns.1;
+            line.errors.push(Error {
+                error_name: ImproperlyFormattedConstant,
+                token_causing_error: line.tokens[0].token_name.clone(),
+                start_end_columns: (line.tokens[0].start_end_columns.0, last),
+                message: format!(
+                    "Expected a name and a value, as in `{} NAME, value`.\n",
+                    line.tokens[0].token_name
+                ),
+                fix_its: Vec::new(),
+            });
+            return;
+        }
+
+        let value = constant_value(&substitute_constants(&value, &self.constants));
+        line.tokens[1].token_type = Label;
+        line.mouse_hover_string = format!("`{name}` is a constant with the value `{value}`.");
+        self.constants.insert(name, value);
+    }
+
+    ///Records a placement directive, such as `.org 0x800` or the address given to `.text`, which starts at token j of the
+    /// line. In the text section it applies to the next instruction, while in data it is kept with the data so it can
+    /// be applied as the data is assembled.
+    fn place(
+        &mut self,
+        line: &mut MonacoLineInfo,
+        j: usize,
+        line_number: usize,
+        macro_line_number: Option<usize>,
+    ) {
+        line.tokens[j].token_type = Directive;
+        if self.is_text {
+            let placement = TextPlacement {
+                directive: line.tokens[j].clone(),
+                argument: line.tokens.get(j + 1).cloned(),
+                line_number,
+                ..Default::default()
+            };
+            self.text_placements
+                .push((self.instructions.len(), placement));
+        } else {
+            self.data.push(Data {
+                line_number,
+                macro_line_number,
+                data_type: line.tokens[j].clone(),
+                data_entries: line.tokens[j + 1..].to_vec(),
+                ..Default::default()
+            });
+        }
+    }
+
+    ///Handles any labels left unassigned and returns the instructions, data, and text placements.
+    fn finish(
+        mut self,
+        lines: &mut [MonacoLineInfo],
+    ) -> (Vec<Instruction>, Vec<Data>, Vec<TextPlacement>) {
+        for label in self.unassigned_labels {
             lines[label.token_line].errors.push(Error {
                 error_name: LabelAssignmentError,
                 token_causing_error: label.token.token_name,
                 start_end_columns: label.token.start_end_columns,
                 message: "".to_string(),
+                fix_its: Vec::new(),
             });
         }
+
+        //handle any unfinished labels
+        if self.is_text {
+            //unfinished labels in text cause an error
+            for label in self.labels {
+                lines[label.token_line].errors.push(Error {
+                    error_name: LabelAssignmentError,
+                    token_causing_error: label.token.token_name,
+                    start_end_columns: label.token.start_end_columns,
+                    message: "".to_string(),
+                    fix_its: Vec::new(),
+                });
+            }
+        } else {
+            //unfinished labels in data are pushed to the list to be initialized as empty words in the assembler
+            for label in self.labels {
+                self.data.push(Data {
+                    line_number: label.token_line,
+                    label: label.token,
+                    ..Default::default()
+                });
+            }
+        }
+
+        //placements apply to the line of the instruction that follows them, since pseudo-instructions have not been expanded
+        let instructions = &self.instructions;
+        let text_placements = self
+            .text_placements
+            .into_iter()
+            .filter_map(|(index, mut placement)| {
+                placement.first_instruction_line = instructions.get(index)?.line_number;
+                Some(placement)
+            })
+            .collect();
+
+        (self.instructions, self.data, text_placements)
+    }
+}
+
+///Returns the name of the section a line switches to, for lines such as `.text`, `.data 0x2000`, `.bss`, or
+/// `.section .rodata`.
+fn read_section(tokens: &[Token]) -> Option<String> {
+    let directive = tokens[0].token_name.to_lowercase();
+    match &*directive {
+        ".text" | ".data" | ".bss" => Some(directive),
+        ".section" => Some(
+            tokens
+                .get(1)?
+                .token_name
+                .trim_end_matches(',')
+                .to_lowercase(),
+        ),
+        _ => None,
+    }
+}
+
+///Returns true if the line declares symbols with .globl, .global, or .extern.
+fn is_symbol_declaration(tokens: &[Token]) -> bool {
+    matches!(
+        &*tokens[0].token_name.to_lowercase(),
+        ".globl" | ".global" | ".extern"
+    )
+}
+
+///Marks the symbols of a .globl, .global, or .extern line and describes them in the mouse hover.
+fn declare_symbols(line: &mut MonacoLineInfo) {
+    line.tokens[0].token_type = Directive;
+    let is_extern = line.tokens[0].token_name.to_lowercase() == ".extern";
+    //.extern may be followed by the size of the symbol, which is not a symbol itself
+    let symbol_count = if is_extern { 1 } else { line.tokens.len() - 1 };
+    let mut symbols = Vec::new();
+    for token in line.tokens.iter_mut().skip(1).take(symbol_count) {
+        token.token_type = Label;
+        symbols.push(format!("`{}`", token.token_name.trim_end_matches(',')));
+    }
+    if symbols.is_empty() {
+        return;
+    }
+
+    line.mouse_hover_string = if is_extern {
+        format!("{} is defined in another file.", symbols.join(", "))
     } else {
-        //unfinished labels in data are pushed to the list to be initialized as empty words in the assembler
-        for label in labels {
-            data_list.push(Data {
-                line_number: label.token_line,
-                label: label.token,
-                ..Default::default()
-            });
+        format!(
+            "{} can be referred to from other files.",
+            symbols.join(", ")
+        )
+    };
+}
+
+///Returns the symbols named on the .globl, .global, and .extern lines of a program along with the line each is named on.
+/// The last value is true for symbols declared with .extern, which are defined in another file.
+pub fn declared_symbols(lines: &[MonacoLineInfo]) -> Vec<(Token, usize, bool)> {
+    let mut symbols = Vec::new();
+    for line in lines {
+        if line.tokens.is_empty() || !is_symbol_declaration(&line.tokens) {
+            continue;
+        }
+        let is_extern = line.tokens[0].token_name.to_lowercase() == ".extern";
+        let symbol_count = if is_extern { 1 } else { line.tokens.len() - 1 };
+        for token in line.tokens.iter().skip(1).take(symbol_count) {
+            let mut symbol = token.clone();
+            symbol.token_name = symbol.token_name.trim_end_matches(',').to_string();
+            symbols.push((symbol, line.line_number, is_extern));
         }
     }
+    symbols
+}
+
+///Returns true if the line defines a symbolic constant with .eqv, .equ, or .set.
+fn is_constant_definition(tokens: &[Token]) -> bool {
+    matches!(
+        &*tokens[0].token_name.to_lowercase(),
+        ".eqv" | ".equ" | ".set"
+    )
+}
 
-    (instruction_list, data_list)
+///Joins operand tokens which were split apart by spaces inside of a constant expression, such as `label + 8` or
+/// `(end - start) / 4`, back into a single token. Tokens are joined when there is no comma between them and one ends
+/// or the next begins with an operator.
+pub fn merge_expression_tokens(line: &mut MonacoLineInfo) {
+    let is_string = |token: &Token| token.token_name.starts_with('\"');
+    let mut k = count_leading_labels(&line.tokens) + 1;
+    while k + 1 < line.tokens.len() {
+        let current = &line.tokens[k];
+        let next = &line.tokens[k + 1];
+        let joins = !current.token_name.ends_with(',')
+            && !is_string(current)
+            && !is_string(next)
+            && (current
+                .token_name
+                .ends_with(|char| "+-*/%<>&|^~(".contains(char))
+                || next
+                    .token_name
+                    .starts_with(|char| "+-*/%<>&|^)".contains(char)));
+        if joins {
+            let next = line.tokens.remove(k + 1);
+            line.tokens[k].token_name.push(' ');
+            line.tokens[k].token_name.push_str(&next.token_name);
+            line.tokens[k].start_end_columns.1 = next.start_end_columns.1;
+        } else {
+            k += 1;
+        }
+    }
+}
+
+///Marks the labels at the start of a line and adds them to the label stack. Returns the number of labels read.
+fn read_labels(
+    line: &mut MonacoLineInfo,
+    token_line: usize,
+    labels: &mut Vec<LabelInstance>,
+) -> usize {
+    let mut j = 0;
+    while line.tokens.len() > j && line.tokens[j].token_name.ends_with(':') {
+        line.tokens[j].token_name.pop();
+        line.tokens[j].start_end_columns.1 -= 1;
+        line.tokens[j].token_type = Label;
+        labels.push(LabelInstance {
+            token: line.tokens[j].clone(),
+            token_line,
+        });
+        j += 1;
+    }
+    j
 }
 
 ///Create_label_map builds a hashmap of addresses for labels in memory
 pub fn create_label_map(
-    instruction_list: &mut Vec<Instruction>,
+    instruction_list: &mut [Instruction],
     data_list: &mut [Data],
+) -> HashMap<String, usize> {
+    let data_base = data_base_address(instruction_list);
+    create_label_map_at(instruction_list, data_list, data_base)
+}
+
+///Builds the hashmap of addresses for labels in memory when the data starts at data_base rather than directly after the
+/// last instruction, as it does for the files of a linked program.
+pub fn create_label_map_at(
+    instruction_list: &mut [Instruction],
+    data_list: &mut [Data],
+    data_base: usize,
 ) -> HashMap<String, usize> {
     let mut labels: HashMap<String, usize> = HashMap::new();
     //iterate through every instance of instruction and try to add the label to the map
@@ -352,6 +724,7 @@ pub fn create_label_map(
                     token_causing_error: label.token.token_name,
                     start_end_columns: label.token.start_end_columns,
                     message: "".to_string(),
+                    fix_its: Vec::new(),
                 });
                 //otherwise, it is inserted
             } else {
@@ -363,13 +736,11 @@ pub fn create_label_map(
         }
     }
 
-    let last_instruction = instruction_list.last();
-    let offset_for_instructions: u32 = match last_instruction {
-        Some(last_instruction) => ((last_instruction.instruction_number + 1) << 2) as u32,
-        None => 0_u32,
-    };
-
     for data in data_list.iter_mut() {
+        //directives such as .align do not have a label
+        if data.label.token_name.is_empty() {
+            continue;
+        }
         //if the given label name is already used, an error is generated
         if labels.contains_key(&*data.label.clone().token_name) {
             data.errors.push(Error {
@@ -377,13 +748,11 @@ pub fn create_label_map(
                 token_causing_error: data.label.token_name.to_string(),
                 start_end_columns: data.label.start_end_columns,
                 message: "".to_string(),
+                fix_its: Vec::new(),
             });
             //otherwise, it is inserted
         } else {
-            labels.insert(
-                data.label.token_name.clone(),
-                data.data_number + offset_for_instructions as usize,
-            );
+            labels.insert(data.label.token_name.clone(), data.data_number + data_base);
         }
     }
 
@@ -448,6 +817,7 @@ pub fn suggest_error_corrections(
                                 " A valid, similar register is: {}.\n",
                                 &closest.1
                             ));
+                            error.fix_its.push(replace_token_fix_it(error, &closest.1));
                         } else {
                             message.push('\n');
                         }
@@ -483,6 +853,7 @@ pub fn suggest_error_corrections(
                                 " A valid, similar register is: {}.\n",
                                 &closest.1
                             ));
+                            error.fix_its.push(replace_token_fix_it(error, &closest.1));
                         } else {
                             message.push('\n');
                         }
@@ -517,6 +888,7 @@ pub fn suggest_error_corrections(
                                 " A valid, similar instruction is: {}.\n",
                                 &closest.1
                             ));
+                            error.fix_its.push(replace_token_fix_it(error, &closest.1));
                         } else {
                             message.push('\n');
                         }
@@ -532,7 +904,7 @@ pub fn suggest_error_corrections(
                     }
                     MissingComma => {
                         error.message =
-                            "Operand expected to end with a comma but it does not.\n".to_string()
+                            "Operand expected to end with a comma but it does not.\n".to_string();
                     }
                     ImmediateOutOfBounds => {
                         error.message = "Immediate value given cannot be expressed in the available number of bits.\n".to_string();
@@ -564,6 +936,7 @@ pub fn suggest_error_corrections(
                                 " A valid, similar label is: {}.\n",
                                 &closest.1
                             ));
+                            error.fix_its.push(replace_token_fix_it(error, &closest.1));
                         }
                         error.message.push_str(&message);
                     }
@@ -600,6 +973,7 @@ pub fn suggest_error_corrections(
                                 " A valid, similar label is: {}.\n",
                                 &closest.1
                             ));
+                            error.fix_its.push(replace_token_fix_it(error, &closest.1));
                         } else {
                             message.push('\n');
                         }
@@ -610,9 +984,16 @@ pub fn suggest_error_corrections(
                             "The destination address for JALR cannot be the zero register\n"
                                 .to_string();
                     }
+                    InvalidRelocation => {
+                        error.message = "The relocation operator is not available for this architecture. %pcrel_lo must be given the label of an auipc that uses %pcrel_hi, or directly follow that auipc.\n".to_string();
+                    }
                     UnnecessaryComma => {
-                        error.message = "The given token should not end with a comma\n".to_string()
+                        error.message = "The given token should not end with a comma\n".to_string();
                     }
+                    //the message depends on the character that could not be read, so it is written when the error is found
+                    ImproperlyFormattedChar | NonASCIIChar => {}
+                    //the message depends on the direction the label is looked for, so it is written when the error is found
+                    LocalLabelNotFound => {}
                     _ => {
                         error.message = format!("{:?} PARSER/ASSEMBLER ERROR. THIS ERROR TYPE SHOULD NOT BE ABLE TO BE ASSOCIATED WITH TEXT.\n", error.error_name);
                     }
@@ -626,6 +1007,14 @@ pub fn suggest_error_corrections(
                     monaco_line_info[instruction.labels.clone().last().unwrap().token_line]
                         .errors
                         .push(error.clone());
+                } else if let Some(macro_line_number) = instruction.macro_line_number {
+                    //errors in a macro expansion are added to both the invocation and the macro body
+                    add_macro_error(
+                        monaco_line_info,
+                        instruction.line_number,
+                        macro_line_number,
+                        error.clone(),
+                    );
                 } else {
                     //add error to monaco_line_info
                     monaco_line_info[instruction.line_number]
@@ -635,9 +1024,10 @@ pub fn suggest_error_corrections(
 
                 //push a message about the error to the string for console
                 console_out_string.push_str(&format!(
-                    "{} on line {} with token \"{}\"\n{}\n",
+                    "{} on {}{} with token \"{}\"\n{}\n",
                     &error.error_name.to_string(),
-                    &(instruction.line_number + 1).to_string(),
+                    monaco_line_info[instruction.line_number].location(),
+                    macro_location(monaco_line_info, instruction.macro_line_number),
                     &error.token_causing_error,
                     &error.message
                 ));
@@ -665,8 +1055,8 @@ pub fn suggest_error_corrections(
             match &error.error_name {
                 UnrecognizedDataType => {
                     let recognized_data_types = [
-                        ".ascii", ".asciiz", ".byte", ".double", ".float", ".half", ".space",
-                        ".word",
+                        ".ascii", ".asciiz", ".byte", ".double", ".dword", ".float", ".half",
+                        ".quad", ".space", ".word",
                     ];
 
                     let given_string = &datum.data_type.token_name.to_string();
@@ -687,6 +1077,7 @@ pub fn suggest_error_corrections(
                             " A valid, similar data type is: {}.\n",
                             &closest.1
                         ));
+                        error.fix_its.push(replace_token_fix_it(error, &closest.1));
                     } else {
                         message.push('\n');
                     }
@@ -703,17 +1094,9 @@ pub fn suggest_error_corrections(
                 ImmediateOutOfBounds => {
                     error.message = "Immediate value given cannot be expressed in the available number of bits.\n".to_string();
                 }
-                ImproperlyFormattedASCII => {
-                    error.message =
-                        "Token recognized as ASCII does not start and or end with double quotes (\").\n"
-                            .to_string();
-                }
-                ImproperlyFormattedChar => {
-                    error.message = "Token recognized as a char does not end with ' or is larger than a single char.\n".to_string();
-                }
                 MissingComma => {
                     error.message =
-                        "Operand expected to end with a comma but it does not.\n".to_string()
+                        "Operand expected to end with a comma but it does not.\n".to_string();
                 }
                 NonIntImmediate => {
                     error.message =
@@ -727,15 +1110,37 @@ pub fn suggest_error_corrections(
                     error.message =
                         "Label assignment recognized but does not end in a colon.\n".to_string();
                 }
-                NonASCIIChar => {
-                    error.message = "The given char cannot be represented in ASCII\n".to_string();
-                }
-                NonASCIIString => {
-                    error.message =
-                        "One or multiple characters within the given string cannot be represented in ASCII.\n".to_string();
-                }
                 UnnecessaryComma => {
-                    error.message = "The given token should not end with a comma\n".to_string()
+                    error.message = "The given token should not end with a comma\n".to_string();
+                }
+                //the message depends on the reason the placement failed, so it is written when the error is found
+                InvalidPlacement | InitializedBssData => {}
+                //the message depends on the character that could not be read, so it is written when the error is found
+                ImproperlyFormattedASCII
+                | ImproperlyFormattedChar
+                | NonASCIIChar
+                | NonASCIIString => {}
+                //the message depends on the direction the label is looked for, so it is written when the error is found
+                LocalLabelNotFound => {}
+                LabelNotFound => {
+                    let given_string = &error.token_causing_error;
+                    let mut closest: (usize, String) = (usize::MAX, "".to_string());
+
+                    for label in labels {
+                        if levenshtein(given_string, label.0) < closest.0 {
+                            closest.0 = levenshtein(given_string, label.0);
+                            closest.1 = label.0.to_string();
+                        }
+                    }
+                    let mut message = "Given label is not found in the project.".to_string();
+                    //only suggest a different label if the ratio of chars needed to change vs chars in string is under a threshold
+                    if (closest.0 as f32 / given_string.len() as f32) < levenshtein_threshold {
+                        message.push_str(&format!(" A valid, similar label is: {}.\n", &closest.1));
+                        error.fix_its.push(replace_token_fix_it(error, &closest.1));
+                    } else {
+                        message.push('\n');
+                    }
+                    error.message = message;
                 }
                 _ => {
                     error.message = format!("{:?} PARSER/ASSEMBLER ERROR. THIS ERROR TYPE SHOULD NOT BE ABLE TO BE ASSOCIATED WITH DATA.\n", error.error_name);
@@ -743,14 +1148,23 @@ pub fn suggest_error_corrections(
             }
 
             //add error to monaco_line_info
-            monaco_line_info[datum.line_number]
-                .errors
-                .push(error.clone());
+            match datum.macro_line_number {
+                Some(macro_line_number) => add_macro_error(
+                    monaco_line_info,
+                    datum.line_number,
+                    macro_line_number,
+                    error.clone(),
+                ),
+                None => monaco_line_info[datum.line_number]
+                    .errors
+                    .push(error.clone()),
+            }
 
             console_out_string.push_str(&format!(
-                "{} on line {} with token \"{}\"\n{}\n",
+                "{} on {}{} with token \"{}\"\n{}\n",
                 &error.error_name.to_string(),
-                &(datum.line_number + 1).to_string(),
+                monaco_line_info[datum.line_number].location(),
+                macro_location(monaco_line_info, datum.macro_line_number),
                 error.token_causing_error,
                 error.message.clone()
             ));
@@ -763,3 +1177,42 @@ pub fn suggest_error_corrections(
 
     console_out_string
 }
+
+///Creates the fix-it which replaces the token causing an error with a valid, similar token.
+fn replace_token_fix_it(error: &Error, replacement: &str) -> FixIt {
+    FixIt {
+        title: format!("Replace with `{replacement}`"),
+        start_end_columns: error.start_end_columns,
+        replacement: replacement.to_string(),
+    }
+}
+
+///Creates the fix-it which adds the comma missing after a token.
+fn missing_comma_fix_it(token_columns: (usize, usize)) -> FixIt {
+    FixIt {
+        title: "Add missing comma".to_string(),
+        start_end_columns: (token_columns.1, token_columns.1),
+        replacement: ",".to_string(),
+    }
+}
+
+///Creates the fix-it which removes the comma following a token. The columns of the token do not include the comma, which
+/// is written right after it.
+fn unnecessary_comma_fix_it(token_columns: (usize, usize)) -> FixIt {
+    FixIt {
+        title: "Remove comma".to_string(),
+        start_end_columns: (token_columns.1, token_columns.1 + 1),
+        replacement: "".to_string(),
+    }
+}
+
+///Describes where in a macro body an error was found, for errors reported to the console.
+fn macro_location(monaco_line_info: &[MonacoLineInfo], macro_line_number: Option<usize>) -> String {
+    match macro_line_number {
+        Some(macro_line_number) => format!(
+            " ({})",
+            macro_body_location(&monaco_line_info[macro_line_number])
+        ),
+        None => "".to_string(),
+    }
+}
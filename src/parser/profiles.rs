@@ -0,0 +1,314 @@
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::parser::parser_structs_and_enums::ErrorType::ProfileViolation;
+use crate::parser::parser_structs_and_enums::{
+    Error, Instruction, ProgramInfo, TokenType, PSEUDO_INSTRUCTIONS_MIPS, PSEUDO_INSTRUCTIONS_RISCV,
+};
+use std::collections::BTreeMap;
+
+///The instructions of the core MIPS subset, which are the integer instructions most courses start with.
+const MIPS_CORE_INSTRUCTIONS: [&str; 21] = [
+    "add", "addu", "sub", "addi", "addiu", "and", "andi", "or", "ori", "slt", "sltu", "sll", "lui",
+    "lw", "sw", "beq", "bne", "j", "jal", "jr", "syscall",
+];
+
+///The instructions of RV32I, the base integer instruction set of 32-bit RISC-V.
+const RV32I_INSTRUCTIONS: [&str; 39] = [
+    "lui", "auipc", "jal", "jalr", "beq", "bne", "blt", "bge", "bltu", "bgeu", "lb", "lh", "lw",
+    "lbu", "lhu", "sb", "sh", "sw", "addi", "slti", "sltiu", "xori", "ori", "andi", "slli", "srli",
+    "srai", "add", "sub", "sll", "slt", "sltu", "xor", "srl", "sra", "or", "and", "ecall",
+    "ebreak",
+];
+
+///The integer multiply and divide instructions of both architectures, including the pseudo-instructions that expand to
+/// them.
+const MULTIPLY_DIVIDE_INSTRUCTIONS: [&str; 27] = [
+    "mul", "mult", "multu", "div", "divu", "dmul", "dmulu", "dmult", "dmultu", "ddiv", "ddivu",
+    "muli", "dmuli", "dmuliu", "divi", "ddivi", "ddiviu", "mulh", "mulhsu", "mulhu", "rem", "remu",
+    "mulw", "divw", "divuw", "remw", "remuw",
+];
+
+///A named set of restrictions on the instructions a program may use, such as a course allowing only the core
+/// instructions it teaches. Each instruction that breaks a rule of the profile is an error.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Profile {
+    pub name: String,
+    ///Explains the restrictions of the profile, which is added to the message of every violation of it.
+    pub description: String,
+    ///The architecture the profile is written for, or None if it can be used with either.
+    pub arch: Option<AvailableDatapaths>,
+    pub allow_pseudo_instructions: bool,
+    ///If given, the only instructions that may be used. Pseudo-instructions may only expand to these.
+    pub allowed_instructions: Option<Vec<String>>,
+    ///Instructions that may not be used, either as written or in the expansion of a pseudo-instruction.
+    pub forbidden_instructions: Vec<String>,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Profile {
+            name: "Unrestricted".to_string(),
+            description: "Every supported instruction may be used.".to_string(),
+            arch: None,
+            allow_pseudo_instructions: true,
+            allowed_instructions: None,
+            forbidden_instructions: Vec::new(),
+        }
+    }
+}
+
+impl Profile {
+    ///Returns true if the profile can be used with the given architecture.
+    pub fn applies_to(&self, arch: AvailableDatapaths) -> bool {
+        self.arch.map_or(true, |profile_arch| profile_arch == arch)
+    }
+
+    ///Returns true if the profile does not restrict any instruction.
+    pub fn is_unrestricted(&self) -> bool {
+        self.allow_pseudo_instructions
+            && self.allowed_instructions.is_none()
+            && self.forbidden_instructions.is_empty()
+    }
+
+    ///Returns true if the given hardware instruction may be used under the profile.
+    fn allows(&self, instruction: &str) -> bool {
+        !self
+            .forbidden_instructions
+            .iter()
+            .any(|name| name == instruction)
+            && self.allowed_instructions.as_ref().map_or(true, |allowed| {
+                allowed.iter().any(|name| name == instruction)
+            })
+    }
+}
+
+///Returns the profiles that are always available, starting with the unrestricted profile.
+pub fn built_in_profiles() -> Vec<Profile> {
+    let names = |names: &[&str]| names.iter().map(|name| name.to_string()).collect();
+    vec![
+        Profile::default(),
+        Profile {
+            name: "No pseudo-instructions".to_string(),
+            description: "Only hardware instructions may be used, so each pseudo-instruction must be written as the instructions it expands to.".to_string(),
+            allow_pseudo_instructions: false,
+            ..Default::default()
+        },
+        Profile {
+            name: "No multiply or divide".to_string(),
+            description: "Integer multiply and divide instructions may not be used, so products and quotients must be computed with shifts and adds.".to_string(),
+            forbidden_instructions: names(&MULTIPLY_DIVIDE_INSTRUCTIONS),
+            ..Default::default()
+        },
+        Profile {
+            name: "MIPS core subset".to_string(),
+            description: format!(
+                "Only the core MIPS instructions may be used: {}.",
+                MIPS_CORE_INSTRUCTIONS.join(", ")
+            ),
+            arch: Some(AvailableDatapaths::MIPS),
+            allow_pseudo_instructions: false,
+            allowed_instructions: Some(names(&MIPS_CORE_INSTRUCTIONS)),
+            ..Default::default()
+        },
+        Profile {
+            name: "RV32I only".to_string(),
+            description: "Only the RV32I base integer instructions may be used. Pseudo-instructions are allowed as long as they expand to RV32I instructions.".to_string(),
+            arch: Some(AvailableDatapaths::RISCV),
+            allowed_instructions: Some(names(&RV32I_INSTRUCTIONS)),
+            ..Default::default()
+        },
+    ]
+}
+
+///Reads a profile from a config file. Each line of the file is a `key = value` setting, and anything after a `#` is a
+/// comment. The settings are:
+///
+///- `name`, which is required.
+///- `description`, which is shown with every violation of the profile.
+///- `architecture`, either `mips` or `riscv`, if the profile is only for one architecture.
+///- `pseudo_instructions`, `yes` or `no`, which is `yes` unless given.
+///- `allow`, the only instructions that may be used, separated by commas or spaces.
+///- `forbid`, instructions that may not be used, separated by commas or spaces.
+///
+///`allow` and `forbid` can be given on more than one line to add to their lists. Returns a message describing the first
+/// problem with the file if it cannot be read.
+pub fn parse_profile(text: &str) -> Result<Profile, String> {
+    let mut profile = Profile {
+        name: String::new(),
+        description: String::new(),
+        ..Default::default()
+    };
+    for (line_number, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(format!(
+                "Line {} of the profile is not a `key = value` setting.",
+                line_number + 1
+            ));
+        };
+        let value = value.trim();
+        let instructions = value
+            .split(|char: char| char == ',' || char.is_whitespace())
+            .filter(|name| !name.is_empty())
+            .map(str::to_lowercase);
+        match &*key.trim().to_lowercase() {
+            "name" => profile.name = value.to_string(),
+            "description" => profile.description = value.to_string(),
+            "architecture" => {
+                profile.arch = match &*value.to_lowercase() {
+                    "mips" => Some(AvailableDatapaths::MIPS),
+                    "riscv" | "risc-v" => Some(AvailableDatapaths::RISCV),
+                    _ => {
+                        return Err(format!(
+                            "Line {} of the profile gives the architecture `{value}`, which is neither mips nor riscv.",
+                            line_number + 1
+                        ))
+                    }
+                }
+            }
+            "pseudo_instructions" => {
+                profile.allow_pseudo_instructions = match &*value.to_lowercase() {
+                    "yes" | "true" => true,
+                    "no" | "false" => false,
+                    _ => {
+                        return Err(format!(
+                            "Line {} of the profile gives pseudo_instructions the value `{value}`, which is neither yes nor no.",
+                            line_number + 1
+                        ))
+                    }
+                }
+            }
+            "allow" => profile
+                .allowed_instructions
+                .get_or_insert_with(Vec::new)
+                .extend(instructions),
+            "forbid" => profile.forbidden_instructions.extend(instructions),
+            key => {
+                return Err(format!(
+                    "Line {} of the profile has the unknown setting `{key}`. The settings are name, description, architecture, pseudo_instructions, allow, and forbid.",
+                    line_number + 1
+                ))
+            }
+        }
+    }
+
+    if profile.name.is_empty() {
+        return Err(
+            "The profile does not have a name. Give it one with a `name = ...` line.".to_string(),
+        );
+    }
+    Ok(profile)
+}
+
+///Checks an assembled program against a profile. Every instruction that breaks a rule of the profile is given a
+/// ProfileViolation error on the line it is written on, which is also described in the console output.
+///
+///Each instruction is checked as written, then each instruction it expands to is checked against the instructions the
+/// profile allows. The syscall added to the end of a MIPS program is not written in it, so it is not checked.
+pub fn enforce_profile(
+    program_info: &mut ProgramInfo,
+    profile: &Profile,
+    arch: AvailableDatapaths,
+) {
+    if profile.is_unrestricted() || !profile.applies_to(arch) {
+        return;
+    }
+    let pseudo_instructions: &[&str] = match arch {
+        AvailableDatapaths::MIPS => &PSEUDO_INSTRUCTIONS_MIPS,
+        AvailableDatapaths::RISCV => &PSEUDO_INSTRUCTIONS_RISCV,
+    };
+
+    //the instructions written on each line, along with what they expand to. Instructions from a macro are written on
+    // the line of the macro body
+    let mut written: BTreeMap<usize, Vec<&Instruction>> = BTreeMap::new();
+    for instruction in &program_info.instructions {
+        if instruction.is_added_syscall() {
+            continue;
+        }
+        written
+            .entry(
+                instruction
+                    .macro_line_number
+                    .unwrap_or(instruction.line_number),
+            )
+            .or_default()
+            .push(instruction);
+    }
+
+    let mut errors: Vec<(usize, Error)> = Vec::new();
+    for (line_number, expansion) in written {
+        let Some(line) = program_info.monaco_line_info.get(line_number) else {
+            continue;
+        };
+        let operator = line
+            .tokens
+            .iter()
+            .find(|token| token.token_type == TokenType::Operator)
+            .unwrap_or(&expansion[0].operator);
+        let name = operator.token_name.to_lowercase();
+        //an instruction is a pseudo-instruction if it is not among the instructions it is assembled to
+        let is_pseudo = pseudo_instructions.contains(&name.as_str())
+            || !expansion
+                .iter()
+                .any(|instruction| instruction.operator.token_name.to_lowercase() == name);
+
+        let message = if is_pseudo && !profile.allow_pseudo_instructions {
+            Some(format!(
+                "`{name}` is a pseudo-instruction, which the {} profile does not allow.",
+                profile.name
+            ))
+        } else if profile.forbidden_instructions.contains(&name) {
+            Some(format!(
+                "`{name}` is not allowed by the {} profile.",
+                profile.name
+            ))
+        } else if !is_pseudo {
+            (!profile.allows(&name))
+                .then(|| format!("`{name}` is not allowed by the {} profile.", profile.name))
+        } else {
+            expansion
+                .iter()
+                .map(|instruction| instruction.operator.token_name.to_lowercase())
+                .find(|expanded| !profile.allows(expanded))
+                .map(|expanded| {
+                    format!(
+                        "`{name}` is expanded to `{expanded}`, which is not allowed by the {} profile.",
+                        profile.name
+                    )
+                })
+        };
+
+        if let Some(message) = message {
+            errors.push((
+                line_number,
+                Error {
+                    error_name: ProfileViolation,
+                    token_causing_error: operator.token_name.clone(),
+                    start_end_columns: operator.start_end_columns,
+                    message: format!("{message} {}\n", profile.description),
+                    fix_its: Vec::new(),
+                },
+            ));
+        }
+    }
+
+    let mut console_out = String::new();
+    for (line_number, error) in errors {
+        let line = &mut program_info.monaco_line_info[line_number];
+        console_out.push_str(&format!(
+            "{} on {} with token \"{}\"\n{}\n",
+            error.error_name,
+            line.location(),
+            error.token_causing_error,
+            error.message
+        ));
+        line.errors.push(error);
+    }
+    if !console_out.is_empty() {
+        program_info
+            .console_out_post_assembly
+            .push_str(&console_out);
+    }
+}
@@ -0,0 +1,199 @@
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::parser::linker::link_files;
+use crate::parser::parser_assembler_main::assemble_lines;
+use crate::parser::parser_structs_and_enums::ErrorType::{IncludedFileNotFound, RecursiveInclude};
+use crate::parser::parser_structs_and_enums::{
+    Error, MonacoLineInfo, ProgramInfo, SourceLocation, Token,
+};
+use crate::parser::parsing::{declared_symbols, tokenize_program};
+use std::collections::HashMap;
+
+///A single file of a project made up of several assembly files.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ProjectFile {
+    pub name: String,
+    pub contents: String,
+    ///Read-only files, such as a provided library of I/O helpers, cannot be edited.
+    pub read_only: bool,
+}
+
+///Assembles a project of several files. The first file is the main file of the project. The other files are assembled
+/// where they are included with `.include "file"`, except for files that are never included and export symbols with
+/// `.globl`, which are assembled on their own and linked after the main file. Every line of the returned program info
+/// records the file and line it was written on.
+pub fn parse_project(
+    files: &[ProjectFile],
+    arch: AvailableDatapaths,
+) -> (ProgramInfo, Vec<u32>, HashMap<String, usize>) {
+    parse_tokenized_project(files, &tokenize_files(files), arch)
+}
+
+///Assembles a project whose files have already been tokenized, with the lines of each file given in the same order as
+/// the files. This lets a file be tokenized again only where it has changed.
+pub fn parse_tokenized_project(
+    files: &[ProjectFile],
+    file_lines: &[Vec<MonacoLineInfo>],
+    arch: AvailableDatapaths,
+) -> (ProgramInfo, Vec<u32>, HashMap<String, usize>) {
+    let linked_files = linked_files(files, file_lines);
+    if linked_files.len() > 1 {
+        return link_files(files, file_lines, &linked_files, arch);
+    }
+    if files.is_empty() {
+        return assemble_lines(tokenize_program(String::new()), arch);
+    }
+    assemble_lines(file_with_includes(files, file_lines, 0), arch)
+}
+
+///Tokenizes each file of a project on its own, without following its includes.
+pub fn tokenize_files(files: &[ProjectFile]) -> Vec<Vec<MonacoLineInfo>> {
+    files
+        .iter()
+        .map(|file| tokenize_program(file.contents.clone()))
+        .collect()
+}
+
+///Finds the files of a project that are assembled as separate units and linked together: the main file, followed by
+/// every file that no file includes and that exports symbols with `.globl` or `.global`. The lines of each file are
+/// given by tokenize_files.
+pub fn linked_files(files: &[ProjectFile], file_lines: &[Vec<MonacoLineInfo>]) -> Vec<usize> {
+    let included: Vec<String> = file_lines
+        .iter()
+        .flatten()
+        .filter_map(|line| read_included_file_name(&line.tokens))
+        .collect();
+
+    (0..files.len())
+        .filter(|file| {
+            *file == 0
+                || (!included.contains(&files[*file].name)
+                    && declared_symbols(&file_lines[*file])
+                        .iter()
+                        .any(|(_, _, is_extern)| !is_extern))
+        })
+        .collect()
+}
+
+///Tokenizes the main file of a project, following every `.include` line with the lines of the included file.
+pub fn tokenize_project(files: &[ProjectFile]) -> Vec<MonacoLineInfo> {
+    if files.is_empty() {
+        return tokenize_program(String::new());
+    }
+
+    file_with_includes(files, &tokenize_files(files), 0)
+}
+
+///Joins the lines of a single file of a project, as given by tokenize_files, with the lines of the files it includes
+/// following every `.include` line.
+pub fn file_with_includes(
+    files: &[ProjectFile],
+    file_lines: &[Vec<MonacoLineInfo>],
+    file: usize,
+) -> Vec<MonacoLineInfo> {
+    let mut lines: Vec<MonacoLineInfo> = Vec::new();
+    include_file(files, file_lines, file, &mut Vec::new(), &mut lines);
+    lines
+}
+
+///Adds the lines of a file to the lines of the project, recursively adding the lines of the files it includes.
+/// include_stack holds the files which are currently being included so that a file cannot include itself.
+fn include_file(
+    files: &[ProjectFile],
+    file_lines: &[Vec<MonacoLineInfo>],
+    file: usize,
+    include_stack: &mut Vec<usize>,
+    lines: &mut Vec<MonacoLineInfo>,
+) {
+    include_stack.push(file);
+    for mut line in file_lines[file].iter().cloned() {
+        line.source = Some(SourceLocation {
+            file: files[file].name.clone(),
+            line: line.line_number,
+        });
+        line.line_number = lines.len();
+
+        //includes of files outside of the project are left on the line to be reported when the line is separated
+        let included = read_included_file_name(&line.tokens).and_then(|name| {
+            files
+                .iter()
+                .position(|project_file| project_file.name == name)
+        });
+        let Some(included) = included else {
+            lines.push(line);
+            continue;
+        };
+
+        if include_stack.contains(&included) {
+            line.errors.push(Error {
+                error_name: RecursiveInclude,
+                token_causing_error: files[included].name.clone(),
+                start_end_columns: line.tokens[1].start_end_columns,
+                message: format!(
+                    "`{}` is already being included, so including it again would never end.\n",
+                    files[included].name
+                ),
+                fix_its: Vec::new(),
+            });
+            line.tokens.clear();
+            lines.push(line);
+            continue;
+        }
+
+        line.mouse_hover_string = format!(
+            "Includes the contents of `{}` in place of this line.",
+            files[included].name
+        );
+        line.tokens.clear();
+        lines.push(line);
+        include_file(files, file_lines, included, include_stack, lines);
+    }
+    include_stack.pop();
+}
+
+///Returns true if the line is an `.include` directive.
+pub fn is_include(tokens: &[Token]) -> bool {
+    tokens
+        .first()
+        .is_some_and(|token| token.token_name.to_lowercase() == ".include")
+}
+
+///Reads the name of the file from a line such as `.include "io.asm"`. Returns None if the line is not an include or the
+/// name is not given as a single string.
+pub fn read_included_file_name(tokens: &[Token]) -> Option<String> {
+    if !is_include(tokens) || tokens.len() != 2 {
+        return None;
+    }
+    let name = tokens[1]
+        .token_name
+        .strip_prefix('\"')?
+        .strip_suffix('\"')?;
+    if name.is_empty() {
+        return None;
+    }
+    Some(name.to_string())
+}
+
+///Creates the error for an `.include` line that does not name a file in the project.
+pub fn included_file_not_found(line: &MonacoLineInfo) -> Error {
+    let (token_causing_error, start_end_columns, message) =
+        match read_included_file_name(&line.tokens) {
+            Some(name) => (
+                name.clone(),
+                line.tokens[1].start_end_columns,
+                format!("`{name}` is not a file in the project.\n"),
+            ),
+            None => (
+                line.tokens[0].token_name.clone(),
+                line.tokens[0].start_end_columns,
+                ".include must be followed by the name of a file in quotes, such as .include \"io.asm\".\n"
+                    .to_string(),
+            ),
+        };
+    Error {
+        error_name: IncludedFileNotFound,
+        token_causing_error,
+        start_end_columns,
+        message,
+        fix_its: Vec::new(),
+    }
+}
@@ -5,6 +5,7 @@
 use crate::parser::parser_structs_and_enums::{
     Data, Error, Instruction, MonacoLineInfo, PseudoDescription, Token,
 };
+use crate::parser::parsing::declared_symbols;
 use std::collections::HashMap;
 
 ///Iterates through the instruction list and translates pseudo-instructions into real instructions.
@@ -27,6 +28,10 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
     for data in data {
         list_of_labels.push(data.label.token_name.clone());
     }
+    //symbols declared by .extern or .globl may be defined in another file of the project
+    for (symbol, _, _) in declared_symbols(monaco_line_info) {
+        list_of_labels.push(symbol.token_name);
+    }
 
     //vec_of_added_instructions is needed because of rust ownership rules. It will not let us
     //insert into instruction_list while instruction_list is being iterated over.
@@ -50,6 +55,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                         token_causing_error: "".to_string(),
                         start_end_columns: instruction.operator.start_end_columns,
                         message: "".to_string(),
+                        fix_its: Vec::new(),
                     });
                     continue;
                 }
@@ -82,6 +88,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                         token_causing_error: "".to_string(),
                         start_end_columns: instruction.operator.start_end_columns,
                         message: "".to_string(),
+                        fix_its: Vec::new(),
                     });
                     continue;
                 }
@@ -119,6 +126,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                         token_causing_error: "".to_string(),
                         start_end_columns: instruction.operator.start_end_columns,
                         message: "".to_string(),
+                        fix_its: Vec::new(),
                     });
                     continue;
                 }
@@ -159,6 +167,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: Vec::new(),
+                    macro_line_number: instruction.macro_line_number,
                 };
                 vec_of_added_instructions.push(extra_instruction_2.clone());
 
@@ -194,6 +203,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                         token_causing_error: "".to_string(),
                         start_end_columns: instruction.operator.start_end_columns,
                         message: "".to_string(),
+                        fix_its: Vec::new(),
                     });
                     continue;
                 }
@@ -235,6 +245,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                         token_causing_error: "".to_string(),
                         start_end_columns: instruction.operator.start_end_columns,
                         message: "".to_string(),
+                        fix_its: Vec::new(),
                     });
                     continue;
                 }
@@ -271,6 +282,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: Vec::new(),
+                    macro_line_number: instruction.macro_line_number,
                 };
                 vec_of_added_instructions.push(extra_instruction_2.clone());
 
@@ -307,6 +319,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                         token_causing_error: "".to_string(),
                         start_end_columns: instruction.operator.start_end_columns,
                         message: "".to_string(),
+                        fix_its: Vec::new(),
                     });
                     continue;
                 }
@@ -343,6 +356,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: Vec::new(),
+                    macro_line_number: instruction.macro_line_number,
                 };
                 vec_of_added_instructions.push(extra_instruction_2.clone());
 
@@ -375,6 +389,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                         token_causing_error: "".to_string(),
                         start_end_columns: instruction.operator.start_end_columns,
                         message: "".to_string(),
+                        fix_its: Vec::new(),
                     });
                     continue;
                 }
@@ -401,6 +416,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                         token_causing_error: "".to_string(),
                         start_end_columns: instruction.operator.start_end_columns,
                         message: "".to_string(),
+                        fix_its: Vec::new(),
                     });
                     continue;
                 }
@@ -431,6 +447,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                         token_causing_error: "".to_string(),
                         start_end_columns: instruction.operator.start_end_columns,
                         message: "".to_string(),
+                        fix_its: Vec::new(),
                     });
                     continue;
                 }
@@ -464,6 +481,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: Vec::new(),
+                    macro_line_number: instruction.macro_line_number,
                 };
                 vec_of_added_instructions.push(extra_instruction_2.clone());
 
@@ -500,6 +518,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                         token_causing_error: "".to_string(),
                         start_end_columns: instruction.operator.start_end_columns,
                         message: "".to_string(),
+                        fix_its: Vec::new(),
                     });
                     continue;
                 }
@@ -533,6 +552,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: Vec::new(),
+                    macro_line_number: instruction.macro_line_number,
                 };
                 vec_of_added_instructions.push(extra_instruction_2.clone());
 
@@ -550,7 +570,63 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                     instruction,
                 ]);
             }
-            "lw" | "sw" => {
+            "la" => {
+                //la $regA, label is translated to:
+                //lui $regA, %hi(label)
+                //daddiu $regA, $regA, %lo(label)
+                let info = PseudoDescription {
+                    name: "la".to_string(),
+                    syntax: "la rt, target".to_string(),
+                    translation_lines: vec![
+                        "lui rt, %hi(target)".to_string(),
+                        "daddiu rt, rt, %lo(target)".to_string(),
+                    ],
+                };
+                monaco_line_info[instruction.line_number].mouse_hover_string = info.to_string();
+
+                //make sure there are enough operands
+                if !check_operands(instruction, 2) {
+                    continue;
+                }
+
+                let target = instruction.operands[1].token_name.clone();
+                let mut extra_instruction = Instruction {
+                    operator: Token {
+                        token_name: "lui".to_string(),
+                        start_end_columns: (0, 0),
+                        token_type: Operator,
+                    },
+                    operands: vec![
+                        instruction.operands[0].clone(),
+                        Token {
+                            token_name: format!("%hi({target})"),
+                            start_end_columns: instruction.operands[1].start_end_columns,
+                            token_type: Default::default(),
+                        },
+                    ],
+                    binary: 0,
+                    instruction_number: instruction.instruction_number,
+                    line_number: instruction.line_number,
+                    errors: vec![],
+                    labels: instruction.labels.clone(),
+                    macro_line_number: instruction.macro_line_number,
+                };
+                vec_of_added_instructions.push(extra_instruction.clone());
+
+                //adjust la for the added instruction
+                instruction.operator.token_name = "daddiu".to_string();
+                instruction.operator.start_end_columns = (0, 0);
+                instruction
+                    .operands
+                    .insert(1, instruction.operands[0].clone());
+                instruction.operands[2].token_name = format!("%lo({target})");
+                instruction.instruction_number += 1;
+                instruction.labels = Vec::new(); //if the pseudo-instruction had a label, remove it so it's only on the first expanded instruction
+
+                monaco_line_info[instruction.line_number]
+                    .update_pseudo_string(vec![&mut extra_instruction, instruction]);
+            }
+            "lw" | "sw" | "lwc1" | "swc1" => {
                 //lw $regA, label is translated to:
                 //lui $at, label
                 //lw $regA, lower16($at)
@@ -565,34 +641,22 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                             token_causing_error: "".to_string(),
                             start_end_columns: instruction.operator.start_end_columns,
                             message: "".to_string(),
+                            fix_its: Vec::new(),
                         });
                         continue;
                     }
 
-                    //create mouse hover message dependent on lw / sw
-                    if instruction.operator.token_name == "lw" {
-                        let info = PseudoDescription {
-                            name: "lw rt target".to_string(),
-                            syntax: "lw rt target".to_string(),
-                            translation_lines: vec![
-                                "lui $at, upper48".to_string(),
-                                "lw rt, lower16($at)".to_string(),
-                            ],
-                        };
-                        monaco_line_info[instruction.line_number].mouse_hover_string =
-                            info.to_string();
-                    } else {
-                        let info = PseudoDescription {
-                            name: "sw rt target".to_string(),
-                            syntax: "sw rt target".to_string(),
-                            translation_lines: vec![
-                                "lui $at, upper48".to_string(),
-                                "sw rt, lower16($at)".to_string(),
-                            ],
-                        };
-                        monaco_line_info[instruction.line_number].mouse_hover_string =
-                            info.to_string();
-                    }
+                    //create mouse hover message dependent on the load / store
+                    let operator = instruction.operator.token_name.to_lowercase();
+                    let info = PseudoDescription {
+                        name: format!("{operator} rt target"),
+                        syntax: format!("{operator} rt target"),
+                        translation_lines: vec![
+                            "lui $at, upper48".to_string(),
+                            format!("{operator} rt, lower16($at)"),
+                        ],
+                    };
+                    monaco_line_info[instruction.line_number].mouse_hover_string = info.to_string();
                     monaco_line_info[instruction.line_number]
                         .mouse_hover_string
                         .push_str(
@@ -618,6 +682,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                         line_number: instruction.line_number,
                         errors: vec![],
                         labels: instruction.labels.clone(),
+                        macro_line_number: instruction.macro_line_number,
                     };
 
                     instruction.labels = Vec::new(); //if the pseudo-instruction had a label, remove it so it's only on the first expanded instruction
@@ -646,6 +711,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                         token_causing_error: "".to_string(),
                         start_end_columns: instruction.operator.start_end_columns,
                         message: "".to_string(),
+                        fix_its: Vec::new(),
                     });
                     continue;
                 }
@@ -673,6 +739,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: instruction.labels.clone(),
+                    macro_line_number: instruction.macro_line_number,
                 };
 
                 vec_of_added_instructions.push(extra_instruction.clone());
@@ -706,6 +773,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                         token_causing_error: "".to_string(),
                         start_end_columns: instruction.operator.start_end_columns,
                         message: "".to_string(),
+                        fix_its: Vec::new(),
                     });
                     continue;
                 }
@@ -733,6 +801,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: instruction.labels.clone(),
+                    macro_line_number: instruction.macro_line_number,
                 };
 
                 vec_of_added_instructions.push(extra_instruction.clone());
@@ -765,6 +834,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                         token_causing_error: "".to_string(),
                         start_end_columns: instruction.operator.start_end_columns,
                         message: "".to_string(),
+                        fix_its: Vec::new(),
                     });
                     continue;
                 }
@@ -793,6 +863,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: instruction.labels.clone(),
+                    macro_line_number: instruction.macro_line_number,
                 };
                 vec_of_added_instructions.push(extra_instruction.clone());
 
@@ -825,6 +896,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                         token_causing_error: "".to_string(),
                         start_end_columns: instruction.operator.start_end_columns,
                         message: "".to_string(),
+                        fix_its: Vec::new(),
                     });
                     continue;
                 }
@@ -852,6 +924,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: instruction.labels.clone(),
+                    macro_line_number: instruction.macro_line_number,
                 };
                 vec_of_added_instructions.push(extra_instruction.clone());
 
@@ -884,6 +957,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                         token_causing_error: "".to_string(),
                         start_end_columns: instruction.operator.start_end_columns,
                         message: "".to_string(),
+                        fix_its: Vec::new(),
                     });
                     continue;
                 }
@@ -911,6 +985,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: instruction.labels.clone(),
+                    macro_line_number: instruction.macro_line_number,
                 };
                 vec_of_added_instructions.push(extra_instruction.clone());
                 //adjust dmuli for the added instruction
@@ -942,6 +1017,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                         token_causing_error: "".to_string(),
                         start_end_columns: instruction.operator.start_end_columns,
                         message: "".to_string(),
+                        fix_its: Vec::new(),
                     });
                     continue;
                 }
@@ -969,6 +1045,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: instruction.labels.clone(),
+                    macro_line_number: instruction.macro_line_number,
                 };
                 vec_of_added_instructions.push(extra_instruction.clone());
                 //adjust dmuliu for the added instruction
@@ -1000,6 +1077,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                         token_causing_error: "".to_string(),
                         start_end_columns: instruction.operator.start_end_columns,
                         message: "".to_string(),
+                        fix_its: Vec::new(),
                     });
                     continue;
                 }
@@ -1027,6 +1105,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: instruction.labels.clone(),
+                    macro_line_number: instruction.macro_line_number,
                 };
                 vec_of_added_instructions.push(extra_instruction.clone());
 
@@ -1080,6 +1159,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: instruction.labels.clone(),
+                    macro_line_number: instruction.macro_line_number,
                 };
                 vec_of_added_instructions.push(extra_instruction.clone());
 
@@ -1137,6 +1217,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: instruction.labels.clone(),
+                    macro_line_number: instruction.macro_line_number,
                 };
                 vec_of_added_instructions.push(extra_instruction.clone());
 
@@ -1171,6 +1252,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: Vec::new(),
+                    macro_line_number: instruction.macro_line_number,
                 };
 
                 if !check_operands(instruction, 0) {
@@ -1234,6 +1316,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: Vec::new(),
+                    macro_line_number: instruction.macro_line_number,
                 };
 
                 match instruction.operands.len() {
@@ -1308,6 +1391,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                             line_number: instruction.line_number,
                             errors: vec![],
                             labels: Vec::new(),
+                            macro_line_number: instruction.macro_line_number,
                         };
 
                         // Adjust current print_int instruction to set argument register
@@ -1350,6 +1434,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                             token_causing_error: "".to_string(),
                             start_end_columns: instruction.operator.start_end_columns,
                             message: "".to_string(),
+                            fix_its: Vec::new(),
                         });
                         continue;
                     }
@@ -1375,6 +1460,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: Vec::new(),
+                    macro_line_number: instruction.macro_line_number,
                 };
 
                 if !check_operands(instruction, 0) {
@@ -1437,6 +1523,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: Vec::new(),
+                    macro_line_number: instruction.macro_line_number,
                 };
 
                 if !check_operands(instruction, 0) {
@@ -1525,6 +1612,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: Vec::new(),
+                    macro_line_number: instruction.macro_line_number,
                 };
 
                 // Set up syscall instruction
@@ -1540,6 +1628,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: Vec::new(),
+                    macro_line_number: instruction.macro_line_number,
                 };
 
                 // Adjust print_string for io syscall
@@ -1593,6 +1682,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: Vec::new(),
+                    macro_line_number: instruction.macro_line_number,
                 };
 
                 if !check_operands(instruction, 0) {
@@ -1655,6 +1745,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: Vec::new(),
+                    macro_line_number: instruction.macro_line_number,
                 };
 
                 if !check_operands(instruction, 0) {
@@ -1717,6 +1808,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: Vec::new(),
+                    macro_line_number: instruction.macro_line_number,
                 };
 
                 if !check_operands(instruction, 0) {
@@ -1805,6 +1897,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: Vec::new(),
+                    macro_line_number: instruction.macro_line_number,
                 };
 
                 // Set up syscall instruction
@@ -1820,6 +1913,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: Vec::new(),
+                    macro_line_number: instruction.macro_line_number,
                 };
 
                 // Adjust read_string for io syscall
@@ -1891,6 +1985,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                 line_number: dot_text_index,
                 errors: vec![],
                 labels: Vec::new(),
+                macro_line_number: None,
             });
         } else {
             let offset = monaco_line_info[0].get_tab_space_offset().0;
@@ -1911,6 +2006,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                 line_number: 0,
                 errors: vec![],
                 labels: Vec::new(),
+                macro_line_number: None,
             });
         }
     } else {
@@ -1936,6 +2032,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers(
                 line_number: last_instruction.line_number,
                 errors: vec![],
                 labels: Vec::new(),
+                macro_line_number: None,
             })
         }
     }
@@ -1960,6 +2057,10 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers_riscv(
     for data in data {
         list_of_labels.push(data.label.token_name.clone());
     }
+    //symbols declared by .extern or .globl may be defined in another file of the project
+    for (symbol, _, _) in declared_symbols(monaco_line_info) {
+        list_of_labels.push(symbol.token_name);
+    }
 
     //vec_of_added_instructions is needed because of rust ownership rules. It will not let us
     //insert into instruction_list while instruction_list is being iterated over.
@@ -2040,6 +2141,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers_riscv(
                             token_causing_error: instruction.operands[1].token_name.clone(),
                             start_end_columns: instruction.operands[1].start_end_columns,
                             message: "Invalid immediate value".to_string(),
+                            fix_its: Vec::new(),
                         });
                         continue;
                     }
@@ -2081,6 +2183,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers_riscv(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: Vec::new(),
+                    macro_line_number: instruction.macro_line_number,
                 };
                 vec_of_added_instructions.push(addi_instruction.clone());
 
@@ -2112,6 +2215,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers_riscv(
                             token_causing_error: instruction.operands[1].token_name.clone(),
                             start_end_columns: instruction.operands[1].start_end_columns,
                             message: "Invalid immediate value".to_string(),
+                            fix_its: Vec::new(),
                         });
                         continue;
                     }
@@ -2167,6 +2271,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers_riscv(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: Vec::new(),
+                    macro_line_number: instruction.macro_line_number,
                 };
                 vec_of_added_instructions.push(jalr_instruction.clone());
 
@@ -2198,6 +2303,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers_riscv(
                             token_causing_error: instruction.operands[1].token_name.clone(),
                             start_end_columns: instruction.operands[1].start_end_columns,
                             message: "Invalid immediate value".to_string(),
+                            fix_its: Vec::new(),
                         });
                         continue;
                     }
@@ -2253,6 +2359,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers_riscv(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: Vec::new(),
+                    macro_line_number: instruction.macro_line_number,
                 };
                 vec_of_added_instructions.push(jalr_instruction.clone());
 
@@ -3063,6 +3170,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers_riscv(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: Vec::new(),
+                    macro_line_number: instruction.macro_line_number,
                 };
 
                 if !check_operands(instruction, 0) {
@@ -3126,6 +3234,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers_riscv(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: Vec::new(),
+                    macro_line_number: instruction.macro_line_number,
                 };
 
                 match instruction.operands.len() {
@@ -3200,6 +3309,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers_riscv(
                             line_number: instruction.line_number,
                             errors: vec![],
                             labels: Vec::new(),
+                            macro_line_number: instruction.macro_line_number,
                         };
 
                         // Adjust current print_int instruction set argument register
@@ -3242,6 +3352,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers_riscv(
                             token_causing_error: "".to_string(),
                             start_end_columns: instruction.operator.start_end_columns,
                             message: "".to_string(),
+                            fix_its: Vec::new(),
                         });
                         continue;
                     }
@@ -3267,6 +3378,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers_riscv(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: Vec::new(),
+                    macro_line_number: instruction.macro_line_number,
                 };
 
                 if !check_operands(instruction, 0) {
@@ -3355,6 +3467,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers_riscv(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: Vec::new(),
+                    macro_line_number: instruction.macro_line_number,
                 };
 
                 // Set up syscall instruction
@@ -3370,6 +3483,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers_riscv(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: Vec::new(),
+                    macro_line_number: instruction.macro_line_number,
                 };
 
                 // Adjust print_string for io syscall
@@ -3423,6 +3537,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers_riscv(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: Vec::new(),
+                    macro_line_number: instruction.macro_line_number,
                 };
 
                 if !check_operands(instruction, 0) {
@@ -3485,6 +3600,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers_riscv(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: Vec::new(),
+                    macro_line_number: instruction.macro_line_number,
                 };
 
                 if !check_operands(instruction, 0) {
@@ -3573,6 +3689,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers_riscv(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: Vec::new(),
+                    macro_line_number: instruction.macro_line_number,
                 };
 
                 // Set up syscall instruction
@@ -3588,6 +3705,7 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers_riscv(
                     line_number: instruction.line_number,
                     errors: vec![],
                     labels: Vec::new(),
+                    macro_line_number: instruction.macro_line_number,
                 };
 
                 // Adjust read_string for io syscall
@@ -3621,6 +3739,137 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers_riscv(
                     &mut syscall_instruction,
                 ]);
             }
+            "la" | "lla" => {
+                // Set Pseudo Description
+                let operator = instruction.operator.token_name.to_lowercase();
+                let info = PseudoDescription {
+                    name: operator.clone(),
+                    syntax: format!("{operator} rd, symbol"),
+                    translation_lines: vec![
+                        "auipc rd, %pcrel_hi(symbol)".to_string(),
+                        "addi rd, rd, %pcrel_lo(symbol)".to_string(),
+                    ],
+                };
+                monaco_line_info[instruction.line_number].mouse_hover_string = info.to_string();
+
+                // Check operands
+                if !check_operands(instruction, 2) {
+                    continue;
+                }
+
+                let symbol = instruction.operands[1].token_name.clone();
+
+                // auipc instruction
+                instruction.operator.token_name = "auipc".to_string();
+                instruction.operator.start_end_columns = (0, 0);
+                instruction.operands[1].token_name = format!("%pcrel_hi({symbol})");
+
+                // addi instruction
+                let mut addi_instruction = Instruction {
+                    operator: Token {
+                        token_name: "addi".to_string(),
+                        start_end_columns: (0, 0),
+                        token_type: Default::default(),
+                    },
+                    operands: vec![
+                        instruction.operands[0].clone(),
+                        instruction.operands[0].clone(),
+                        Token {
+                            token_name: format!("%pcrel_lo({symbol})"),
+                            start_end_columns: instruction.operands[1].start_end_columns,
+                            token_type: Default::default(),
+                        },
+                    ],
+                    binary: 0,
+                    instruction_number: instruction.instruction_number + 1,
+                    line_number: instruction.line_number,
+                    errors: vec![],
+                    labels: Vec::new(),
+                    macro_line_number: instruction.macro_line_number,
+                };
+                vec_of_added_instructions.push(addi_instruction.clone());
+
+                monaco_line_info[instruction.line_number]
+                    .update_pseudo_string(vec![instruction, &mut addi_instruction]);
+            }
+            "lb" | "lh" | "lw" | "ld" | "lbu" | "lhu" | "lwu" | "sb" | "sh" | "sw" | "sd"
+            | "flw" | "fld" | "fsw" | "fsd"
+                if instruction.operands.len() > 1
+                    && list_of_labels.contains(&instruction.operands[1].token_name) =>
+            {
+                // Loads into a general purpose register use it to hold the address. Stores and
+                // floating point loads need a temporary register given as a third operand.
+                let operator = instruction.operator.token_name.to_lowercase();
+                let uses_destination = operator.starts_with('l');
+                let (syntax, address_register) = if uses_destination {
+                    (format!("{operator} rd, symbol"), "rd")
+                } else {
+                    (format!("{operator} rs, symbol, rt"), "rt")
+                };
+                let info = PseudoDescription {
+                    name: syntax.clone(),
+                    syntax,
+                    translation_lines: vec![
+                        format!("auipc {address_register}, %pcrel_hi(symbol)"),
+                        format!(
+                            "{operator} {}, %pcrel_lo(symbol)({address_register})",
+                            if uses_destination { "rd" } else { "rs" }
+                        ),
+                    ],
+                };
+                monaco_line_info[instruction.line_number].mouse_hover_string = info.to_string();
+
+                // Check operands
+                if !check_operands(instruction, if uses_destination { 2 } else { 3 }) {
+                    continue;
+                }
+
+                let symbol = instruction.operands[1].clone();
+                let address_register = if uses_destination {
+                    instruction.operands[0].clone()
+                } else {
+                    instruction.operands.remove(2)
+                };
+
+                // memory instruction
+                let mut memory_instruction = Instruction {
+                    operator: instruction.operator.clone(),
+                    operands: vec![
+                        instruction.operands[0].clone(),
+                        Token {
+                            token_name: format!(
+                                "%pcrel_lo({})({})",
+                                symbol.token_name, address_register.token_name
+                            ),
+                            start_end_columns: symbol.start_end_columns,
+                            token_type: Default::default(),
+                        },
+                    ],
+                    binary: 0,
+                    instruction_number: instruction.instruction_number + 1,
+                    line_number: instruction.line_number,
+                    errors: vec![],
+                    labels: Vec::new(),
+                    macro_line_number: instruction.macro_line_number,
+                };
+                memory_instruction.operator.start_end_columns = (0, 0);
+                vec_of_added_instructions.push(memory_instruction.clone());
+
+                // auipc instruction
+                instruction.operator.token_name = "auipc".to_string();
+                instruction.operator.start_end_columns = (0, 0);
+                instruction.operands = vec![
+                    address_register,
+                    Token {
+                        token_name: format!("%pcrel_hi({})", symbol.token_name),
+                        start_end_columns: symbol.start_end_columns,
+                        token_type: Default::default(),
+                    },
+                ];
+
+                monaco_line_info[instruction.line_number]
+                    .update_pseudo_string(vec![instruction, &mut memory_instruction]);
+            }
             _ => {}
         }
     }
@@ -3631,8 +3880,8 @@ pub fn expand_pseudo_instructions_and_assign_instruction_numbers_riscv(
     }
 }
 
-///the second part of completing pseudo-instructions. LW and SW with labels requires the address of the label to be known,
-/// the second part of this must occur after the label hashmap is completed.
+///the second part of completing pseudo-instructions. LW, SW, LWC1, and SWC1 with labels requires the address of the label
+/// to be known, the second part of this must occur after the label hashmap is completed.
 pub fn complete_lw_sw_pseudo_instructions(
     instructions: &mut [Instruction],
     labels: &HashMap<String, usize>,
@@ -3646,8 +3895,7 @@ pub fn complete_lw_sw_pseudo_instructions(
         if instructions[index].operator.token_name == "lui"
             && instructions[index].operands.len() > 1
             && labels.contains_key(&*instructions[index].operands[1].token_name)
-            && (instructions[index + 1].operator.token_name == "sw"
-                || instructions[index + 1].operator.token_name == "lw")
+            && ["lw", "sw", "lwc1", "swc1"].contains(&&*instructions[index + 1].operator.token_name)
         {
             //upper 16 bits are stored in $at using lui
             let address = *labels
@@ -3680,6 +3928,7 @@ fn check_operands(instruction: &mut Instruction, num_operands: usize) -> bool {
             token_causing_error: "".to_string(),
             start_end_columns: instruction.operator.start_end_columns,
             message: "".to_string(),
+            fix_its: Vec::new(),
         });
         return false;
     }
@@ -0,0 +1,140 @@
+use crate::parser::parser_structs_and_enums::{
+    Instruction, MonacoLineInfo, ProgramInfo, SourceMapEntry, TokenType,
+};
+use std::collections::HashMap;
+
+///Builds the source map of a program, with an entry for each instruction in the order of their addresses. Each
+/// instruction a pseudo-instruction or macro invocation expanded to is given the text written on its line, so the
+/// expansion can be shown beside the source rather than written into it.
+pub fn create_source_map(
+    instructions: &[Instruction],
+    monaco_line_info: &[MonacoLineInfo],
+) -> Vec<SourceMapEntry> {
+    //the instructions assembled from each line. The syscall added to the end of a program is not part of any line
+    let mut assembled: HashMap<usize, Vec<&Instruction>> = HashMap::new();
+    for instruction in instructions {
+        if !instruction.is_added_syscall() {
+            assembled
+                .entry(instruction.line_number)
+                .or_default()
+                .push(instruction);
+        }
+    }
+
+    instructions
+        .iter()
+        .map(|instruction| {
+            let expanded_from = monaco_line_info
+                .get(instruction.line_number)
+                .filter(|line| {
+                    !instruction.is_added_syscall()
+                        && is_expanded(line, &assembled[&instruction.line_number])
+                })
+                .map(written_text);
+            SourceMapEntry {
+                address: instruction.instruction_number << 2,
+                line_number: instruction.line_number,
+                instruction: assembled_text(instruction),
+                expanded_from,
+            }
+        })
+        .collect()
+}
+
+///Finds the instructions each expanded line of a file was assembled to, joined into a hint for the line such as
+/// `lui $at, 4097; lw $t1, 0($at)`. Lines of a program assembled on its own with `parser` are not from any file of a
+/// project, so they are found whatever file is given. A file which is included more than once is given the hints of the
+/// first time it was included.
+pub fn expansion_hints_in_file(program_info: &ProgramInfo, file: &str) -> Vec<(usize, String)> {
+    //the line of the program each hint is for, the line of the file it is shown on, and the hint
+    let mut hints: Vec<(usize, usize, String)> = Vec::new();
+    for entry in &program_info.source_map {
+        if entry.expanded_from.is_none() {
+            continue;
+        }
+        let Some(line) = program_info.monaco_line_info.get(entry.line_number) else {
+            continue;
+        };
+        let line_number = match &line.source {
+            Some(source) if source.file != file => continue,
+            Some(source) => source.line,
+            None => line.line_number,
+        };
+        match hints.iter_mut().find(|(_, other, _)| *other == line_number) {
+            Some((program_line, _, hint)) if *program_line == entry.line_number => {
+                hint.push_str(&format!("; {}", entry.instruction));
+            }
+            Some(_) => {}
+            None => hints.push((entry.line_number, line_number, entry.instruction.clone())),
+        }
+    }
+    hints
+        .into_iter()
+        .map(|(_, line_number, hint)| (line_number, hint))
+        .collect()
+}
+
+///Writes an instruction as it was assembled, after pseudo-instructions, macros, and constants are expanded.
+pub fn assembled_text(instruction: &Instruction) -> String {
+    let operands: Vec<&str> = instruction
+        .operands
+        .iter()
+        .map(|operand| operand.token_name.as_str())
+        .collect();
+    format!(
+        "{} {}",
+        instruction.operator.token_name,
+        operands.join(", ")
+    )
+    .trim_end()
+    .to_string()
+}
+
+///Returns true if the instructions assembled from a line are not simply the instruction written on it, as with `li`
+/// expanded to `addi`, `lw` of a label expanded to `lui` and `lw`, or a macro invocation.
+fn is_expanded(line: &MonacoLineInfo, assembled: &[&Instruction]) -> bool {
+    let written = line
+        .tokens
+        .iter()
+        .find(|token| token.token_type != TokenType::Label);
+    match assembled {
+        [instruction] => {
+            instruction.macro_line_number.is_some()
+                || !written.is_some_and(|token| {
+                    token
+                        .token_name
+                        .eq_ignore_ascii_case(&instruction.operator.token_name)
+                })
+        }
+        _ => true,
+    }
+}
+
+///Takes the instruction written on a line as it appears in the editor, without the labels before it or the comment
+/// after it.
+fn written_text(line: &MonacoLineInfo) -> String {
+    let text: Vec<char> = line
+        .updated_monaco_string
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .replacen("#Pseudo-Instruction: ", "", 1)
+        .chars()
+        .collect();
+    let start = line
+        .tokens
+        .iter()
+        .find(|token| token.token_type != TokenType::Label)
+        .map_or(0, |token| token.start_end_columns.0)
+        .min(text.len());
+    let end = line
+        .tokens
+        .last()
+        .map_or(text.len(), |token| token.start_end_columns.1)
+        .clamp(start, text.len());
+    text[start..end]
+        .iter()
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
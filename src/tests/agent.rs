@@ -1 +1,4 @@
+pub mod console_buffer;
+pub mod heap;
+pub mod printf;
 pub mod system_scanner;
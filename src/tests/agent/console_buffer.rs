@@ -0,0 +1,57 @@
+use crate::agent::console_buffer::ConsoleBuffer;
+use crate::agent::messages::OutputChannel::{Stderr, Stdout, System};
+
+fn texts(buffer: &ConsoleBuffer) -> Vec<&str> {
+    buffer.lines().map(|line| line.text.as_str()).collect()
+}
+
+#[test]
+fn append_without_newline_joins_line() {
+    let mut buffer = ConsoleBuffer::default();
+    buffer.append(Stdout, "a");
+    buffer.append(Stdout, "b");
+    assert_eq!(texts(&buffer), vec!["ab"]);
+}
+
+#[test]
+fn newline_starts_new_line() {
+    let mut buffer = ConsoleBuffer::default();
+    buffer.append(Stdout, "a\n");
+    buffer.append(Stdout, "b\nc");
+    buffer.append(Stdout, "d");
+    assert_eq!(texts(&buffer), vec!["a", "b", "cd"]);
+}
+
+#[test]
+fn blank_lines_are_kept() {
+    let mut buffer = ConsoleBuffer::default();
+    buffer.append(Stdout, "a\n\n");
+    buffer.append(Stdout, "\n");
+    buffer.append(Stdout, "b");
+    assert_eq!(texts(&buffer), vec!["a", "", "", "b"]);
+}
+
+#[test]
+fn channels_do_not_share_lines() {
+    let mut buffer = ConsoleBuffer::default();
+    buffer.append(Stdout, "Enter a number: ");
+    buffer.append(System, "> 5\n");
+    buffer.append(Stderr, "bad");
+    let channels: Vec<_> = buffer.lines().map(|line| line.channel).collect();
+    assert_eq!(texts(&buffer), vec!["Enter a number: ", "> 5", "bad"]);
+    assert_eq!(channels, vec![Stdout, System, Stderr]);
+}
+
+#[test]
+fn scrollback_is_bounded() {
+    let mut buffer = ConsoleBuffer::with_capacity(3);
+    for i in 0..5 {
+        buffer.append(Stdout, &format!("{i}\n"));
+    }
+    assert_eq!(texts(&buffer), vec!["2", "3", "4"]);
+    assert_eq!(buffer.dropped_lines(), 2);
+
+    buffer.clear();
+    assert!(buffer.is_empty());
+    assert_eq!(buffer.dropped_lines(), 0);
+}
@@ -0,0 +1,129 @@
+use crate::agent::heap::{Heap, HeapGrowth};
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::emulation_core::datapath::Syscall;
+use crate::parser::parser_assembler_main::parser;
+
+const STACK_POINTER: u64 = 0x1000;
+
+#[test]
+fn heap_starts_aligned_after_program() {
+    let heap = Heap::new(0x2c);
+    assert_eq!(heap.start, 0x30);
+    assert_eq!(heap.program_break, 0x30);
+}
+
+#[test]
+fn heap_starts_after_text_placed_past_the_data() {
+    let (_, binary, labels) = parser(
+        ".data\nvalue: .word 7\n.text 0x400\nmain: addi $t0, $zero, 1\nsyscall".to_string(),
+        AvailableDatapaths::MIPS,
+    );
+    let heap = Heap::after_image(&binary);
+
+    assert_eq!(labels.get("main"), Some(&0x400));
+    assert!(heap.start >= 0x408);
+    assert!(labels
+        .values()
+        .all(|address| (*address as u64) < heap.start));
+}
+
+#[test]
+fn sbrk_returns_previous_break() {
+    let mut heap = Heap::new(0x100);
+    assert_eq!(heap.sbrk(16, STACK_POINTER), Ok(0x100));
+    assert_eq!(heap.sbrk(5, STACK_POINTER), Ok(0x110));
+    assert_eq!(heap.program_break, 0x118);
+    assert_eq!(
+        heap.growths,
+        vec![
+            HeapGrowth {
+                address: 0x100,
+                size: 16
+            },
+            HeapGrowth {
+                address: 0x110,
+                size: 8
+            },
+        ]
+    );
+}
+
+#[test]
+fn sbrk_zero_queries_break() {
+    let mut heap = Heap::new(0x100);
+    assert_eq!(heap.sbrk(0, STACK_POINTER), Ok(0x100));
+    assert!(heap.growths.is_empty());
+}
+
+#[test]
+fn sbrk_cannot_collide_with_stack() {
+    let mut heap = Heap::new(0x100);
+    assert!(heap.sbrk(0x1000, STACK_POINTER).is_err());
+    assert_eq!(heap.program_break, 0x100);
+}
+
+#[test]
+fn sbrk_cannot_shrink_below_start() {
+    let mut heap = Heap::new(0x100);
+    heap.sbrk(8, STACK_POINTER).unwrap();
+    assert_eq!(heap.sbrk(-8, STACK_POINTER), Ok(0x108));
+    assert!(heap.sbrk(-8, STACK_POINTER).is_err());
+}
+
+#[test]
+fn malloc_reuses_freed_blocks() {
+    let mut heap = Heap::new(0x100);
+    let first = heap.malloc(12, STACK_POINTER).unwrap();
+    let second = heap.malloc(4, STACK_POINTER).unwrap();
+    assert_eq!(first, 0x100);
+    assert_eq!(second, 0x110);
+    assert_eq!(heap.live_bytes(), 24);
+
+    heap.free(first).unwrap();
+    assert_eq!(heap.live_bytes(), 8);
+
+    // A block that fits is reused instead of growing the heap.
+    assert_eq!(heap.malloc(8, STACK_POINTER), Ok(first));
+    assert_eq!(heap.program_break, 0x118);
+}
+
+#[test]
+fn free_rejects_invalid_pointers() {
+    let mut heap = Heap::new(0x100);
+    let block = heap.malloc(8, STACK_POINTER).unwrap();
+
+    assert!(heap.free(0x104).is_err());
+    assert!(heap.free(block).is_ok());
+    assert!(heap.free(block).is_err());
+    assert!(heap.free(0).is_ok());
+}
+
+#[test]
+fn malloc_fails_when_out_of_memory() {
+    let mut heap = Heap::new(0x100);
+    assert!(heap.malloc(0, STACK_POINTER).is_err());
+    assert!(heap.malloc(0x2000, STACK_POINTER).is_err());
+    assert!(heap.allocations.is_empty());
+}
+
+#[test]
+fn heap_syscall_numbers() {
+    assert_eq!(
+        Syscall::from_register_data(9, 16, 0.0, 0.0),
+        Syscall::Sbrk(16)
+    );
+    assert_eq!(
+        Syscall::from_register_data(13, 8, 0.0, 0.0),
+        Syscall::Malloc(8)
+    );
+    assert_eq!(
+        Syscall::from_register_data(14, 0x100, 0.0, 0.0),
+        Syscall::Free(0x100)
+    );
+}
+
+#[test]
+fn exit_numbers_of_other_simulators_still_exit() {
+    assert_eq!(Syscall::from_register_data(10, 8, 0.0, 0.0), Syscall::Exit);
+    assert_eq!(Syscall::from_register_data(17, 0, 0.0, 0.0), Syscall::Exit);
+}
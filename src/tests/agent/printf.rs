@@ -0,0 +1,143 @@
+use crate::agent::printf::{format_printf, printf, read_null_terminated_string, PrintfArguments};
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::emulation_core::datapath::Datapath;
+use crate::emulation_core::mips::datapath::MipsDatapath;
+use crate::emulation_core::mips::gp_registers::GpRegisterType;
+use crate::emulation_core::mips::memory::Memory;
+use crate::emulation_core::riscv::datapath::RiscDatapath;
+use crate::emulation_core::riscv::registers::RiscGpRegisterType;
+use crate::parser::parser_assembler_main::parser;
+
+fn format_with(format: &str, registers: Vec<u64>) -> Result<String, String> {
+    let memory = Memory::default();
+    let mut arguments = PrintfArguments::new(registers, &memory, 0);
+    format_printf(format, &mut arguments)
+}
+
+#[test]
+fn read_string_stops_at_null() {
+    let mut memory = Memory::default();
+    memory.memory[5..9].copy_from_slice(b"abc\0");
+    assert_eq!(
+        read_null_terminated_string(&memory, 5),
+        Ok("abc".to_string())
+    );
+}
+
+#[test]
+fn read_string_rejects_invalid_utf8() {
+    let mut memory = Memory::default();
+    memory.memory[0] = 0xff;
+    assert!(read_null_terminated_string(&memory, 0).is_err());
+}
+
+#[test]
+fn integer_conversions() {
+    assert_eq!(
+        format_with("x = %d, y = %u, z = %x", vec![-5i64 as u64, 7, 255]),
+        Ok("x = -5, y = 7, z = ff".to_string())
+    );
+}
+
+#[test]
+fn integers_are_32_bit_without_long_modifier() {
+    assert_eq!(
+        format_with("%u %lu", vec![u64::MAX, u64::MAX]),
+        Ok("4294967295 18446744073709551615".to_string())
+    );
+}
+
+#[test]
+fn width_and_precision() {
+    assert_eq!(
+        format_with("[%5d][%-5d][%05d][%.3d]", vec![42, 42, -42i64 as u64, 7]),
+        Ok("[   42][42   ][-0042][007]".to_string())
+    );
+}
+
+#[test]
+fn float_conversions() {
+    assert_eq!(
+        format_with(
+            "%f %.2lf %8.1f",
+            vec![
+                1.5f32.to_bits() as u64,
+                1.23456f64.to_bits(),
+                (-2.25f32).to_bits() as u64
+            ]
+        ),
+        Ok("1.500000 1.23     -2.2".to_string())
+    );
+}
+
+#[test]
+fn char_and_percent() {
+    assert_eq!(
+        format_with("%c%c 100%%", vec!['o' as u64, 'k' as u64]),
+        Ok("ok 100%".to_string())
+    );
+}
+
+#[test]
+fn unsupported_conversion_is_an_error() {
+    assert!(format_with("%q", vec![]).is_err());
+    assert!(format_with("trailing %", vec![]).is_err());
+}
+
+#[test]
+fn arguments_continue_on_stack() {
+    let mut memory = Memory::default();
+    memory.store_double_word(0x100, 3).unwrap();
+    memory.store_double_word(0x108, 4).unwrap();
+    let mut arguments = PrintfArguments::new(vec![1, 2], &memory, 0x100);
+    assert_eq!(
+        format_printf("%d %d %d %d", &mut arguments),
+        Ok("1 2 3 4".to_string())
+    );
+}
+
+#[test]
+fn printf_mips_strings() -> Result<(), String> {
+    let program = String::from(
+        ".text
+nop
+.data
+fmt: .asciiz \"%s = %d\"
+name: .asciiz \"x\"",
+    );
+    let (_, binary, labels) = parser(program, AvailableDatapaths::MIPS);
+    let mut datapath = MipsDatapath::default();
+    datapath.initialize(0, binary)?;
+
+    datapath.registers[GpRegisterType::A2] = labels["name"] as u64;
+    datapath.registers[GpRegisterType::A3] = 5;
+
+    assert_eq!(
+        printf(datapath.as_datapath_ref(), labels["fmt"] as u64),
+        Ok("x = 5".to_string())
+    );
+    Ok(())
+}
+
+#[test]
+fn printf_riscv_arguments() -> Result<(), String> {
+    let program = String::from(
+        ".text
+nop
+.data
+fmt: .asciiz \"%d%d%d\"",
+    );
+    let (_, binary, labels) = parser(program, AvailableDatapaths::RISCV);
+    let mut datapath = RiscDatapath::default();
+    datapath.initialize(0, binary)?;
+
+    datapath.registers[RiscGpRegisterType::X12] = 1;
+    datapath.registers[RiscGpRegisterType::X13] = 2;
+    datapath.registers[RiscGpRegisterType::X14] = 3;
+
+    assert_eq!(
+        printf(datapath.as_datapath_ref(), labels["fmt"] as u64),
+        Ok("123".to_string())
+    );
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use crate::agent::system_scanner::Scanner;
+use crate::agent::system_scanner::{ScanError, Scanner};
 
 #[test]
 fn next_int_basic() {
@@ -137,3 +137,109 @@ fn mixed_reads() {
     assert_eq!(scanner.next_int().unwrap(), 56);
     assert_eq!(scanner.next_double().unwrap(), 2f64);
 }
+
+#[test]
+fn read_int_negative() {
+    let mut scanner = Scanner::new();
+    scanner.feed("-5".to_string());
+    assert_eq!(scanner.read_int(), Some(Ok(-5i64 as u64)));
+}
+
+#[test]
+fn read_int_hex_and_binary() {
+    let mut scanner = Scanner::new();
+    scanner.feed("0x1F -0b101 +12".to_string());
+    assert_eq!(scanner.read_int(), Some(Ok(31)));
+    assert_eq!(scanner.read_int(), Some(Ok(-5i64 as u64)));
+    assert_eq!(scanner.read_int(), Some(Ok(12)));
+    assert_eq!(scanner.read_int(), None);
+}
+
+#[test]
+fn read_int_rejects_decimal_point() {
+    let mut scanner = Scanner::new();
+    scanner.feed("1.5".to_string());
+    assert_eq!(
+        scanner.read_int(),
+        Some(Err(ScanError::InvalidInt("1.5".to_string())))
+    );
+}
+
+#[test]
+fn read_int_rejects_overflow() {
+    let mut scanner = Scanner::new();
+    scanner.feed("-9223372036854775809".to_string());
+    assert!(matches!(
+        scanner.read_int(),
+        Some(Err(ScanError::OutOfRange(_)))
+    ));
+
+    scanner.feed("-9223372036854775808".to_string());
+    assert_eq!(scanner.read_int(), Some(Ok(i64::MIN as u64)));
+}
+
+#[test]
+fn read_int_invalid_discards_line() {
+    let mut scanner = Scanner::new();
+    scanner.feed("abc 12".to_string());
+    scanner.feed("34".to_string());
+    assert_eq!(
+        scanner.read_int(),
+        Some(Err(ScanError::InvalidInt("abc".to_string())))
+    );
+    assert_eq!(scanner.read_int(), Some(Ok(34)));
+}
+
+#[test]
+fn read_int_return_none_on_whitespace() {
+    let mut scanner = Scanner::new();
+    scanner.feed("   ".to_string());
+    assert_eq!(scanner.read_int(), None);
+}
+
+#[test]
+fn read_double_scientific() {
+    let mut scanner = Scanner::new();
+    scanner.feed("-1.5e3 .25 2E-2".to_string());
+    assert_eq!(scanner.read_double(), Some(Ok(-1500.0)));
+    assert_eq!(scanner.read_double(), Some(Ok(0.25)));
+    assert_eq!(scanner.read_double(), Some(Ok(0.02)));
+}
+
+#[test]
+fn read_double_special_values() {
+    let mut scanner = Scanner::new();
+    scanner.feed("-inf Infinity NaN".to_string());
+    assert_eq!(scanner.read_double(), Some(Ok(f64::NEG_INFINITY)));
+    assert_eq!(scanner.read_double(), Some(Ok(f64::INFINITY)));
+    assert!(scanner.read_double().unwrap().unwrap().is_nan());
+}
+
+#[test]
+fn read_double_rejects_invalid() {
+    let mut scanner = Scanner::new();
+    scanner.feed("1.2.3".to_string());
+    assert_eq!(
+        scanner.read_double(),
+        Some(Err(ScanError::InvalidFloat("1.2.3".to_string())))
+    );
+
+    scanner.feed("1e999".to_string());
+    assert!(matches!(
+        scanner.read_double(),
+        Some(Err(ScanError::OutOfRange(_)))
+    ));
+}
+
+#[test]
+fn read_float_rejects_overflow() {
+    let mut scanner = Scanner::new();
+    scanner.feed("1e300 -2.5".to_string());
+    assert!(matches!(
+        scanner.read_float(),
+        Some(Err(ScanError::OutOfRange(_)))
+    ));
+
+    scanner.feed("-2.5".to_string());
+    assert_eq!(scanner.read_float(), Some(Ok(-2.5f32)));
+}
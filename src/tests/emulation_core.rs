@@ -1,3 +1,5 @@
+pub mod elf;
+pub mod interrupt;
 pub mod memory;
 pub mod mips;
 pub mod mips_instruction;
@@ -0,0 +1,619 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::emulation_core::elf::*;
+use crate::emulation_core::mips::memory::CAPACITY_BYTES;
+use crate::parser::disassembler::disassemble_with_symbols;
+use crate::parser::parser_assembler_main::parser;
+
+/// A loadable segment of a test executable.
+struct Segment {
+    address: usize,
+    flags: u32,
+    contents: Vec<u8>,
+    memory_size: usize,
+}
+
+/// The fields of a minimal ELF file, written out by `build`.
+struct TestElf {
+    is_64_bit: bool,
+    little_endian: bool,
+    file_type: u16,
+    machine: u16,
+    flags: u32,
+    entry: usize,
+    segments: Vec<Segment>,
+    // (address, size) of each section of instructions
+    code_sections: Vec<(usize, usize)>,
+    // (name, value, type)
+    symbols: Vec<(&'static str, usize, u8)>,
+}
+
+impl TestElf {
+    fn new(is_64_bit: bool, little_endian: bool, machine: u16) -> Self {
+        TestElf {
+            is_64_bit,
+            little_endian,
+            file_type: ET_EXEC,
+            machine,
+            flags: 0,
+            entry: 0,
+            segments: Vec::new(),
+            code_sections: Vec::new(),
+            symbols: Vec::new(),
+        }
+    }
+
+    fn half(&self, bytes: &mut Vec<u8>, value: u16) {
+        if self.little_endian {
+            bytes.extend(value.to_le_bytes());
+        } else {
+            bytes.extend(value.to_be_bytes());
+        }
+    }
+
+    fn u32(&self, bytes: &mut Vec<u8>, value: u32) {
+        if self.little_endian {
+            bytes.extend(value.to_le_bytes());
+        } else {
+            bytes.extend(value.to_be_bytes());
+        }
+    }
+
+    fn word(&self, bytes: &mut Vec<u8>, value: usize) {
+        if !self.is_64_bit {
+            self.u32(bytes, value as u32);
+        } else if self.little_endian {
+            bytes.extend((value as u64).to_le_bytes());
+        } else {
+            bytes.extend((value as u64).to_be_bytes());
+        }
+    }
+
+    fn build(&self) -> Vec<u8> {
+        let (header_size, program_header_size, section_header_size, symbol_size) = if self.is_64_bit
+        {
+            (64, 56, 64, 24)
+        } else {
+            (52, 32, 40, 16)
+        };
+
+        // Segment contents, then the symbol and string tables, then the section headers.
+        let mut offset = header_size + program_header_size * self.segments.len();
+        let mut segment_offsets = Vec::new();
+        for segment in &self.segments {
+            segment_offsets.push(offset);
+            offset += segment.contents.len();
+        }
+        let mut strings = vec![0];
+        let mut symbol_table = vec![0; symbol_size];
+        for (name, value, symbol_type) in &self.symbols {
+            let name_offset = strings.len() as u32;
+            strings.extend(name.bytes());
+            strings.push(0);
+            if self.is_64_bit {
+                self.u32(&mut symbol_table, name_offset);
+                symbol_table.push(*symbol_type);
+                symbol_table.push(0);
+                self.half(&mut symbol_table, 1);
+                self.word(&mut symbol_table, *value);
+                self.word(&mut symbol_table, 0);
+            } else {
+                self.u32(&mut symbol_table, name_offset);
+                self.word(&mut symbol_table, *value);
+                self.word(&mut symbol_table, 0);
+                symbol_table.push(*symbol_type);
+                symbol_table.push(0);
+                self.half(&mut symbol_table, 1);
+            }
+        }
+        let symbol_table_offset = offset;
+        let strings_offset = symbol_table_offset + symbol_table.len();
+        let section_headers = strings_offset + strings.len();
+
+        let mut bytes = ELF_MAGIC.to_vec();
+        bytes.push(if self.is_64_bit {
+            ELFCLASS64
+        } else {
+            ELFCLASS32
+        });
+        bytes.push(if self.little_endian {
+            ELFDATA2LSB
+        } else {
+            ELFDATA2MSB
+        });
+        bytes.push(EV_CURRENT);
+        bytes.resize(16, 0);
+        self.half(&mut bytes, self.file_type);
+        self.half(&mut bytes, self.machine);
+        self.u32(&mut bytes, EV_CURRENT as u32);
+        self.word(&mut bytes, self.entry);
+        self.word(&mut bytes, header_size);
+        self.word(&mut bytes, section_headers);
+        self.u32(&mut bytes, self.flags);
+        self.half(&mut bytes, header_size as u16);
+        self.half(&mut bytes, program_header_size as u16);
+        self.half(&mut bytes, self.segments.len() as u16);
+        self.half(&mut bytes, section_header_size as u16);
+        self.half(&mut bytes, 3 + self.code_sections.len() as u16);
+        self.half(&mut bytes, 0);
+
+        for (segment, offset) in self.segments.iter().zip(&segment_offsets) {
+            self.u32(&mut bytes, PT_LOAD);
+            if self.is_64_bit {
+                self.u32(&mut bytes, segment.flags);
+                self.word(&mut bytes, *offset);
+                self.word(&mut bytes, segment.address);
+                self.word(&mut bytes, segment.address);
+                self.word(&mut bytes, segment.contents.len());
+                self.word(&mut bytes, segment.memory_size);
+                self.word(&mut bytes, 4);
+            } else {
+                self.word(&mut bytes, *offset);
+                self.word(&mut bytes, segment.address);
+                self.word(&mut bytes, segment.address);
+                self.word(&mut bytes, segment.contents.len());
+                self.word(&mut bytes, segment.memory_size);
+                self.u32(&mut bytes, segment.flags);
+                self.word(&mut bytes, 4);
+            }
+        }
+        for segment in &self.segments {
+            bytes.extend(&segment.contents);
+        }
+        bytes.extend(&symbol_table);
+        bytes.extend(&strings);
+
+        // The null section, the symbol table, its string table, and the sections of instructions.
+        bytes.resize(bytes.len() + section_header_size, 0);
+        let code_sections = self.code_sections.iter().map(|(address, size)| {
+            (
+                SHT_PROGBITS,
+                (SHF_ALLOC | SHF_EXECINSTR) as usize,
+                *address,
+                0,
+                *size,
+                0,
+                0,
+            )
+        });
+        for (section_type, flags, address, offset, size, link, entry_size) in [
+            (
+                SHT_SYMTAB,
+                0,
+                0,
+                symbol_table_offset,
+                symbol_table.len(),
+                2,
+                symbol_size,
+            ),
+            (SHT_STRTAB, 0, 0, strings_offset, strings.len(), 0, 0),
+        ]
+        .into_iter()
+        .chain(code_sections)
+        {
+            self.u32(&mut bytes, 0);
+            self.u32(&mut bytes, section_type);
+            self.word(&mut bytes, flags);
+            self.word(&mut bytes, address);
+            self.word(&mut bytes, offset);
+            self.word(&mut bytes, size);
+            self.u32(&mut bytes, link);
+            self.u32(&mut bytes, 0);
+            self.word(&mut bytes, 4);
+            self.word(&mut bytes, entry_size);
+        }
+        bytes
+    }
+}
+
+/// Writes words in the executable's byte order.
+fn words_to_bytes(words: &[u32], little_endian: bool) -> Vec<u8> {
+    words
+        .iter()
+        .flat_map(|word| {
+            if little_endian {
+                word.to_le_bytes()
+            } else {
+                word.to_be_bytes()
+            }
+        })
+        .collect()
+}
+
+/// A big-endian MIPS executable with a text segment, a data segment with bss after it, and a symbol table.
+fn mips_executable() -> TestElf {
+    let mut elf = TestElf::new(false, false, EM_MIPS);
+    elf.entry = 4;
+    elf.segments = vec![
+        Segment {
+            address: 0,
+            flags: PF_R | PF_X,
+            // addi $t0, $zero, 1; addi $t0, $t0, 1; syscall
+            contents: words_to_bytes(&[0x20080001, 0x21080001, 0x0000000C], false),
+            memory_size: 12,
+        },
+        Segment {
+            address: 0xC,
+            flags: PF_R | PF_W,
+            contents: words_to_bytes(&[7, 9], false),
+            memory_size: 16,
+        },
+    ];
+    elf.symbols = vec![
+        ("_start", 0, STT_FUNC),
+        ("increment", 4, STT_NOTYPE),
+        ("values", 0xC, STT_OBJECT),
+    ];
+    elf
+}
+
+#[test]
+fn segments_are_placed_at_their_addresses() {
+    let elf = load_elf(&mips_executable().build()).unwrap();
+
+    assert_eq!(elf.architecture, AvailableDatapaths::MIPS);
+    assert_eq!(elf.entry, 4);
+    assert_eq!(elf.words[0..3], [0x20080001, 0x21080001, 0x0000000C]);
+    assert_eq!(elf.words[3..5], [7, 9]);
+    // The rest of the data segment is zero-filled.
+    assert_eq!(elf.words.len(), 7);
+    assert_eq!(elf.words[5..], [0, 0]);
+    assert_eq!(elf.data_start, Some(3));
+}
+
+#[test]
+fn symbols_are_imported() {
+    let elf = load_elf(&mips_executable().build()).unwrap();
+
+    assert_eq!(
+        elf.symbols,
+        HashMap::from([
+            ("_start".to_string(), 0),
+            ("increment".to_string(), 4),
+            ("values".to_string(), 0xC),
+        ])
+    );
+}
+
+#[test]
+fn symbols_name_the_disassembly() {
+    let elf = load_elf(&mips_executable().build()).unwrap();
+    let source = disassemble_with_symbols(
+        &elf.words,
+        elf.data_start,
+        &elf.symbols,
+        AvailableDatapaths::MIPS,
+    );
+
+    assert!(source.contains("increment:\n        addi $t0, $t0, 1"));
+    assert!(source.contains("values: .word 0x00000007, 0x00000009"));
+
+    let (program_info, binary, labels) = parser(source.clone(), AvailableDatapaths::MIPS);
+    assert!(program_info
+        .monaco_line_info
+        .iter()
+        .all(|line| line.errors.is_empty()));
+    assert_eq!(binary[..5], elf.words[..5], "{source}");
+    assert_eq!(labels.get("increment"), Some(&4));
+}
+
+#[test]
+fn little_endian_64_bit_riscv_is_loaded() {
+    let mut elf = TestElf::new(true, true, EM_RISCV);
+    elf.entry = 0;
+    elf.segments = vec![Segment {
+        address: 0,
+        flags: PF_R | PF_X,
+        // addi t0, zero, 5; ecall
+        contents: words_to_bytes(&[0x00500293, 0x00000073], true),
+        memory_size: 8,
+    }];
+    elf.symbols = vec![("main", 0, STT_FUNC)];
+
+    let elf = load_elf(&elf.build()).unwrap();
+
+    assert_eq!(elf.architecture, AvailableDatapaths::RISCV);
+    assert_eq!(elf.words, vec![0x00500293, 0x00000073]);
+    assert_eq!(elf.data_start, None);
+    assert_eq!(elf.symbols.get("main"), Some(&0));
+}
+
+#[test]
+fn unsupported_machine_is_rejected() {
+    let mut elf = mips_executable();
+    // x86-64
+    elf.machine = 62;
+
+    assert_eq!(
+        load_elf(&elf.build()),
+        Err("ELF files for machine type 62 are not supported. SWIM runs MIPS (8) and RISC-V (243) executables.".to_string())
+    );
+}
+
+#[test]
+fn object_file_is_rejected() {
+    let mut elf = mips_executable();
+    elf.file_type = ET_REL;
+
+    assert!(load_elf(&elf.build())
+        .unwrap_err()
+        .contains("not been linked"));
+}
+
+#[test]
+fn segments_linked_above_memory_are_moved_down() {
+    let mut elf = mips_executable();
+    elf.entry = 0x40_0004;
+    elf.segments[0].address = 0x40_0000;
+    // j 0x400004; syscall
+    elf.segments[0].contents = words_to_bytes(&[0x08100001, 0x0000000C], false);
+    elf.segments[0].memory_size = 8;
+    elf.segments[1].address = 0x40_8000;
+    elf.symbols = vec![
+        ("_start", 0x40_0004, STT_FUNC),
+        ("values", 0x40_8000, STT_OBJECT),
+    ];
+
+    let elf = load_elf(&elf.build()).unwrap();
+
+    assert_eq!(elf.base, 0x40_0000);
+    assert_eq!(elf.entry, 4);
+    // The jump is moved with the text, to the word at address 4.
+    assert_eq!(elf.words[0..2], [0x08000001, 0x0000000C]);
+    assert_eq!(elf.words[0x2000..0x2002], [7, 9]);
+    assert_eq!(elf.data_start, Some(0x2000));
+    assert_eq!(elf.symbols.get("_start"), Some(&4));
+    assert_eq!(elf.symbols.get("values"), Some(&0x8000));
+}
+
+#[test]
+fn segments_spanning_more_than_memory_are_rejected() {
+    let mut elf = mips_executable();
+    elf.segments[1].address = CAPACITY_BYTES;
+
+    assert!(load_elf(&elf.build()).unwrap_err().contains("do not fit"));
+}
+
+#[test]
+fn riscv_branches_and_jumps_become_word_addresses() {
+    let mut elf = TestElf::new(true, true, EM_RISCV);
+    elf.entry = 0x1_0000;
+    elf.segments = vec![Segment {
+        address: 0x1_0000,
+        flags: PF_R | PF_X,
+        // addi t0, zero, 5; addi t0, t0, -1; bne t0, zero, .-4; jal ra, .-12; ecall
+        contents: words_to_bytes(
+            &[0x00500293, 0xfff28293, 0xfe029ee3, 0xff5ff0ef, 0x00000073],
+            true,
+        ),
+        memory_size: 20,
+    }];
+    let (_, expected, _) = parser(
+        "main: addi t0, zero, 5\nloop: addi t0, t0, -1\nbne t0, zero, loop\njal ra, main\necall"
+            .to_string(),
+        AvailableDatapaths::RISCV,
+    );
+
+    let loaded = load_elf(&elf.build()).unwrap();
+
+    assert_eq!(loaded.base, 0x1_0000);
+    assert_eq!(loaded.words, expected);
+}
+
+#[test]
+fn read_only_data_beside_riscv_text_keeps_its_byte_order() {
+    let mut elf = TestElf::new(true, true, EM_RISCV);
+    let mut contents = words_to_bytes(&[0x00000073], true);
+    contents.extend(b"hi!\0");
+    elf.segments = vec![Segment {
+        address: 0,
+        flags: PF_R | PF_X,
+        contents,
+        memory_size: 8,
+    }];
+    elf.code_sections = vec![(0, 4)];
+
+    let elf = load_elf(&elf.build()).unwrap();
+
+    assert_eq!(elf.words, vec![0x00000073, u32::from_be_bytes(*b"hi!\0")]);
+}
+
+#[test]
+fn compressed_riscv_executable_is_rejected() {
+    let mut elf = TestElf::new(true, true, EM_RISCV);
+    elf.flags = EF_RISCV_RVC;
+    elf.segments = vec![Segment {
+        address: 0,
+        flags: PF_R | PF_X,
+        contents: words_to_bytes(&[0x00000073], true),
+        memory_size: 4,
+    }];
+
+    assert!(load_elf(&elf.build())
+        .unwrap_err()
+        .contains("compressed instructions"));
+}
+
+#[test]
+fn table_past_the_end_of_memory_is_rejected() {
+    let mut bytes = mips_executable().build();
+    // The program headers are said to start 4 bytes before the end of the address space.
+    bytes[28..32].copy_from_slice(&0xffff_fffc_u32.to_be_bytes());
+
+    assert_eq!(
+        load_elf(&bytes),
+        Err("The ELF file ends before all of its contents.".to_string())
+    );
+}
+
+#[test]
+fn non_elf_file_is_rejected() {
+    assert_eq!(
+        load_elf(b"addi $t0, $zero, 1"),
+        Err("The file is not an ELF file.".to_string())
+    );
+}
+
+/// Assembles the program and writes it as an ELF file.
+fn assemble_elf(program: &str, arch: AvailableDatapaths) -> (ElfExecutable, Vec<u8>) {
+    let (program_info, binary, labels) = parser(program.to_string(), arch);
+    assert!(program_info
+        .monaco_line_info
+        .iter()
+        .all(|line| line.errors.is_empty()));
+    let executable = ElfExecutable::from_assembly(&program_info, &binary, &labels, arch);
+    let bytes = write_elf(&executable).unwrap();
+    (executable, bytes)
+}
+
+#[test]
+fn written_mips_executable_loads_back() {
+    let (executable, bytes) = assemble_elf(
+        ".globl main\n.data\nvalue: .word 7\n.text\nhelper: syscall\nmain: lw $t0, value\nsyscall",
+        AvailableDatapaths::MIPS,
+    );
+    let loaded = load_elf(&bytes).unwrap();
+
+    assert_eq!(bytes[4], ELFCLASS64);
+    assert_eq!(bytes[5], ELFDATA2MSB);
+    // An executable read from a file has no relocations, since its words already hold the addresses it is linked at.
+    assert_eq!(
+        loaded,
+        ElfExecutable {
+            relocations: Vec::new(),
+            ..executable
+        }
+    );
+    assert_eq!(loaded.entry, 4);
+    assert_eq!(loaded.data_start, Some(4));
+    assert_eq!(loaded.symbols.get("value"), Some(&16));
+    assert_eq!(loaded.global_symbols, HashSet::from(["main".to_string()]));
+}
+
+#[test]
+fn written_riscv_executable_is_little_endian() {
+    let (executable, bytes) = assemble_elf(
+        "main: addi t0, zero, 5\nloop: addi t0, t0, -1\nbne t0, zero, loop\njal ra, main\necall\n.data\ngreeting: .asciiz \"hi!\"",
+        AvailableDatapaths::RISCV,
+    );
+
+    assert_eq!(bytes[5], ELFDATA2LSB);
+    // The text segment starts one page into the file, at the same offset within the page as its address.
+    assert_eq!(bytes[0x1000..0x1004], executable.words[0].to_le_bytes());
+    // The branch and jump hold the distance to their target, as they do in executables of the GNU toolchain.
+    assert_eq!(bytes[0x1008..0x100c], 0xfe029ee3_u32.to_le_bytes());
+    assert_eq!(bytes[0x100c..0x1010], 0xff5ff0ef_u32.to_le_bytes());
+    // The string keeps the order of its characters.
+    assert_eq!(bytes[0x1014..0x1018], *b"hi!\0");
+    assert_eq!(
+        load_elf(&bytes).unwrap(),
+        ElfExecutable {
+            relocations: Vec::new(),
+            ..executable
+        }
+    );
+}
+
+/// Assembles the program and writes it as an ELF file linked at base.
+fn assemble_elf_at(program: &str, arch: AvailableDatapaths, base: usize) -> ElfExecutable {
+    let (program_info, binary, labels) = parser(program.to_string(), arch);
+    let mut executable = ElfExecutable::from_assembly(&program_info, &binary, &labels, arch);
+    executable.base = base;
+    executable
+}
+
+#[test]
+fn riscv_executable_is_written_at_its_base() {
+    let executable = assemble_elf_at(
+        "main: la a0, greeting\nlui t1, %hi(greeting)\naddi t1, t1, %lo(greeting)\nloop: addi t0, t0, -1\nbne t0, zero, loop\njal ra, main\necall\n.data\ngreeting: .asciiz \"hi!\"\npointer: .word greeting",
+        AvailableDatapaths::RISCV,
+        0x10000,
+    );
+    let bytes = write_elf(&executable).unwrap();
+    let u64_at = |offset: usize| u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap());
+
+    // The entry and the address of the text segment are moved up by the base.
+    assert_eq!(u64_at(24), 0x10000);
+    assert_eq!(u64_at(64 + 16), 0x10000);
+    let loaded = load_elf(&bytes).unwrap();
+    assert_eq!(loaded.base, 0x10000);
+    assert_eq!(loaded.entry, executable.entry);
+    assert_eq!(loaded.symbols, executable.symbols);
+    // lui and the pointer hold where greeting is linked, while la, the branch, and the jump are relative to the PC.
+    assert_eq!(loaded.words[2], 0x00010337);
+    assert_eq!(loaded.words[9], 0x10020);
+    for i in [0, 1, 3, 4, 5, 6, 7, 8] {
+        assert_eq!(loaded.words[i], executable.words[i]);
+    }
+}
+
+#[test]
+fn mips_executable_is_written_at_its_base() {
+    let executable = assemble_elf_at(
+        "main: la $t0, value\njal helper\nsyscall\nhelper: jr $ra\n.data\nvalue: .word helper",
+        AvailableDatapaths::MIPS,
+        0x8000_0000,
+    );
+    let loaded = load_elf(&write_elf(&executable).unwrap()).unwrap();
+
+    assert_eq!(loaded.base, 0x8000_0000);
+    assert_eq!(loaded.symbols, executable.symbols);
+    // The upper bits of the address of value are moved up, and the jump is moved both ways.
+    assert_eq!(loaded.words[0] & 0xffff, 0x8000);
+    for i in 1..6 {
+        assert_eq!(loaded.words[i], executable.words[i]);
+    }
+    assert_eq!(loaded.words[6], 0x8000_0010);
+}
+
+#[test]
+fn address_that_does_not_fit_at_the_base_is_rejected() {
+    let executable = assemble_elf_at(
+        "main: addi $t0, $zero, value + 4\nsyscall\n.data\nvalue: .word 1",
+        AvailableDatapaths::MIPS,
+        0x10000,
+    );
+
+    assert_eq!(
+        write_elf(&executable),
+        Err("The address 0x1000c held at 0x10000 does not fit there once the executable is linked at 0x10000.".to_string())
+    );
+}
+
+#[test]
+fn base_that_is_not_a_page_is_rejected() {
+    let executable = assemble_elf_at("main: syscall", AvailableDatapaths::MIPS, 0x10004);
+
+    assert!(write_elf(&executable).is_err());
+}
+
+#[test]
+fn written_symbols_are_typed_and_sized() {
+    let (_, bytes) = assemble_elf(
+        ".data\nvalue: .word 7\n.text\nhelper: syscall\nmain: lw $t0, value\nsyscall",
+        AvailableDatapaths::MIPS,
+    );
+    let u64_at = |offset: usize| u64::from_be_bytes(bytes[offset..offset + 8].try_into().unwrap());
+
+    // The symbol table follows the null, .text, and .data sections.
+    let symbol_table_header = u64_at(40) as usize + 3 * 64;
+    let symbol_table = u64_at(symbol_table_header + 24) as usize;
+    let symbol_count = u64_at(symbol_table_header + 32) as usize / 24;
+    // Every symbol is local, so they are sorted by address after the null symbol.
+    let symbols: Vec<(u8, u64, u64)> = (1..symbol_count)
+        .map(|index| {
+            let symbol = symbol_table + index * 24;
+            (
+                bytes[symbol + 4] & 0xf,
+                u64_at(symbol + 8),
+                u64_at(symbol + 16),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        symbols,
+        vec![(STT_FUNC, 0, 4), (STT_FUNC, 4, 12), (STT_OBJECT, 16, 4)]
+    );
+}
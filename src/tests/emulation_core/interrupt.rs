@@ -0,0 +1,265 @@
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::emulation_core::datapath::Datapath;
+use crate::emulation_core::interrupt::*;
+use crate::parser::parser_assembler_main::parser;
+
+pub mod controller {
+    use super::*;
+
+    #[test]
+    fn timer_fires_when_count_reaches_compare() {
+        let mut controller = InterruptController::default();
+        controller.timer.compare = 3;
+
+        controller.tick();
+        controller.tick();
+        assert!(!controller.is_pending(TIMER_INTERRUPT));
+
+        controller.tick();
+        assert!(controller.is_pending(TIMER_INTERRUPT));
+    }
+
+    #[test]
+    fn disarmed_timer_never_fires() {
+        let mut controller = InterruptController::default();
+
+        for _ in 0..100 {
+            controller.tick();
+        }
+
+        assert_eq!(controller.timer.count, 100);
+        assert!(!controller.is_pending(TIMER_INTERRUPT));
+    }
+
+    #[test]
+    fn writing_compare_acknowledges_timer() {
+        let mut controller = InterruptController::default();
+        controller.timer.compare = 1;
+        controller.tick();
+        assert!(controller.is_pending(TIMER_INTERRUPT));
+
+        controller.mmio_write(TIMECMP_ADDRESS, 10);
+
+        assert!(!controller.is_pending(TIMER_INTERRUPT));
+        assert_eq!(controller.mmio_read(TIMECMP_ADDRESS), Some(10));
+    }
+
+    #[test]
+    fn interrupt_requires_enable_bits() {
+        let mut controller = InterruptController::default();
+        controller.raise(3);
+        assert_eq!(controller.next_interrupt(), None);
+
+        controller.enabled = 1 << 3;
+        assert_eq!(controller.next_interrupt(), None);
+
+        controller.global_enable = true;
+        assert_eq!(controller.next_interrupt(), Some(3));
+    }
+
+    #[test]
+    fn highest_line_has_priority() {
+        let mut controller = InterruptController {
+            enabled: u32::MAX,
+            global_enable: true,
+            ..Default::default()
+        };
+        controller.raise(2);
+        controller.raise(TIMER_INTERRUPT);
+
+        assert_eq!(controller.next_interrupt(), Some(TIMER_INTERRUPT));
+    }
+
+    #[test]
+    fn taking_interrupt_saves_state() {
+        let mut controller = InterruptController {
+            enabled: 1 << 4,
+            global_enable: true,
+            vector: 0x80,
+            ..Default::default()
+        };
+        controller.raise(4);
+
+        assert_eq!(controller.take_interrupt(0x24), Some(0x80));
+        assert_eq!(controller.return_address, 0x24);
+        assert_eq!(controller.cause, 4);
+        assert!(!controller.global_enable);
+
+        // Interrupts stay masked until the handler re-enables them.
+        assert_eq!(controller.take_interrupt(0x28), None);
+    }
+
+    #[test]
+    fn pending_bits_are_write_one_to_clear() {
+        let mut controller = InterruptController::default();
+        controller.raise(1);
+        controller.raise(2);
+
+        controller.mmio_write(INTERRUPT_PENDING_ADDRESS, 1 << 1);
+
+        assert!(!controller.is_pending(1));
+        assert!(controller.is_pending(2));
+    }
+
+    #[test]
+    fn addresses_outside_window_are_ignored() {
+        let mut controller = InterruptController::default();
+
+        assert_eq!(controller.mmio_read(0x100), None);
+        assert!(!controller.mmio_write(0x100, 5));
+        assert_eq!(controller.mmio_read(MMIO_BASE), Some(0));
+    }
+}
+
+pub mod mips {
+    use super::*;
+    use crate::emulation_core::mips::datapath::MipsDatapath;
+    use crate::emulation_core::mips::gp_registers::GpRegisterType;
+
+    #[test]
+    fn count_advances_per_instruction() -> Result<(), String> {
+        let mut datapath = MipsDatapath::default();
+        let instructions = String::from("nop\nnop\nnop\n");
+        let (_, instruction_bits, _) = parser(instructions, AvailableDatapaths::MIPS);
+        datapath.initialize(0, instruction_bits)?;
+
+        datapath.execute_instruction();
+        datapath.execute_instruction();
+        datapath.execute_instruction();
+
+        assert_eq!(datapath.interrupts.timer.count, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn compare_is_memory_mapped() -> Result<(), String> {
+        let mut datapath = MipsDatapath::default();
+        let instructions = String::from(
+            "lui $t0, 0x0200
+ori $t1, $zero, 5
+sw $t1, 0x4000($t0)
+lw $t2, 0x4000($t0)",
+        );
+        let (_, instruction_bits, _) = parser(instructions, AvailableDatapaths::MIPS);
+        datapath.initialize(0, instruction_bits)?;
+
+        for _ in 0..4 {
+            datapath.execute_instruction();
+        }
+
+        assert_eq!(datapath.interrupts.timer.compare, 5);
+        assert_eq!(datapath.registers[GpRegisterType::T2], 5);
+        Ok(())
+    }
+
+    #[test]
+    fn mmio_words_are_sign_extended() -> Result<(), String> {
+        let mut datapath = MipsDatapath::default();
+        let instructions = String::from(
+            "lui $t0, 0x0200
+lw $t2, 0x4000($t0)",
+        );
+        let (_, instruction_bits, _) = parser(instructions, AvailableDatapaths::MIPS);
+        datapath.initialize(0, instruction_bits)?;
+        datapath.interrupts.timer.compare = 0x8000_0001;
+
+        datapath.execute_instruction();
+        datapath.execute_instruction();
+
+        assert_eq!(
+            datapath.registers[GpRegisterType::T2],
+            0xFFFF_FFFF_8000_0001
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn timer_interrupt_jumps_to_vector() -> Result<(), String> {
+        let mut datapath = MipsDatapath::default();
+        let instructions = String::from("nop\nnop\nnop\nnop\nnop\nnop\n");
+        let (_, instruction_bits, _) = parser(instructions, AvailableDatapaths::MIPS);
+        datapath.initialize(0, instruction_bits)?;
+
+        datapath.interrupts.timer.compare = 2;
+        datapath.interrupts.enabled = 1 << TIMER_INTERRUPT;
+        datapath.interrupts.global_enable = true;
+        datapath.interrupts.vector = 0x14;
+
+        datapath.execute_instruction();
+        assert_eq!(datapath.registers.pc, 4);
+
+        datapath.execute_instruction();
+        assert_eq!(datapath.registers.pc, 0x14);
+        assert_eq!(datapath.interrupts.return_address, 8);
+        assert_eq!(datapath.interrupts.cause, TIMER_INTERRUPT);
+        assert!(!datapath.interrupts.global_enable);
+        Ok(())
+    }
+}
+
+pub mod riscv {
+    use super::*;
+    use crate::emulation_core::riscv::datapath::RiscDatapath;
+    use crate::emulation_core::riscv::registers::RiscGpRegisterType;
+
+    #[test]
+    fn mtimecmp_is_memory_mapped() -> Result<(), String> {
+        let mut datapath = RiscDatapath::default();
+        let instructions = String::from(
+            "lui t0, 0x2004
+addi t1, zero, 7
+sw t1, 0(t0)
+lw t2, 0(t0)",
+        );
+        let (_, instruction_bits, _) = parser(instructions, AvailableDatapaths::RISCV);
+        datapath.initialize(0, instruction_bits)?;
+
+        for _ in 0..4 {
+            datapath.execute_instruction();
+        }
+
+        assert_eq!(datapath.interrupts.timer.compare, 7);
+        assert_eq!(datapath.registers[RiscGpRegisterType::X7], 7);
+        Ok(())
+    }
+
+    #[test]
+    fn mmio_words_are_sign_extended() -> Result<(), String> {
+        let mut datapath = RiscDatapath::default();
+        let instructions = String::from(
+            "lui t0, 0x2004
+lw t2, 0(t0)",
+        );
+        let (_, instruction_bits, _) = parser(instructions, AvailableDatapaths::RISCV);
+        datapath.initialize(0, instruction_bits)?;
+        datapath.interrupts.timer.compare = 0x8000_0001;
+
+        datapath.execute_instruction();
+        datapath.execute_instruction();
+
+        assert_eq!(
+            datapath.registers[RiscGpRegisterType::X7],
+            0xFFFF_FFFF_8000_0001
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn timer_interrupt_jumps_to_vector() -> Result<(), String> {
+        let mut datapath = RiscDatapath::default();
+        let instructions = String::from("nop\nnop\nnop\nnop\nnop\nnop\n");
+        let (_, instruction_bits, _) = parser(instructions, AvailableDatapaths::RISCV);
+        datapath.initialize(0, instruction_bits)?;
+
+        datapath.interrupts.timer.compare = 1;
+        datapath.interrupts.enabled = 1 << TIMER_INTERRUPT;
+        datapath.interrupts.global_enable = true;
+        datapath.interrupts.vector = 0x10;
+
+        datapath.execute_instruction();
+        assert_eq!(datapath.registers.pc, 0x10);
+        assert_eq!(datapath.interrupts.return_address, 4);
+        assert!(datapath.interrupts.is_pending(TIMER_INTERRUPT));
+        Ok(())
+    }
+}
@@ -968,3 +968,34 @@ fn bne_test_loop() -> Result<(), String> {
         Ok(())
     }
 }
+
+pub mod load_address {
+    use super::*;
+    use crate::{
+        emulation_core::architectures::AvailableDatapaths, parser::parser_assembler_main::parser,
+    };
+
+    #[test]
+    fn la_and_label_load_store() -> Result<(), String> {
+        let mut datapath = RiscDatapath::default();
+
+        let instructions = String::from(
+            ".data\nfirst: .word 9\nsecond: .word 0\n.text\nla s0, second\nlw s1, first\naddi s1, s1, 1\nsw s1, second, t0",
+        );
+        let (_, instruction_bits, labels) = parser(instructions, AvailableDatapaths::RISCV);
+        datapath.initialize(0, instruction_bits)?;
+
+        for _ in 0..7 {
+            datapath.execute_instruction();
+        }
+
+        assert_eq!(datapath.registers.gpr[8], labels["second"] as u64); // s0
+        assert_eq!(datapath.registers.gpr[9], 10); // s1
+        assert_eq!(
+            datapath.memory.load_word(labels["second"] as u64).unwrap(),
+            10
+        );
+
+        Ok(())
+    }
+}
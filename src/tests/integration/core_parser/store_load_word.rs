@@ -122,3 +122,35 @@ fn basic_lwc1() -> Result<(), String> {
 
     Ok(())
 }
+
+#[test]
+fn la_and_lwc1_label() -> Result<(), String> {
+    let mut datapath = MipsDatapath::default();
+
+    let instructions = String::from(
+        r#".data
+values: .word 7, 11
+ratio: .float 2.5
+
+.text
+la $s0, values
+lw $s1, 4($s0)
+lwc1 $f2, ratio"#,
+    );
+
+    let (_, instruction_bits, labels) = parser(instructions, AvailableDatapaths::MIPS);
+    datapath.initialize_legacy(instruction_bits)?;
+
+    while !datapath.is_halted() {
+        datapath.execute_instruction();
+    }
+
+    assert_eq!(datapath.registers.gpr[16], labels["values"] as u64); // $s0
+    assert_eq!(datapath.registers.gpr[17], 11); // $s1
+    assert_eq!(
+        datapath.coprocessor.registers.fpr[2],
+        f32::to_bits(2.5) as u64
+    );
+
+    Ok(())
+}
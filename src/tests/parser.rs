@@ -1,4 +1,31 @@
+use crate::parser::project::ProjectFile;
+
 pub mod assembling;
+pub mod assembly_cache;
+pub mod completion;
+pub mod disassembler;
+pub mod expressions;
+pub mod fix_its;
+pub mod image_export;
+pub mod layout;
+pub mod linker;
+pub mod lints;
+pub mod listing;
+pub mod local_labels;
+pub mod macro_parsing;
+pub mod navigation;
 pub mod parser_assembler_main;
 pub mod parsing;
+pub mod profiles;
+pub mod project;
 pub mod pseudo_instruction_parsing;
+pub mod source_map;
+
+///Creates a file of a project that can be edited, for the tests of projects of several files.
+pub fn file(name: &str, contents: &str) -> ProjectFile {
+    ProjectFile {
+        name: name.to_string(),
+        contents: contents.to_string(),
+        read_only: false,
+    }
+}
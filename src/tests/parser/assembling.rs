@@ -1,7 +1,9 @@
 use crate::emulation_core::architectures::AvailableDatapaths;
 use crate::parser::assembling::assemble_data_binary;
 use crate::parser::parser_assembler_main::parser;
-use crate::parser::parser_structs_and_enums::ErrorType::{NonASCIIChar, NonASCIIString};
+use crate::parser::parser_structs_and_enums::ErrorType::{
+    ImproperlyFormattedASCII, ImproperlyFormattedChar, NonASCIIChar, NonASCIIString,
+};
 use crate::parser::parsing::{separate_data_and_text, tokenize_program};
 mod read_register_tests {
     use crate::parser::assembling::read_register;
@@ -373,6 +375,40 @@ fn assemble_data_binary_works_for_asciiz() {
     assert_eq!(result[5], 0);
 }
 
+#[test]
+fn assemble_data_binary_decodes_escape_sequences() {
+    let mut lines = tokenize_program(
+        ".data\nlabel: .asciiz \"a\\\"# b\\n\\x41\\101\\0\"\nchars: .byte '\\t', ' ', ',', '\\\\'"
+            .to_string(),
+    );
+    let mut modified_data = separate_data_and_text(&mut lines).1;
+    let result = assemble_data_binary(&mut modified_data);
+
+    assert_eq!(
+        result,
+        vec![b'a', b'"', b'#', b' ', b'b', b'\n', b'A', b'A', 0, 0, b'\t', b' ', b',', b'\\']
+    );
+}
+
+#[test]
+fn assemble_data_binary_gives_errors_on_invalid_escape_sequences() {
+    let result = parser(
+        ".data\nlabel: .ascii \"tab\\q\"\nchar: .byte '\\y'".to_string(),
+        AvailableDatapaths::MIPS,
+    )
+    .0;
+
+    let error = &result.monaco_line_info[1].errors[0];
+    assert_eq!(error.error_name, ImproperlyFormattedASCII);
+    assert!(error
+        .message
+        .starts_with("\\q is not a recognized escape sequence"));
+    assert_eq!(
+        result.monaco_line_info[2].errors[0].error_name,
+        ImproperlyFormattedChar
+    );
+}
+
 #[test]
 fn assemble_data_binary_gives_errors_on_non_ascii_characters_for_ascii_asciiz_and_byte() {
     let result = parser(
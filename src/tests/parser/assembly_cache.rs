@@ -0,0 +1,70 @@
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::parser::assembly_cache::{source_version, AssemblyCache};
+use crate::parser::project::parse_project;
+use crate::tests::parser::file;
+
+const PROGRAM: &str = "main: addi $t0, $zero, 3\nloop: addi $t0, $t0, -1\nbne $t0, $zero, loop\n.data\nvalue: .word 5\n";
+
+#[test]
+fn unchanged_source_is_not_assembled_again() {
+    let files = [file("main.asm", PROGRAM)];
+    let mut cache = AssemblyCache::default();
+    assert!(!cache.is_current(&files, AvailableDatapaths::MIPS));
+
+    let first = cache.assemble(&files, AvailableDatapaths::MIPS).clone();
+    assert_eq!(cache.tokenized_lines(), 5);
+    assert_eq!(first.version, source_version(&files));
+    assert!(cache.is_current(&files, AvailableDatapaths::MIPS));
+
+    assert_eq!(cache.assemble(&files, AvailableDatapaths::MIPS), &first);
+    assert_eq!(cache.tokenized_lines(), 5);
+    //the same source for another architecture is a different program
+    assert!(!cache.is_current(&files, AvailableDatapaths::RISCV));
+}
+
+#[test]
+fn only_changed_lines_are_tokenized_again() {
+    let mut cache = AssemblyCache::default();
+    cache.assemble(&[file("main.asm", PROGRAM)], AvailableDatapaths::MIPS);
+
+    let edited = [file(
+        "main.asm",
+        &PROGRAM.replace("addi $t0, $zero, 3", "addi $t0, $zero, 7\nnop"),
+    )];
+    assert!(!cache.is_current(&edited, AvailableDatapaths::MIPS));
+    let assembly = cache.assemble(&edited, AvailableDatapaths::MIPS).clone();
+    assert_eq!(cache.tokenized_lines(), 2);
+
+    //lines that moved keep their tokens but take their new line numbers
+    let (program_info, binary, labels) = parse_project(&edited, AvailableDatapaths::MIPS);
+    assert_eq!(assembly.program_info, program_info);
+    assert_eq!(assembly.binary, binary);
+    assert_eq!(assembly.labels, labels);
+}
+
+#[test]
+fn edited_projects_match_assembling_from_scratch() {
+    let mut files = vec![
+        file("main.asm", ".include \"lib.asm\"\njal count\nsyscall"),
+        file("lib.asm", "count: addi $t1, $zero, 2\njr $ra"),
+    ];
+    let mut cache = AssemblyCache::default();
+    cache.assemble(&files, AvailableDatapaths::MIPS);
+
+    files[1].contents = "count: addi $t1, $zero, 4\njr $ra".to_string();
+    let assembly = cache.assemble(&files, AvailableDatapaths::MIPS).clone();
+    assert_eq!(cache.tokenized_lines(), 1);
+    assert_eq!(
+        assembly.program_info,
+        parse_project(&files, AvailableDatapaths::MIPS).0
+    );
+
+    //an emptied file is assembled like any other empty program
+    files[0].contents = String::new();
+    files.truncate(1);
+    let assembly = cache.assemble(&files, AvailableDatapaths::MIPS).clone();
+    assert_eq!(
+        assembly.program_info,
+        parse_project(&files, AvailableDatapaths::MIPS).0
+    );
+}
@@ -0,0 +1,100 @@
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::parser::completion::{
+    complete, instruction_syntaxes, CompletionKind, Completions, OperandKind,
+};
+
+fn labels_of(completions: &Completions) -> Vec<&str> {
+    completions
+        .items
+        .iter()
+        .map(|item| item.label.as_str())
+        .collect()
+}
+
+#[test]
+fn operator_is_completed_with_instructions_of_the_architecture() {
+    let completions = complete("main: ad", 8, &[], AvailableDatapaths::MIPS);
+    assert_eq!(completions.start_column, 6);
+    let add = completions
+        .items
+        .iter()
+        .find(|item| item.label == "add")
+        .unwrap();
+    assert_eq!(add.kind, CompletionKind::Instruction);
+    assert_eq!(add.detail, "add rd, rs, rt");
+    assert_eq!(add.insert_text, "add ${1:rd}, ${2:rs}, ${3:rt}");
+    assert!(add.documentation.starts_with("**Syntax:**"));
+
+    let li = completions
+        .items
+        .iter()
+        .find(|item| item.label == "li")
+        .unwrap();
+    assert_eq!(li.kind, CompletionKind::PseudoInstruction);
+    assert!(labels_of(&completions).contains(&"syscall"));
+
+    let riscv = complete("  ", 2, &[], AvailableDatapaths::RISCV);
+    assert!(labels_of(&riscv).contains(&"fmadd.s"));
+    assert!(!labels_of(&riscv).contains(&"dadd"));
+}
+
+#[test]
+fn memory_operands_have_a_placeholder_for_offset_and_base() {
+    let lw = instruction_syntaxes(AvailableDatapaths::MIPS)
+        .iter()
+        .find(|syntax| syntax.mnemonic == "lw")
+        .unwrap();
+    assert_eq!(lw.snippet(), "lw ${1:rt}, ${2:offset}(${3:base})");
+    assert_eq!(lw.operands[1].1, OperandKind::MemoryAddress);
+}
+
+#[test]
+fn operands_are_completed_with_registers_of_their_kind() {
+    let mips = complete("add.s $f0, ", 11, &[], AvailableDatapaths::MIPS);
+    assert_eq!(mips.start_column, 11);
+    assert!(labels_of(&mips).contains(&"$f2"));
+    assert!(!labels_of(&mips).contains(&"$t0"));
+
+    let gp = complete("add $t0, $", 10, &[], AvailableDatapaths::MIPS);
+    assert_eq!(gp.start_column, 9);
+    assert!(labels_of(&gp).contains(&"$t1"));
+
+    //the destination of a comparison is a general-purpose register even though its sources are not
+    let destination = complete("feq.s ", 6, &[], AvailableDatapaths::RISCV);
+    assert!(labels_of(&destination).contains(&"a0"));
+    assert!(!labels_of(&destination).contains(&"ft0"));
+    let source = complete("feq.s a0, ", 10, &[], AvailableDatapaths::RISCV);
+    assert!(labels_of(&source).contains(&"ft0"));
+    assert_eq!(source.items[0].detail, "f0");
+}
+
+#[test]
+fn labels_are_completed_where_an_instruction_accepts_one() {
+    let labels = vec!["loop".to_string(), "done".to_string()];
+    let branch = complete("bne $t0, $zero, lo", 18, &labels, AvailableDatapaths::MIPS);
+    assert_eq!(branch.start_column, 16);
+    assert_eq!(labels_of(&branch), vec!["loop", "done"]);
+    assert_eq!(branch.items[0].kind, CompletionKind::Label);
+
+    let address = complete("lw $t0, ", 8, &labels, AvailableDatapaths::MIPS);
+    assert_eq!(labels_of(&address), vec!["loop", "done"]);
+    let base = complete("lw $t0, 4(", 10, &labels, AvailableDatapaths::MIPS);
+    assert!(labels_of(&base).contains(&"$sp"));
+
+    //immediates are left to the user
+    let immediate = complete("addi $t0, $t0, ", 15, &labels, AvailableDatapaths::MIPS);
+    assert!(immediate.items.is_empty());
+}
+
+#[test]
+fn nothing_is_completed_in_comments_or_strings() {
+    assert!(complete("add $t0 # ad", 12, &[], AvailableDatapaths::MIPS)
+        .items
+        .is_empty());
+    assert!(complete(".asciiz \"ad", 11, &[], AvailableDatapaths::MIPS)
+        .items
+        .is_empty());
+    assert!(complete(".wo", 3, &[], AvailableDatapaths::MIPS)
+        .items
+        .is_empty());
+}
@@ -0,0 +1,192 @@
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::emulation_core::mips::memory::CAPACITY_BYTES;
+use crate::parser::disassembler::{
+    disassemble, read_binary_image, read_hex_image, reproduces_image,
+};
+use crate::parser::parser_assembler_main::parser;
+
+///Assembles the program, disassembles its binary, and checks that the disassembly assembles to the same binary.
+fn assert_round_trip(program: &str, arch: AvailableDatapaths) -> String {
+    let (program_info, binary, _) = parser(program.to_string(), arch);
+    assert!(program_info
+        .monaco_line_info
+        .iter()
+        .all(|line| line.errors.is_empty()));
+
+    let source = disassemble(&binary, Some(program_info.data_starting_point), arch);
+    let (disassembled_info, disassembled_binary, _) = parser(source.clone(), arch);
+    assert!(
+        disassembled_info
+            .monaco_line_info
+            .iter()
+            .all(|line| line.errors.is_empty()),
+        "{source}"
+    );
+    assert_eq!(disassembled_binary[..binary.len()], binary, "{source}");
+    source
+}
+
+mod image_reading_tests {
+    use super::*;
+
+    #[test]
+    fn binary_image_is_read_big_endian_and_padded() {
+        assert_eq!(
+            read_binary_image(&[0x20, 0x08, 0x00, 0x01, 0xAB]),
+            Ok(vec![0x20080001, 0xAB000000])
+        );
+    }
+
+    #[test]
+    fn hex_image_skips_addresses_and_comments() {
+        assert_eq!(
+            read_hex_image("0000: 20080001 0x0000000c # syscall\n\n0008: ff, 0X10").unwrap(),
+            vec![0x20080001, 0x0000000c, 0xff, 0x10]
+        );
+    }
+
+    #[test]
+    fn hex_image_rejects_words_that_are_too_long() {
+        assert_eq!(
+            read_hex_image("20080001\n123456789"),
+            Err("`123456789` on line 2 is not a hexadecimal word.".to_string())
+        );
+    }
+
+    #[test]
+    fn images_larger_than_memory_are_rejected() {
+        assert!(read_binary_image(&vec![0; CAPACITY_BYTES]).is_ok());
+        assert!(read_binary_image(&vec![0; CAPACITY_BYTES + 1]).is_err());
+
+        let words = vec!["0"; CAPACITY_BYTES / 4 + 1].join(" ");
+        assert_eq!(
+            read_hex_image(&words),
+            Err(format!(
+                "The image is {} bytes, which does not fit in the {CAPACITY_BYTES} bytes of memory.",
+                CAPACITY_BYTES + 4
+            ))
+        );
+    }
+}
+
+mod disassembly_tests {
+    use super::*;
+
+    #[test]
+    fn branch_targets_are_given_labels() {
+        let source = assert_round_trip(
+            "addi $t0, $zero, 3\nloop: addi $t0, $t0, -1\nbne $t0, $zero, loop\nj loop\nsyscall",
+            AvailableDatapaths::MIPS,
+        );
+
+        assert!(source.contains("loc_0004:\n        addi $t0, $t0, -1"));
+        assert!(source.contains("bne $t0, $zero, loc_0004"));
+        assert!(source.contains("j loc_0004"));
+    }
+
+    #[test]
+    fn data_section_is_kept_after_the_text() {
+        let source = assert_round_trip(
+            ".data\nvalue: .word 7, 9\n.text\nlw $t0, value\nsyscall",
+            AvailableDatapaths::MIPS,
+        );
+
+        assert!(source.contains(".data 0x"));
+        assert!(source.contains(".word 0x00000007, 0x00000009"));
+    }
+
+    #[test]
+    fn undecodable_words_are_written_as_words() {
+        let source = disassemble(&[0xFFFFFFFF, 0x0000000C], None, AvailableDatapaths::MIPS);
+
+        assert_eq!(source, ".text\n        .word 0xffffffff\n        syscall\n");
+    }
+
+    #[test]
+    fn trailing_zeros_are_left_out() {
+        let source = disassemble(&[0x0000000C, 0, 0], None, AvailableDatapaths::MIPS);
+
+        assert_eq!(source, ".text\n        syscall\n");
+    }
+
+    #[test]
+    fn long_runs_of_zeros_are_skipped() {
+        let source = assert_round_trip(
+            ".data\nbuffer: .space 64\nvalue: .word 3\n.text\nj end\n.org 0x100\nend: syscall",
+            AvailableDatapaths::MIPS,
+        );
+
+        assert!(source.contains(".org 0x100\nloc_0100:\n        syscall"));
+        assert!(source.contains(": .space 64\n"));
+    }
+
+    #[test]
+    fn arbitrary_words_assemble_back_to_the_image() {
+        //a linear congruential generator gives the same words on every run
+        let mut seed: u32 = 12345;
+        let image: Vec<u32> = (0..512)
+            .map(|_| {
+                seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+                seed
+            })
+            .collect();
+
+        for arch in [AvailableDatapaths::MIPS, AvailableDatapaths::RISCV] {
+            let source = disassemble(&image, None, arch);
+            let (_, binary, _) = parser(source.clone(), arch);
+            assert_eq!(binary[..image.len()], image, "{source}");
+        }
+    }
+
+    #[test]
+    fn fibonacci_examples_round_trip() {
+        assert_round_trip(
+            include_str!("../../../static/assembly_examples/fibonacci.asm"),
+            AvailableDatapaths::MIPS,
+        );
+        assert_round_trip(
+            include_str!("../../../static/assembly_examples/riscv_fib_recursive.asm"),
+            AvailableDatapaths::RISCV,
+        );
+    }
+
+    #[test]
+    fn riscv_branches_are_given_labels() {
+        let source = assert_round_trip(
+            "addi t0, zero, 3\nloop: addi t0, t0, -1\nbne t0, zero, loop\necall",
+            AvailableDatapaths::RISCV,
+        );
+
+        assert!(source.contains("loc_0004:"));
+        assert!(source.contains(", loc_0004"));
+    }
+
+    #[test]
+    fn reassembled_disassembly_reproduces_the_image() {
+        //the image does not end with a syscall, so one is added in place of its trailing zeros
+        let image = [0x20080001, 0x20090002, 0, 0];
+        let source = disassemble(&image, None, AvailableDatapaths::MIPS);
+        let (_, binary, _) = parser(source, AvailableDatapaths::MIPS);
+
+        assert!(reproduces_image(&binary, &image));
+        assert!(!reproduces_image(&binary, &[0x20080001, 0x20090003]));
+    }
+}
+
+mod raw_word_tests {
+    use super::*;
+
+    #[test]
+    fn word_in_text_is_placed_between_instructions() {
+        let (program_info, binary, _) = parser(
+            "addi $t0, $zero, 1\n.word 0xDEADBEEF\nsyscall".to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert!(program_info
+            .monaco_line_info
+            .iter()
+            .all(|line| line.errors.is_empty()));
+        assert_eq!(binary[1], 0xDEADBEEF);
+    }
+}
@@ -0,0 +1,396 @@
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::parser::expressions::*;
+use crate::parser::parser_assembler_main::parser;
+use crate::parser::parser_structs_and_enums::ErrorType::{
+    ImproperlyFormattedConstant, InvalidRelocation, LabelNotFound, NonASCIIChar,
+};
+use std::collections::HashMap;
+
+mod evaluate_expression_tests {
+    use super::*;
+
+    fn evaluate(expression: &str) -> Result<i64, ExpressionError> {
+        evaluate_expression(expression, &HashMap::new())
+    }
+
+    #[test]
+    fn literals() {
+        assert_eq!(evaluate("42"), Ok(42));
+        assert_eq!(evaluate("-7"), Ok(-7));
+        assert_eq!(evaluate("0x1F"), Ok(31));
+        assert_eq!(evaluate("0b101"), Ok(5));
+        assert_eq!(evaluate("'A'"), Ok(65));
+        assert_eq!(evaluate("'\\n'"), Ok(10));
+    }
+
+    #[test]
+    fn char_literals_support_escape_sequences() {
+        assert_eq!(evaluate("'\\0'"), Ok(0));
+        assert_eq!(evaluate("'\\x41'"), Ok(65));
+        assert_eq!(evaluate("'\\101'"), Ok(65));
+        assert_eq!(evaluate("'\\''"), Ok(39));
+        assert_eq!(evaluate("' ' + 1"), Ok(33));
+        assert_eq!(evaluate("'é'"), Err(ExpressionError::NonASCII('é')));
+        assert_eq!(
+            evaluate("'\\q'"),
+            Err(ExpressionError::InvalidEscape("\\q".to_string()))
+        );
+        assert_eq!(evaluate("'ab'"), Err(ExpressionError::Syntax));
+    }
+
+    #[test]
+    fn strings_are_decoded_into_bytes() {
+        assert_eq!(
+            decode_string("a\\tb\\n\\\"\\x7f\\0"),
+            Ok(vec![b'a', b'\t', b'b', b'\n', b'"', 0x7f, 0])
+        );
+        assert_eq!(decode_string("\\1234"), Ok(vec![0o123, b'4']));
+        assert_eq!(
+            decode_string("\\xg"),
+            Err(ExpressionError::InvalidEscape("\\x".to_string()))
+        );
+        assert_eq!(decode_string("🦧"), Err(ExpressionError::NonASCII('🦧')));
+    }
+
+    #[test]
+    fn operators_follow_precedence() {
+        assert_eq!(evaluate("2 + 3 * 4"), Ok(14));
+        assert_eq!(evaluate("(2 + 3) * 4"), Ok(20));
+        assert_eq!(evaluate("1 << 4 | 1"), Ok(17));
+        assert_eq!(evaluate("0xFF & ~0x0F"), Ok(0xF0));
+        assert_eq!(evaluate("17 % 5 - 10 / 4"), Ok(0));
+        assert_eq!(evaluate("-8 >> 1"), Ok(-4));
+        assert_eq!(evaluate("6 ^ 3"), Ok(5));
+    }
+
+    #[test]
+    fn labels_evaluate_to_their_address() {
+        let labels = HashMap::from([("start".to_string(), 0x10), ("end".to_string(), 0x30)]);
+
+        assert_eq!(evaluate_expression("(end - start) / 4", &labels), Ok(8));
+        assert_eq!(evaluate_expression("start + 8", &labels), Ok(0x18));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(evaluate("1 +"), Err(ExpressionError::Syntax));
+        assert_eq!(evaluate("(1 + 2"), Err(ExpressionError::Syntax));
+        assert_eq!(evaluate("$t0"), Err(ExpressionError::Syntax));
+        assert_eq!(evaluate("5 / 0"), Err(ExpressionError::DivisionByZero));
+        assert_eq!(
+            evaluate("missing + 1"),
+            Err(ExpressionError::UnknownSymbol("missing".to_string()))
+        );
+    }
+
+    #[test]
+    fn constants_are_substituted_by_whole_word() {
+        let constants = HashMap::from([
+            ("SIZE".to_string(), "16".to_string()),
+            ("t0".to_string(), "99".to_string()),
+        ]);
+
+        assert_eq!(substitute_constants("SIZE*2", &constants), "16*2");
+        assert_eq!(substitute_constants("SIZES", &constants), "SIZES");
+        assert_eq!(substitute_constants("$t0", &constants), "$t0");
+        assert_eq!(substitute_constants("\"SIZE\"", &constants), "\"SIZE\"");
+    }
+}
+
+mod assembled_expression_tests {
+    use super::*;
+
+    #[test]
+    fn eqv_constants_in_immediates() {
+        let (_, binary, _) = parser(
+            ".eqv PRINT_INT 1\n.equ OFFSET, PRINT_INT * 8\naddi $t0, $zero, PRINT_INT\naddi $t1, $zero, OFFSET + 2"
+                .to_string(),
+            AvailableDatapaths::MIPS,
+        );
+        let (_, expected, _) = parser(
+            "addi $t0, $zero, 1\naddi $t1, $zero, 10".to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert_eq!(binary, expected);
+    }
+
+    #[test]
+    fn constants_can_name_registers() {
+        let (_, binary, _) = parser(
+            ".eqv COUNTER t2\n.set STEP, 4\naddi COUNTER, COUNTER, STEP".to_string(),
+            AvailableDatapaths::RISCV,
+        );
+        let (_, expected, _) = parser("addi t2, t2, 4".to_string(), AvailableDatapaths::RISCV);
+
+        assert_eq!(binary, expected);
+    }
+
+    #[test]
+    fn set_can_redefine_a_constant() {
+        let (program_info, _, _) = parser(
+            ".set N, 1\naddi $t0, $zero, N\n.set N, N + 1\naddi $t0, $zero, N".to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert_eq!(program_info.instructions[0].operands[2].token_name, "1");
+        assert_eq!(program_info.instructions[1].operands[2].token_name, "2");
+    }
+
+    #[test]
+    fn memory_offsets_and_char_literals() {
+        let (_, binary, _) = parser(
+            "lw $t0, 4 * 2($sp)\naddi $t1, $zero, 'a' + 1".to_string(),
+            AvailableDatapaths::MIPS,
+        );
+        let (_, expected, _) = parser(
+            "lw $t0, 8($sp)\naddi $t1, $zero, 98".to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert_eq!(binary, expected);
+    }
+
+    #[test]
+    fn char_literals_with_spaces_commas_and_escapes_in_immediates() {
+        let (_, binary, _) = parser(
+            "li $t0, ' '\naddi $t1, $zero, ','\naddi $t2, $zero, '\\n' # newline\naddi $t3, $zero, '#'"
+                .to_string(),
+            AvailableDatapaths::MIPS,
+        );
+        let (_, expected, _) = parser(
+            "li $t0, 32\naddi $t1, $zero, 44\naddi $t2, $zero, 10\naddi $t3, $zero, 35".to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert_eq!(binary, expected);
+    }
+
+    #[test]
+    fn multi_byte_char_literals_are_rejected() {
+        let (program_info, _, _) = parser("li $t0, 'é'".to_string(), AvailableDatapaths::MIPS);
+        let error = &program_info.monaco_line_info[0].errors[0];
+
+        assert_eq!(error.error_name, NonASCIIChar);
+        assert!(error
+            .message
+            .starts_with("'é' is encoded in UTF-8 as 2 bytes"));
+    }
+
+    #[test]
+    fn label_differences_in_data() {
+        let (program_info, binary, labels) = parser(
+            ".data\nstart: .word 1, 2, 3\nend: .word 0\nlength: .word (end - start) / 4\npointer: .word start + 8\n.text\nnop"
+                .to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert!(program_info
+            .data
+            .iter()
+            .all(|datum| datum.errors.is_empty()));
+        let start = labels["start"];
+        let length_word = binary[labels["length"] / 4];
+        let pointer_word = binary[labels["pointer"] / 4];
+        assert_eq!(length_word, 3);
+        assert_eq!(pointer_word as usize, start + 8);
+    }
+
+    #[test]
+    fn label_expressions_in_instructions() {
+        let (program_info, _, _) = parser(
+            "first: nop\nsecond: nop\naddi $t0, $zero, second - first".to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert!(program_info.instructions[2].errors.is_empty());
+        assert_eq!(program_info.instructions[2].operands[2].token_name, "4");
+    }
+
+    #[test]
+    fn unknown_label_in_expression() {
+        let (program_info, _, _) = parser(
+            ".data\nvalue: .word nowhere + 4\n.text\naddi $t0, $zero, nowhere - 1".to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert_eq!(program_info.data[0].errors[0].error_name, LabelNotFound);
+        assert_eq!(
+            program_info.instructions[0].errors[0].error_name,
+            LabelNotFound
+        );
+        assert_eq!(program_info.instructions[0].errors.len(), 1);
+    }
+
+    #[test]
+    fn constant_without_value_is_an_error() {
+        let (program_info, _, _) = parser(".eqv SIZE\nnop".to_string(), AvailableDatapaths::MIPS);
+
+        assert_eq!(
+            program_info.monaco_line_info[0].errors[0].error_name,
+            ImproperlyFormattedConstant
+        );
+    }
+}
+
+mod relocation_tests {
+    use super::*;
+
+    #[test]
+    fn read_relocation_splits_operator_expression_and_base() {
+        assert_eq!(
+            read_relocation("%hi(label)"),
+            Some((Relocation::Hi, "label", ""))
+        );
+        assert_eq!(
+            read_relocation("%lo((end - start) * 2)(t0)"),
+            Some((Relocation::Lo, "(end - start) * 2", "(t0)"))
+        );
+        assert_eq!(
+            read_relocation("%pcrel_hi(value)"),
+            Some((Relocation::PcrelHi, "value", ""))
+        );
+        assert_eq!(read_relocation("%mid(label)"), None);
+        assert_eq!(read_relocation("%lo(label"), None);
+        assert_eq!(read_relocation("label"), None);
+    }
+
+    #[test]
+    fn riscv_hi_and_lo_rebuild_the_address() {
+        let (program_info, binary, labels) = parser(
+            ".data\nfiller: .space 2044\nvalue: .word 5\n.text\nlui t0, %hi(value)\naddi t0, t0, %lo(value)\nlw t1, %lo(value + 4)(t0)"
+                .to_string(),
+            AvailableDatapaths::RISCV,
+        );
+
+        let address = labels["value"] as i64;
+        let hi: i64 = program_info.instructions[0].operands[1]
+            .token_name
+            .parse()
+            .unwrap();
+        let lo: i64 = program_info.instructions[1].operands[2]
+            .token_name
+            .parse()
+            .unwrap();
+        assert!(program_info
+            .instructions
+            .iter()
+            .all(|instruction| instruction.errors.is_empty()));
+        assert!(lo < 0);
+        assert_eq!((hi << 12) + lo, address);
+        assert_eq!(
+            program_info.instructions[2].operands[1].token_name,
+            format!("{}(t0)", ((address + 4) << 52) >> 52)
+        );
+        assert!(!binary.is_empty());
+    }
+
+    #[test]
+    fn mips_operators_select_each_half_word() {
+        let (program_info, _, _) = parser(
+            ".eqv BIG 0x123456789ABCDEF0\nlui $t0, %highest(BIG)\nori $t0, $t0, %higher(BIG)\nori $t0, $t0, %hi(BIG)\nori $t0, $t0, %lo(BIG)"
+                .to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        let operand =
+            |i: usize, j: usize| program_info.instructions[i].operands[j].token_name.clone();
+        assert_eq!(operand(0, 1), 0x1234.to_string());
+        assert_eq!(operand(1, 2), 0x5679.to_string());
+        assert_eq!(operand(2, 2), 0x9ABD.to_string());
+        assert_eq!(operand(3, 2), (-0x2110).to_string());
+    }
+
+    #[test]
+    fn pcrel_lo_refers_to_the_labelled_auipc() {
+        let (program_info, _, labels) = parser(
+            ".data\nvalue: .word 5\n.text\nnop\nhere: auipc t0, %pcrel_hi(value)\nnop\nlw t1, %pcrel_lo(here)(t0)"
+                .to_string(),
+            AvailableDatapaths::RISCV,
+        );
+
+        let distance = labels["value"] as i64 - 4;
+        assert!(program_info
+            .instructions
+            .iter()
+            .all(|instruction| instruction.errors.is_empty()));
+        assert_eq!(
+            program_info.instructions[1].operands[1].token_name,
+            ((distance + 0x800) >> 12).to_string()
+        );
+        assert_eq!(
+            program_info.instructions[3].operands[1].token_name,
+            format!("{}(t0)", (distance << 52) >> 52)
+        );
+    }
+
+    #[test]
+    fn relocation_errors() {
+        let (program_info, _, _) = parser(
+            "lui t0, %higher(0)\naddi t0, t0, %pcrel_lo(nowhere)\nlui t1, %hi(missing)".to_string(),
+            AvailableDatapaths::RISCV,
+        );
+
+        assert_eq!(
+            program_info.instructions[0].errors[0].error_name,
+            InvalidRelocation
+        );
+        assert_eq!(
+            program_info.instructions[1].errors[0].error_name,
+            InvalidRelocation
+        );
+        assert_eq!(
+            program_info.instructions[2].errors[0].error_name,
+            LabelNotFound
+        );
+        assert_eq!(program_info.instructions[2].errors.len(), 1);
+    }
+
+    #[test]
+    fn mips_la_expands_to_relocations() {
+        let (program_info, _, labels) = parser(
+            ".data\nmessage: .asciiz \"hi\"\n.text\nla $a0, message".to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert_eq!(program_info.instructions[0].operator.token_name, "lui");
+        assert_eq!(program_info.instructions[1].operator.token_name, "daddiu");
+        assert_eq!(
+            program_info.instructions[1].operands[2].token_name,
+            labels["message"].to_string()
+        );
+        assert!(program_info
+            .updated_monaco_string
+            .contains("lui $a0, %hi(message) #Pseudo-Instruction Translation"));
+    }
+
+    #[test]
+    fn riscv_loads_and_stores_of_labels() {
+        let (program_info, _, _) = parser(
+            ".data\nvalue: .word 5\n.text\nlb a0, value\nsh a0, value, t1\nflw ft0, value, t2"
+                .to_string(),
+            AvailableDatapaths::RISCV,
+        );
+
+        let operators: Vec<&str> = program_info
+            .instructions
+            .iter()
+            .map(|instruction| instruction.operator.token_name.as_str())
+            .collect();
+        assert_eq!(
+            operators[..6],
+            ["auipc", "lb", "auipc", "sh", "auipc", "flw"]
+        );
+        assert!(program_info
+            .instructions
+            .iter()
+            .all(|instruction| instruction.errors.is_empty()));
+        assert_eq!(program_info.instructions[2].operands[0].token_name, "t1");
+        assert!(program_info.instructions[3].operands[1]
+            .token_name
+            .ends_with("(t1)"));
+        assert!(program_info.instructions[5].operands[1]
+            .token_name
+            .ends_with("(t2)"));
+    }
+}
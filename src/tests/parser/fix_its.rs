@@ -0,0 +1,77 @@
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::parser::fix_its::{apply_fix_it, apply_fix_its, fix_its_in_file};
+use crate::parser::parser_assembler_main::parser;
+use crate::parser::parser_structs_and_enums::FixIt;
+use crate::parser::project::parse_project;
+use crate::tests::parser::file;
+
+fn fixed_program(program: &str, arch: AvailableDatapaths) -> String {
+    let (program_info, _, _) = parser(program.to_string(), arch);
+    apply_fix_its(program, &fix_its_in_file(&program_info, ""))
+}
+
+#[test]
+fn misspelled_register_is_replaced() {
+    let (program_info, _, _) = parser("add $t0, $t1, $tt2".to_string(), AvailableDatapaths::MIPS);
+    let error = &program_info.monaco_line_info[0].errors[0];
+    assert_eq!(
+        error.fix_its,
+        vec![FixIt {
+            title: "Replace with `$t2`".to_string(),
+            start_end_columns: (14, 18),
+            replacement: "$t2".to_string(),
+        }]
+    );
+    assert_eq!(
+        apply_fix_it("add $t0, $t1, $tt2", &error.fix_its[0]),
+        "add $t0, $t1, $t2"
+    );
+}
+
+#[test]
+fn every_fix_it_of_a_program_is_applied() {
+    assert_eq!(
+        fixed_program(
+            "addd $t0, $t1, $t2\nloop: j lop\nsub.s $f1, $f2, $f3,\nadd $t0 $t1, $t2\n",
+            AvailableDatapaths::MIPS
+        ),
+        "add $t0, $t1, $t2\nloop: j loop\nsub.s $f1, $f2, $f3\nadd $t0, $t1, $t2\n"
+    );
+    assert_eq!(
+        fixed_program(".data\nx: .wrd 4 5", AvailableDatapaths::MIPS),
+        ".data\nx: .word 4, 5"
+    );
+    assert_eq!(
+        fixed_program("add x1, x2, xx3", AvailableDatapaths::RISCV),
+        "add x1, x2, x3"
+    );
+}
+
+#[test]
+fn errors_without_a_likely_correction_have_no_fix_it() {
+    let (program_info, _, _) = parser(
+        "add $t0, $t1, $qqqqqq".to_string(),
+        AvailableDatapaths::MIPS,
+    );
+    assert!(program_info.monaco_line_info[0].errors[0]
+        .fix_its
+        .is_empty());
+}
+
+#[test]
+fn fix_its_are_found_on_the_lines_of_their_file() {
+    let files = [
+        file("main.asm", ".include \"lib.asm\"\nadd $t0, $t1, $t2"),
+        file("lib.asm", "# helpers\nsub $t0, $tt1, $t2"),
+    ];
+    let (program_info, _, _) = parse_project(&files, AvailableDatapaths::MIPS);
+
+    assert!(fix_its_in_file(&program_info, "main.asm").is_empty());
+    let fix_its = fix_its_in_file(&program_info, "lib.asm");
+    assert_eq!(fix_its.len(), 1);
+    assert_eq!(fix_its[0].0, 1);
+    assert_eq!(
+        apply_fix_its(&files[1].contents, &fix_its),
+        "# helpers\nsub $t0, $t1, $t2"
+    );
+}
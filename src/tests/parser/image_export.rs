@@ -0,0 +1,130 @@
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::parser::image_export::{export_image, Endianness, ExportOptions, ImageFormat};
+use crate::parser::parser_assembler_main::parser;
+
+///Exports the words with the given format, endianness, and word width.
+fn export(
+    binary: &[u32],
+    format: ImageFormat,
+    endianness: Endianness,
+    word_bytes: usize,
+) -> String {
+    export_image(
+        binary,
+        ExportOptions {
+            format,
+            endianness,
+            word_bytes,
+        },
+    )
+    .unwrap()
+}
+
+#[test]
+fn readmemh_writes_one_word_per_line() {
+    assert_eq!(
+        export(
+            &[0x20080001, 0x0000000C],
+            ImageFormat::ReadMemH,
+            Endianness::Big,
+            4
+        ),
+        "// 2 words of 32 bits, starting at address 0\n20080001\n0000000c\n"
+    );
+}
+
+#[test]
+fn readmemb_writes_binary_words() {
+    assert_eq!(
+        export(&[0x8001], ImageFormat::ReadMemB, Endianness::Big, 2),
+        "// 2 words of 16 bits, starting at address 0\n0000000000000000\n1000000000000001\n"
+    );
+}
+
+#[test]
+fn little_endian_reverses_bytes_within_words() {
+    assert_eq!(
+        export(&[0x20080001], ImageFormat::ReadMemH, Endianness::Little, 4),
+        "// 1 words of 32 bits, starting at address 0\n01000820\n"
+    );
+    assert_eq!(
+        export(
+            &[0x20080001, 0x0000000C],
+            ImageFormat::ReadMemH,
+            Endianness::Little,
+            8
+        ),
+        "// 1 words of 64 bits, starting at address 0\n0c00000001000820\n"
+    );
+}
+
+#[test]
+fn byte_wide_words_split_each_word() {
+    assert_eq!(
+        export(&[0x20080001], ImageFormat::ReadMemH, Endianness::Big, 1),
+        "// 4 words of 8 bits, starting at address 0\n20\n08\n00\n01\n"
+    );
+}
+
+#[test]
+fn logisim_image_compresses_repeated_words() {
+    assert_eq!(
+        export(
+            &[0x20080001, 0, 0, 0, 0, 0x0000000C, 0x0000000C],
+            ImageFormat::LogisimRaw,
+            Endianness::Big,
+            4
+        ),
+        "v2.0 raw\n20080001 4*0 c c\n"
+    );
+}
+
+#[test]
+fn intel_hex_records_have_checksums() {
+    assert_eq!(
+        export(&[0x20080001], ImageFormat::IntelHex, Endianness::Big, 4),
+        ":0400000020080001D3\n:00000001FF\n"
+    );
+}
+
+#[test]
+fn intel_hex_splits_records_every_16_bytes() {
+    let image = export(&[1; 5], ImageFormat::IntelHex, Endianness::Big, 4);
+    let records: Vec<&str> = image.lines().collect();
+
+    assert_eq!(records.len(), 3);
+    assert!(records[0].starts_with(":10000000"));
+    assert!(records[1].starts_with(":04001000"));
+}
+
+#[test]
+fn s_record_has_header_data_and_termination() {
+    assert_eq!(
+        export(&[0x20080001], ImageFormat::SRecord, Endianness::Big, 4),
+        "S00700005357494DB8\nS107000020080001CF\nS9030000FC\n"
+    );
+}
+
+#[test]
+fn unsupported_word_width_is_rejected() {
+    assert!(export_image(
+        &[0],
+        ExportOptions {
+            word_bytes: 3,
+            ..Default::default()
+        }
+    )
+    .is_err());
+}
+
+#[test]
+fn assembled_program_is_exported() {
+    let (_, binary, _) = parser(
+        "addi $t0, $zero, 1\nsyscall".to_string(),
+        AvailableDatapaths::MIPS,
+    );
+    let image = export(&binary, ImageFormat::ReadMemH, Endianness::Big, 4);
+
+    assert_eq!(image.lines().nth(1), Some("20080001"));
+    assert_eq!(image.lines().nth(2), Some("0000000c"));
+}
@@ -0,0 +1,204 @@
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::parser::parser_assembler_main::parser;
+use crate::parser::parser_structs_and_enums::ErrorType::{InitializedBssData, InvalidPlacement};
+use crate::parser::parser_structs_and_enums::ProgramInfo;
+
+fn has_no_errors(program_info: &ProgramInfo) -> bool {
+    program_info
+        .monaco_line_info
+        .iter()
+        .all(|line| line.errors.is_empty())
+}
+
+mod data_layout_tests {
+    use super::*;
+
+    #[test]
+    fn align_pads_data_to_the_alignment() {
+        let (program_info, binary, labels) = parser(
+            ".data\nflag: .byte 1\n.align 2\nvalue: .word 5\n.text\nsyscall".to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert!(has_no_errors(&program_info));
+        assert_eq!(labels.get("flag"), Some(&4));
+        assert_eq!(labels.get("value"), Some(&8));
+        assert_eq!(binary[1..], [0x01000000, 5]);
+    }
+
+    #[test]
+    fn label_on_balign_line_refers_to_the_aligned_address() {
+        let (program_info, _, labels) = parser(
+            ".data\nflag: .byte 1\naligned: .balign 8\n.word 3\n.text\nsyscall".to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert!(has_no_errors(&program_info));
+        assert_eq!(labels.get("aligned"), Some(&8));
+    }
+
+    #[test]
+    fn balign_must_be_a_power_of_two() {
+        let (program_info, _, _) = parser(
+            ".data\nflag: .byte 1\n.balign 3\n.text\nsyscall".to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert_eq!(
+            program_info.monaco_line_info[2].errors[0].error_name,
+            InvalidPlacement
+        );
+    }
+
+    #[test]
+    fn dword_and_quad_store_64_bit_values() {
+        let (program_info, binary, _) = parser(
+            ".data\nbig: .dword 0x0102030405060708\nall: .quad -1\n.text\necall".to_string(),
+            AvailableDatapaths::RISCV,
+        );
+
+        assert!(has_no_errors(&program_info));
+        let data = &binary[program_info.data_starting_point..];
+        assert_eq!(data, [0x01020304, 0x05060708, 0xFFFFFFFF, 0xFFFFFFFF]);
+    }
+
+    #[test]
+    fn dword_can_hold_the_address_of_a_label() {
+        let (program_info, binary, labels) = parser(
+            ".data\n.align 3\npointer: .dword target\n.text\nnop\ntarget: syscall".to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert!(has_no_errors(&program_info));
+        assert_eq!(labels.get("pointer"), Some(&8));
+        assert_eq!(binary[2..4], [0, *labels.get("target").unwrap() as u32]);
+    }
+
+    #[test]
+    fn data_address_moves_the_data_section() {
+        let (program_info, binary, labels) = parser(
+            ".data 0x200\nvalue: .word 7\n.text\nsyscall".to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert!(has_no_errors(&program_info));
+        assert_eq!(labels.get("value"), Some(&0x200));
+        assert_eq!(binary[0x200 / 4], 7);
+    }
+
+    #[test]
+    fn rodata_section_holds_data() {
+        let (program_info, _, labels) = parser(
+            ".section .rodata\nmessage: .asciiz \"hi\"\n.section .text\nmain: syscall".to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert!(has_no_errors(&program_info));
+        assert_eq!(labels.get("message"), Some(&4));
+    }
+
+    #[test]
+    fn bss_only_reserves_space() {
+        let (program_info, _, _) = parser(
+            ".bss\nbuffer: .space 8\ncount: .word 1\n.text\nsyscall".to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert!(program_info.monaco_line_info[1].errors.is_empty());
+        assert_eq!(
+            program_info.monaco_line_info[2].errors[0].error_name,
+            InitializedBssData
+        );
+    }
+}
+
+mod text_layout_tests {
+    use super::*;
+
+    #[test]
+    fn text_address_moves_the_instructions() {
+        let (program_info, binary, labels) = parser(
+            ".text 0x100\nmain: addi $t0, $zero, 1\nsyscall".to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert!(has_no_errors(&program_info));
+        assert_eq!(labels.get("main"), Some(&0x100));
+        assert_eq!(program_info.pc_starting_point, 0x100);
+        assert_eq!(binary.len(), 0x42);
+        assert_eq!(binary[0x40], program_info.instructions[0].binary);
+        assert_eq!(program_info.address_to_line_number[0x40], 1);
+    }
+
+    #[test]
+    fn program_without_main_starts_at_its_first_instruction() {
+        let (program_info, _, _) = parser(
+            ".text 0x80\naddi $t0, $zero, 1\nsyscall".to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert_eq!(program_info.pc_starting_point, 0x80);
+    }
+
+    #[test]
+    fn org_leaves_a_gap_that_branches_cross() {
+        let (program_info, binary, labels) = parser(
+            "start: j end\n.org 0x40\nend: syscall".to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert!(has_no_errors(&program_info));
+        assert_eq!(labels.get("end"), Some(&0x40));
+        assert!(binary[1..0x10].iter().all(|word| *word == 0));
+    }
+
+    #[test]
+    fn align_in_text_aligns_the_next_instruction() {
+        let (program_info, _, labels) = parser(
+            "addi t0, t0, 1\n.align 3\nloop: addi t0, t0, 1\nj loop".to_string(),
+            AvailableDatapaths::RISCV,
+        );
+
+        assert!(has_no_errors(&program_info));
+        assert_eq!(labels.get("loop"), Some(&8));
+    }
+
+    #[test]
+    fn org_cannot_move_backwards() {
+        let (program_info, _, _) = parser(
+            "nop\nnop\n.org 0\nsyscall".to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert_eq!(
+            program_info.monaco_line_info[2].errors[0].error_name,
+            InvalidPlacement
+        );
+    }
+
+    #[test]
+    fn org_in_text_must_be_word_aligned() {
+        let (program_info, _, _) = parser(
+            "nop\n.org 0x42\nsyscall".to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert_eq!(
+            program_info.monaco_line_info[1].errors[0].error_name,
+            InvalidPlacement
+        );
+    }
+
+    #[test]
+    fn global_and_extern_symbols_are_accepted() {
+        let (program_info, _, _) = parser(
+            ".globl main\n.extern helper 4\n.text\nmain: syscall".to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert!(has_no_errors(&program_info));
+        assert!(program_info.monaco_line_info[0]
+            .mouse_hover_string
+            .contains("`main` can be referred to from other files."));
+    }
+}
@@ -0,0 +1,432 @@
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::parser::linker::*;
+use crate::parser::parser_assembler_main::parser;
+use crate::parser::parser_structs_and_enums::ErrorType::{
+    DuplicateGlobalSymbol, ImmediateOutOfBounds, UndefinedSymbol,
+};
+use crate::parser::parser_structs_and_enums::{Error, ProgramInfo};
+use crate::parser::project::{linked_files, parse_project, tokenize_files};
+use crate::tests::parser::file;
+
+///Collects the errors on the lines of a file along with the line of the file they are on.
+fn errors_in_file(program_info: &ProgramInfo, file: &str) -> Vec<(usize, Error)> {
+    program_info
+        .lines_of_file(file)
+        .flat_map(|line| {
+            let source_line = line.source.as_ref().unwrap().line;
+            line.errors
+                .iter()
+                .map(move |error| (source_line, error.clone()))
+        })
+        .collect()
+}
+
+mod linking_tests {
+    use super::*;
+
+    #[test]
+    fn only_files_exporting_symbols_are_linked() {
+        let files = [
+            file("main.asm", ".include \"io.asm\"\nsyscall"),
+            file("io.asm", ".globl print\nprint: jr $ra"),
+            file("scratch.asm", "nop"),
+            file("lib.asm", ".globl helper\nhelper: jr $ra"),
+        ];
+
+        assert_eq!(linked_files(&files, &tokenize_files(&files)), vec![0, 3]);
+    }
+
+    #[test]
+    fn linked_text_follows_the_main_file() {
+        let files = [
+            file(
+                "main.asm",
+                "main: addi $a0, $zero, 1\njal increment\nsyscall",
+            ),
+            file(
+                "lib.asm",
+                ".globl increment\nincrement: addi $a0, $a0, 1\njr $ra",
+            ),
+        ];
+        let (program_info, binary, labels) = parse_project(&files, AvailableDatapaths::MIPS);
+        let (_, expected, _) = parser(
+            "main: addi $a0, $zero, 1\njal increment\nsyscall\nincrement: addi $a0, $a0, 1\njr $ra"
+                .to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert!(errors_in_file(&program_info, "main.asm").is_empty());
+        assert!(errors_in_file(&program_info, "lib.asm").is_empty());
+        //the single file has a syscall added after the last instruction, which is not added to a linked file
+        assert_eq!(binary, expected[..binary.len()]);
+        assert_eq!(binary.len(), 5);
+        assert_eq!(labels.get("increment"), Some(&12));
+        assert_eq!(program_info.address_to_line_number, vec![0, 1, 2, 4, 5]);
+        assert_eq!(
+            program_info.console_out_post_assembly,
+            "Program assembled successfully!"
+        );
+    }
+
+    #[test]
+    fn data_of_each_file_follows_all_of_the_text() {
+        let files = [
+            file(
+                "main.asm",
+                ".extern value 4\nmain: lw $t0, value\nsyscall\n.data\nflag: .byte 1",
+            ),
+            file(
+                "lib.asm",
+                ".globl value\n.data\nvalue: .word 42\n.text\nnop",
+            ),
+        ];
+        let (program_info, binary, labels) = parse_project(&files, AvailableDatapaths::MIPS);
+
+        assert!(errors_in_file(&program_info, "main.asm").is_empty());
+        //main has three instructions and lib one, then the byte of main is followed by the word of lib at the next 8 bytes
+        assert_eq!(program_info.data_starting_point, 4);
+        assert_eq!(labels.get("flag"), Some(&16));
+        assert_eq!(labels.get("value"), Some(&24));
+        assert_eq!(binary[4], 0x01000000);
+        assert_eq!(binary[6], 42);
+        assert_eq!(binary[1] & 0xFFFF, 24);
+    }
+
+    #[test]
+    fn local_labels_belong_to_their_own_file() {
+        let files = [
+            file(
+                "main.asm",
+                "main: addi $t0, $zero, 2\nloop: addi $t0, $t0, -1\nbne $t0, $zero, loop\njal count\nsyscall",
+            ),
+            file(
+                "lib.asm",
+                ".globl count\ncount: addi $t1, $zero, 2\nloop: addi $t1, $t1, -1\nbne $t1, $zero, loop\njr $ra",
+            ),
+        ];
+        let (program_info, binary, labels) = parse_project(&files, AvailableDatapaths::MIPS);
+
+        assert!(errors_in_file(&program_info, "main.asm").is_empty());
+        assert!(errors_in_file(&program_info, "lib.asm").is_empty());
+        assert_eq!(labels.get("loop"), Some(&4));
+        //both branches jump back by two instructions from the instruction after them
+        assert_eq!(binary[2] & 0xFFFF, 0xFFFE);
+        assert_eq!(binary[7] & 0xFFFF, 0xFFFE);
+    }
+
+    #[test]
+    fn riscv_files_are_linked() {
+        let files = [
+            file(
+                "main.asm",
+                "main: la a0, message\njal ra, print\necall\n.data\nflag: .word 1",
+            ),
+            file(
+                "lib.asm",
+                ".globl print\n.globl message\nprint: addi a7, zero, 4\necall\njalr zero, ra, 0\n.data\nmessage: .asciiz \"hi\"",
+            ),
+        ];
+        let (program_info, binary, labels) = parse_project(&files, AvailableDatapaths::RISCV);
+
+        assert!(errors_in_file(&program_info, "main.asm").is_empty());
+        assert!(errors_in_file(&program_info, "lib.asm").is_empty());
+        assert_eq!(labels.get("print"), Some(&16));
+        assert_eq!(labels.get("message"), Some(&32));
+        //the word of main ends exactly on an 8-byte boundary, and la is auipc a0, 0 followed by addi a0, a0, 32
+        assert_eq!(binary[0], 0x00000517);
+        assert_eq!(binary[1] >> 20, 32);
+        assert_eq!(binary[8], u32::from_be_bytes(*b"hi\0\0"));
+    }
+
+    #[test]
+    fn linked_mips_project_matches_the_same_program_in_one_file() {
+        let main = ".extern count 4\nmain: la $t0, table\nlw $t1, count\naddi $t2, $zero, table + 4\njal helper\nbeq $t0, $t1, main\nsyscall\n.data\nflag: .word helper";
+        let files = [
+            file("main.asm", main),
+            file(
+                "lib.asm",
+                ".globl helper\n.globl table\n.globl count\nhelper: lui $t3, %hi(count)\nlw $t3, %lo(count)($t3)\njr $ra\nsyscall\n.data\ntable: .word 1, 2\ncount: .word table",
+            ),
+        ];
+        let (program_info, binary, labels) = parse_project(&files, AvailableDatapaths::MIPS);
+        //the data of lib starts on the next 8 bytes after the data of main
+        let (_, expected, expected_labels) = parser(
+            format!("{}\n.text\nhelper: lui $t3, %hi(count)\nlw $t3, %lo(count)($t3)\njr $ra\nsyscall\n.data\n.align 3\ntable: .word 1, 2\ncount: .word table", main),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert!(errors_in_file(&program_info, "main.asm").is_empty());
+        assert!(errors_in_file(&program_info, "lib.asm").is_empty());
+        assert_eq!(binary, expected);
+        assert_eq!(labels, expected_labels);
+    }
+
+    #[test]
+    fn linked_riscv_project_matches_the_same_program_in_one_file() {
+        let main = ".extern count 4\nmain: la a0, message\nlw a1, count\njal ra, print\nbeq a0, a1, main\necall\n.data\nflag: .word print";
+        let lib_text = "print: lui t0, %hi(count)\nlw t0, %lo(count)(t0)\naddi t1, zero, message + 1\nfar: auipc t2, %pcrel_hi(count)\naddi t1, t1, 1\nsw t1, %pcrel_lo(far)(t2)\njalr zero, ra, 0";
+        let lib_data = "message: .asciiz \"hi\"\n.align 2\ncount: .word message";
+        let files = [
+            file("main.asm", main),
+            file(
+                "lib.asm",
+                &format!(
+                    ".globl print\n.globl message\n.globl count\n{lib_text}\n.data\n{lib_data}"
+                ),
+            ),
+        ];
+        let (program_info, binary, labels) = parse_project(&files, AvailableDatapaths::RISCV);
+        let (_, expected, expected_labels) = parser(
+            format!("{main}\n.text\n{lib_text}\n.data\n.align 3\n{lib_data}"),
+            AvailableDatapaths::RISCV,
+        );
+
+        assert!(errors_in_file(&program_info, "main.asm").is_empty());
+        assert!(errors_in_file(&program_info, "lib.asm").is_empty());
+        assert_eq!(binary, expected);
+        assert_eq!(labels, expected_labels);
+    }
+
+    #[test]
+    fn address_that_no_longer_fits_is_reported() {
+        let files = [
+            file("main.asm", "main: beq t0, t1, far\necall"),
+            file("lib.asm", ".globl far\n.org 0x4000\nfar: ecall"),
+        ];
+        let (program_info, _, labels) = parse_project(&files, AvailableDatapaths::RISCV);
+
+        //a branch can only reach the first 16 KB of memory
+        assert_eq!(labels.get("far"), Some(&0x4008));
+        let errors = errors_in_file(&program_info, "main.asm");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, 0);
+        assert_eq!(errors[0].1.error_name, ImmediateOutOfBounds);
+        assert_eq!(errors[0].1.token_causing_error, "far");
+    }
+}
+
+mod symbol_error_tests {
+    use super::*;
+
+    #[test]
+    fn duplicate_global_is_reported_where_it_is_defined_again() {
+        let files = [
+            file(
+                "main.asm",
+                ".globl helper\nmain: jal helper\nsyscall\nhelper: jr $ra",
+            ),
+            file("lib.asm", ".globl helper\nnop\nhelper: jr $ra"),
+        ];
+        let (program_info, _, _) = parse_project(&files, AvailableDatapaths::MIPS);
+
+        assert!(errors_in_file(&program_info, "main.asm").is_empty());
+        let errors = errors_in_file(&program_info, "lib.asm");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, 2);
+        assert_eq!(errors[0].1.error_name, DuplicateGlobalSymbol);
+        assert!(errors[0].1.message.contains("main.asm"));
+        assert!(program_info
+            .console_out_post_assembly
+            .contains("DuplicateGlobalSymbol on line 3 of lib.asm"));
+    }
+
+    #[test]
+    fn undefined_symbol_is_reported_where_it_is_used() {
+        let files = [
+            file("main.asm", "main: nop\njal missing\nsyscall"),
+            file("lib.asm", ".globl helper\nhelper: jr $ra"),
+        ];
+        let (program_info, _, _) = parse_project(&files, AvailableDatapaths::MIPS);
+
+        let errors = errors_in_file(&program_info, "main.asm");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, 1);
+        assert_eq!(errors[0].1.error_name, UndefinedSymbol);
+        assert_eq!(errors[0].1.token_causing_error, "missing");
+    }
+
+    #[test]
+    fn symbol_that_is_not_exported_names_its_file() {
+        let files = [
+            file("main.asm", "main: jal helper\nsyscall"),
+            file("lib.asm", ".globl other\nother: jr $ra\nhelper: jr $ra"),
+        ];
+        let (program_info, _, _) = parse_project(&files, AvailableDatapaths::MIPS);
+
+        let errors = errors_in_file(&program_info, "main.asm");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].1.error_name, UndefinedSymbol);
+        assert!(errors[0].1.message.contains("defined in lib.asm"));
+    }
+}
+
+mod object_unit_tests {
+    use super::*;
+
+    ///Lists the relocations of a unit without their lines.
+    fn relocations_of(unit: &ObjectUnit) -> Vec<(Section, usize, RelocationType, &str, i64)> {
+        unit.relocations
+            .iter()
+            .map(|relocation| {
+                (
+                    relocation.section,
+                    relocation.offset,
+                    relocation.relocation_type,
+                    relocation.symbol.as_str(),
+                    relocation.addend,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn mips_unit_records_symbols_and_relocations() {
+        let files = [file(
+            "main.asm",
+            ".globl main\nmain: jal increment\nla $t0, table\nloop: beq $t0, $zero, loop\nsyscall\n.data\ntable: .word increment + 4, table\nsize: .word size - table",
+        )];
+        let unit = assemble_unit(&files, &tokenize_files(&files), 0, AvailableDatapaths::MIPS);
+
+        assert_eq!(
+            unit.undefined_symbols,
+            vec![
+                SymbolReference {
+                    symbol: "increment".to_string(),
+                    line_number: 1,
+                    start_end_columns: (10, 19),
+                },
+                SymbolReference {
+                    symbol: "increment".to_string(),
+                    line_number: 6,
+                    start_end_columns: (13, 26),
+                },
+            ]
+        );
+        assert_eq!(
+            unit.symbols.get("main"),
+            Some(&UnitSymbol {
+                section: Section::Text,
+                offset: 0,
+                global: true,
+                line_number: 1,
+                start_end_columns: (0, 4),
+            })
+        );
+        assert_eq!(unit.symbols["table"].section, Section::Data);
+        assert_eq!(unit.symbols["table"].offset, 0);
+        assert!(!unit.symbols["table"].global);
+
+        //the branch to loop stays the same wherever the unit is placed, as does the difference of two labels
+        assert_eq!(
+            relocations_of(&unit),
+            vec![
+                (Section::Text, 0, RelocationType::Mips26, "increment", 0),
+                (Section::Text, 4, RelocationType::MipsHi16, "table", 0),
+                (Section::Text, 8, RelocationType::MipsLo16, "table", 0),
+                (Section::Data, 0, RelocationType::Data(4), "increment", 4),
+                (Section::Data, 4, RelocationType::Data(4), "table", 0),
+            ]
+        );
+        assert_eq!(unit.text.len(), 5);
+        assert_eq!(unit.data.len(), 12);
+    }
+
+    #[test]
+    fn riscv_unit_records_pc_relative_relocations() {
+        let files = [file(
+            "lib.asm",
+            ".extern buffer 4\nstart: la t0, buffer\nsw t1, buffer, t2\nbeq t0, t1, start\njal ra, done\ndone: ecall",
+        )];
+        let unit = assemble_unit(
+            &files,
+            &tokenize_files(&files),
+            0,
+            AvailableDatapaths::RISCV,
+        );
+
+        assert_eq!(
+            relocations_of(&unit),
+            vec![
+                (
+                    Section::Text,
+                    0,
+                    RelocationType::RiscvPcrelHi20,
+                    "buffer",
+                    0
+                ),
+                (
+                    Section::Text,
+                    4,
+                    RelocationType::RiscvPcrelLo12I,
+                    "buffer",
+                    0
+                ),
+                (
+                    Section::Text,
+                    8,
+                    RelocationType::RiscvPcrelHi20,
+                    "buffer",
+                    0
+                ),
+                (
+                    Section::Text,
+                    12,
+                    RelocationType::RiscvPcrelLo12S,
+                    "buffer",
+                    0
+                ),
+                (Section::Text, 16, RelocationType::RiscvBranch, "start", 0),
+                (Section::Text, 20, RelocationType::RiscvJal, "done", 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn whole_values_and_loads_of_labels_are_relocated() {
+        let files = [file(
+            "main.asm",
+            ".extern count 4\nmain: lw $t1, count\naddi $t2, $zero, count + 4\nsyscall",
+        )];
+        let unit = assemble_unit(&files, &tokenize_files(&files), 0, AvailableDatapaths::MIPS);
+
+        //the load is a lui of the upper bits of the address into $at followed by a load of the lower bits from $at
+        assert_eq!(
+            relocations_of(&unit),
+            vec![
+                (Section::Text, 0, RelocationType::MipsHi16, "count", 0),
+                (Section::Text, 4, RelocationType::MipsLo16, "count", 0),
+                (Section::Text, 8, RelocationType::Mips16, "count", 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn pcrel_lo_of_an_auipc_label_is_relative_to_the_instruction_before_it() {
+        let files = [file(
+            "lib.asm",
+            ".extern count 4\nstart: addi t1, zero, count + 1\nfar: auipc t2, %pcrel_hi(count)\naddi t1, t1, 1\nsw t1, %pcrel_lo(far)(t2)",
+        )];
+        let unit = assemble_unit(
+            &files,
+            &tokenize_files(&files),
+            0,
+            AvailableDatapaths::RISCV,
+        );
+
+        //the auipc is two instructions before the store, so the distance it finds is 4 bytes more
+        assert_eq!(
+            relocations_of(&unit),
+            vec![
+                (Section::Text, 0, RelocationType::Riscv12I, "count", 1),
+                (Section::Text, 4, RelocationType::RiscvPcrelHi20, "count", 0),
+                (
+                    Section::Text,
+                    12,
+                    RelocationType::RiscvPcrelLo12S,
+                    "count",
+                    4
+                ),
+            ]
+        );
+    }
+}
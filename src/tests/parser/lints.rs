@@ -0,0 +1,207 @@
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::parser::lints::{all_lints, lint_program};
+use crate::parser::parser_assembler_main::parser;
+use crate::parser::parser_structs_and_enums::{Lint, LintSeverity, ProgramInfo};
+use std::collections::HashSet;
+
+fn lint(program: &str, arch: AvailableDatapaths, enabled_lints: &HashSet<Lint>) -> ProgramInfo {
+    let (mut program_info, _, labels) = parser(program.to_string(), arch);
+    lint_program(&mut program_info, &labels, arch, enabled_lints);
+    program_info
+}
+
+///Lists every warning of a program checked with a single lint, with the line it is on and the token causing it.
+fn warnings_of(
+    program: &str,
+    arch: AvailableDatapaths,
+    lint_checked: Lint,
+) -> Vec<(usize, String)> {
+    lint(program, arch, &HashSet::from([lint_checked]))
+        .monaco_line_info
+        .iter()
+        .flat_map(|line| {
+            line.warnings
+                .iter()
+                .map(|warning| (line.line_number, warning.token_causing_warning.clone()))
+        })
+        .collect()
+}
+
+#[test]
+fn write_to_zero_register_is_found_on_written_instructions_only() {
+    let program = "main: add $zero, $t0, $t1\nnop\naddi $t0, $zero, 1\nli $zero, 4\nsyscall";
+
+    //li expands to an instruction writing to $zero, which was not written by the user as such
+    assert_eq!(
+        warnings_of(program, AvailableDatapaths::MIPS, Lint::WriteToZeroRegister),
+        vec![(0, "$zero".to_string())]
+    );
+    assert_eq!(
+        warnings_of(
+            "main: add x0, t0, t1\naddi x0, x0, 0\njalr zero, ra, 0",
+            AvailableDatapaths::RISCV,
+            Lint::WriteToZeroRegister
+        ),
+        vec![(0, "x0".to_string())]
+    );
+}
+
+#[test]
+fn unused_label_is_reported_as_information() {
+    let program = "main: addi $t0, $zero, 1\nloop: addi $t0, $t0, -1\nbne $t0, $zero, loop\nunused: syscall\n.data\nvalue: .word 1\ntable: .word value";
+    let program_info = lint(program, AvailableDatapaths::MIPS, &all_lints());
+
+    let unused: Vec<(usize, &str)> = program_info
+        .monaco_line_info
+        .iter()
+        .flat_map(|line| {
+            line.warnings
+                .iter()
+                .filter(|warning| warning.lint == Lint::UnusedLabel)
+                .map(|warning| (line.line_number, warning.token_causing_warning.as_str()))
+        })
+        .collect();
+    assert_eq!(unused, vec![(3, "unused"), (6, "table")]);
+    assert_eq!(Lint::UnusedLabel.severity(), LintSeverity::Info);
+    assert!(program_info.console_out_post_assembly.contains(
+        "Info: UnusedLabel on line 4 with token \"unused\"\nThe label unused is never used.\n"
+    ));
+}
+
+#[test]
+fn fall_through_into_data_is_found_after_the_last_instruction() {
+    let program = "main: addi a0, zero, 0\naddi t0, t0, 1\n.data\nvalue: .word 1";
+
+    assert_eq!(
+        warnings_of(
+            program,
+            AvailableDatapaths::RISCV,
+            Lint::FallThroughIntoData
+        ),
+        vec![(1, "addi".to_string())]
+    );
+    assert!(warnings_of(
+        "main: addi a0, zero, 0\necall\n.data\nvalue: .word 1",
+        AvailableDatapaths::RISCV,
+        Lint::FallThroughIntoData
+    )
+    .is_empty());
+}
+
+#[test]
+fn branch_to_data_is_found() {
+    let program = "main: beq $t0, $zero, value\nj main\njal value\n.data\nvalue: .word 1";
+
+    assert_eq!(
+        warnings_of(program, AvailableDatapaths::MIPS, Lint::BranchToData),
+        vec![(0, "value".to_string()), (2, "value".to_string())]
+    );
+}
+
+#[test]
+fn saved_register_changed_on_one_path_is_found_at_the_return() {
+    let program = "main: jal function\nsyscall\nfunction: beq $a0, $zero, done\naddi $s0, $zero, 1\ndone: jr $ra";
+    let program_info = lint(
+        program,
+        AvailableDatapaths::MIPS,
+        &HashSet::from([Lint::UnrestoredSavedRegister]),
+    );
+
+    assert_eq!(program_info.monaco_line_info[4].warnings.len(), 1);
+    assert!(program_info.monaco_line_info[4].warnings[0]
+        .message
+        .contains("without restoring $s0, which is changed on line 4"));
+}
+
+#[test]
+fn saved_register_loaded_back_before_returning_is_not_reported() {
+    let program = "main: jal function\nsyscall\nfunction: addi $sp, $sp, -8\nsd $s0, 0($sp)\naddi $s0, $zero, 1\nld $s0, 0($sp)\naddi $sp, $sp, 8\njr $ra";
+    assert!(warnings_of(
+        program,
+        AvailableDatapaths::MIPS,
+        Lint::UnrestoredSavedRegister
+    )
+    .is_empty());
+
+    let program = "main: jal ra, function\necall\nfunction: addi s1, s1, 1\njalr zero, ra, 0";
+    assert_eq!(
+        warnings_of(
+            program,
+            AvailableDatapaths::RISCV,
+            Lint::UnrestoredSavedRegister
+        ),
+        vec![(3, "jalr".to_string())]
+    );
+}
+
+#[test]
+fn sign_extended_immediate_is_found() {
+    let program =
+        "main: addi $t0, $zero, 0xFFFF\naddi $t1, $zero, 0x7FFF\naddi $t2, $zero, -1\nsyscall";
+    assert_eq!(
+        warnings_of(
+            program,
+            AvailableDatapaths::MIPS,
+            Lint::SignExtendedImmediate
+        ),
+        vec![(0, "0xFFFF".to_string())]
+    );
+
+    let program_info = lint(
+        "main: andi t0, t0, 0xFFF\necall",
+        AvailableDatapaths::RISCV,
+        &HashSet::from([Lint::SignExtendedImmediate]),
+    );
+    assert!(program_info.monaco_line_info[0].warnings[0]
+        .message
+        .contains("sign-extended to -1"));
+}
+
+#[test]
+fn missing_exit_is_found_when_main_ends_with_another_syscall() {
+    let program = "main: addi $a0, $zero, 1\naddi $a1, $zero, 5\nsyscall\njal function\naddi $a0, $zero, 1\nsyscall\nfunction: jr $ra";
+    assert_eq!(
+        warnings_of(program, AvailableDatapaths::MIPS, Lint::MissingExit),
+        vec![(5, "syscall".to_string())]
+    );
+
+    let program = "main: addi a0, zero, 1\necall\naddi a0, zero, 0\necall";
+    assert!(warnings_of(program, AvailableDatapaths::RISCV, Lint::MissingExit).is_empty());
+
+    //10 is the exit call of MARS, which also ends the program
+    let program = "main: li $a0, 10\nsyscall";
+    assert!(warnings_of(program, AvailableDatapaths::MIPS, Lint::MissingExit).is_empty());
+
+    let program = "main: li $a0, 13\nsyscall";
+    assert_eq!(
+        warnings_of(program, AvailableDatapaths::MIPS, Lint::MissingExit),
+        vec![(1, "syscall".to_string())]
+    );
+
+    let program = "main: addi a0, zero, 1\naddi t0, t0, 1";
+    assert_eq!(
+        warnings_of(program, AvailableDatapaths::RISCV, Lint::MissingExit),
+        vec![(1, "addi".to_string())]
+    );
+}
+
+#[test]
+fn disabled_lints_are_not_checked() {
+    let program = "main: add $zero, $t0, $t1\nunused: addi $t0, $zero, 0xFFFF\nsyscall";
+    let mut enabled_lints = all_lints();
+    enabled_lints.remove(&Lint::WriteToZeroRegister);
+    enabled_lints.remove(&Lint::UnusedLabel);
+    let program_info = lint(program, AvailableDatapaths::MIPS, &enabled_lints);
+
+    let linted: Vec<Lint> = program_info
+        .monaco_line_info
+        .iter()
+        .flat_map(|line| line.warnings.iter().map(|warning| warning.lint))
+        .collect();
+    assert_eq!(linted, vec![Lint::SignExtendedImmediate]);
+    //warnings are not errors, so the program still assembles
+    assert!(program_info
+        .monaco_line_info
+        .iter()
+        .all(|line| line.errors.is_empty()));
+}
@@ -0,0 +1,66 @@
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::parser::listing::create_listing;
+use crate::parser::parser_assembler_main::parser;
+use crate::parser::project::parse_project;
+use crate::tests::parser::file;
+
+fn listing_of(program: &str) -> String {
+    let (program_info, binary, labels) = parser(program.to_string(), AvailableDatapaths::MIPS);
+    create_listing(&program_info, &binary, &labels)
+}
+
+const PROGRAM: &str = "main: addi $t0, $zero, 1 # one\n\n.data\nvalue: .word 7, 9\nbuffer: .space 40\n.text\nlw $t2, value";
+
+#[test]
+fn instruction_is_listed_with_its_address_and_word() {
+    let listing = listing_of(PROGRAM);
+
+    assert!(listing.starts_with(" Line  Address   Word      Source\n"));
+    assert!(
+        listing.contains("\n    1  00000000  20080001  main: addi $t0, $zero, 1 # one\n    2\n")
+    );
+}
+
+#[test]
+fn pseudo_instruction_is_followed_by_every_expanded_word() {
+    let listing = listing_of(PROGRAM);
+
+    //the syscall added to the end of the program is listed with the last line
+    assert!(listing.contains(
+        "    7                      lw $t2, value\n       00000004  3c010000      > lui $at, 0\n       00000008  8c2a0010      > lw $t2, 16($at)\n       0000000c  0000000c      > syscall\n"
+    ));
+}
+
+#[test]
+fn data_is_listed_a_word_per_row() {
+    let listing = listing_of(PROGRAM);
+
+    assert!(listing
+        .contains("    4  00000010  00000007  value: .word 7, 9\n       00000014  00000009\n"));
+    assert!(listing.contains(
+        "    5  00000018  00000000  buffer: .space 40\n       0000001c  00000000\n       00000020  00000000\n       00000024  00000000\n                               (24 more bytes)\n"
+    ));
+}
+
+#[test]
+fn symbol_table_lists_addresses_and_sizes() {
+    let listing = listing_of(PROGRAM);
+
+    assert!(listing.ends_with(
+        "\nSymbols\nAddress       Size  Section  Name\n00000000        16  text     main\n00000010         8  data     value\n00000018        40  data     buffer\n"
+    ));
+}
+
+#[test]
+fn project_lines_are_grouped_by_file() {
+    let files = [
+        file("main.asm", ".include \"io.asm\"\nsyscall"),
+        file("io.asm", "nop"),
+    ];
+    let (program_info, binary, labels) = parse_project(&files, AvailableDatapaths::MIPS);
+    let listing = create_listing(&program_info, &binary, &labels);
+
+    assert!(listing.contains(
+        "\nFile: main.asm\n    1                      .include \"io.asm\"\n\nFile: io.asm\n    1  00000000  00000000  nop\n\nFile: main.asm\n    2  00000004  0000000c  syscall\n"
+    ));
+}
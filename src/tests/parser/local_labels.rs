@@ -0,0 +1,71 @@
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::parser::local_labels::{is_local_label_name, read_local_label_reference};
+use crate::parser::parser_assembler_main::parser;
+use crate::parser::parser_structs_and_enums::ErrorType::{LabelNotFound, LocalLabelNotFound};
+
+#[test]
+fn references_are_read_with_their_direction() {
+    assert_eq!(read_local_label_reference("1b"), Some(("1", false)));
+    assert_eq!(read_local_label_reference("42f"), Some(("42", true)));
+    assert_eq!(read_local_label_reference("loopb"), None);
+    assert_eq!(read_local_label_reference("f"), None);
+    assert!(is_local_label_name(".L1.2"));
+    assert!(!is_local_label_name(".LC0"));
+}
+
+#[test]
+fn references_resolve_to_the_nearest_definition() {
+    let (program_info, binary, _) = parser(
+        "1: addi $t0, $t0, 1\nbne $t0, $zero, 1b\nj 1f\nnop\n1: beq $t0, $zero, 1b\n".to_string(),
+        AvailableDatapaths::MIPS,
+    );
+    let (_, expected, _) = parser(
+        "first: addi $t0, $t0, 1\nbne $t0, $zero, first\nj second\nnop\nsecond: beq $t0, $zero, second\n"
+            .to_string(),
+        AvailableDatapaths::MIPS,
+    );
+
+    assert!(program_info
+        .monaco_line_info
+        .iter()
+        .all(|line| line.errors.is_empty()));
+    assert_eq!(binary, expected);
+}
+
+#[test]
+fn references_resolve_in_pseudo_instructions_and_data() {
+    let (program_info, binary, labels) = parser(
+        "1:\naddi t0, t0, 1\nbnez t0, 1b\nla t1, 1f\n.data\npointers: .word 1b, 2f\n1: .word 0\n2: .word 0"
+            .to_string(),
+        AvailableDatapaths::RISCV,
+    );
+
+    assert!(program_info
+        .monaco_line_info
+        .iter()
+        .all(|line| line.errors.is_empty()));
+    let pointers = labels["pointers"] / 4;
+    assert_eq!(binary[pointers] as usize, labels[".L1.1"]);
+    assert_eq!(binary[pointers + 1] as usize, labels[".L2.1"]);
+    //the 1: after la is the one in the data
+    assert_eq!(labels[".L1.2"], labels["pointers"] + 8);
+}
+
+#[test]
+fn missing_local_labels_are_reported_by_direction() {
+    let (program_info, _, _) = parser(
+        "1: nop\nj 1f\nj 2b\nj missing".to_string(),
+        AvailableDatapaths::MIPS,
+    );
+
+    let error = &program_info.monaco_line_info[1].errors[0];
+    assert_eq!(error.error_name, LocalLabelNotFound);
+    assert!(error.message.starts_with("There is no following label 1."));
+    let error = &program_info.monaco_line_info[2].errors[0];
+    assert_eq!(error.error_name, LocalLabelNotFound);
+    assert!(error.message.starts_with("There is no preceding label 2."));
+    assert_eq!(
+        program_info.monaco_line_info[3].errors[0].error_name,
+        LabelNotFound
+    );
+}
@@ -0,0 +1,205 @@
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::parser::parser_assembler_main::parser;
+use crate::parser::parser_structs_and_enums::ErrorType::{
+    ImproperlyFormattedMacro, IncorrectNumberOfOperands, RecursiveMacroExpansion,
+    UnrecognizedGPRegister,
+};
+
+mod macro_expansion_tests {
+    use super::*;
+
+    #[test]
+    fn mars_macro_expands_to_body_with_arguments() {
+        let (with_macro, binary, _) = parser(
+            ".macro add3(%d, %a, %b)\nadd %d, %a, %b\nadd %d, %d, %b\n.end_macro\nadd3($t0, $t1, $t2)\nsyscall"
+                .to_string(),
+            AvailableDatapaths::MIPS,
+        );
+        let (_, expected, _) = parser(
+            "add $t0, $t1, $t2\nadd $t0, $t0, $t2\nsyscall".to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert_eq!(binary, expected);
+        assert_eq!(with_macro.instructions[0].line_number, 4);
+        assert_eq!(with_macro.instructions[0].macro_line_number, Some(1));
+        assert_eq!(with_macro.instructions[1].macro_line_number, Some(2));
+        assert_eq!(with_macro.address_to_line_number[..2], [4, 4]);
+    }
+
+    #[test]
+    fn gnu_macro_expands_to_body_with_arguments() {
+        let (_, binary, _) = parser(
+            ".macro inc reg, amount\naddi \\reg, \\reg, \\amount\n.endm\ninc t0, 5\ninc t1 2"
+                .to_string(),
+            AvailableDatapaths::RISCV,
+        );
+        let (_, expected, _) = parser(
+            "addi t0, t0, 5\naddi t1, t1, 2".to_string(),
+            AvailableDatapaths::RISCV,
+        );
+
+        assert_eq!(binary, expected);
+    }
+
+    #[test]
+    fn macro_without_parameters() {
+        let (program_info, _, _) = parser(
+            ".macro done\nli $v0, 10\nsyscall\n.end_macro\ndone".to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert_eq!(program_info.instructions.len(), 2);
+        assert_eq!(program_info.instructions[0].operator.token_name, "ori");
+        assert_eq!(program_info.instructions[1].operator.token_name, "syscall");
+    }
+
+    #[test]
+    fn local_labels_are_unique_per_expansion() {
+        let (program_info, _, labels) = parser(
+            ".macro spin(%r)\nloop: addi %r, %r, -1\nbne %r, $zero, loop\n.end_macro\nspin($t0)\nspin($t1)"
+                .to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert_eq!(labels.get("loop_M0"), Some(&0));
+        assert_eq!(labels.get("loop_M1"), Some(&8));
+        assert!(!labels.contains_key("loop"));
+        assert!(program_info
+            .monaco_line_info
+            .iter()
+            .all(|line| line.errors.is_empty()));
+    }
+
+    #[test]
+    fn label_on_invocation_refers_to_start_of_expansion() {
+        let (_, _, labels) = parser(
+            ".macro two\nnop\nnop\n.end_macro\nnop\nstart: two\nj start".to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert_eq!(labels.get("start"), Some(&4));
+    }
+
+    #[test]
+    fn nested_macros_expand() {
+        let (program_info, _, _) = parser(
+            ".macro one(%r)\naddi %r, %r, 1\n.end_macro\n.macro two(%r)\none(%r)\none(%r)\n.end_macro\ntwo($t3)"
+                .to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert_eq!(program_info.instructions[0].operator.token_name, "addi");
+        assert_eq!(program_info.instructions[1].operator.token_name, "addi");
+        assert_eq!(program_info.instructions[0].line_number, 7);
+        assert_eq!(program_info.instructions[0].macro_line_number, Some(1));
+    }
+
+    #[test]
+    fn macros_are_overloaded_by_argument_count() {
+        let (program_info, _, _) = parser(
+            ".macro set(%r)\naddi %r, $zero, 1\n.end_macro\n.macro set(%r, %v)\naddi %r, $zero, %v\n.end_macro\nset($t0)\nset($t1, 7)"
+                .to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert_eq!(program_info.instructions[0].operands[2].token_name, "1");
+        assert_eq!(program_info.instructions[1].operands[2].token_name, "7");
+    }
+
+    #[test]
+    fn editor_text_is_unchanged_for_macro_lines() {
+        let program = ".macro load(%r)\nli %r, 5\n.end_macro\nload($t0)\nsyscall";
+        let (program_info, _, _) = parser(program.to_string(), AvailableDatapaths::MIPS);
+
+        assert_eq!(program_info.updated_monaco_string, format!("{program}\n"));
+    }
+}
+
+mod macro_error_tests {
+    use super::*;
+
+    #[test]
+    fn error_in_body_points_to_invocation_and_body() {
+        let (program_info, _, _) = parser(
+            ".macro bad(%r)\nadd %r, $t1, $nope\n.end_macro\nbad($t0)".to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        let body_errors = &program_info.monaco_line_info[1].errors;
+        let invocation_errors = &program_info.monaco_line_info[3].errors;
+        assert_eq!(body_errors.len(), 1);
+        assert_eq!(invocation_errors.len(), 1);
+        assert_eq!(body_errors[0].error_name, UnrecognizedGPRegister);
+        assert_eq!(invocation_errors[0].error_name, UnrecognizedGPRegister);
+
+        //the body error keeps its own columns while the invocation error covers the invocation
+        assert_eq!(body_errors[0].start_end_columns, (13, 18));
+        assert_eq!(invocation_errors[0].start_end_columns, (0, 8));
+        assert!(body_errors[0].message.contains("invoked on line 4"));
+        assert!(invocation_errors[0]
+            .message
+            .contains("line 2 of the macro body"));
+    }
+
+    #[test]
+    fn wrong_number_of_arguments() {
+        let (program_info, _, _) = parser(
+            ".macro pair(%a, %b)\nadd %a, %a, %b\n.end_macro\npair($t0)".to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert_eq!(
+            program_info.monaco_line_info[3].errors[0].error_name,
+            IncorrectNumberOfOperands
+        );
+    }
+
+    #[test]
+    fn unclosed_macro_is_an_error() {
+        let (program_info, _, _) = parser(".macro open\nnop".to_string(), AvailableDatapaths::MIPS);
+
+        assert_eq!(
+            program_info.monaco_line_info[0].errors[0].error_name,
+            ImproperlyFormattedMacro
+        );
+    }
+
+    #[test]
+    fn end_without_macro_is_an_error() {
+        let (program_info, _, _) = parser("nop\n.endm".to_string(), AvailableDatapaths::RISCV);
+
+        assert_eq!(
+            program_info.monaco_line_info[1].errors[0].error_name,
+            ImproperlyFormattedMacro
+        );
+    }
+
+    #[test]
+    fn recursive_macro_is_an_error() {
+        let (program_info, _, _) = parser(
+            ".macro forever\nforever\n.end_macro\nforever".to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert_eq!(
+            program_info.monaco_line_info[3].errors[0].error_name,
+            RecursiveMacroExpansion
+        );
+    }
+
+    #[test]
+    fn hover_shows_expansion_and_body_location() {
+        let (program_info, _, _) = parser(
+            ".macro twice(%r)\naddi %r, %r, 1\naddi %r, %r, 1\n.end_macro\ntwice($t0)".to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        let hover = &program_info.monaco_line_info[4].mouse_hover_string;
+        assert!(hover.contains("`twice` is a macro."));
+        assert!(hover.contains("addi $t0, $t0, 1"));
+        assert!(program_info.monaco_line_info[1]
+            .mouse_hover_string
+            .contains("Expanded on line 5."));
+    }
+}
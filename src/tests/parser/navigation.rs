@@ -0,0 +1,130 @@
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::parser::linker::Section;
+use crate::parser::navigation::{LabelIndex, LabelLocation};
+use crate::parser::project::{parse_project, ProjectFile};
+use crate::tests::parser::file;
+
+fn index_of(files: &[ProjectFile], arch: AvailableDatapaths) -> LabelIndex {
+    let (program_info, _, _) = parse_project(files, arch);
+    LabelIndex::new(files, &program_info)
+}
+
+fn location(file: &str, line: usize, start_end_columns: (usize, usize)) -> LabelLocation {
+    LabelLocation {
+        file: file.to_string(),
+        line,
+        start_end_columns,
+    }
+}
+
+const PROGRAM: &str = "main: addi $t0, $zero, 3 # loop three times\nloop: addi $t0, $t0, -1\nbne $t0, $zero, loop\nla $t1, table\nsyscall\n.data\ntable: .word table + 4, 0\nmessage: .asciiz \"loop\"";
+
+#[test]
+fn branch_target_goes_to_its_label() {
+    let files = [file("main.asm", PROGRAM)];
+    let index = index_of(&files, AvailableDatapaths::MIPS);
+
+    assert_eq!(
+        index.find_definition("main.asm", 2, 17),
+        Some(&location("main.asm", 1, (0, 4)))
+    );
+    //the comment, string, and instruction of a line are not labels
+    assert_eq!(index.label_at("main.asm", 0, 35), None);
+    assert_eq!(index.label_at("main.asm", 7, 20), None);
+    assert_eq!(index.label_at("main.asm", 2, 1), None);
+}
+
+#[test]
+fn references_include_the_definition_and_every_use() {
+    let files = [file("main.asm", PROGRAM)];
+    let index = index_of(&files, AvailableDatapaths::MIPS);
+
+    assert_eq!(
+        index.find_references("main.asm", 3, 10),
+        vec![
+            &location("main.asm", 3, (8, 13)),
+            &location("main.asm", 6, (0, 5)),
+            &location("main.asm", 6, (13, 18)),
+        ]
+    );
+}
+
+#[test]
+fn rename_is_refused_when_it_would_change_another_symbol() {
+    let files = [file("main.asm", PROGRAM)];
+    let index = index_of(&files, AvailableDatapaths::MIPS);
+
+    assert_eq!(
+        index
+            .rename_label("main.asm", 1, 2, "again", AvailableDatapaths::MIPS)
+            .unwrap()
+            .len(),
+        2
+    );
+    assert!(index
+        .rename_label("main.asm", 1, 2, "table", AvailableDatapaths::MIPS)
+        .unwrap_err()
+        .contains("already used on line 4"));
+    assert!(index
+        .rename_label("main.asm", 1, 2, "$t0", AvailableDatapaths::MIPS)
+        .is_err());
+    assert!(index
+        .rename_label("main.asm", 1, 2, "beq", AvailableDatapaths::MIPS)
+        .unwrap_err()
+        .contains("instruction"));
+    assert!(index
+        .rename_label("main.asm", 2, 1, "again", AvailableDatapaths::MIPS)
+        .is_err());
+}
+
+#[test]
+fn local_labels_of_other_files_are_not_references() {
+    let files = [
+        file(
+            "main.asm",
+            "main: jal count\nloop: j loop\n.globl main",
+        ),
+        file(
+            "lib.asm",
+            ".globl count\ncount: addi t1, zero, 2\nloop: addi t1, t1, -1\nbne t1, zero, loop\njalr zero, ra, 0",
+        ),
+    ];
+    let index = index_of(&files, AvailableDatapaths::RISCV);
+
+    assert_eq!(
+        index.find_references("lib.asm", 3, 16),
+        vec![
+            &location("lib.asm", 2, (0, 4)),
+            &location("lib.asm", 3, (14, 18)),
+        ]
+    );
+    //an exported label is found from the file that uses it, along with the line exporting it
+    assert_eq!(
+        index.find_definition("main.asm", 0, 10),
+        Some(&location("lib.asm", 1, (0, 5)))
+    );
+    assert_eq!(index.find_references("main.asm", 0, 10).len(), 3);
+    //s1 is a register of RISC-V
+    assert!(index
+        .rename_label("lib.asm", 2, 1, "s1", AvailableDatapaths::RISCV)
+        .is_err());
+}
+
+#[test]
+fn outline_lists_text_labels_before_data_labels() {
+    let files = [file(
+        "main.asm",
+        ".data\nvalue: .word 1\n.text\nmain: lw $t0, value\nsyscall",
+    )];
+    let index = index_of(&files, AvailableDatapaths::MIPS);
+
+    let outline: Vec<(&str, Section, usize)> = index
+        .outline("main.asm")
+        .into_iter()
+        .map(|label| (label.name.as_str(), label.section, label.location.line))
+        .collect();
+    assert_eq!(
+        outline,
+        vec![("main", Section::Text, 3), ("value", Section::Data, 1)]
+    );
+}
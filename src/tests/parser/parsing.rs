@@ -6,7 +6,7 @@
 };
 use crate::parser::parser_structs_and_enums::TokenType::{Label, Operator, Unknown};
 use crate::parser::parser_structs_and_enums::{
-    Data, Error, ErrorType, Instruction, LabelInstance, MonacoLineInfo, Token,
+    Data, Error, ErrorType, FixIt, Instruction, LabelInstance, MonacoLineInfo, Token,
 };
 use crate::parser::parsing::create_label_map;
 #[cfg(test)]
@@ -66,6 +66,8 @@ fn tokenize_program_works_basic_version() {
         tokens: vec![i_0_t_0, i_0_t_1],
         updated_monaco_string: "This line".to_string(),
         errors: vec![],
+        warnings: vec![],
+        source: None,
     };
 
     let line_1 = MonacoLineInfo {
@@ -75,6 +77,8 @@ fn tokenize_program_works_basic_version() {
         tokens: vec![i_1_t_0, i_1_t_1, i_1_t_2],
         updated_monaco_string: "This second line".to_string(),
         errors: vec![],
+        warnings: vec![],
+        source: None,
     };
 
     let line_2 = MonacoLineInfo {
@@ -84,6 +88,8 @@ fn tokenize_program_works_basic_version() {
         tokens: vec![i_2_t_0, i_2_t_1, i_2_t_2],
         updated_monaco_string: "Here's a third!".to_string(),
         errors: vec![],
+        warnings: vec![],
+        source: None,
     };
 
     let correct_result = vec![line_0, line_1, line_2];
@@ -144,6 +150,8 @@ fn tokenize_program_handles_no_spaces_between_commas() {
         tokens: vec![i_0_t_0, i_0_t_1, i_0_t_2, i_0_t_3],
         updated_monaco_string: "add $t1, $t2, $t3".to_string(),
         errors: vec![],
+        warnings: vec![],
+        source: None,
     };
 
     let line_1 = MonacoLineInfo {
@@ -153,6 +161,8 @@ fn tokenize_program_handles_no_spaces_between_commas() {
         tokens: vec![i_1_t_0, i_1_t_1, i_1_t_2, i_1_t_3],
         updated_monaco_string: "sub $s1,$s2,$s3".to_string(),
         errors: vec![],
+        warnings: vec![],
+        source: None,
     };
 
     let correct_result = vec![line_0, line_1];
@@ -190,6 +200,8 @@ fn tokenize_program_handles_comma_after_space() {
         tokens: vec![i_0_t_0, i_0_t_1, i_0_t_2, i_0_t_3],
         updated_monaco_string: "add $t1 , $t2, $t3".to_string(),
         errors: vec![],
+        warnings: vec![],
+        source: None,
     };
 
     let correct_result = vec![line_0];
@@ -220,6 +232,8 @@ fn tokenize_program_ignores_comments() {
         tokens: vec![i_0_t_0, i_0_t_1],
         updated_monaco_string: "This Line".to_string(),
         errors: vec![],
+        warnings: vec![],
+        source: None,
     };
     let line_1 = MonacoLineInfo {
         mouse_hover_string: "".to_string(),
@@ -228,6 +242,8 @@ fn tokenize_program_ignores_comments() {
         tokens: vec![],
         updated_monaco_string: "#this line is a comment".to_string(),
         errors: vec![],
+        warnings: vec![],
+        source: None,
     };
     let line_2 = MonacoLineInfo {
         mouse_hover_string: "".to_string(),
@@ -240,6 +256,8 @@ fn tokenize_program_ignores_comments() {
         }],
         updated_monaco_string: "but_this_isn't".to_string(),
         errors: vec![],
+        warnings: vec![],
+        source: None,
     };
     let line_3 = MonacoLineInfo {
         mouse_hover_string: "".to_string(),
@@ -252,6 +270,8 @@ fn tokenize_program_ignores_comments() {
         }],
         updated_monaco_string: "this#has a comment in the middle".to_string(),
         errors: vec![],
+        warnings: vec![],
+        source: None,
     };
 
     let correct_result = vec![line_0, line_1, line_2, line_3];
@@ -362,6 +382,11 @@ fn separate_data_and_text_generates_error_on_missing_commas_text() {
         token_causing_error: "add".to_string(),
         start_end_columns: (0, 3),
         message: "".to_string(),
+        fix_its: vec![FixIt {
+            title: "Remove comma".to_string(),
+            start_end_columns: (3, 4),
+            replacement: "".to_string(),
+        }],
     };
 
     assert_eq!(error_0_line_0, result[0].errors[0]);
@@ -371,6 +396,11 @@ fn separate_data_and_text_generates_error_on_missing_commas_text() {
         token_causing_error: "$t3".to_string(),
         start_end_columns: (15, 18),
         message: "".to_string(),
+        fix_its: vec![FixIt {
+            title: "Remove comma".to_string(),
+            start_end_columns: (18, 19),
+            replacement: "".to_string(),
+        }],
     };
 
     assert_eq!(error_1_line_0, result[0].errors[1]);
@@ -380,6 +410,11 @@ fn separate_data_and_text_generates_error_on_missing_commas_text() {
         token_causing_error: "$t1".to_string(),
         start_end_columns: (3, 6),
         message: "".to_string(),
+        fix_its: vec![FixIt {
+            title: "Add missing comma".to_string(),
+            start_end_columns: (6, 6),
+            replacement: ",".to_string(),
+        }],
     };
     assert_eq!(error_line_1, result[1].errors[0]);
 }
@@ -986,6 +1021,7 @@ fn suggest_error_suggestions_associates_error_with_monaco_line_info() {
         token_causing_error: "100".to_string(),
         start_end_columns: (9, 12),
         message: "GP register is not recognized.\n".to_string(),
+        fix_its: Vec::new(),
     };
     assert_eq!(lines[0].errors[0], actual);
 
@@ -994,6 +1030,7 @@ fn suggest_error_suggestions_associates_error_with_monaco_line_info() {
         token_causing_error: "$t2".to_string(),
         start_end_columns: (14, 17),
         message: "The given string cannot be recognized as an integer.\n".to_string(),
+        fix_its: Vec::new(),
     };
     assert_eq!(lines[0].errors[1], actual);
 
@@ -1002,6 +1039,7 @@ fn suggest_error_suggestions_associates_error_with_monaco_line_info() {
         token_causing_error: "$f1".to_string(),
         start_end_columns: (3, 6),
         message: "Expected GP register but received FP register.\n".to_string(),
+        fix_its: Vec::new(),
     };
     assert_eq!(lines[1].errors[0], actual);
 
@@ -1010,6 +1048,7 @@ fn suggest_error_suggestions_associates_error_with_monaco_line_info() {
         token_causing_error: "word".to_string(),
         start_end_columns: (0, 4),
         message: "".to_string(),
+        fix_its: Vec::new(),
     };
     assert_eq!(lines[3].errors[0], actual);
 
@@ -1018,6 +1057,11 @@ fn suggest_error_suggestions_associates_error_with_monaco_line_info() {
         token_causing_error: ".wod".to_string(),
         start_end_columns: (5, 9),
         message: "Given string does not match data type directives. A valid, similar data type is: .word.\n".to_string(),
+        fix_its: vec![FixIt {
+            title: "Replace with `.word`".to_string(),
+            start_end_columns: (5, 9),
+            replacement: ".word".to_string(),
+        }],
     };
     assert_eq!(lines[3].errors[1], actual);
 }
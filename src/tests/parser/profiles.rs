@@ -0,0 +1,131 @@
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::parser::parser_assembler_main::parser;
+use crate::parser::parser_structs_and_enums::ErrorType::ProfileViolation;
+use crate::parser::parser_structs_and_enums::ProgramInfo;
+use crate::parser::profiles::{built_in_profiles, enforce_profile, parse_profile, Profile};
+
+fn profile(name: &str) -> Profile {
+    built_in_profiles()
+        .into_iter()
+        .find(|profile| profile.name == name)
+        .unwrap()
+}
+
+fn enforced(program: &str, profile: &Profile, arch: AvailableDatapaths) -> ProgramInfo {
+    let (mut program_info, _, _) = parser(program.to_string(), arch);
+    enforce_profile(&mut program_info, profile, arch);
+    program_info
+}
+
+///Returns the lines with a profile violation and the message of each.
+fn violations(program_info: &ProgramInfo) -> Vec<(usize, String)> {
+    program_info
+        .monaco_line_info
+        .iter()
+        .flat_map(|line| {
+            line.errors
+                .iter()
+                .filter(|error| error.error_name == ProfileViolation)
+                .map(|error| (line.line_number, error.message.clone()))
+        })
+        .collect()
+}
+
+#[test]
+fn pseudo_instructions_are_rejected_without_pseudo_instructions() {
+    let program_info = enforced(
+        "li $t0, 5\nmove $t1, $t0\nadd $t2, $t1, $t0\nprint_int $t2",
+        &profile("No pseudo-instructions"),
+        AvailableDatapaths::MIPS,
+    );
+
+    let found = violations(&program_info);
+    assert_eq!(
+        found.iter().map(|(line, _)| *line).collect::<Vec<_>>(),
+        vec![0, 1, 3]
+    );
+    assert!(found[0].1.starts_with(
+        "`li` is a pseudo-instruction, which the No pseudo-instructions profile does not allow."
+    ));
+    assert!(program_info
+        .console_out_post_assembly
+        .contains("ProfileViolation on line 2 with token \"move\""));
+}
+
+#[test]
+fn forbidden_instructions_are_rejected_as_written_and_expanded() {
+    let program_info = enforced(
+        "mul $t0, $t1, $t2\nmuli $t0, $t1, 4\nsll $t0, $t1, 2",
+        &profile("No multiply or divide"),
+        AvailableDatapaths::MIPS,
+    );
+
+    let found = violations(&program_info);
+    assert_eq!(found.len(), 2);
+    assert!(found[0]
+        .1
+        .starts_with("`mul` is not allowed by the No multiply or divide profile."));
+    assert!(found[1].1.starts_with("`muli` is not allowed"));
+}
+
+#[test]
+fn allowed_instructions_limit_what_pseudo_instructions_expand_to() {
+    let rv32i = profile("RV32I only");
+    let program_info = enforced(
+        "li t0, 5\nmv t1, t0\naddiw t2, t1, 1\nmul t3, t1, t2",
+        &rv32i,
+        AvailableDatapaths::RISCV,
+    );
+
+    let found = violations(&program_info);
+    assert_eq!(
+        found.iter().map(|(line, _)| *line).collect::<Vec<_>>(),
+        vec![2, 3]
+    );
+    assert!(found[0]
+        .1
+        .starts_with("`addiw` is not allowed by the RV32I only profile."));
+
+    //the profile is only for RISC-V, so MIPS programs are not checked against it
+    let program_info = enforced("mul $t0, $t1, $t2", &rv32i, AvailableDatapaths::MIPS);
+    assert!(violations(&program_info).is_empty());
+}
+
+#[test]
+fn profiles_are_read_from_config_files() {
+    let profile = parse_profile(
+        "# week 3 lab\nname = Week 3\ndescription = Only arithmetic so far.\narchitecture = mips\npseudo_instructions = no\nallow = add, addi sub\nallow = syscall\n",
+    )
+    .unwrap();
+    assert_eq!(profile.name, "Week 3");
+    assert_eq!(profile.arch, Some(AvailableDatapaths::MIPS));
+    assert!(!profile.allow_pseudo_instructions);
+    assert_eq!(
+        profile.allowed_instructions,
+        Some(vec![
+            "add".to_string(),
+            "addi".to_string(),
+            "sub".to_string(),
+            "syscall".to_string()
+        ])
+    );
+
+    let program_info = enforced(
+        "addi $t0, $zero, 1\nand $t1, $t0, $t0",
+        &profile,
+        AvailableDatapaths::MIPS,
+    );
+    assert_eq!(
+        violations(&program_info),
+        vec![(
+            1,
+            "`and` is not allowed by the Week 3 profile. Only arithmetic so far.\n".to_string()
+        )]
+    );
+
+    assert_eq!(
+        parse_profile("name = x\ncolour = blue"),
+        Err("Line 2 of the profile has the unknown setting `colour`. The settings are name, description, architecture, pseudo_instructions, allow, and forbid.".to_string())
+    );
+    assert!(parse_profile("allow = add").is_err());
+}
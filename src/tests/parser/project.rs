@@ -0,0 +1,147 @@
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::parser::parser_assembler_main::parser;
+use crate::parser::parser_structs_and_enums::ErrorType::{
+    IncludedFileNotFound, RecursiveInclude, UnrecognizedGPRegister,
+};
+use crate::parser::parser_structs_and_enums::SourceLocation;
+use crate::parser::project::*;
+use crate::tests::parser::file;
+
+mod include_tests {
+    use super::*;
+
+    #[test]
+    fn included_lines_follow_the_include() {
+        let files = [
+            file("main.asm", "jal print\n.include \"io.asm\"\nnop"),
+            file("io.asm", "print: addi $a0, $zero, 1\njr $ra"),
+        ];
+        let (program_info, binary, labels) = parse_project(&files, AvailableDatapaths::MIPS);
+        let (_, expected, _) = parser(
+            "jal print\nprint: addi $a0, $zero, 1\njr $ra\nnop".to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert_eq!(binary, expected);
+        assert_eq!(labels.get("print"), Some(&4));
+        assert_eq!(
+            program_info.source_location(2),
+            Some(&SourceLocation {
+                file: "io.asm".to_string(),
+                line: 0
+            })
+        );
+        assert_eq!(
+            program_info.source_location(4),
+            Some(&SourceLocation {
+                file: "main.asm".to_string(),
+                line: 2
+            })
+        );
+    }
+
+    #[test]
+    fn errors_are_reported_in_the_file_they_are_written_in() {
+        let files = [
+            file("main.asm", ".include \"lib.asm\"\nadd $t0, $t1, $t2"),
+            file("lib.asm", "nop\nadd $t0, $t1, $bad"),
+        ];
+        let (program_info, _, _) = parse_project(&files, AvailableDatapaths::MIPS);
+
+        let library_errors: Vec<_> = program_info
+            .lines_of_file("lib.asm")
+            .flat_map(|line| {
+                line.errors
+                    .iter()
+                    .map(|_| line.source.clone().unwrap().line)
+            })
+            .collect();
+        assert_eq!(library_errors, [1]);
+        assert!(program_info
+            .lines_of_file("main.asm")
+            .all(|line| line.errors.is_empty()));
+        assert_eq!(
+            program_info.monaco_line_info[2].errors[0].error_name,
+            UnrecognizedGPRegister
+        );
+        assert!(program_info
+            .console_out_post_assembly
+            .contains("on line 2 of lib.asm"));
+    }
+
+    #[test]
+    fn missing_and_malformed_includes() {
+        let files = [file(
+            "main.asm",
+            ".include \"nowhere.asm\"\n.include io.asm\nnop",
+        )];
+        let (program_info, _, _) = parse_project(&files, AvailableDatapaths::RISCV);
+
+        let first = &program_info.monaco_line_info[0].errors[0];
+        let second = &program_info.monaco_line_info[1].errors[0];
+        assert_eq!(first.error_name, IncludedFileNotFound);
+        assert_eq!(first.token_causing_error, "nowhere.asm");
+        assert_eq!(second.error_name, IncludedFileNotFound);
+        assert!(second.message.contains("in quotes"));
+    }
+
+    #[test]
+    fn include_outside_of_a_project_is_an_error() {
+        let (program_info, _, _) = parser(
+            ".include \"io.asm\"\nnop".to_string(),
+            AvailableDatapaths::MIPS,
+        );
+
+        assert_eq!(
+            program_info.monaco_line_info[0].errors[0].error_name,
+            IncludedFileNotFound
+        );
+    }
+
+    #[test]
+    fn recursive_include_is_an_error() {
+        let files = [
+            file("main.asm", ".include \"a.asm\""),
+            file("a.asm", "nop\n.include \"b.asm\""),
+            file("b.asm", ".include \"a.asm\""),
+        ];
+        let (program_info, _, _) = parse_project(&files, AvailableDatapaths::MIPS);
+
+        let line = program_info.lines_of_file("b.asm").next().unwrap();
+        assert_eq!(line.errors[0].error_name, RecursiveInclude);
+        assert_eq!(program_info.instructions.len(), 2);
+    }
+
+    #[test]
+    fn updated_strings_are_split_by_file() {
+        let files = [
+            file("main.asm", "li $t0, 5\n.include \"lib.asm\"\nsyscall"),
+            file("lib.asm", "move $t1, $t0"),
+        ];
+        let (program_info, _, _) = parse_project(&files, AvailableDatapaths::MIPS);
+
+        let main = program_info.updated_file_string("main.asm").unwrap();
+        let library = program_info.updated_file_string("lib.asm").unwrap();
+        assert!(main.starts_with("#Pseudo-Instruction: li $t0, 5\n"));
+        assert!(main.ends_with(".include \"lib.asm\"\nsyscall\n"));
+        assert!(library.starts_with("#Pseudo-Instruction: move $t1, $t0\n"));
+        assert_eq!(program_info.updated_file_string("unused.asm"), None);
+    }
+
+    #[test]
+    fn macros_from_a_library_report_the_library_line() {
+        let files = [
+            file("main.asm", ".include \"macros.asm\"\nbad($t0)"),
+            file(
+                "macros.asm",
+                ".macro bad(%r)\nadd %r, $t1, $nope\n.end_macro",
+            ),
+        ];
+        let (program_info, _, _) = parse_project(&files, AvailableDatapaths::MIPS);
+
+        let invocation = &program_info.monaco_line_info[4];
+        assert!(invocation.errors[0]
+            .message
+            .contains("line 2 of the macro body in macros.asm"));
+    }
+}
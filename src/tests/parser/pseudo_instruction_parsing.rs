@@ -137,6 +137,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_li() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
 }
@@ -186,6 +187,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_move() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
 }
@@ -235,6 +237,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_subi() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
     assert_eq!(
@@ -267,6 +270,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_subi() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
 }
@@ -316,6 +320,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_muli() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
     assert_eq!(
@@ -348,6 +353,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_muli() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
 }
@@ -397,6 +403,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_divi() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
     assert_eq!(
@@ -429,6 +436,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_divi() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
 }
@@ -478,6 +486,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_dsubi() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
     assert_eq!(
@@ -510,6 +519,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_dsubi() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
 }
@@ -559,6 +569,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_dsubiu() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
     assert_eq!(
@@ -591,6 +602,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_dsubiu() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
 }
@@ -640,6 +652,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_dmuli() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
     assert_eq!(
@@ -672,6 +685,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_dmuli() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
 }
@@ -721,6 +735,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_dmuliu() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
     assert_eq!(
@@ -753,6 +768,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_dmuliu() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
 }
@@ -802,6 +818,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_ddivi() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
     assert_eq!(
@@ -834,6 +851,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_ddivi() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
 }
@@ -883,6 +901,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_ddiviu() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
     assert_eq!(
@@ -915,6 +934,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_ddiviu() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
 }
@@ -964,6 +984,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_sgt() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
 }
@@ -1013,6 +1034,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_sgtu() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
 }
@@ -1062,6 +1084,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_seq() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
     assert_eq!(
@@ -1094,6 +1117,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_seq() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
     assert_eq!(
@@ -1126,6 +1150,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_seq() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
 }
@@ -1175,6 +1200,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_sne() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
     assert_eq!(
@@ -1207,6 +1233,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_sne() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
 }
@@ -1256,6 +1283,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_sle() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
     assert_eq!(
@@ -1288,6 +1316,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_sle() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
 
@@ -1321,6 +1350,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_sle() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
 }
@@ -1370,6 +1400,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_sleu() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
     assert_eq!(
@@ -1402,6 +1433,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_sleu() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
 
@@ -1435,6 +1467,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_sleu() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
 }
@@ -1484,6 +1517,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_sge() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
     assert_eq!(
@@ -1516,6 +1550,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_sge() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
 
@@ -1549,6 +1584,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_sge() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
 }
@@ -1610,6 +1646,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_sgeu() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
     assert_eq!(
@@ -1642,6 +1679,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_sgeu() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
 
@@ -1675,6 +1713,7 @@ fn expand_pseudo_instructions_and_assign_instruction_numbers_works_sgeu() {
             line_number: 0,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
 }
@@ -1729,6 +1768,7 @@ fn complete_lw_sw_pseudo_instructions_works_multiple_using_same_label() {
             line_number: 3,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
     assert_eq!(
@@ -1756,6 +1796,7 @@ fn complete_lw_sw_pseudo_instructions_works_multiple_using_same_label() {
             line_number: 3,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
     assert_eq!(
@@ -1783,6 +1824,7 @@ fn complete_lw_sw_pseudo_instructions_works_multiple_using_same_label() {
             line_number: 4,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
     assert_eq!(
@@ -1810,6 +1852,7 @@ fn complete_lw_sw_pseudo_instructions_works_multiple_using_same_label() {
             line_number: 4,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
 }
@@ -1863,6 +1906,7 @@ fn complete_lw_sw_pseudo_instructions_works() {
             line_number: 3,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
     assert_eq!(
@@ -1890,6 +1934,7 @@ fn complete_lw_sw_pseudo_instructions_works() {
             line_number: 3,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
     assert_eq!(
@@ -1917,6 +1962,7 @@ fn complete_lw_sw_pseudo_instructions_works() {
             line_number: 4,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
     assert_eq!(
@@ -1944,6 +1990,7 @@ fn complete_lw_sw_pseudo_instructions_works() {
             line_number: 4,
             errors: vec![],
             labels: Vec::new(),
+            macro_line_number: None,
         }
     );
 }
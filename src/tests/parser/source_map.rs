@@ -0,0 +1,89 @@
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::parser::parser_assembler_main::parser;
+use crate::parser::parser_structs_and_enums::SourceMapEntry;
+use crate::parser::project::parse_project;
+use crate::parser::source_map::expansion_hints_in_file;
+use crate::tests::parser::file;
+
+fn entry(
+    address: usize,
+    line_number: usize,
+    instruction: &str,
+    expanded_from: Option<&str>,
+) -> SourceMapEntry {
+    SourceMapEntry {
+        address,
+        line_number,
+        instruction: instruction.to_string(),
+        expanded_from: expanded_from.map(str::to_string),
+    }
+}
+
+#[test]
+fn every_word_maps_to_the_line_and_pseudo_instruction_it_came_from() {
+    let (program_info, _, _) = parser(
+        "main: li $t0, 5 # five\nlw $t1, value\nadd $t2, $t0, $t1\n.data\nvalue: .word 3"
+            .to_string(),
+        AvailableDatapaths::MIPS,
+    );
+
+    assert_eq!(
+        program_info.source_map,
+        vec![
+            entry(0, 0, "ori $t0, $zero, 5", Some("li $t0, 5")),
+            entry(4, 1, "lui $at, 0", Some("lw $t1, value")),
+            entry(8, 1, "lw $t1, 20($at)", Some("lw $t1, value")),
+            entry(12, 2, "add $t2, $t0, $t1", None),
+            //the syscall added to the end of the program was not expanded from anything
+            entry(16, 2, "syscall", None),
+        ]
+    );
+    let lines: Vec<usize> = program_info
+        .source_map
+        .iter()
+        .map(|entry| entry.line_number)
+        .collect();
+    assert_eq!(lines, program_info.address_to_line_number);
+}
+
+#[test]
+fn macro_invocations_are_mapped_and_hinted_on_their_line() {
+    let (program_info, _, _) = parser(
+        ".macro add3(%d, %a, %b)\nadd %d, %a, %b\nadd %d, %d, %b\n.end_macro\nadd3($t0, $t1, $t2)\nsyscall"
+            .to_string(),
+        AvailableDatapaths::MIPS,
+    );
+
+    assert_eq!(
+        program_info.source_map[1],
+        entry(4, 4, "add $t0, $t0, $t2", Some("add3($t0, $t1, $t2)"))
+    );
+    assert_eq!(
+        expansion_hints_in_file(&program_info, "main.asm"),
+        vec![(4, "add $t0, $t1, $t2; add $t0, $t0, $t2".to_string())]
+    );
+}
+
+#[test]
+fn hints_are_given_on_the_lines_of_each_file() {
+    let files = [
+        file(
+            "main.asm",
+            ".include \"lib.asm\"\nmv t1, t0\nadd t2, t1, t0",
+        ),
+        file("lib.asm", "setup: li t0, 5\nret"),
+    ];
+    let (program_info, _, _) = parse_project(&files, AvailableDatapaths::RISCV);
+
+    assert_eq!(
+        expansion_hints_in_file(&program_info, "main.asm"),
+        vec![(1, "addi t1, t0, 0".to_string())]
+    );
+    assert_eq!(
+        expansion_hints_in_file(&program_info, "lib.asm"),
+        vec![
+            (0, "lui t0, 0; addi t0, t0, 5".to_string()),
+            (1, "jalr x0, x1, 0".to_string()),
+        ]
+    );
+}
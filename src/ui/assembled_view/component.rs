@@ -5,6 +5,7 @@
 use std::rc::Rc;
 
 use crate::agent::datapath_communicator::DatapathCommunicator;
+use crate::agent::heap::Heap;
 use crate::emulation_core::mips::memory::{Memory, MemoryIter};
 use crate::emulation_core::stack::Stack;
 // use monaco::api::TextModel;
@@ -16,7 +17,7 @@
 use yew::{Html, Properties};
 
 // ** Segment Viewer Components ** //
-// Displays the text, data, stack segments, and stack frame view
+// Displays the text, data, stack segments, stack frame view, and heap
 // IDEA: Create Segment Viewer component for extendability to any segment
 
 #[derive(PartialEq, Properties)]
@@ -122,7 +123,6 @@ pub fn TextSegment(props: &TextSegmentProps) -> Html {
         )
     };
 
-    let mut address = -4;
     html! {
         <table class="h-[96%] bg-primary-900 overflow-x-auto">
         // | breakpoint checkbox | address | instruction in binary | instruction in hex | updated string | source string
@@ -141,9 +141,20 @@ pub fn TextSegment(props: &TextSegmentProps) -> Html {
                     let on_address_click = Callback::clone(&on_address_click);
                     let on_assembled_click = Callback::clone(&on_assembled_click);
                     let executed_ref = executed_ref.clone();
-                    address += 4;
+                    // instruction numbers are word addresses, which .org and .align can leave gaps between
+                    let address = (instruction.instruction_number * 4) as i64;
 
                     let line_number = instruction.line_number;
+                    // Words expanded from a pseudo-instruction or macro show what was written on their line
+                    let expanded_from = program_info.source_map.get(index).and_then(|entry| entry.expanded_from.clone());
+                    let assembled_title = match &expanded_from {
+                        Some(written) => format!("Expanded from `{written}`. Go to line in editor"),
+                        None => "Go to line in editor".to_string(),
+                    };
+                    let expansion_note = match expanded_from {
+                        Some(written) => html! { <span class="text-primary-300">{format!(" (from {written})")}</span> },
+                        None => html! {},
+                    };
 
                     let mut conditional_class = "";
                     if **editor_curr_line != 0.0 && props.pc as i64 == address + 4 {
@@ -164,8 +175,9 @@ pub fn TextSegment(props: &TextSegmentProps) -> Html {
                                 <td>
                                     {format!("0x{:08x}", instruction.binary)}
                                 </td>
-                                <td class="text-accent-blue-200 hover:text-accent-blue-100 cursor-pointer" title="Go to line in editor" onclick={move |e: MouseEvent| {on_assembled_click.emit((e, line_number))}}>
+                                <td class="text-accent-blue-200 hover:text-accent-blue-100 cursor-pointer" title={assembled_title} onclick={move |e: MouseEvent| {on_assembled_click.emit((e, line_number))}}>
                                     {recreated_string}
+                                    {expansion_note}
                                 </td>
                                 <td>
                                     {format!("{}: {:?}", line_number + 1, lines_content.get(line_number).unwrap_or(&String::from("")))}
@@ -190,8 +202,9 @@ pub fn TextSegment(props: &TextSegmentProps) -> Html {
                                 <td>
                                     {format!("0x{:08x}", instruction.binary)}
                                 </td>
-                                <td class="text-accent-blue-200 hover:text-accent-blue-100 cursor-pointer" title="Go to line in editor" onclick={move |e: MouseEvent| {on_assembled_click.emit((e, line_number))}}>
+                                <td class="text-accent-blue-200 hover:text-accent-blue-100 cursor-pointer" title={assembled_title} onclick={move |e: MouseEvent| {on_assembled_click.emit((e, line_number))}}>
                                     {recreated_string}
+                                    {expansion_note}
                                 </td>
                                 <td>
                                     {format!("{}: {:?}", line_number + 1, lines_content.get(line_number).unwrap_or(&String::from("")))}
@@ -255,7 +268,7 @@ pub fn DataSegment(props: &DataSegmentProps) -> Html {
             </tr>
             {
                 if !program_info.instructions.is_empty() && !binary.is_empty() {
-                    let mut address = program_info.instructions.len() * 4 - 4;
+                    let mut address = program_info.data_starting_point * 4 - 4;
                     let mut data_binary_index = program_info.data_starting_point - 1;
                     program_info.data.iter().enumerate().map(|(index, data)| {
                         let recreated_string = data.recreate_string();
@@ -477,3 +490,108 @@ pub fn StackFrameView(props: &StackFrameProps) -> Html {
         </table>
     }
 }
+
+#[derive(PartialEq, Properties)]
+pub struct HeapSegmentProps {
+    pub heap: Heap,
+    pub memory_curr_instr: UseStateHandle<u64>,
+    pub console_active_tab: UseStateHandle<TabState>,
+}
+
+#[function_component]
+pub fn HeapSegment(props: &HeapSegmentProps) -> Html {
+    let heap = &props.heap;
+    let console_active_tab = &props.console_active_tab;
+    let memory_curr_instr = &props.memory_curr_instr;
+
+    // Go to the memory address in hex editor
+    let on_address_click = {
+        let memory_curr_instr = memory_curr_instr.clone();
+        let console_active_tab = console_active_tab.clone();
+        use_callback(
+            move |args: (MouseEvent, usize), memory_curr_instr| {
+                let (_e, address) = args;
+                memory_curr_instr.set(address as u64);
+                console_active_tab.set(TabState::HexEditor);
+            },
+            memory_curr_instr,
+        )
+    };
+
+    let on_start_click = Callback::clone(&on_address_click);
+    let on_break_click = Callback::clone(&on_address_click);
+
+    html! {
+        <div class="h-[96%] bg-primary-900 overflow-x-auto">
+            <table>
+            // | heap start | program break | size | in use
+                <tr>
+                    <th>{"Heap Start"}</th>
+                    <th>{"Program Break"}</th>
+                    <th>{"Size"}</th>
+                    <th>{"In Use"}</th>
+                </tr>
+                <tr>
+                    <td class="text-accent-green-300 hover:text-accent-green-200 cursor-pointer" title={format!("Go to address in memory {:08x}", heap.start)} onclick={
+                        let start = heap.start as usize;
+                        move |e: MouseEvent| {on_start_click.emit((e, start))}
+                    }>
+                        {format!("0x{:08x}", heap.start)}
+                    </td>
+                    <td class="text-accent-green-300 hover:text-accent-green-200 cursor-pointer" title={format!("Go to address in memory {:08x}", heap.program_break)} onclick={
+                        let program_break = heap.program_break as usize;
+                        move |e: MouseEvent| {on_break_click.emit((e, program_break))}
+                    }>
+                        {format!("0x{:08x}", heap.program_break)}
+                    </td>
+                    <td>{format!("{} bytes", heap.program_break - heap.start)}</td>
+                    <td>{format!("{} bytes", heap.live_bytes())}</td>
+                </tr>
+            </table>
+            <table>
+            // | address | size | status
+                <tr>
+                    <th>{"Address"}</th>
+                    <th>{"Size"}</th>
+                    <th>{"Status"}</th>
+                </tr>
+                {
+                    heap.allocations.iter().map(|block| {
+                        let on_address_click = Callback::clone(&on_address_click);
+                        let address = block.address as usize;
+                        html! {
+                            <tr>
+                                <td class="text-accent-green-300 hover:text-accent-green-200 cursor-pointer" title={format!("Go to address in memory {:08x}", block.address)} onclick={move |e: MouseEvent| {on_address_click.emit((e, address))}}>
+                                    {format!("0x{:08x}", block.address)}
+                                </td>
+                                <td>{format!("{} bytes", block.size)}</td>
+                                if block.live {
+                                    <td class="text-accent-green-300">{"live"}</td>
+                                } else {
+                                    <td class="text-accent-red-200">{"freed"}</td>
+                                }
+                            </tr>
+                        }
+                    }).collect::<Html>()
+                }
+            </table>
+            <table>
+            // | growth address | growth size
+                <tr>
+                    <th>{"sbrk Address"}</th>
+                    <th>{"Growth"}</th>
+                </tr>
+                {
+                    heap.growths.iter().map(|growth| {
+                        html! {
+                            <tr>
+                                <td>{format!("0x{:08x}", growth.address)}</td>
+                                <td>{format!("{} bytes", growth.size)}</td>
+                            </tr>
+                        }
+                    }).collect::<Html>()
+                }
+            </table>
+        </div>
+    }
+}
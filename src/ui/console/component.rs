@@ -1,4 +1,6 @@
+use crate::agent::console_buffer::ConsoleBuffer;
 use crate::agent::datapath_communicator::DatapathCommunicator;
+use crate::agent::messages::OutputChannel;
 use wasm_bindgen::JsCast;
 use web_sys::{HtmlInputElement, InputEvent, KeyboardEvent};
 use yew::prelude::*;
@@ -9,7 +11,7 @@
 #[derive(PartialEq, Properties)]
 pub struct Consoleprops {
     pub communicator: &'static DatapathCommunicator,
-    pub messages: Vec<String>,
+    pub output: ConsoleBuffer,
     pub parsermsg: String,
     pub show_input: UseStateHandle<bool>,
 }
@@ -61,14 +63,20 @@ pub fn console(props: &Consoleprops) -> Html {
                     .collect::<Html>()
             }
             <div>
+                if props.output.dropped_lines() > 0 {
+                    <div class="text-gray-400">{format!("({} earlier lines hidden)", props.output.dropped_lines())}</div>
+                }
                 {
                     props
-                        .messages
-                        .iter()
-                        .flat_map(|msg| {
-                            msg.split('\n').map(|line| {
-                                html! { <div>{line}</div> }
-                            })
+                        .output
+                        .lines()
+                        .map(|line| {
+                            let class = match line.channel {
+                                OutputChannel::Stdout => "",
+                                OutputChannel::Stderr => "text-accent-red-200",
+                                OutputChannel::System => "text-gray-400",
+                            };
+                            html! { <div class={class}>{&line.text}</div> }
                         })
                         .collect::<Html>()
                 }
@@ -1,4 +1,5 @@
 use crate::agent::datapath_communicator::DatapathCommunicator;
+use crate::emulation_core::interrupt::{InterruptController, TIMER_INTERRUPT};
 use crate::emulation_core::register::RegisterType;
 use crate::ui::swim_editor::tab::Tab;
 use std::rc::Rc;
@@ -15,6 +16,7 @@
 pub struct Regviewprops {
     pub gp: Vec<(Rc<dyn RegisterType>, u64)>,
     pub fp: Vec<(Rc<dyn RegisterType>, u64)>,
+    pub interrupts: InterruptController,
     pub pc_limit: usize,
     pub communicator: &'static DatapathCommunicator,
 }
@@ -23,6 +25,7 @@ pub enum RegviewTabState {
     #[default]
     Gp,
     Fp,
+    Int,
 }
 
 #[derive(Default, PartialEq, Clone, Copy, Debug)]
@@ -200,6 +203,50 @@ pub fn generate_fpr_rows(props: &Regviewprops, unit_type: UnitState) -> Html {
         .collect::<Html>()
 }
 
+// ============= Interrupt Controller =============
+// Read-only view of the timer and interrupt controller state
+pub fn generate_interrupt_rows(props: &Regviewprops, radix: u32) -> Html {
+    let interrupts = props.interrupts;
+    let format_value = |data: u64| match radix {
+        16 => format!("{:#04x?}", data),
+        2 => format!("{:#b}", data),
+        _ => data.to_string(),
+    };
+    let format_flag = |flag: bool| if flag { "yes" } else { "no" }.to_string();
+
+    let rows = [
+        ("Global Enable", format_flag(interrupts.global_enable)),
+        ("Enabled", format_value(interrupts.enabled as u64)),
+        ("Pending", format_value(interrupts.pending as u64)),
+        (
+            "Timer Enabled",
+            format_flag(interrupts.is_enabled(TIMER_INTERRUPT)),
+        ),
+        (
+            "Timer Pending",
+            format_flag(interrupts.is_pending(TIMER_INTERRUPT)),
+        ),
+        ("Count / mtime", format_value(interrupts.timer.count)),
+        ("Compare / mtimecmp", format_value(interrupts.timer.compare)),
+        ("Vector", format_value(interrupts.vector)),
+        ("Return Address", format_value(interrupts.return_address)),
+        ("Cause", format_value(interrupts.cause as u64)),
+    ];
+
+    rows.into_iter()
+        .map(|(name, value)| {
+            html! {
+                <tr>
+                    <td>{name}</td>
+                    <td>
+                        <input type="text" readonly={true} value={value}/>
+                    </td>
+                </tr>
+            }
+        })
+        .collect::<Html>()
+}
+
 #[function_component(Regview)]
 pub fn regview(props: &Regviewprops) -> Html {
     let active_view = use_state_eq(UnitState::default);
@@ -238,6 +285,7 @@ pub fn regview(props: &Regviewprops) -> Html {
             let new_tab = match tab_name.as_str() {
                 "gp" => RegviewTabState::Gp,
                 "fp" => RegviewTabState::Fp,
+                "int" => RegviewTabState::Int,
                 _ => RegviewTabState::default(),
             };
 
@@ -251,6 +299,7 @@ pub fn regview(props: &Regviewprops) -> Html {
                 <div>
                     <Tab<RegviewTabState> label="gp" text="GP" on_click={change_tab.clone()} disabled={false} active_tab={active_tab.clone()} tab_name={RegviewTabState::Gp}/>
                     <Tab<RegviewTabState> label="fp" text="FP" on_click={change_tab.clone()} disabled={false} active_tab={active_tab.clone()} tab_name={RegviewTabState::Fp}/>
+                    <Tab<RegviewTabState> label="int" text="INT" on_click={change_tab.clone()} disabled={false} active_tab={active_tab.clone()} tab_name={RegviewTabState::Int}/>
                 </div>
                 <select class="text-right bg-primary-600 text-primary-200 flex items-center flex-row" name="units" onchange={change_view.clone()} value={
                     match *active_view {
@@ -288,6 +337,15 @@ pub fn regview(props: &Regviewprops) -> Html {
                             } else {
                                 {generate_gpr_rows(props, 10)}
                             }
+                        } else if *active_tab == RegviewTabState::Int {
+                            if *active_view == UnitState::Bin {
+                                {generate_interrupt_rows(props, 2)}
+                            }
+                            else if *active_view == UnitState::Hex {
+                                {generate_interrupt_rows(props, 16)}
+                            } else {
+                                {generate_interrupt_rows(props, 10)}
+                            }
                         } else {
                             {generate_fpr_rows(props, *active_view.clone())}
                         }
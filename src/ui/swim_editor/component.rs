@@ -21,16 +21,25 @@
 use yew::{html, Callback, Properties};
 use yew_hooks::prelude::*;
 
+use crate::agent::heap::Heap;
 use crate::emulation_core::mips::memory::Memory;
 use crate::emulation_core::stack::Stack;
-use crate::parser::parser_assembler_main::parser;
-use crate::ui::assembled_view::component::{StackFrameView, StackSegment};
+use crate::parser::project::parse_project;
+use crate::ui::assembled_view::component::{HeapSegment, StackFrameView, StackSegment};
 use crate::{
     agent::datapath_communicator::DatapathCommunicator,
     emulation_core::architectures::AvailableDatapaths,
     parser::parser_structs_and_enums::ProgramInfo,
     ui::{
         assembled_view::component::{DataSegment, TextSegment},
+        swim_editor::file_tree::{
+            active_file_name, find_active_file, project_files, EditorFile, FileTree, MAIN_FILE_NAME,
+        },
+        swim_editor::outline::Outline,
+        swim_editor::providers::{
+            register_code_action_provider, register_completion_provider,
+            register_expansion_hints_provider, register_label_providers, ProviderContext,
+        },
         swim_editor::tab::{Tab, TabState},
     },
 };
@@ -42,11 +51,14 @@
 // It also contains the buttons that allow the user to copy the code to the clipboard and
 // change the execution speed and architecture.
 // Finally, it has the mouse hover event that allows the user to see the parsed information
-// from the parser when hovering over the lines of code.
+// from the parser when hovering over the lines of code, and registers the providers that
+// let the user move between labels and their uses.
 
 #[derive(PartialEq, Properties)]
 pub struct SwimEditorProps {
     pub text_model: UseStateHandle<TextModel>,
+    pub editor_files: UseStateHandle<Vec<EditorFile>>,
+    pub active_file: UseStateHandle<usize>,
     pub lines_content: Rc<RefCell<Vec<String>>>,
     pub program_info: ProgramInfo,
     pub binary: Vec<u32>,
@@ -63,6 +75,7 @@ pub struct SwimEditorProps {
     pub sp: u64,
     pub memory: Memory,
     pub stack: Stack,
+    pub heap: Heap,
     pub breakpoints: UseStateHandle<HashSet<u64>>,
     pub initialized: bool,
     pub executing: bool,
@@ -76,10 +89,11 @@ pub enum EditorTabState {
     DataSegment,
 }
 
-fn get_options() -> IStandaloneEditorConstructionOptions {
+fn get_options(read_only: bool) -> IStandaloneEditorConstructionOptions {
     let options = IStandaloneEditorConstructionOptions::default();
     options.set_theme("vs-dark".into());
     options.set_language("mips".into());
+    options.set_read_only(read_only.into());
     options.set_scroll_beyond_last_line(false.into());
     options.set_automatic_layout(true.into());
 
@@ -94,7 +108,6 @@ fn get_options() -> IStandaloneEditorConstructionOptions {
     let suggest = ISuggestOptions::default();
     suggest.set_show_keywords(false.into());
     suggest.set_show_variables(false.into());
-    suggest.set_show_icons(false.into());
     suggest.set_show_words(false.into());
     suggest.set_filter_graceful(false.into());
     options.set_suggest(Some(&suggest));
@@ -109,6 +122,84 @@ pub fn SwimEditor(props: &SwimEditorProps) -> Html {
     let editor_active_tab = &props.editor_active_tab;
     let console_active_tab = &props.console_active_tab;
 
+    // The editor shows the file that is active in the file tree
+    let active_file = props
+        .active_file
+        .checked_sub(1)
+        .and_then(|index| props.editor_files.get(index));
+    let active_model = active_file.map_or(text_model, |file| &file.model);
+    let read_only = active_file.is_some_and(|file| file.read_only);
+    let active_name = active_file_name(*props.active_file, &props.editor_files);
+
+    // The current line is a line of the assembled program, so find the file and line it was written on
+    let curr_source = (*props.editor_curr_line as usize)
+        .checked_sub(1)
+        .and_then(|line| props.program_info.source_location(line))
+        .cloned();
+
+    // Open the file the current line was written in when execution moves into another file
+    {
+        let active_file = props.active_file.clone();
+        let editor_files = props.editor_files.clone();
+        use_effect_with_deps(
+            move |curr_source| {
+                if let Some(index) = curr_source
+                    .as_ref()
+                    .and_then(|source| find_active_file(&source.file, &editor_files))
+                {
+                    active_file.set(index);
+                }
+            },
+            curr_source.clone(),
+        );
+    }
+
+    // The label, completion, and code action providers read the files of the project
+    // when Monaco asks them for a result, so they are registered once and given the
+    // files of every render. They keep the project they last assembled between
+    // renders so that it is only assembled again once it changes.
+    let provider_context = use_mut_ref(ProviderContext::default);
+    {
+        let mut provider_context = provider_context.borrow_mut();
+        provider_context.models =
+            std::iter::once((MAIN_FILE_NAME.to_string(), text_model.clone(), false))
+                .chain(
+                    props
+                        .editor_files
+                        .iter()
+                        .map(|file| (file.name.clone(), file.model.clone(), file.read_only)),
+                )
+                .collect();
+        provider_context.current_architecture = props.current_architecture;
+    }
+    {
+        let provider_context = Rc::clone(&provider_context);
+        use_effect_with_deps(
+            move |_| {
+                let providers = (
+                    register_label_providers(Rc::clone(&provider_context)),
+                    register_completion_provider(Rc::clone(&provider_context)),
+                    register_code_action_provider(Rc::clone(&provider_context)),
+                    register_expansion_hints_provider(provider_context),
+                );
+                move || drop(providers)
+            },
+            (),
+        );
+    }
+
+    // Moves the editor to a label picked from the outline
+    let on_outline_select = {
+        let link = link.clone();
+        Callback::from(move |line: f64| {
+            link.with_editor(|editor| {
+                editor
+                    .as_ref()
+                    .reveal_line_in_center(line, Some(ScrollType::Smooth));
+            });
+        })
+    };
+
     // Set up the array that would store hover decorations applied to the
     // text model and initialize the options for it.
     let hover_jsarray = js_sys::Array::new();
@@ -130,7 +221,8 @@ pub fn SwimEditor(props: &SwimEditorProps) -> Html {
         }
 
         use_callback(
-            move |editor_link: CodeEditorLink, (curr_line, initialized)| {
+            move |editor_link: CodeEditorLink,
+                  (curr_line, initialized, program_info, files, active_name)| {
                 editor_link.with_editor(|editor| {
                     let raw_editor = editor.as_ref();
                     let model = raw_editor.get_model().unwrap();
@@ -138,15 +230,36 @@ pub fn SwimEditor(props: &SwimEditorProps) -> Html {
                     let line_count = model.get_line_count() as usize;
                     let mut lines_content = lines_content.borrow_mut();
                     let mut lines = Vec::new();
-                    for i in 1..line_count {
-                        lines.push(model.get_line_content(i as f64));
+                    if program_info.source_location(0).is_some() {
+                        // lines of a project are looked up in the file they were written in
+                        for line in &program_info.monaco_line_info {
+                            let content = line.source.as_ref().and_then(|source| {
+                                let file = files.iter().find(|file| file.name == source.file)?;
+                                file.contents.lines().nth(source.line).map(str::to_string)
+                            });
+                            lines.push(content.unwrap_or_default());
+                        }
+                    } else {
+                        for i in 1..line_count {
+                            lines.push(model.get_line_content(i as f64));
+                        }
                     }
                     *lines_content = lines;
 
+                    // The highlighted line is only shown in the file it was written in
+                    let curr_source = (**curr_line as usize)
+                        .checked_sub(1)
+                        .and_then(|line| program_info.source_location(line));
+                    let curr_line = match curr_source {
+                        Some(source) if source.file == *active_name => (source.line + 1) as f64,
+                        Some(_) => 0.0,
+                        None => **curr_line,
+                    };
+
                     // Only scroll to current line / highlight if the program has been initialized / begun execution
                     if *initialized {
                         // Scroll to current line
-                        raw_editor.reveal_line_in_center(**curr_line, Some(ScrollType::Smooth));
+                        raw_editor.reveal_line_in_center(curr_line, Some(ScrollType::Smooth));
                         // Highlight current line using delta decorations
                         let not_highlighted = js_sys::Array::new();
                         let executed_line = js_sys::Array::new();
@@ -154,13 +267,13 @@ pub fn SwimEditor(props: &SwimEditorProps) -> Html {
                             js_sys::Object::new().unchecked_into();
                         let options: IModelDecorationOptions =
                             js_sys::Object::new().unchecked_into();
-                        if **curr_line != 0.0 {
+                        if curr_line != 0.0 {
                             // Show highlight if current line is not 0
                             options.set_inline_class_name("executedLine".into());
                             options.set_is_whole_line(true.into());
                         }
                         decoration.set_options(&options);
-                        let curr_range = Range::new(**curr_line, 0.0, **curr_line, 0.0);
+                        let curr_range = Range::new(curr_line, 0.0, curr_line, 0.0);
                         let range_js = curr_range
                             .dyn_into::<JsValue>()
                             .expect("Range is not found.");
@@ -173,7 +286,13 @@ pub fn SwimEditor(props: &SwimEditorProps) -> Html {
                     }
                 });
             },
-            (curr_line, props.initialized),
+            (
+                curr_line,
+                props.initialized,
+                props.program_info.clone(),
+                project_files(text_model, &props.editor_files),
+                active_name.clone(),
+            ),
         )
     };
 
@@ -236,17 +355,30 @@ pub fn SwimEditor(props: &SwimEditorProps) -> Html {
     // We'll have the Mouse Hover event running at all times.
     {
         let text_model = text_model.clone();
+        let editor_files = props.editor_files.clone();
+        let active_model = active_model.clone();
+        let active_name = active_name.clone();
         let current_arch = props.current_architecture;
         use_event_with_window("mouseover", move |_: MouseEvent| {
             let hover_jsarray = hover_jsarray.clone();
             let hover_decor_array = hover_decor_array.clone();
-            let text_model = text_model.clone();
-            let curr_model = text_model.as_ref();
-            let (program_info, _, _) = parser(text_model.get_value(), current_arch);
+            let curr_model = active_model.as_ref();
+            let (program_info, _, _) =
+                parse_project(&project_files(&text_model, &editor_files), current_arch);
+
+            // Parse output from parser and create an instance of IModelDeltaDecoration for each line
+            // of the file that is open. A file included more than once shows its first inclusion.
+            let mut next_line = 0;
+            for line_information in program_info.lines_of_file(&active_name) {
+                let line_number = line_information
+                    .source
+                    .as_ref()
+                    .map_or(next_line, |source| source.line);
+                if line_number != next_line {
+                    break;
+                }
+                next_line += 1;
 
-            // Parse output from parser and create an instance of IModelDeltaDecoration for each line.
-            for (line_number, line_information) in program_info.monaco_line_info.iter().enumerate()
-            {
                 let decoration: IModelDeltaDecoration = js_sys::Object::new().unchecked_into();
 
                 let hover_range =
@@ -319,6 +451,7 @@ pub fn SwimEditor(props: &SwimEditorProps) -> Html {
                     <Tab<TabState> label={TabState::DataSegment.to_string()} text={"Data Segment".to_string()} on_click={change_tab.clone()} disabled={false} active_tab={editor_active_tab.clone()} tab_name={TabState::DataSegment}/>
                     <Tab<TabState> label={TabState::StackSegment.to_string()} text={"Stack Segment".to_string()} on_click={change_tab.clone()} disabled={false} active_tab={editor_active_tab.clone()} tab_name={TabState::StackSegment}/>
                     <Tab<TabState> label={TabState::StackFrameView.to_string()} text={"Stack Frame".to_string()} on_click={change_tab.clone()} disabled={false} active_tab={editor_active_tab.clone()} tab_name={TabState::StackFrameView}/>
+                    <Tab<TabState> label={TabState::HeapSegment.to_string()} text={"Heap".to_string()} on_click={change_tab.clone()} disabled={false} active_tab={editor_active_tab.clone()} tab_name={TabState::HeapSegment}/>
                 </div>
                 <div class="flex flex-row flex-wrap justify-end items-center gap-2 cursor-default">
                     <button class={classes!("copy-button", conditional_class)} title="Copy to Clipboard" onclick={on_clipboard_clicked}>{"Copy to Clipboard "}<i class={classes!("fa-regular", "fa-copy")}></i></button>
@@ -330,7 +463,11 @@ pub fn SwimEditor(props: &SwimEditorProps) -> Html {
                 </div>
             </div>
             if **editor_active_tab == TabState::Editor {
-                <CodeEditor classes={"editor"} link={link} options={get_options()} model={text_model.clone()} on_editor_created={on_editor_created}/>
+                <div class="flex flex-row flex-grow min-h-0">
+                    <FileTree files={props.editor_files.clone()} active_file={props.active_file.clone()} current_architecture={props.current_architecture} />
+                    <CodeEditor classes={"editor"} link={link} options={get_options(read_only)} model={active_model.clone()} on_editor_created={on_editor_created}/>
+                    <Outline files={project_files(text_model, &props.editor_files)} active_name={active_name.clone()} current_architecture={props.current_architecture} on_select={on_outline_select} />
+                </div>
             } else if **editor_active_tab == TabState::TextSegment {
                 <TextSegment lines_content={props.lines_content.clone()} program_info={props.program_info.clone()} breakpoints={props.breakpoints.clone()} pc={props.pc} editor_active_tab={editor_active_tab.clone()} console_active_tab={console_active_tab.clone()} memory_curr_instr={props.memory_curr_instr.clone()} editor_curr_line={props.editor_curr_line.clone()} communicator={props.communicator}/>
             } else if **editor_active_tab == TabState::DataSegment {
@@ -352,6 +489,12 @@ pub fn SwimEditor(props: &SwimEditorProps) -> Html {
                     editor_curr_line={props.editor_curr_line.clone()}
                     editor_active_tab={props.editor_active_tab.clone()}
                 />
+            } else if **editor_active_tab == TabState::HeapSegment {
+                <HeapSegment
+                    memory_curr_instr={props.memory_curr_instr.clone()}
+                    console_active_tab={console_active_tab.clone()}
+                    heap={props.heap.clone()}
+                />
             }
         </>
     }
@@ -0,0 +1,222 @@
+use gloo::file::FileList;
+use monaco::api::TextModel;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew::{html, Callback, Properties};
+
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::parser::project::ProjectFile;
+
+// ** FileTree Component ** //
+// Lists the files of the project next to the editor. The main file is always
+// first and is the file that gets assembled, while the other files are only
+// assembled where the main file includes them with `.include "file"`.
+// Files can be added as editable files or uploaded as read-only libraries,
+// such as I/O helpers or a test harness provided with an assignment.
+
+/// The name the main file of the project is known by in `.include` diagnostics.
+pub const MAIN_FILE_NAME: &str = "main.asm";
+
+/// A file of the project other than the main file, which is kept as the
+/// editor's own text model.
+#[derive(Clone, PartialEq)]
+pub struct EditorFile {
+    pub name: String,
+    pub model: TextModel,
+    pub read_only: bool,
+}
+
+/// Collects the contents of the main file and every other file of the project
+/// for the parser, with the main file first.
+pub fn project_files(main_model: &TextModel, files: &[EditorFile]) -> Vec<ProjectFile> {
+    let mut project = vec![ProjectFile {
+        name: MAIN_FILE_NAME.to_string(),
+        contents: main_model.get_value(),
+        read_only: false,
+    }];
+    project.extend(files.iter().map(|file| ProjectFile {
+        name: file.name.clone(),
+        contents: file.model.get_value(),
+        read_only: file.read_only,
+    }));
+    project
+}
+
+/// Returns the name of the file that is open in the editor. An active file of
+/// 0 is the main file, otherwise it is an index into files offset by one.
+pub fn active_file_name(active_file: usize, files: &[EditorFile]) -> String {
+    match active_file
+        .checked_sub(1)
+        .and_then(|index| files.get(index))
+    {
+        Some(file) => file.name.clone(),
+        None => MAIN_FILE_NAME.to_string(),
+    }
+}
+
+/// Finds the active file index of the file with the given name.
+pub fn find_active_file(name: &str, files: &[EditorFile]) -> Option<usize> {
+    if name == MAIN_FILE_NAME {
+        return Some(0);
+    }
+    files
+        .iter()
+        .position(|file| file.name == name)
+        .map(|index| index + 1)
+}
+
+fn language(architecture: AvailableDatapaths) -> &'static str {
+    match architecture {
+        AvailableDatapaths::MIPS => "mips",
+        AvailableDatapaths::RISCV => "riscv",
+    }
+}
+
+#[derive(PartialEq, Properties)]
+pub struct FileTreeProps {
+    pub files: UseStateHandle<Vec<EditorFile>>,
+    pub active_file: UseStateHandle<usize>,
+    pub current_architecture: AvailableDatapaths,
+}
+
+#[function_component]
+pub fn FileTree(props: &FileTreeProps) -> Html {
+    let library_input = use_node_ref();
+
+    // Asks for the name of a new, empty file and opens it
+    let on_new_file_clicked = {
+        let files = props.files.clone();
+        let active_file = props.active_file.clone();
+        let current_architecture = props.current_architecture;
+        Callback::from(move |_: MouseEvent| {
+            let Some(name) = gloo::dialogs::prompt("Name of the new file:", Some("file.asm"))
+            else {
+                return;
+            };
+            let name = name.trim().to_string();
+            if name.is_empty() {
+                return;
+            }
+            if find_active_file(&name, &files).is_some() {
+                gloo::dialogs::alert(&format!("There is already a file named \"{name}\"."));
+                return;
+            }
+
+            let mut new_files = (*files).clone();
+            new_files.push(EditorFile {
+                name,
+                model: TextModel::create("", Some(language(current_architecture)), None).unwrap(),
+                read_only: false,
+            });
+            active_file.set(new_files.len());
+            files.set(new_files);
+        })
+    };
+
+    // Opens the file picker for a library file
+    let on_add_library_clicked = {
+        let library_input = library_input.clone();
+        Callback::from(move |_: MouseEvent| {
+            if let Some(input) = library_input.cast::<HtmlInputElement>() {
+                // workaround for https://github.com/yewstack/yew/pull/3037 since it's not in 0.20
+                spawn_local(async move {
+                    input.click();
+                });
+            }
+        })
+    };
+
+    // Adds the picked file to the project as a read-only library
+    let on_library_picked = {
+        let files = props.files.clone();
+        let current_architecture = props.current_architecture;
+        Callback::from(move |event: Event| {
+            let input: HtmlInputElement = event.target_unchecked_into();
+            let Some(file) = input
+                .files()
+                .map(FileList::from)
+                .and_then(|list| list.first().cloned())
+            else {
+                return;
+            };
+            let name = file.name();
+            let contents = gloo::file::futures::read_as_text(&file);
+            let files = files.clone();
+            spawn_local(async move {
+                let Ok(contents) = contents.await else {
+                    gloo::dialogs::alert(&format!("\"{name}\" could not be read as text."));
+                    return;
+                };
+                let mut new_files: Vec<EditorFile> = (*files)
+                    .iter()
+                    .filter(|file| file.name != name)
+                    .cloned()
+                    .collect();
+                new_files.push(EditorFile {
+                    name,
+                    model: TextModel::create(&contents, Some(language(current_architecture)), None)
+                        .unwrap(),
+                    read_only: true,
+                });
+                files.set(new_files);
+            });
+            // Allow the same file to be picked again after it is changed on disk
+            input.set_value("");
+        })
+    };
+
+    let file_entries = std::iter::once((MAIN_FILE_NAME.to_string(), false))
+        .chain(props.files.iter().map(|file| (file.name.clone(), file.read_only)))
+        .enumerate()
+        .map(|(index, (name, read_only))| {
+            let active_class = if *props.active_file == index {
+                "bg-primary-500"
+            } else {
+                "bg-primary-700"
+            };
+            let on_open = {
+                let active_file = props.active_file.clone();
+                Callback::from(move |_: MouseEvent| active_file.set(index))
+            };
+            // The main file cannot be removed
+            let on_remove = (index > 0).then(|| {
+                let files = props.files.clone();
+                let active_file = props.active_file.clone();
+                Callback::from(move |event: MouseEvent| {
+                    event.stop_propagation();
+                    let mut new_files = (*files).clone();
+                    new_files.remove(index - 1);
+                    if *active_file >= index {
+                        active_file.set(*active_file - 1);
+                    }
+                    files.set(new_files);
+                })
+            });
+            html! {
+                <li class={format!("flex flex-row items-center justify-between gap-2 px-2 py-1 cursor-pointer rounded-md hover:bg-primary-600 {}", active_class)} title={name.clone()} onclick={on_open}>
+                    <span class="whitespace-nowrap text-ellipsis overflow-hidden min-w-0">
+                        if read_only {
+                            <i class={classes!("fa-solid", "fa-lock", "mr-1")} title="Read-only"></i>
+                        }
+                        {name}
+                    </span>
+                    if let Some(on_remove) = on_remove {
+                        <button class="opacity-60 hover:opacity-100" title="Remove file" onclick={on_remove}>{"×"}</button>
+                    }
+                </li>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class="flex flex-col gap-2 w-40 shrink-0 p-2 border-r-2 border-r-solid border-r-primary-200 overflow-auto">
+            <input type="file" class="hidden" ref={library_input} accept=".txt,.asm,.mips,.s" onchange={on_library_picked} />
+            <ul class="flex flex-col gap-1">
+                {file_entries}
+            </ul>
+            <button class="rounded-md px-2 py-1 bg-primary-700 hover:bg-primary-600" title="Add an empty file to the project" onclick={on_new_file_clicked}>{"New File"}</button>
+            <button class="rounded-md px-2 py-1 bg-primary-700 hover:bg-primary-600" title="Upload a read-only library file" onclick={on_add_library_clicked}>{"Add Library"}</button>
+        </div>
+    }
+}
@@ -1,2 +1,5 @@
 pub mod component;
+pub mod file_tree;
+pub mod outline;
+pub mod providers;
 pub mod tab;
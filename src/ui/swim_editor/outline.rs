@@ -0,0 +1,66 @@
+use yew::prelude::*;
+use yew::{html, Callback, Properties};
+
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::parser::linker::Section;
+use crate::parser::navigation::LabelIndex;
+use crate::parser::project::{parse_project, ProjectFile};
+
+// ** Outline Component ** //
+// Lists the labels of the file that is open in the editor, grouped by the
+// section they are in. Clicking a label moves the editor to the line it is
+// defined on.
+
+#[derive(PartialEq, Properties)]
+pub struct OutlineProps {
+    pub files: Vec<ProjectFile>,
+    pub active_name: String,
+    pub current_architecture: AvailableDatapaths,
+    /// Called with the line of the label that was clicked, counted from 1.
+    pub on_select: Callback<f64>,
+}
+
+#[function_component]
+pub fn Outline(props: &OutlineProps) -> Html {
+    let (program_info, _, _) = parse_project(&props.files, props.current_architecture);
+    let index = LabelIndex::new(&props.files, &program_info);
+    let labels = index.outline(&props.active_name);
+
+    let sections = [(Section::Text, ".text"), (Section::Data, ".data")]
+        .into_iter()
+        .filter(|(section, _)| labels.iter().any(|label| label.section == *section))
+        .map(|(section, section_name)| {
+            let entries = labels
+                .iter()
+                .filter(|label| label.section == section)
+                .map(|label| {
+                    let on_click = {
+                        let on_select = props.on_select.clone();
+                        let line = (label.location.line + 1) as f64;
+                        Callback::from(move |_: MouseEvent| on_select.emit(line))
+                    };
+                    html! {
+                        <li class="px-2 py-1 cursor-pointer rounded-md whitespace-nowrap text-ellipsis overflow-hidden hover:bg-primary-600" title={format!("Line {}", label.location.line + 1)} onclick={on_click}>
+                            {label.name.clone()}
+                        </li>
+                    }
+                })
+                .collect::<Html>();
+            html! {
+                <>
+                    <span class="text-primary-300">{section_name}</span>
+                    <ul class="flex flex-col gap-1">
+                        {entries}
+                    </ul>
+                </>
+            }
+        })
+        .collect::<Html>();
+
+    html! {
+        <div class="flex flex-col gap-2 w-40 shrink-0 p-2 border-l-2 border-l-solid border-l-primary-200 overflow-auto">
+            <span title="Labels of the open file">{"Outline"}</span>
+            {sections}
+        </div>
+    }
+}
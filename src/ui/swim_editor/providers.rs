@@ -0,0 +1,678 @@
+use std::{cell::RefCell, rc::Rc};
+
+use js_sys::{Array, Object, Reflect};
+use monaco::{api::TextModel, sys::IDisposable};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+
+use crate::emulation_core::architectures::AvailableDatapaths;
+use crate::parser::assembly_cache::{Assembly, AssemblyCache};
+use crate::parser::completion::{complete, CompletionKind};
+use crate::parser::fix_its::fix_its_in_file;
+use crate::parser::linker::Section;
+use crate::parser::local_labels::is_local_label_name;
+use crate::parser::navigation::{LabelIndex, LabelLocation};
+use crate::parser::parser_structs_and_enums::FixIt;
+use crate::parser::project::ProjectFile;
+use crate::parser::source_map::expansion_hints_in_file;
+
+// ** Editor Language Providers ** //
+// Monaco asks these providers for the definition and references of a label
+// when the user Ctrl-clicks it or looks up its references, for the places to
+// change when a label is renamed, and for the labels of a file when the user
+// goes to a symbol. The completion provider suggests instructions, registers,
+// and labels as the user types, the code action provider offers the fix-its of
+// assembler errors as quick fixes, and the inlay hints provider shows what each
+// pseudo-instruction expands to at the end of its line. Each provider assembles the project as it is in the editor
+// so that the result matches what the user has typed since the last assembly.
+
+/// The languages the providers are registered for.
+const LANGUAGES: [&str; 2] = ["mips", "riscv"];
+
+// Values of Monaco's SymbolKind enum.
+const SYMBOL_KIND_MODULE: f64 = 1.0;
+const SYMBOL_KIND_FUNCTION: f64 = 11.0;
+const SYMBOL_KIND_VARIABLE: f64 = 12.0;
+
+// Values of Monaco's CompletionItemKind enum.
+const COMPLETION_KIND_FUNCTION: f64 = 1.0;
+const COMPLETION_KIND_VARIABLE: f64 = 4.0;
+const COMPLETION_KIND_REFERENCE: f64 = 21.0;
+const COMPLETION_KIND_SNIPPET: f64 = 27.0;
+
+// Value of Monaco's CompletionItemInsertTextRule enum for inserting a snippet.
+const INSERT_AS_SNIPPET: f64 = 4.0;
+
+// Value of Monaco's InlayHintKind enum for a hint that is not a parameter name.
+const INLAY_HINT_KIND_TYPE: f64 = 1.0;
+
+/// The kind of code action Monaco shows as a quick fix.
+const CODE_ACTION_KIND_QUICK_FIX: &str = "quickfix";
+
+/// The characters that open the list of completions without typing a letter.
+const TRIGGER_CHARACTERS: [&str; 4] = ["$", ",", " ", "("];
+
+/// The files of the project open in the editor, kept up to date by the editor
+/// component and read whenever Monaco asks a provider for a result.
+#[derive(Clone, Default)]
+pub struct ProviderContext {
+    /// The name and text model of every file of the project, with the main file
+    /// first and whether the file is read-only.
+    pub models: Vec<(String, TextModel, bool)>,
+    pub current_architecture: AvailableDatapaths,
+    /// The project as the providers last assembled it, so that it is only
+    /// assembled again once the user edits it.
+    pub assembly_cache: RefCell<AssemblyCache>,
+}
+
+impl ProviderContext {
+    /// Reads the files of the project as they are currently typed.
+    fn files(&self) -> Vec<ProjectFile> {
+        self.models
+            .iter()
+            .map(|(name, model, read_only)| ProjectFile {
+                name: name.clone(),
+                contents: model.get_value(),
+                read_only: *read_only,
+            })
+            .collect()
+    }
+
+    /// Assembles the project as it is currently typed, unless it has not changed
+    /// since it was last assembled.
+    fn assemble(&self, files: &[ProjectFile]) -> Assembly {
+        self.assembly_cache
+            .borrow_mut()
+            .assemble(files, self.current_architecture)
+            .clone()
+    }
+
+    /// Assembles the project as it is currently typed and indexes its labels.
+    fn label_index(&self) -> LabelIndex {
+        let files = self.files();
+        LabelIndex::new(&files, &self.assemble(&files).program_info)
+    }
+
+    /// Assembles the project as it is currently typed and lists its labels.
+    fn labels(&self) -> Vec<String> {
+        // Local labels such as `1:` are referred to as `1b` or `1f` rather than by
+        // the names they are given when assembled.
+        let mut labels: Vec<String> = self
+            .assemble(&self.files())
+            .labels
+            .into_keys()
+            .filter(|label| !is_local_label_name(label))
+            .collect();
+        labels.sort();
+        labels
+    }
+
+    /// Assembles the project as it is currently typed and finds the fix-its of
+    /// the errors in a file, with the line each one edits.
+    fn fix_its(&self, file: &str) -> Vec<(usize, FixIt)> {
+        let program_info = self.assemble(&self.files()).program_info;
+        fix_its_in_file(&program_info, file)
+            .into_iter()
+            .map(|(line, fix_it)| (line, fix_it.clone()))
+            .collect()
+    }
+
+    /// Assembles the project as it is currently typed and finds the instructions
+    /// each pseudo-instruction and macro invocation of a file expands to, with
+    /// the line it is written on.
+    fn expansion_hints(&self, file: &str) -> Vec<(usize, String)> {
+        let program_info = self.assemble(&self.files()).program_info;
+        expansion_hints_in_file(&program_info, file)
+    }
+
+    /// Finds the name of the file a model Monaco gives a provider belongs to.
+    fn file_of_model(&self, model: &JsValue) -> Option<String> {
+        let uri = model_uri(model);
+        self.models
+            .iter()
+            .find(|(_, file_model, _)| model_uri(file_model.as_ref()) == uri)
+            .map(|(name, _, _)| name.clone())
+    }
+
+    /// Creates the Monaco location of a label, with the URI of the model of its file.
+    fn location_object(&self, location: &LabelLocation) -> Option<Object> {
+        let (_, model, _) = self
+            .models
+            .iter()
+            .find(|(name, _, _)| *name == location.file)?;
+        let object = Object::new();
+        set(
+            &object,
+            "uri",
+            &Reflect::get(model.as_ref(), &"uri".into()).ok()?,
+        );
+        set(&object, "range", &range_object(location));
+        Some(object)
+    }
+}
+
+type ProviderFunction = Closure<dyn Fn(JsValue, JsValue, JsValue) -> JsValue>;
+
+/// Providers registered with Monaco. They are removed when this is dropped.
+pub struct RegisteredProviders {
+    disposables: Vec<IDisposable>,
+    // The closures must live as long as Monaco may call them.
+    _functions: Vec<ProviderFunction>,
+}
+
+impl Drop for RegisteredProviders {
+    fn drop(&mut self) {
+        for disposable in &self.disposables {
+            disposable.dispose();
+        }
+    }
+}
+
+/// Registers the definition, reference, rename, and document symbol providers
+/// for labels with Monaco.
+pub fn register_label_providers(context: Rc<RefCell<ProviderContext>>) -> RegisteredProviders {
+    let definition = {
+        let context = Rc::clone(&context);
+        provider_function(move |model, position, _| {
+            let context = context.borrow();
+            let (Some(file), (line, column)) =
+                (context.file_of_model(&model), position_of(&position))
+            else {
+                return JsValue::NULL;
+            };
+            context
+                .label_index()
+                .find_definition(&file, line, column)
+                .and_then(|location| context.location_object(location))
+                .map_or(JsValue::NULL, JsValue::from)
+        })
+    };
+
+    let references = {
+        let context = Rc::clone(&context);
+        provider_function(move |model, position, _| {
+            let context = context.borrow();
+            let Some(file) = context.file_of_model(&model) else {
+                return Array::new().into();
+            };
+            let (line, column) = position_of(&position);
+            context
+                .label_index()
+                .find_references(&file, line, column)
+                .into_iter()
+                .filter_map(|location| context.location_object(location))
+                .collect::<Array>()
+                .into()
+        })
+    };
+
+    let rename_edits = {
+        let context = Rc::clone(&context);
+        provider_function(move |model, position, new_name| {
+            let context = context.borrow();
+            let edit = Object::new();
+            let Some(file) = context.file_of_model(&model) else {
+                set(&edit, "edits", &Array::new());
+                return edit.into();
+            };
+            let (line, column) = position_of(&position);
+            let new_name = new_name.as_string().unwrap_or_default();
+            let index = context.label_index();
+            match index.rename_label(&file, line, column, &new_name, context.current_architecture) {
+                Ok(locations) => {
+                    let edits = locations
+                        .into_iter()
+                        .filter_map(|location| {
+                            let target = context.location_object(location)?;
+                            let text_edit = Object::new();
+                            set(&text_edit, "range", &range_object(location));
+                            set(&text_edit, "text", &new_name.as_str().into());
+                            let edit = Object::new();
+                            set(
+                                &edit,
+                                "resource",
+                                &Reflect::get(&target, &"uri".into()).ok()?,
+                            );
+                            set(&edit, "textEdit", &text_edit);
+                            Some(edit)
+                        })
+                        .collect::<Array>();
+                    set(&edit, "edits", &edits);
+                }
+                Err(message) => {
+                    set(&edit, "edits", &Array::new());
+                    set(&edit, "rejectReason", &message.into());
+                }
+            }
+            edit.into()
+        })
+    };
+
+    // Only labels can be renamed, so Monaco is told when the cursor is not on one.
+    let rename_location = {
+        let context = Rc::clone(&context);
+        provider_function(move |model, position, _| {
+            let context = context.borrow();
+            let (line, column) = position_of(&position);
+            let index = context.label_index();
+            let location = context.file_of_model(&model).and_then(|file| {
+                index
+                    .occurrence_at(&file, line, column)
+                    .map(|(name, location)| (name.to_string(), location.clone()))
+            });
+            let result = Object::new();
+            match location {
+                Some((name, location)) => {
+                    set(&result, "range", &range_object(&location));
+                    set(&result, "text", &name.into());
+                }
+                None => {
+                    let range = range_object(&LabelLocation {
+                        file: String::new(),
+                        line,
+                        start_end_columns: (column, column),
+                    });
+                    set(&result, "range", &range);
+                    set(&result, "text", &"".into());
+                    set(
+                        &result,
+                        "rejectReason",
+                        &"Only labels can be renamed.".into(),
+                    );
+                }
+            }
+            result.into()
+        })
+    };
+
+    // The labels of a file are grouped by the section they are in.
+    let document_symbols = {
+        let context = Rc::clone(&context);
+        provider_function(move |model, _, _| {
+            let context = context.borrow();
+            let Some(file) = context.file_of_model(&model) else {
+                return Array::new().into();
+            };
+            let index = context.label_index();
+            let labels = index.outline(&file);
+            let groups = Array::new();
+            for (section, name) in [(Section::Text, ".text"), (Section::Data, ".data")] {
+                let children: Array = labels
+                    .iter()
+                    .filter(|label| label.section == section)
+                    .map(|label| {
+                        let kind = match section {
+                            Section::Text => SYMBOL_KIND_FUNCTION,
+                            Section::Data => SYMBOL_KIND_VARIABLE,
+                        };
+                        symbol_object(&label.name, kind, &label.location, &Array::new())
+                    })
+                    .collect();
+                if children.length() == 0 {
+                    continue;
+                }
+                let first = labels
+                    .iter()
+                    .find(|label| label.section == section)
+                    .unwrap();
+                groups.push(&symbol_object(
+                    name,
+                    SYMBOL_KIND_MODULE,
+                    &first.location,
+                    &children,
+                ));
+            }
+            groups.into()
+        })
+    };
+
+    let mut disposables = Vec::new();
+    for language in LANGUAGES {
+        let provider = Object::new();
+        set(&provider, "provideDefinition", definition.as_ref());
+        disposables.push(monaco::sys::languages::register_definition_provider(
+            language,
+            provider.unchecked_ref(),
+        ));
+
+        let provider = Object::new();
+        set(&provider, "provideReferences", references.as_ref());
+        disposables.push(monaco::sys::languages::register_reference_provider(
+            language,
+            provider.unchecked_ref(),
+        ));
+
+        let provider = Object::new();
+        set(&provider, "provideRenameEdits", rename_edits.as_ref());
+        set(&provider, "resolveRenameLocation", rename_location.as_ref());
+        disposables.push(monaco::sys::languages::register_rename_provider(
+            language,
+            provider.unchecked_ref(),
+        ));
+
+        let provider = Object::new();
+        set(&provider, "displayName", &"Labels".into());
+        set(
+            &provider,
+            "provideDocumentSymbols",
+            document_symbols.as_ref(),
+        );
+        disposables.push(monaco::sys::languages::register_document_symbol_provider(
+            language,
+            provider.unchecked_ref(),
+        ));
+    }
+
+    RegisteredProviders {
+        disposables,
+        _functions: vec![
+            definition,
+            references,
+            rename_edits,
+            rename_location,
+            document_symbols,
+        ],
+    }
+}
+
+/// Registers the completion provider, which suggests the instructions of the
+/// current architecture, then registers of the right kind for each operand, and
+/// labels where an instruction accepts one.
+pub fn register_completion_provider(context: Rc<RefCell<ProviderContext>>) -> RegisteredProviders {
+    let completion_items = provider_function(move |model, position, _| {
+        let context = context.borrow();
+        let (line, column) = position_of(&position);
+        let text = Reflect::get(&model, &"getLineContent".into())
+            .ok()
+            .and_then(|function| {
+                function
+                    .unchecked_into::<js_sys::Function>()
+                    .call1(&model, &((line + 1) as f64).into())
+                    .ok()
+            })
+            .and_then(|text| text.as_string())
+            .unwrap_or_default();
+        let completions = complete(
+            &text,
+            column,
+            &context.labels(),
+            context.current_architecture,
+        );
+        let range = range_object(&LabelLocation {
+            file: String::new(),
+            line,
+            start_end_columns: (completions.start_column, column),
+        });
+        let suggestions: Array = completions
+            .items
+            .into_iter()
+            .map(|completion| {
+                let kind = match completion.kind {
+                    CompletionKind::Instruction => COMPLETION_KIND_FUNCTION,
+                    CompletionKind::PseudoInstruction => COMPLETION_KIND_SNIPPET,
+                    CompletionKind::Register => COMPLETION_KIND_VARIABLE,
+                    CompletionKind::Label => COMPLETION_KIND_REFERENCE,
+                };
+                let documentation = Object::new();
+                set(&documentation, "value", &completion.documentation.into());
+                let suggestion = Object::new();
+                set(&suggestion, "label", &completion.label.into());
+                set(&suggestion, "kind", &kind.into());
+                set(&suggestion, "detail", &completion.detail.into());
+                set(&suggestion, "documentation", &documentation);
+                set(&suggestion, "insertText", &completion.insert_text.into());
+                if completion.is_snippet {
+                    set(&suggestion, "insertTextRules", &INSERT_AS_SNIPPET.into());
+                }
+                set(&suggestion, "range", &range);
+                suggestion
+            })
+            .collect();
+        let result = Object::new();
+        set(&result, "suggestions", &suggestions);
+        result.into()
+    });
+
+    let mut disposables = Vec::new();
+    for language in LANGUAGES {
+        let provider = Object::new();
+        set(
+            &provider,
+            "triggerCharacters",
+            &TRIGGER_CHARACTERS
+                .iter()
+                .map(|character| JsValue::from(*character))
+                .collect::<Array>(),
+        );
+        set(
+            &provider,
+            "provideCompletionItems",
+            completion_items.as_ref(),
+        );
+        disposables.push(monaco::sys::languages::register_completion_item_provider(
+            language,
+            provider.unchecked_ref(),
+        ));
+    }
+
+    RegisteredProviders {
+        disposables,
+        _functions: vec![completion_items],
+    }
+}
+
+/// Registers the code action provider, which offers the fix-its of the errors on
+/// the lines the user selects, such as replacing a misspelled register.
+pub fn register_code_action_provider(context: Rc<RefCell<ProviderContext>>) -> RegisteredProviders {
+    let code_actions = provider_function(move |model, range, code_action_context| {
+        let context = context.borrow();
+        let read_line = |key: &str| {
+            (Reflect::get(&range, &key.into())
+                .ok()
+                .and_then(|value| value.as_f64())
+                .unwrap_or(1.0) as usize)
+                .saturating_sub(1)
+        };
+        let lines = read_line("startLineNumber")..=read_line("endLineNumber");
+        let markers: Array = Reflect::get(&code_action_context, &"markers".into())
+            .map(|markers| markers.unchecked_into())
+            .unwrap_or_default();
+        let uri = Reflect::get(&model, &"uri".into()).unwrap_or(JsValue::NULL);
+
+        let fix_its = context
+            .file_of_model(&model)
+            .map(|file| context.fix_its(&file))
+            .unwrap_or_default();
+        let actions: Array = fix_its
+            .into_iter()
+            .filter(|(line, _)| lines.contains(line))
+            .map(|(line, fix_it)| {
+                let location = LabelLocation {
+                    file: String::new(),
+                    line,
+                    start_end_columns: fix_it.start_end_columns,
+                };
+                let text_edit = Object::new();
+                set(&text_edit, "range", &range_object(&location));
+                set(&text_edit, "text", &fix_it.replacement.into());
+                let edit = Object::new();
+                set(&edit, "resource", &uri);
+                set(&edit, "textEdit", &text_edit);
+                set(&edit, "versionId", &JsValue::UNDEFINED);
+                let workspace_edit = Object::new();
+                set(&workspace_edit, "edits", &Array::of1(&edit));
+
+                // The action resolves the error markers on the line it edits.
+                let diagnostics: Array = markers
+                    .iter()
+                    .filter(|marker| {
+                        Reflect::get(marker, &"startLineNumber".into())
+                            .ok()
+                            .and_then(|value| value.as_f64())
+                            == Some((line + 1) as f64)
+                    })
+                    .collect();
+
+                let action = Object::new();
+                set(&action, "title", &fix_it.title.into());
+                set(&action, "kind", &CODE_ACTION_KIND_QUICK_FIX.into());
+                set(&action, "diagnostics", &diagnostics);
+                set(&action, "edit", &workspace_edit);
+                set(&action, "isPreferred", &true.into());
+                action
+            })
+            .collect();
+
+        let result = Object::new();
+        set(&result, "actions", &actions);
+        set(&result, "dispose", &js_sys::Function::new_no_args(""));
+        result.into()
+    });
+
+    let mut disposables = Vec::new();
+    for language in LANGUAGES {
+        let provider = Object::new();
+        set(&provider, "provideCodeActions", code_actions.as_ref());
+        disposables.push(monaco::sys::languages::register_code_action_provider(
+            language,
+            provider.unchecked_ref(),
+            None,
+        ));
+    }
+
+    RegisteredProviders {
+        disposables,
+        _functions: vec![code_actions],
+    }
+}
+
+/// Registers the inlay hints provider, which shows the instructions each
+/// pseudo-instruction and macro invocation expands to at the end of its line, so
+/// the expansion can be read without the source being rewritten.
+pub fn register_expansion_hints_provider(
+    context: Rc<RefCell<ProviderContext>>,
+) -> RegisteredProviders {
+    let inlay_hints = provider_function(move |model, range, _| {
+        let context = context.borrow();
+        let read_line = |key: &str| {
+            (Reflect::get(&range, &key.into())
+                .ok()
+                .and_then(|value| value.as_f64())
+                .unwrap_or(1.0) as usize)
+                .saturating_sub(1)
+        };
+        let lines = read_line("startLineNumber")..=read_line("endLineNumber");
+        let end_of_line = |line: usize| {
+            Reflect::get(&model, &"getLineMaxColumn".into())
+                .ok()
+                .and_then(|function| {
+                    function
+                        .unchecked_into::<js_sys::Function>()
+                        .call1(&model, &((line + 1) as f64).into())
+                        .ok()
+                })
+                .and_then(|column| column.as_f64())
+                .unwrap_or(1.0)
+        };
+
+        let hints: Array = context
+            .file_of_model(&model)
+            .map(|file| context.expansion_hints(&file))
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(line, _)| lines.contains(line))
+            .map(|(line, hint)| {
+                let position = Object::new();
+                set(&position, "lineNumber", &((line + 1) as f64).into());
+                set(&position, "column", &end_of_line(line).into());
+                let inlay_hint = Object::new();
+                set(&inlay_hint, "label", &format!("=> {hint}").into());
+                set(&inlay_hint, "position", &position);
+                set(&inlay_hint, "kind", &INLAY_HINT_KIND_TYPE.into());
+                set(&inlay_hint, "paddingLeft", &true.into());
+                inlay_hint
+            })
+            .collect();
+
+        let result = Object::new();
+        set(&result, "hints", &hints);
+        set(&result, "dispose", &js_sys::Function::new_no_args(""));
+        result.into()
+    });
+
+    let mut disposables = Vec::new();
+    for language in LANGUAGES {
+        let provider = Object::new();
+        set(&provider, "provideInlayHints", inlay_hints.as_ref());
+        disposables.push(monaco::sys::languages::register_inlay_hints_provider(
+            language,
+            provider.unchecked_ref(),
+        ));
+    }
+
+    RegisteredProviders {
+        disposables,
+        _functions: vec![inlay_hints],
+    }
+}
+
+fn provider_function(
+    function: impl Fn(JsValue, JsValue, JsValue) -> JsValue + 'static,
+) -> ProviderFunction {
+    Closure::wrap(Box::new(function) as Box<dyn Fn(JsValue, JsValue, JsValue) -> JsValue>)
+}
+
+fn set(object: &Object, key: &str, value: &JsValue) {
+    Reflect::set(object, &key.into(), value).unwrap();
+}
+
+fn model_uri(model: &JsValue) -> String {
+    Reflect::get(model, &"uri".into())
+        .ok()
+        .map(|uri| String::from(uri.unchecked_into::<Object>().to_string()))
+        .unwrap_or_default()
+}
+
+/// Reads a Monaco position, which counts lines and columns from 1, as a line and
+/// column counted from 0.
+fn position_of(position: &JsValue) -> (usize, usize) {
+    let read = |key: &str| {
+        Reflect::get(position, &key.into())
+            .ok()
+            .and_then(|value| value.as_f64())
+            .unwrap_or(1.0) as usize
+    };
+    (
+        read("lineNumber").saturating_sub(1),
+        read("column").saturating_sub(1),
+    )
+}
+
+fn range_object(location: &LabelLocation) -> Object {
+    let range = Object::new();
+    let line = (location.line + 1) as f64;
+    set(&range, "startLineNumber", &line.into());
+    set(
+        &range,
+        "startColumn",
+        &((location.start_end_columns.0 + 1) as f64).into(),
+    );
+    set(&range, "endLineNumber", &line.into());
+    set(
+        &range,
+        "endColumn",
+        &((location.start_end_columns.1 + 1) as f64).into(),
+    );
+    range
+}
+
+fn symbol_object(name: &str, kind: f64, location: &LabelLocation, children: &Array) -> Object {
+    let symbol = Object::new();
+    set(&symbol, "name", &name.into());
+    set(&symbol, "detail", &"".into());
+    set(&symbol, "kind", &kind.into());
+    set(&symbol, "tags", &Array::new());
+    set(&symbol, "range", &range_object(location));
+    set(&symbol, "selectionRange", &range_object(location));
+    set(&symbol, "children", children);
+    symbol
+}
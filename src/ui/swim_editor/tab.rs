@@ -16,6 +16,7 @@ pub enum TabState {
     DataSegment,
     StackSegment,
     StackFrameView,
+    HeapSegment,
 }
 
 impl FromStr for TabState {
@@ -31,6 +32,7 @@ fn from_str(tab_name: &str) -> Result<TabState, Self::Err> {
             "DataSegment" => Ok(TabState::DataSegment),
             "StackSegment" => Ok(TabState::StackSegment),
             "StackFrameView" => Ok(TabState::StackFrameView),
+            "HeapSegment" => Ok(TabState::HeapSegment),
             _ => Err(()),
         }
     }